@@ -56,9 +56,9 @@ fn main() {
         into_string.len()
     );
 
-    // Mostrar el XML generado (formateado para legibilidad)
+    // Mostrar el XML generado (indentado para legibilidad)
     println!("\n📄 XML Generado:");
-    println!("{}", pretty_format_xml(&xml_string));
+    println!("{}", gpx.to_xml_pretty());
 
     // Guardar en archivo
     let filename = "/tmp/ejemplo_gpx.gpx";
@@ -90,15 +90,3 @@ fn main() {
         println!("   ❌ Error en roundtrip");
     }
 }
-
-// Función auxiliar para formatear XML de manera más legible
-fn pretty_format_xml(xml: &str) -> String {
-    xml.replace("><", ">\n<")
-        .replace("<gpx", "\n<gpx")
-        .replace("<trk", "\n  <trk")
-        .replace("<trkseg", "\n    <trkseg")
-        .replace("<trkpt", "\n      <trkpt")
-        .replace("<wpt", "\n  <wpt")
-        .replace("<name", "\n    <name")
-        .replace("<ele", "\n        <ele")
-}
@@ -0,0 +1,128 @@
+/* C interface of gpx-extractor (feature `ffi`).
+ * Regenerate with: cbindgen --config cbindgen.toml --output include/gpx_extractor.h */
+
+#ifndef GPX_EXTRACTOR_H
+#define GPX_EXTRACTOR_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A parsed GPX document
+ */
+typedef struct Gpx Gpx;
+
+/**
+ * Statistics of a document, as returned by [`gpx_statistics`]
+ *
+ * Figures that cannot be computed are `NaN` (floating point) or `-1` (duration).
+ */
+typedef struct GpxCStatistics {
+  /**
+   * Number of tracks
+   */
+  uintptr_t total_tracks;
+  /**
+   * Number of waypoints
+   */
+  uintptr_t total_waypoints;
+  /**
+   * Number of track segments
+   */
+  uintptr_t total_segments;
+  /**
+   * Number of track points
+   */
+  uintptr_t total_points;
+  /**
+   * Distance in kilometers
+   */
+  double total_distance_km;
+  /**
+   * Lowest elevation in meters, or `NaN`
+   */
+  double min_elevation_m;
+  /**
+   * Highest elevation in meters, or `NaN`
+   */
+  double max_elevation_m;
+  /**
+   * Elevation gain in meters, or `NaN`
+   */
+  double elevation_gain_m;
+  /**
+   * Elevation loss in meters, or `NaN`
+   */
+  double elevation_loss_m;
+  /**
+   * Duration in seconds, or `-1`
+   */
+  int64_t duration_seconds;
+  /**
+   * Average speed in km/h, or `NaN`
+   */
+  double average_speed_kmh;
+} GpxCStatistics;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses a NUL-terminated UTF-8 GPX document
+ *
+ * Returns a document to release with [`gpx_free`], or `NULL` on error (see
+ * [`gpx_last_error`]).
+ */
+struct Gpx *gpx_parse(const char *xml);
+
+/**
+ * Reads and parses a GPX file
+ *
+ * Returns a document to release with [`gpx_free`], or `NULL` on error (see
+ * [`gpx_last_error`]).
+ */
+struct Gpx *gpx_parse_file(const char *path);
+
+/**
+ * Statistics of a parsed document
+ *
+ * A `NULL` document gives zero counts and unknown figures.
+ */
+struct GpxCStatistics gpx_statistics(const struct Gpx *gpx);
+
+/**
+ * Converts a parsed document to a GeoJSON `FeatureCollection`
+ *
+ * Returns a string to release with [`gpx_string_free`], or `NULL` on error (see
+ * [`gpx_last_error`]).
+ */
+char *gpx_to_geojson(const struct Gpx *gpx);
+
+/**
+ * Releases a document returned by [`gpx_parse`] or [`gpx_parse_file`]
+ */
+void gpx_free(struct Gpx *gpx);
+
+/**
+ * Releases a string returned by this library, such as [`gpx_to_geojson`]
+ */
+void gpx_string_free(char *text);
+
+/**
+ * Message of the error of the last fallible call on the calling thread, or
+ * `NULL` if that call succeeded
+ *
+ * The string belongs to the library and stays valid until the next call to
+ * `gpx_parse`, `gpx_parse_file` or `gpx_to_geojson` on the same thread.
+ */
+const char *gpx_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GPX_EXTRACTOR_H */
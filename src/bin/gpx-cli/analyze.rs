@@ -0,0 +1,89 @@
+use clap::Args;
+use gpx_extractor::{Gpx, GpxStatistics};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Print the statistics of one document as JSON
+#[derive(Args)]
+pub struct AnalyzeArgs {
+    /// GPX file to analyze
+    #[arg(value_name = "FILE", required_unless_present = "stdin")]
+    path: Option<PathBuf>,
+
+    /// Read the document from standard input instead of a file
+    #[arg(long, conflicts_with = "path")]
+    stdin: bool,
+}
+
+/// Analiza el documento e imprime una única línea JSON; los errores también salen
+/// como JSON (`{"error": ...}`) con código de salida 1, para consumidores automáticos
+pub fn run(args: &AnalyzeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let gpx = match &args.path {
+        Some(path) if !args.stdin => Gpx::from_file(path),
+        _ => Gpx::from_reader(std::io::stdin().lock()),
+    };
+    match gpx.map_err(|e| e.to_string()) {
+        Ok(gpx) => {
+            println!("{}", analysis_json(&gpx));
+            Ok(())
+        }
+        Err(error) => {
+            println!("{}", json!({ "error": error }));
+            std::process::exit(1);
+        }
+    }
+}
+
+fn analysis_json(gpx: &Gpx) -> Value {
+    let tracks: Vec<Value> = gpx
+        .statistics_per_track()
+        .iter()
+        .map(|(name, stats)| json!({ "name": name, "statistics": statistics_json(stats) }))
+        .collect();
+
+    json!({
+        "name": gpx.metadata.as_ref().and_then(|m| m.name.as_deref()),
+        "date": gpx.date(),
+        "statistics": statistics_json(&gpx.statistics()),
+        "tracks": tracks,
+    })
+}
+
+pub fn statistics_json(stats: &GpxStatistics) -> Value {
+    json!({
+        "total_tracks": stats.total_tracks,
+        "total_waypoints": stats.total_waypoints,
+        "total_segments": stats.total_segments,
+        "total_points": stats.total_points,
+        "total_distance_km": stats.total_distance_km,
+        "elevation_range": stats.elevation_range,
+        "elevation_gain": stats.elevation_gain,
+        "elevation_loss": stats.elevation_loss,
+        "duration_seconds": stats.duration_seconds,
+        "duration": stats.duration_formatted(),
+        "average_speed_kmh": stats.average_speed_kmh,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analysis_json() {
+        let gpx = Gpx::try_from_str(
+            r#"<gpx><metadata><name>Tour</name></metadata><trk><name>Day 1</name><trkseg>
+            <trkpt lat="40.0" lon="-3.0"><ele>600</ele></trkpt>
+            <trkpt lat="40.1" lon="-3.0"><ele>650</ele></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+
+        let report = analysis_json(&gpx);
+        assert_eq!(report["name"], "Tour");
+        assert_eq!(report["statistics"]["total_points"], 2);
+        assert_eq!(report["statistics"]["elevation_gain"], 50.0);
+        assert_eq!(report["tracks"][0]["name"], "Day 1");
+        assert!(report["statistics"]["duration"].is_null());
+    }
+}
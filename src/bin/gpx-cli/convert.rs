@@ -0,0 +1,125 @@
+use clap::{builder::PossibleValuesParser, Args};
+use gpx_extractor::{formats::FormatRegistry, Gpx, GpxError};
+use std::path::PathBuf;
+
+/// Convert a track file to another format
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// File to convert, in any format with a registered reader (detected by content)
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Output format, any format with a registered writer
+    #[arg(long, value_parser = writable_formats())]
+    format: String,
+
+    /// Write the result to this file instead of standard output
+    #[arg(short, long, value_name = "FILE")]
+    out: Option<PathBuf>,
+}
+
+/// Formatos del registro por defecto que tienen escritor
+fn writable_formats() -> PossibleValuesParser {
+    let registry = FormatRegistry::default();
+    let names: Vec<String> = registry
+        .format_names()
+        .into_iter()
+        .filter(|name| registry.writer_for_name(name).is_some())
+        .map(str::to_string)
+        .collect();
+    PossibleValuesParser::new(names)
+}
+
+/// Convierte el documento con el escritor registrado para el formato (nombre o extensión)
+fn convert(
+    registry: &FormatRegistry,
+    gpx: &Gpx,
+    format: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let writer = registry
+        .writer_for_name(format)
+        .or_else(|| registry.writer_for_extension(format))
+        .ok_or_else(|| GpxError::UnknownFormat(format!("no writer for `{format}`")))?;
+    Ok(String::from_utf8(writer.write(gpx)?)?)
+}
+
+pub fn run(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = FormatRegistry::default();
+    let bytes = std::fs::read(&args.path)?;
+    let gpx = registry.read_detected(&bytes)?;
+    crate::write_output(
+        args.out.as_deref(),
+        &convert(&registry, &gpx, &args.format)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        convert: ConvertArgs,
+    }
+
+    #[test]
+    fn test_convert_formats() {
+        let cli = TestCli::try_parse_from(["convert", "ride.gpx", "--format", "kml"]).unwrap();
+        assert_eq!(cli.convert.format, "kml");
+        assert!(TestCli::try_parse_from(["convert", "ride.gpx", "--format", "tcx"]).is_ok());
+        // FIT solo tiene lector, no escritor
+        assert!(TestCli::try_parse_from(["convert", "ride.gpx", "--format", "fit"]).is_err());
+
+        let gpx = Gpx::try_from_str(
+            r#"<gpx><trk><trkseg><trkpt lat="40.0" lon="-3.0"/><trkpt lat="40.1" lon="-3.0"/></trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let registry = FormatRegistry::default();
+        assert!(convert(&registry, &gpx, "geojson")
+            .unwrap()
+            .contains("FeatureCollection"));
+        assert!(convert(&registry, &gpx, "kml").unwrap().contains("<kml"));
+        assert!(convert(&registry, &gpx, "tcx")
+            .unwrap()
+            .contains("<TrainingCenterDatabase"));
+        assert!(convert(&registry, &gpx, "gpx").unwrap().contains("<gpx"));
+        assert_eq!(convert(&registry, &gpx, "csv").unwrap().lines().count(), 3);
+        assert!(convert(&registry, &gpx, "fit").is_err());
+    }
+
+    #[test]
+    fn test_convert_detects_tcx_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("run.xml");
+        let output = dir.path().join("run.gpx");
+        std::fs::write(
+            &input,
+            r#"<TrainingCenterDatabase><Activities><Activity Sport="Running">
+                <Lap StartTime="2024-07-01T08:00:00Z"><Track><Trackpoint>
+                    <Time>2024-07-01T08:00:00Z</Time>
+                    <Position><LatitudeDegrees>40.0</LatitudeDegrees><LongitudeDegrees>-3.0</LongitudeDegrees></Position>
+                </Trackpoint></Track></Lap>
+            </Activity></Activities></TrainingCenterDatabase>"#,
+        )
+        .unwrap();
+
+        let args = ConvertArgs {
+            path: input.clone(),
+            format: "gpx".to_string(),
+            out: Some(output.clone()),
+        };
+        run(&args).unwrap();
+        let gpx = Gpx::from_file(&output).unwrap();
+        assert_eq!(gpx.track_names(), vec!["Running"]);
+        assert_eq!(gpx.total_points(), 1);
+
+        std::fs::write(&input, "lat,lon\n40,-3\n").unwrap();
+        let error = run(&args).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<GpxError>(),
+            Some(GpxError::UnknownFormat(_))
+        ));
+    }
+}
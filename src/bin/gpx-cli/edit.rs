@@ -0,0 +1,178 @@
+use chrono::Duration;
+use clap::{ArgGroup, Args};
+use colored::Colorize;
+use gpx_extractor::{Gpx, WriteOptions};
+use std::path::{Path, PathBuf};
+
+/// Edit the metadata of a GPX file in place, keeping a backup
+#[derive(Args)]
+#[command(group(ArgGroup::new("edits").required(true).multiple(true)))]
+pub struct EditArgs {
+    /// GPX file to edit
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Set the document name (and the track name, if there is a single track)
+    #[arg(long, value_name = "NAME", group = "edits")]
+    set_name: Option<String>,
+
+    /// Set the activity type of every track (e.g. `cycling`, `running`)
+    #[arg(long, value_name = "TYPE", group = "edits")]
+    set_type: Option<String>,
+
+    /// Add a keyword to the metadata; repeat to add several
+    #[arg(long, value_name = "KEYWORD", group = "edits")]
+    add_keyword: Vec<String>,
+
+    /// Shift every timestamp, e.g. `+1h`, `-30m` or `+1h30m` (units: h, m, s)
+    #[arg(long, value_name = "OFFSET", value_parser = parse_offset, allow_hyphen_values = true, group = "edits")]
+    set_time_offset: Option<Duration>,
+
+    /// Do not keep a copy of the original file as `<FILE>.bak`
+    #[arg(long)]
+    no_backup: bool,
+}
+
+/// Parsea un desfase con signo opcional y unidades h, m y s (`+1h`, `-1h30m`, `90s`)
+pub(crate) fn parse_offset(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid time offset '{text}' (expected e.g. +1h, -30m, +1h30m)");
+    let (sign, rest) = match text.trim().as_bytes().first() {
+        Some(b'-') => (-1, &text.trim()[1..]),
+        Some(b'+') => (1, &text.trim()[1..]),
+        _ => (1, text.trim()),
+    };
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut seconds = 0_i64;
+    let mut number = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        let value: i64 = number.parse().map_err(|_| invalid())?;
+        seconds += value * unit;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+    Ok(Duration::seconds(sign * seconds))
+}
+
+/// Aplica las ediciones pedidas al documento
+fn apply(gpx: &mut Gpx, args: &EditArgs) {
+    if let Some(name) = &args.set_name {
+        gpx.set_name(name.clone());
+        if let [track] = gpx.tracks.as_mut_slice() {
+            track.name = Some(name.clone());
+        }
+    }
+    if let Some(kind) = &args.set_type {
+        for track in &mut gpx.tracks {
+            track.kind = Some(kind.clone());
+        }
+    }
+    for keyword in &args.add_keyword {
+        gpx.metadata_mut().add_keyword(keyword);
+    }
+    if let Some(offset) = args.set_time_offset {
+        gpx.shift_times(offset);
+    }
+}
+
+/// Ruta de la copia de seguridad: el mismo nombre con `.bak` añadido
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// Sobrescribe el fichero con el documento editado, copiando antes el original
+/// a `<FILE>.bak` salvo que se pida lo contrario
+pub fn save_in_place(
+    gpx: &Gpx,
+    path: &Path,
+    backup: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if backup {
+        let backup = backup_path(path);
+        std::fs::copy(path, &backup)?;
+        println!("{}", format!("Backup saved to {}", backup.display()).cyan());
+    }
+    gpx.save_to_file_with(&path.to_string_lossy(), &WriteOptions::pretty())?;
+    println!("{}", format!("✅ Updated {}", path.display()).green());
+    Ok(())
+}
+
+pub fn run(args: &EditArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut gpx = Gpx::from_file(&args.path)?;
+    apply(&mut gpx, args);
+    save_in_place(&gpx, &args.path, !args.no_backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        edit: EditArgs,
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("+1h"), Ok(Duration::hours(1)));
+        assert_eq!(parse_offset("-1h30m"), Ok(Duration::minutes(-90)));
+        assert_eq!(parse_offset("45s"), Ok(Duration::seconds(45)));
+        assert!(parse_offset("1d").is_err());
+        assert!(parse_offset("+").is_err());
+        assert!(parse_offset("10").is_err());
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut gpx = Gpx::try_from_str(
+            r#"<gpx><metadata><keywords>bike</keywords></metadata><trk><name>Old</name><trkseg>
+            <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let cli = TestCli::try_parse_from([
+            "edit",
+            "ride.gpx",
+            "--set-name",
+            "Col du Galibier",
+            "--set-type",
+            "cycling",
+            "--add-keyword",
+            "alps",
+            "--set-time-offset",
+            "-2h",
+        ])
+        .unwrap();
+        apply(&mut gpx, &cli.edit);
+
+        let xml = gpx.to_xml();
+        assert!(xml.contains("<name>Col du Galibier</name><type>cycling</type>"));
+        assert!(xml.contains("<keywords>bike, alps</keywords>"));
+        assert!(xml.contains("2024-07-01T06:00:00Z"));
+        assert_eq!(
+            backup_path(Path::new("ride.gpx")),
+            Path::new("ride.gpx.bak")
+        );
+
+        // Sin ninguna edición clap rechaza la orden
+        assert!(TestCli::try_parse_from(["edit", "ride.gpx"]).is_err());
+    }
+}
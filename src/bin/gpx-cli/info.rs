@@ -0,0 +1,61 @@
+use crate::output::{self, FileReport, OutputFormat};
+use clap::Args;
+use gpx_extractor::Gpx;
+use std::path::PathBuf;
+
+/// Print a summary of one GPX file
+#[derive(Args)]
+pub struct InfoArgs {
+    /// GPX file to summarize
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Show detailed statistics
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Print plain `label: value` lines without emoji, box drawing or color (for screen readers)
+    #[arg(long)]
+    plain: bool,
+
+    /// Print statistics as a table, as JSON (with a per-track breakdown) or as CSV rows
+    #[arg(long, value_enum, default_value = "table")]
+    output: OutputFormat,
+}
+
+pub fn run(args: &InfoArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.plain {
+        colored::control::set_override(false);
+    }
+    let gpx = Gpx::from_file(&args.path)?;
+    if args.output.is_machine() {
+        let report = FileReport::from_gpx(&args.path, &gpx);
+        return output::print_reports(args.output, &[report], true);
+    }
+    crate::print_file(&args.path, &gpx, args.verbose, args.plain);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        info: InfoArgs,
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let cli = TestCli::try_parse_from(["info", "ride.gpx", "-v", "--plain"]).unwrap();
+        assert_eq!(cli.info.path, PathBuf::from("ride.gpx"));
+        assert!(cli.info.verbose && cli.info.plain);
+        assert_eq!(cli.info.output, OutputFormat::Table);
+
+        let cli = TestCli::try_parse_from(["info", "ride.gpx", "--output", "json"]).unwrap();
+        assert_eq!(cli.info.output, OutputFormat::Json);
+        assert!(TestCli::try_parse_from(["info"]).is_err());
+    }
+}
@@ -0,0 +1,528 @@
+mod analyze;
+mod convert;
+mod edit;
+mod info;
+mod merge;
+mod output;
+mod route;
+mod scan;
+mod serve;
+mod simplify;
+mod split;
+mod waypoint;
+
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use gpx_extractor::aggregate::{ActivityLog, PersonalRecord};
+use gpx_extractor::report::{plain_statistics, plain_summary};
+use gpx_extractor::{Goal, Goals, Gpx, GpxCollection, GpxStatistics, WaypointFilter};
+use output::{FileReport, OutputFormat};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(name = "gpx-cli")]
+#[command(about = "GPX file analyzer and processor", long_about = None)]
+#[command(version)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// GPX file or directory to process
+    #[arg(value_name = "PATH", required = true)]
+    path: Option<PathBuf>,
+
+    /// Show detailed statistics
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Sort GPX files by date
+    #[arg(short, long)]
+    sort: bool,
+
+    /// Keep only waypoints matching a filter (`sym=Summit`, `type=Geocache`, `name=<regex>`);
+    /// repeat to keep waypoints matching any of them
+    #[arg(long = "keep-waypoints", value_name = "FILTER")]
+    keep_waypoints: Vec<WaypointFilter>,
+
+    /// Check a goal against each file (`distance>=100km`, `duration<=4h`, `gain>=1500m`);
+    /// exits with a nonzero code if any goal fails
+    #[arg(long = "assert", value_name = "GOAL")]
+    assert: Vec<Goal>,
+
+    /// Summarize a directory through an index cache file, reparsing only changed files
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["recursive", "glob"])]
+    cache: Option<PathBuf>,
+
+    /// Also read the GPX files of every subdirectory
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Only read files whose path below the directory matches a pattern, e.g. `**/2024/*.gpx`
+    /// (`*` and `?` within a name, `**` for any number of directories)
+    #[arg(long, value_name = "PATTERN")]
+    glob: Option<String>,
+
+    /// Print plain `label: value` lines without emoji, box drawing or color (for screen readers)
+    #[arg(long)]
+    plain: bool,
+
+    /// Print statistics as a table, as JSON (with a per-track breakdown) or as CSV rows
+    #[arg(long, value_enum, default_value = "table")]
+    output: OutputFormat,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the statistics of one document as JSON (from a file or `--stdin`)
+    Analyze(analyze::AnalyzeArgs),
+    /// Convert a file to GeoJSON, KML or CSV
+    Convert(convert::ConvertArgs),
+    /// Edit the name, activity type, keywords or time offset of a file in place
+    Edit(edit::EditArgs),
+    /// Print a summary of one file
+    Info(info::InfoArgs),
+    /// Merge several files into one document with one track per activity
+    Merge(merge::MergeArgs),
+    /// Reverse a route or close it into a loop in place
+    Route(route::RouteArgs),
+    /// Browse a directory of activities through a local web server and REST API
+    Serve(serve::ServeArgs),
+    /// Reduce the number of points of a file within a distance tolerance
+    Simplify(simplify::SimplifyArgs),
+    /// Split a recording into several files at pauses, distances or days
+    Split(split::SplitArgs),
+    /// Add or remove waypoints of a file in place
+    Waypoint(waypoint::WaypointArgs),
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    match &cli.command {
+        Some(Command::Analyze(args)) => return analyze::run(args),
+        Some(Command::Convert(args)) => return convert::run(args),
+        Some(Command::Edit(args)) => return edit::run(args),
+        Some(Command::Info(args)) => return info::run(args),
+        Some(Command::Merge(args)) => return merge::run(args),
+        Some(Command::Route(args)) => return route::run(args),
+        Some(Command::Serve(args)) => return serve::run(args),
+        Some(Command::Simplify(args)) => return simplify::run(args),
+        Some(Command::Split(args)) => return split::run(args),
+        Some(Command::Waypoint(args)) => return waypoint::run(args),
+        None => {}
+    }
+    // clap exige PATH cuando no hay subcomando
+    let Some(path) = &cli.path else {
+        return Ok(());
+    };
+    if cli.plain {
+        colored::control::set_override(false);
+    }
+
+    let passed = if path.is_dir() {
+        match &cli.cache {
+            Some(cache) => process_cached_directory(path, cache, &cli)?,
+            None => process_directory(path, &cli)?,
+        }
+    } else {
+        process_file(path, &cli)?
+    };
+
+    if !passed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn process_file(path: &PathBuf, cli: &Cli) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut gpx = Gpx::from_file(path)?;
+    gpx.keep_waypoints(&cli.keep_waypoints);
+    if cli.output.is_machine() {
+        output::print_reports(cli.output, &[FileReport::from_gpx(path, &gpx)], true)?;
+    } else {
+        print_file(path, &gpx, cli.verbose, cli.plain);
+    }
+
+    Ok(check_goals(&gpx, cli))
+}
+
+/// Imprime el resumen de un fichero, decorado o en formato `--plain`
+fn print_file(path: &Path, gpx: &Gpx, verbose: bool, plain: bool) {
+    if plain {
+        println!("File: {}", path.display());
+        println!("{}", plain_summary(gpx, verbose));
+    } else {
+        println!("{}", format!("📄 {}", path.display()).cyan());
+        print_gpx_info(gpx, verbose);
+    }
+}
+
+/// Escribe el resultado en `out`, o en la salida estándar si no se indica fichero
+fn write_output(out: Option<&Path>, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(path) = out {
+        std::fs::write(path, text)?;
+        // Por stderr, para que la salida estándar quede limpia
+        eprintln!("{}", format!("✅ Saved {}", path.display()).green());
+        return Ok(());
+    }
+    let mut stdout = std::io::stdout().lock();
+    let written = stdout.write_all(text.as_bytes()).and_then(|()| {
+        if text.ends_with('\n') {
+            Ok(())
+        } else {
+            stdout.write_all(b"\n")
+        }
+    });
+    match written {
+        // Cortar la salida con `head` no es un error
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Imprime un mensaje, con su versión decorada o la versión `--plain`; con
+/// `--output json|csv` no imprime nada, para no mezclarlo con los datos
+fn say(cli: &Cli, fancy: impl std::fmt::Display, plain: impl std::fmt::Display) {
+    if cli.output.is_machine() {
+        return;
+    }
+    if cli.plain {
+        println!("{plain}");
+    } else {
+        println!("{fancy}");
+    }
+}
+
+/// Avisa de un fichero que no se pudo cargar
+fn warn_failed(cli: &Cli, file: &std::path::Path, error: impl std::fmt::Display) {
+    if cli.plain {
+        eprintln!("Error loading {}: {}", file.display(), error);
+    } else {
+        eprintln!(
+            "{}",
+            format!("⚠️  Error loading {}: {}", file.display(), error).yellow()
+        );
+    }
+}
+
+fn process_directory(path: &Path, cli: &Cli) -> Result<bool, Box<dyn std::error::Error>> {
+    say(
+        cli,
+        format!("📍 Reading GPX files from directory: {}", path.display()).cyan(),
+        format!("Directory: {}", path.display()),
+    );
+
+    let files = scan::list_gpx_files(path, cli.recursive, cli.glob.as_deref())?;
+
+    let found = format!("Found {} GPX files", files.len());
+    say(cli, found.green(), &found);
+
+    // Load all GPX files
+    let mut gpx_items: Vec<(&PathBuf, Gpx)> = files
+        .iter()
+        .zip(scan::load_all(&files))
+        .filter_map(|(file, loaded)| match loaded {
+            Ok(mut gpx) => {
+                gpx.keep_waypoints(&cli.keep_waypoints);
+                Some((file, gpx))
+            }
+            Err(e) => {
+                warn_failed(cli, file, e);
+                None
+            }
+        })
+        .collect();
+
+    // Sort by date if requested
+    if cli.sort {
+        gpx_items.sort_by(|(_, a), (_, b)| match (a.date(), b.date()) {
+            (Some(date_a), Some(date_b)) => date_a.cmp(date_b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+    }
+
+    let loaded = format!("Successfully loaded {} GPX files", gpx_items.len());
+    say(cli, loaded.green(), &loaded);
+
+    if cli.output.is_machine() {
+        let reports: Vec<FileReport> = gpx_items
+            .iter()
+            .map(|(file, gpx)| FileReport::from_gpx(file, gpx))
+            .collect();
+        output::print_reports(cli.output, &reports, false)?;
+    }
+
+    // Print info for each GPX file
+    let mut passed = true;
+    gpx_items.iter().enumerate().for_each(|(i, (_, gpx))| {
+        if cli.output.is_machine() {
+            passed &= check_goals(gpx, cli);
+            return;
+        }
+        if cli.plain {
+            println!("\nFile {}:", i + 1);
+            println!("{}", plain_summary(gpx, cli.verbose));
+        } else {
+            println!("\n{}", format!("═══ GPX File #{} ═══", i + 1).bold());
+            print_gpx_info(gpx, cli.verbose);
+        }
+        passed &= check_goals(gpx, cli);
+    });
+
+    let log = ActivityLog::from_gpx_iter(gpx_items.iter().map(|(_, gpx)| gpx));
+    print_summary(cli, &log);
+
+    Ok(passed)
+}
+
+/// Imprime los totales de un directorio: distancia, tiempo, desglose mensual y récords
+fn print_summary(cli: &Cli, log: &ActivityLog) {
+    say(
+        cli,
+        format!(
+            "\n{}",
+            format!(
+                "📏 Total distance across all files: {:.2} km",
+                log.totals.distance_km
+            )
+            .green()
+            .bold()
+        ),
+        format!("\nTotal distance: {:.2} km", log.totals.distance_km),
+    );
+    let time = scan::hms(log.totals.duration_seconds);
+    say(
+        cli,
+        format!("⏱️  Total time: {time}").green(),
+        format!("Total time: {time}"),
+    );
+    if !log.monthly.is_empty() {
+        say(cli, "📅 Per month:".bold(), "Per month:");
+    }
+    for ((year, month), totals) in &log.monthly {
+        let line = format!(
+            "{year}-{month:02}: {} activities, {:.2} km, {}, {:.0} m gain",
+            totals.activities,
+            totals.distance_km,
+            scan::hms(totals.duration_seconds),
+            totals.elevation_gain_m
+        );
+        say(cli, format!("  {line}"), line);
+    }
+
+    let records = &log.records;
+    if let Some(record) = &records.longest_distance {
+        print_record(
+            cli,
+            "Longest distance",
+            record,
+            format!("{:.2} km", record.value),
+        );
+    }
+    if let Some(record) = &records.longest_duration {
+        #[allow(clippy::cast_possible_truncation)]
+        let time = scan::hms(record.value as i64);
+        print_record(cli, "Longest time", record, time);
+    }
+    if let Some(record) = &records.biggest_climb {
+        print_record(
+            cli,
+            "Biggest climb",
+            record,
+            format!("{:.0} m", record.value),
+        );
+    }
+}
+
+/// Imprime un récord personal con su valor ya formateado y la actividad que lo tiene
+fn print_record(cli: &Cli, label: &str, record: &PersonalRecord, value: String) {
+    let name = record.name.as_deref().unwrap_or("unnamed");
+    let line = match record.start {
+        Some(start) => format!("{label}: {value} ({name}, {})", start.format("%Y-%m-%d")),
+        None => format!("{label}: {value} ({name})"),
+    };
+    say(cli, format!("🏆 {line}"), line);
+}
+
+fn process_cached_directory(
+    path: &PathBuf,
+    cache: &PathBuf,
+    cli: &Cli,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    say(
+        cli,
+        format!("📍 Indexing directory: {}", path.display()).cyan(),
+        format!("Directory: {}", path.display()),
+    );
+
+    let mut index = GpxCollection::open_cached(path, cache)?;
+    for (file, error) in &index.failures {
+        warn_failed(cli, file, error);
+    }
+    let indexed = format!(
+        "Indexed {} files ({} parsed, {} from cache)",
+        index.len(),
+        index.parsed,
+        index.len() - index.parsed
+    );
+    say(cli, indexed.green(), &indexed);
+
+    if cli.sort {
+        index
+            .entries
+            .sort_by_key(|entry| (entry.start_time.is_none(), entry.start_time));
+    }
+
+    if cli.output.is_machine() {
+        output::print_reports(cli.output, &output::index_reports(&index), false)?;
+    }
+
+    let mut passed = true;
+    for entry in &index.entries {
+        let stats = &entry.statistics;
+        if cli.output.is_machine() {
+            passed &= check_statistics(stats, cli);
+            continue;
+        }
+        if cli.plain {
+            println!("\nFile: {}", entry.file.display());
+            println!("{}", plain_statistics(stats));
+        } else {
+            println!("\n{}", format!("═══ {} ═══", entry.file.display()).bold());
+            println!(
+                "  📊 Tracks: {} | 📍 Waypoints: {} | 🔢 Points: {} | 📏 Distance: {:.2} km",
+                stats.total_tracks,
+                stats.total_waypoints,
+                stats.total_points,
+                stats.total_distance_km
+            );
+        }
+        passed &= check_statistics(stats, cli);
+    }
+
+    say(
+        cli,
+        format!(
+            "\n{}",
+            format!(
+                "📏 Total distance across all files: {:.2} km",
+                index.total_distance_km()
+            )
+            .green()
+            .bold()
+        ),
+        format!("\nTotal distance: {:.2} km", index.total_distance_km()),
+    );
+
+    Ok(passed)
+}
+
+fn check_goals(gpx: &Gpx, cli: &Cli) -> bool {
+    if cli.assert.is_empty() {
+        return true;
+    }
+    check_statistics(&gpx.statistics(), cli)
+}
+
+fn check_statistics(stats: &GpxStatistics, cli: &Cli) -> bool {
+    if cli.assert.is_empty() {
+        return true;
+    }
+
+    let goals: Goals = cli.assert.iter().copied().collect();
+    let report = stats.against(&goals);
+    for result in &report.results {
+        if cli.output.is_machine() {
+            // Por stderr, para no romper el JSON o CSV de la salida estándar
+            eprintln!("Goal: {result}");
+        } else if cli.plain {
+            println!("Goal: {result}");
+        } else if result.passed {
+            println!("  {}", format!("✅ {result}").green());
+        } else {
+            println!("  {}", format!("❌ {result}").red());
+        }
+    }
+
+    report.passed()
+}
+
+fn print_gpx_info(gpx: &Gpx, verbose: bool) {
+    if !verbose {
+        // Compact format
+        println!(
+            "  📊 Tracks: {} | 📍 Waypoints: {} | 🔢 Points: {} | 📏 Distance: {:.2} km",
+            gpx.tracks.len(),
+            gpx.waypoints.len(),
+            gpx.total_points(),
+            gpx.total_distance_km()
+        );
+        return;
+    }
+
+    // Detailed format
+    println!("\n🗂️  GPX Analysis:");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    if let Some(date) = gpx.date() {
+        println!("{}", format!("📅 Date: {}", date).cyan());
+    }
+
+    println!("📊 Tracks: {}", gpx.tracks.len());
+    println!("📍 Waypoints: {}", gpx.waypoints.len());
+    println!("🔢 Total points: {}", gpx.total_points());
+
+    if !gpx.tracks.is_empty() {
+        println!("\n{}", "Track Details:".bold());
+        for (i, track) in gpx.tracks.iter().enumerate() {
+            println!(
+                "  Track #{}: {} ({} segments, {} points)",
+                i + 1,
+                track.name.as_deref().unwrap_or("Unnamed"),
+                track.segments.len(),
+                track.total_points()
+            );
+        }
+    }
+
+    if let Some(formatted) = gpx.total_duration_formatted() {
+        println!("⏱️  Total duration: {}", formatted);
+    } else {
+        println!("⏱️  No time information available");
+    }
+
+    if let Some(avg_speed) = gpx.average_speed_kmh() {
+        println!("🚴 Average speed: {:.2} km/h", avg_speed);
+    } else {
+        println!("🚴 Cannot calculate average speed");
+    }
+
+    let distance = gpx.total_distance_km();
+
+    if distance > 0.0 {
+        println!(
+            "{}",
+            format!("\n📏 Total distance: {:.2} km", distance).green()
+        );
+    }
+
+    if let Some((min_ele, max_ele)) = gpx.elevation_range() {
+        println!("⛰️  Elevation range: {:.1}m - {:.1}m", min_ele, max_ele);
+    }
+
+    if let Some(gain) = gpx.total_elevation_gain() {
+        println!("📈 Total elevation gain: {:.1}m", gain);
+    }
+
+    if let Some(loss) = gpx.total_elevation_loss() {
+        println!("📉 Total elevation loss: {:.1}m", loss);
+    }
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", "✅ GPX file processed successfully!".green());
+}
@@ -0,0 +1,84 @@
+use clap::Args;
+use gpx_extractor::{Gpx, GpxCollection, MergeOptions, WriteOptions};
+use std::path::PathBuf;
+
+/// Merge several GPX files into one document with one track per activity
+#[derive(Args)]
+pub struct MergeArgs {
+    /// GPX files to merge
+    #[arg(value_name = "FILE", required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Name of the merged document
+    #[arg(long, value_name = "NAME")]
+    name: Option<String>,
+
+    /// Keep activities whose points repeat an earlier file
+    #[arg(long)]
+    keep_duplicates: bool,
+
+    /// Leave the waypoints of the files out
+    #[arg(long)]
+    no_waypoints: bool,
+
+    /// Write the result to this file instead of standard output
+    #[arg(short, long, value_name = "FILE")]
+    out: Option<PathBuf>,
+}
+
+impl MergeArgs {
+    /// Opciones de fusión equivalentes a los flags
+    fn options(&self) -> MergeOptions {
+        MergeOptions {
+            name: self.name.clone(),
+            deduplicate: !self.keep_duplicates,
+            include_waypoints: !self.no_waypoints,
+            ..MergeOptions::default()
+        }
+    }
+}
+
+pub fn run(args: &MergeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut collection = GpxCollection::new();
+    for path in &args.paths {
+        let gpx =
+            Gpx::from_file(path).map_err(|e| format!("Error loading {}: {}", path.display(), e))?;
+        collection.push(path.clone(), gpx);
+    }
+    let merged = collection.into_single_gpx(&args.options());
+    let xml = merged.to_xml_with(&WriteOptions::pretty())?;
+    crate::write_output(args.out.as_deref(), &xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        merge: MergeArgs,
+    }
+
+    #[test]
+    fn test_parse_merge() {
+        let cli = TestCli::try_parse_from([
+            "merge",
+            "a.gpx",
+            "b.gpx",
+            "--name",
+            "2024",
+            "--no-waypoints",
+            "-o",
+            "all.gpx",
+        ])
+        .unwrap();
+        assert_eq!(cli.merge.paths.len(), 2);
+        assert_eq!(cli.merge.out, Some(PathBuf::from("all.gpx")));
+        let options = cli.merge.options();
+        assert_eq!(options.name.as_deref(), Some("2024"));
+        assert!(options.deduplicate && !options.include_waypoints);
+        assert!(TestCli::try_parse_from(["merge"]).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use clap::ValueEnum;
+use gpx_extractor::{CollectionIndex, Gpx, GpxStatistics, IndexEntry};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// How statistics are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable summary
+    #[default]
+    Table,
+    /// JSON for jq and other tools (an array when reading a directory)
+    Json,
+    /// One CSV row per file, for spreadsheets
+    Csv,
+}
+
+impl OutputFormat {
+    /// Verifica si la salida es para otro programa y no para una persona
+    pub fn is_machine(self) -> bool {
+        self != Self::Table
+    }
+}
+
+/// Statistics of one file, as printed by `--output json|csv`
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    file: String,
+    name: Option<String>,
+    date: Option<String>,
+    statistics: GpxStatistics,
+    /// Desglose por track; el índice en caché no lo guarda
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tracks: Option<Vec<TrackReport>>,
+}
+
+#[derive(Debug, Serialize)]
+struct TrackReport {
+    name: String,
+    statistics: GpxStatistics,
+}
+
+const CSV_HEADER: &str = "file,name,date,tracks,waypoints,segments,points,distance_km,\
+duration_seconds,average_speed_kmh,elevation_gain_m,elevation_loss_m,min_elevation_m,max_elevation_m";
+
+impl FileReport {
+    /// Informe de un documento cargado, con el desglose por track
+    pub fn from_gpx(path: &Path, gpx: &Gpx) -> Self {
+        let tracks = gpx
+            .statistics_per_track()
+            .into_iter()
+            .map(|(name, statistics)| TrackReport { name, statistics })
+            .collect();
+        Self {
+            file: path.display().to_string(),
+            name: gpx.metadata.as_ref().and_then(|m| m.name.clone()),
+            date: gpx.date().map(str::to_string),
+            statistics: gpx.statistics(),
+            tracks: Some(tracks),
+        }
+    }
+
+    /// Informe de una entrada del índice en caché, sin desglose por track
+    pub fn from_index_entry(entry: &IndexEntry) -> Self {
+        Self {
+            file: entry.file.display().to_string(),
+            name: entry.name.clone(),
+            date: entry.date.clone(),
+            statistics: entry.statistics.clone(),
+            tracks: None,
+        }
+    }
+
+    /// Fila CSV con las mismas columnas que `CSV_HEADER`
+    fn csv_row(&self) -> String {
+        let stats = &self.statistics;
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        let (min, max) = stats.elevation_range.unzip();
+        let mut row = format!(
+            "{},{},{}",
+            csv_field(&self.file),
+            csv_field(self.name.as_deref().unwrap_or_default()),
+            csv_field(self.date.as_deref().unwrap_or_default())
+        );
+        let _ = write!(
+            row,
+            ",{},{},{},{},{},{},{},{},{},{},{}",
+            stats.total_tracks,
+            stats.total_waypoints,
+            stats.total_segments,
+            stats.total_points,
+            stats.total_distance_km,
+            stats
+                .duration_seconds
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            optional(stats.average_speed_kmh),
+            optional(stats.elevation_gain),
+            optional(stats.elevation_loss),
+            optional(min),
+            optional(max)
+        );
+        row
+    }
+}
+
+/// Entrecomilla un campo CSV si contiene separadores, comillas o saltos de línea
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Informes de todas las entradas de un índice
+pub fn index_reports(index: &CollectionIndex) -> Vec<FileReport> {
+    index
+        .entries
+        .iter()
+        .map(FileReport::from_index_entry)
+        .collect()
+}
+
+/// Imprime los informes en JSON o CSV; un único fichero sale como objeto JSON
+pub fn print_reports(
+    format: OutputFormat,
+    reports: &[FileReport],
+    single: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let text = match format {
+        OutputFormat::Json if single && reports.len() == 1 => {
+            serde_json::to_string_pretty(&reports[0])?
+        }
+        OutputFormat::Json => serde_json::to_string_pretty(reports)?,
+        OutputFormat::Csv => {
+            let mut csv = format!("{CSV_HEADER}\n");
+            for report in reports {
+                csv.push_str(&report.csv_row());
+                csv.push('\n');
+            }
+            csv
+        }
+        OutputFormat::Table => return Ok(()),
+    };
+    crate::write_output(None, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> FileReport {
+        let gpx = Gpx::try_from_str(
+            r#"<gpx><metadata><name>Tour, day 1</name></metadata><trk><name>Day 1</name><trkseg>
+            <trkpt lat="40.0" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+            <trkpt lat="40.1" lon="-3.0"><ele>650</ele><time>2024-07-01T09:00:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        FileReport::from_gpx(Path::new("tour.gpx"), &gpx)
+    }
+
+    #[test]
+    fn test_json_report_has_track_breakdown() {
+        let json = serde_json::to_value(sample()).unwrap();
+        assert_eq!(json["file"], "tour.gpx");
+        assert_eq!(json["statistics"]["total_points"], 2);
+        assert_eq!(json["statistics"]["duration_seconds"], 3600);
+        assert_eq!(json["tracks"][0]["name"], "Day 1");
+        assert_eq!(json["tracks"][0]["statistics"]["elevation_gain"], 50.0);
+    }
+
+    #[test]
+    fn test_csv_row() {
+        let row = sample().csv_row();
+        let columns = CSV_HEADER.split(',').count();
+        assert!(row.starts_with("tour.gpx,\"Tour, day 1\",,1,0,1,2,"));
+        assert!(row.ends_with(",50,0,600,650"));
+        // La coma del nombre va entrecomillada
+        assert_eq!(
+            row.replace("\"Tour, day 1\"", "x").split(',').count(),
+            columns
+        );
+    }
+}
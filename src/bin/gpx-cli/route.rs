@@ -0,0 +1,142 @@
+use crate::edit::save_in_place;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use gpx_extractor::{Gpx, LoopClosure};
+use std::path::PathBuf;
+
+/// Reverse a route or close it into a loop in place, keeping a backup
+#[derive(Args)]
+pub struct RouteArgs {
+    #[command(subcommand)]
+    action: RouteAction,
+}
+
+#[derive(Subcommand)]
+enum RouteAction {
+    /// Reverse the direction of travel
+    Reverse(ReverseArgs),
+    /// Join the end back to the start when they are close enough
+    CloseLoop(CloseLoopArgs),
+}
+
+#[derive(Args)]
+struct ReverseArgs {
+    /// GPX file to edit
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Do not keep a copy of the original file as `<FILE>.bak`
+    #[arg(long)]
+    no_backup: bool,
+}
+
+#[derive(Args)]
+struct CloseLoopArgs {
+    /// GPX file to edit
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Largest gap to close, e.g. `200m` or `1.5km` (plain numbers are meters)
+    #[arg(long, value_name = "DISTANCE", value_parser = parse_distance)]
+    max_gap: f64,
+
+    /// Move the last point onto the start instead of adding a straight stretch
+    #[arg(long)]
+    snap: bool,
+
+    /// Do not keep a copy of the original file as `<FILE>.bak`
+    #[arg(long)]
+    no_backup: bool,
+}
+
+/// Parsea una distancia en metros o kilómetros (`200m`, `1.5km`, `300`) a metros
+pub(crate) fn parse_distance(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    let (number, factor) = if let Some(km) = text.strip_suffix("km") {
+        (km, 1000.0)
+    } else {
+        (text.strip_suffix('m').unwrap_or(text), 1.0)
+    };
+    match number.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(value * factor),
+        _ => Err(format!(
+            "invalid distance '{text}' (expected e.g. 200m or 1.5km)"
+        )),
+    }
+}
+
+pub fn run(args: &RouteArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match &args.action {
+        RouteAction::Reverse(args) => {
+            let mut gpx = Gpx::from_file(&args.path)?;
+            gpx.reverse();
+            save_in_place(&gpx, &args.path, !args.no_backup)
+        }
+        RouteAction::CloseLoop(args) => {
+            let mut gpx = Gpx::from_file(&args.path)?;
+            let closure = if args.snap {
+                LoopClosure::Snap
+            } else {
+                LoopClosure::Straight
+            };
+            let Some(gap) = gpx.loop_gap_m() else {
+                println!("{}", "No track points; file left unchanged".yellow());
+                return Ok(());
+            };
+            if gpx.close_loop(args.max_gap, closure).is_none() {
+                let reason = if gap > args.max_gap {
+                    format!("End is {gap:.0} m from the start, beyond --max-gap")
+                } else {
+                    "Route already ends at its start".to_string()
+                };
+                println!("{}", format!("{reason}; file left unchanged").yellow());
+                return Ok(());
+            }
+            println!("Closed a {gap:.0} m gap");
+            save_in_place(&gpx, &args.path, !args.no_backup)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        route: RouteArgs,
+    }
+
+    #[test]
+    fn test_parse_distance() {
+        assert_eq!(parse_distance("200m"), Ok(200.0));
+        assert_eq!(parse_distance("1.5km"), Ok(1500.0));
+        assert_eq!(parse_distance("300"), Ok(300.0));
+        assert!(parse_distance("-5m").is_err());
+        assert!(parse_distance("far").is_err());
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        let cli = TestCli::try_parse_from([
+            "route",
+            "close-loop",
+            "ride.gpx",
+            "--max-gap",
+            "200m",
+            "--snap",
+        ])
+        .unwrap();
+        let RouteAction::CloseLoop(args) = cli.route.action else {
+            panic!("expected close-loop");
+        };
+        assert_eq!(args.max_gap, 200.0);
+        assert!(args.snap);
+
+        let cli = TestCli::try_parse_from(["route", "reverse", "ride.gpx", "--no-backup"]).unwrap();
+        assert!(matches!(cli.route.action, RouteAction::Reverse(args) if args.no_backup));
+        assert!(TestCli::try_parse_from(["route", "close-loop", "ride.gpx"]).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use gpx_extractor::error::GpxError;
+use gpx_extractor::Gpx;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Ficheros `.gpx` de `dir`, ordenados por ruta
+///
+/// Con `recursive` baja por los subdirectorios; con `glob` se recorre todo el
+/// árbol y solo se quedan las rutas relativas a `dir` que encajan con el patrón.
+pub fn list_gpx_files(
+    dir: &Path,
+    recursive: bool,
+    glob: Option<&str>,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    walk(dir, recursive || glob.is_some(), &mut files)?;
+    files.retain(|file| match glob {
+        Some(pattern) => file
+            .strip_prefix(dir)
+            .is_ok_and(|relative| glob_match(pattern, relative)),
+        None => file.extension().is_some_and(|ext| ext == "gpx"),
+    });
+    files.sort();
+    Ok(files)
+}
+
+fn walk(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // `file_type` no sigue enlaces simbólicos, así que no hay ciclos
+        if entry.file_type()?.is_dir() {
+            if recursive {
+                walk(&path, recursive, files)?;
+            }
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compara una ruta relativa con un patrón glob: `*` y `?` dentro de un
+/// componente, y `**` para cualquier número de directorios
+pub fn glob_match(pattern: &str, path: &Path) -> bool {
+    let pattern: Vec<&str> = pattern
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    let path: Vec<String> = path
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    match_components(&pattern, &path)
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_components(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(name, others)| {
+            match_name(first.as_bytes(), name.as_bytes()) && match_components(rest, others)
+        }),
+    }
+}
+
+/// Comodines `*` y `?` sobre un único nombre
+fn match_name(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| match_name(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && match_name(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_name(rest, &name[1..]),
+    }
+}
+
+/// Carga los ficheros en paralelo, en el mismo orden que `files`; si la salida
+/// de errores es una terminal, dibuja una barra de progreso
+pub fn load_all(files: &[PathBuf]) -> Vec<Result<Gpx, GpxError>> {
+    let progress = std::io::stderr().is_terminal() && !files.is_empty();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<Gpx, GpxError>>>> =
+        Mutex::new(files.iter().map(|_| None).collect());
+    let workers = std::thread::available_parallelism()
+        .map_or(1, std::num::NonZeroUsize::get)
+        .min(files.len())
+        .max(1);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(file) = files.get(index) else {
+                    break;
+                };
+                let result = Gpx::from_file(file);
+                results.lock().expect("loader thread panicked")[index] = Some(result);
+                let count = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if progress {
+                    draw_progress(count, files.len());
+                }
+            });
+        }
+    });
+    if progress {
+        eprintln!();
+    }
+
+    results
+        .into_inner()
+        .expect("loader thread panicked")
+        .into_iter()
+        .map(|result| result.expect("every file is loaded once"))
+        .collect()
+}
+
+/// Redibuja la barra `[#####     ] 12/40` en la misma línea de stderr
+fn draw_progress(count: usize, total: usize) {
+    const WIDTH: usize = 30;
+    let filled = count * WIDTH / total;
+    let mut stderr = std::io::stderr().lock();
+    let _ = write!(
+        stderr,
+        "\rLoading [{}{}] {count}/{total}",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled)
+    );
+    let _ = stderr.flush();
+}
+
+/// Duración como `HH:MM:SS`, con tantas horas como haga falta
+pub fn hms(seconds: i64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpx_extractor::aggregate::ActivityLog;
+
+    #[test]
+    fn test_glob_match() {
+        let path = Path::new("rides/2024/morning.gpx");
+        assert!(glob_match("**/2024/*.gpx", path));
+        assert!(glob_match("**/*.gpx", path));
+        assert!(glob_match("rides/**/m?rning.gpx", path));
+        assert!(glob_match("./rides/2024/*", path));
+        assert!(!glob_match("**/2023/*.gpx", path));
+        assert!(!glob_match("*.gpx", path));
+        assert!(glob_match("*.gpx", Path::new("morning.gpx")));
+    }
+
+    #[test]
+    fn test_scan_and_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("2024").join("07");
+        std::fs::create_dir_all(&nested).unwrap();
+        let ride = r#"<gpx><trk><trkseg>
+            <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+            <trkpt lat="40.1" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt>
+        </trkseg></trk></gpx>"#;
+        std::fs::write(dir.path().join("top.gpx"), ride).unwrap();
+        std::fs::write(nested.join("a.gpx"), ride).unwrap();
+        std::fs::write(nested.join("notes.txt"), "").unwrap();
+
+        assert_eq!(list_gpx_files(dir.path(), false, None).unwrap().len(), 1);
+        assert_eq!(list_gpx_files(dir.path(), true, None).unwrap().len(), 2);
+        let globbed = list_gpx_files(dir.path(), false, Some("**/2024/**/*.gpx")).unwrap();
+        assert_eq!(globbed, vec![nested.join("a.gpx")]);
+
+        let files = list_gpx_files(dir.path(), true, None).unwrap();
+        let documents: Vec<Gpx> = load_all(&files).into_iter().map(Result::unwrap).collect();
+        let log = ActivityLog::from_gpx_iter(&documents);
+        assert_eq!(log.totals.activities, 2);
+        assert_eq!(log.monthly[&(2024, 7)].activities, 2);
+        assert_eq!(hms(log.totals.duration_seconds), "02:00:00");
+    }
+}
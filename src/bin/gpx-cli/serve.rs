@@ -0,0 +1,363 @@
+use crate::analyze::statistics_json;
+use clap::Args;
+use colored::Colorize;
+use gpx_extractor::GpxCollection;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Tiempo máximo esperando a que el cliente envíe la petición
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Bytes máximos de la línea de petición más las cabeceras
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+/// Serve a directory of activities over HTTP with a small web viewer
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Directory with the GPX files to serve
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    port: u16,
+
+    /// Address to bind to; use 0.0.0.0 to allow other machines
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    bind: IpAddr,
+}
+
+/// Respuesta HTTP ya resuelta, independiente del socket
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: String) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+
+    fn json(value: &serde_json::Value) -> Self {
+        Self::ok("application/json", value.to_string())
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            431 => "Request Header Fields Too Large",
+            500 => "Internal Server Error",
+            _ => "Unknown",
+        }
+    }
+}
+
+pub fn run(args: &ServeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let collection = GpxCollection::from_dir(&args.dir)?;
+    for (file, error) in &collection.failures {
+        eprintln!(
+            "{}",
+            format!("⚠️  Error loading {}: {}", file.display(), error).yellow()
+        );
+    }
+
+    let listener = TcpListener::bind((args.bind, args.port))?;
+    println!(
+        "{}",
+        format!(
+            "🌍 Serving {} files from {} at http://{}",
+            collection.len(),
+            args.dir.display(),
+            listener.local_addr()?
+        )
+        .green()
+    );
+
+    // Un hilo por conexión para que un cliente lento no bloquee al resto
+    let collection = Arc::new(collection);
+    for stream in listener.incoming() {
+        let collection = Arc::clone(&collection);
+        let result = stream.map(|stream| {
+            thread::spawn(move || {
+                if let Err(e) = handle(stream, &collection) {
+                    eprintln!("{}", format!("⚠️  Connection error: {e}").yellow());
+                }
+            })
+        });
+        if let Err(e) = result {
+            eprintln!("{}", format!("⚠️  Connection error: {e}").yellow());
+        }
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, collection: &GpxCollection) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Las cabeceras no se usan, pero hay que consumirlas antes de responder;
+    // una línea sin salto final es que el cliente cortó o se pasó del límite
+    let mut complete = request_line.ends_with('\n');
+    let mut header = String::new();
+    while complete {
+        header.clear();
+        reader.read_line(&mut header)?;
+        complete = header.ends_with('\n');
+        if header.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        _ if !complete && reader.get_ref().limit() == 0 => {
+            Response::error(431, "request headers are too large")
+        }
+        (Some(method), Some(target)) if complete => route(collection, method, target),
+        _ => Response::error(400, "malformed request"),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+fn route(collection: &GpxCollection, method: &str, target: &str) -> Response {
+    if method != "GET" {
+        return Response::error(405, "only GET is supported");
+    }
+
+    let path = target.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        [] => Response::ok("text/html", VIEWER.to_string()),
+        ["api", "files"] => Response::json(&list_files(collection)),
+        ["api", "files", id, resource] => {
+            let Some(entry) = id
+                .parse::<usize>()
+                .ok()
+                .and_then(|id| collection.entries.get(id))
+            else {
+                return Response::error(404, "unknown file");
+            };
+            let gpx = &entry.gpx;
+            match *resource {
+                "statistics" => Response::json(&statistics_json(&gpx.statistics())),
+                "geojson" => Response::ok("application/geo+json", gpx.to_geojson()),
+                "profile" => Response::json(&json!(gpx.elevation_profile())),
+                _ => Response::error(404, "unknown resource"),
+            }
+        }
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn list_files(collection: &GpxCollection) -> serde_json::Value {
+    let files: Vec<_> = collection
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(id, entry)| {
+            json!({
+                "id": id,
+                "file": entry.path.file_name().map(|name| name.to_string_lossy()),
+                "name": entry.gpx.tracks.iter().find_map(|track| track.name.as_deref()),
+                "date": entry.gpx.date(),
+                "distance_km": entry.gpx.total_distance_km(),
+            })
+        })
+        .collect();
+    json!(files)
+}
+
+const VIEWER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>gpx-cli</title>
+<style>
+  body { margin: 0; display: flex; height: 100vh; font: 14px sans-serif; }
+  #files { width: 280px; overflow-y: auto; border-right: 1px solid #ccc; }
+  #files div { padding: 8px 12px; cursor: pointer; border-bottom: 1px solid #eee; }
+  #files div:hover, #files .active { background: #e8f0fe; }
+  main { flex: 1; display: flex; flex-direction: column; padding: 12px; gap: 12px; }
+  canvas { width: 100%; border: 1px solid #ccc; }
+  #map { flex: 3; } #profile { flex: 1; }
+  small { color: #666; }
+</style>
+</head>
+<body>
+<nav id="files"></nav>
+<main>
+  <div id="stats">Select an activity</div>
+  <canvas id="map"></canvas>
+  <canvas id="profile"></canvas>
+</main>
+<script>
+const $ = (id) => document.getElementById(id);
+
+function fit(canvas) {
+  canvas.width = canvas.clientWidth;
+  canvas.height = canvas.clientHeight;
+  const ctx = canvas.getContext('2d');
+  ctx.clearRect(0, 0, canvas.width, canvas.height);
+  return ctx;
+}
+
+// uniform: misma escala en ambos ejes (mapa); si no, cada eje ocupa todo el lienzo (perfil)
+function drawLines(canvas, lines, color, uniform) {
+  const ctx = fit(canvas), pad = 10;
+  const all = lines.flat();
+  if (!all.length) return;
+  const xs = all.map(p => p[0]), ys = all.map(p => p[1]);
+  const [x0, x1, y0, y1] = [Math.min(...xs), Math.max(...xs), Math.min(...ys), Math.max(...ys)];
+  let sx = (canvas.width - 2 * pad) / ((x1 - x0) || 1), sy = (canvas.height - 2 * pad) / ((y1 - y0) || 1);
+  if (uniform) sx = sy = Math.min(sx, sy);
+  ctx.strokeStyle = color;
+  ctx.lineWidth = 2;
+  for (const line of lines) {
+    ctx.beginPath();
+    line.forEach(([x, y], i) => {
+      const px = pad + (x - x0) * sx, py = canvas.height - pad - (y - y0) * sy;
+      i ? ctx.lineTo(px, py) : ctx.moveTo(px, py);
+    });
+    ctx.stroke();
+  }
+}
+
+async function show(id, item) {
+  document.querySelectorAll('#files div').forEach(el => el.classList.remove('active'));
+  item.classList.add('active');
+  const get = (resource) => fetch(`/api/files/${id}/${resource}`).then(r => r.json());
+  const [stats, geojson, profile] = await Promise.all([get('statistics'), get('geojson'), get('profile')]);
+
+  $('stats').textContent = `${stats.total_distance_km.toFixed(2)} km` +
+    (stats.duration ? ` · ${stats.duration}` : '') +
+    (stats.elevation_gain != null ? ` · +${stats.elevation_gain.toFixed(0)} m` : '');
+
+  const lines = geojson.features
+    .filter(f => f.geometry.type === 'MultiLineString')
+    .flatMap(f => f.geometry.coordinates)
+    .map(line => line.map(([lon, lat]) => [lon * Math.cos(lat * Math.PI / 180), lat]));
+  drawLines($('map'), lines, '#d32f2f', true);
+  drawLines($('profile'), [profile], '#1565c0', false);
+}
+
+fetch('/api/files').then(r => r.json()).then(files => {
+  for (const file of files) {
+    const item = document.createElement('div');
+    item.innerHTML = `<b></b><br><small></small>`;
+    item.querySelector('b').textContent = file.name || file.file;
+    item.querySelector('small').textContent = `${file.date || ''} ${file.distance_km.toFixed(1)} km`;
+    item.onclick = () => show(file.id, item);
+    $('files').appendChild(item);
+  }
+});
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpx_extractor::GpxBuilder;
+
+    fn collection() -> GpxCollection {
+        let gpx = GpxBuilder::new()
+            .track(|t| {
+                t.name("Ride").segment(|s| {
+                    s.point_with_elevation(40.0, -3.0, 600.0)
+                        .point_with_elevation(40.1, -3.0, 650.0)
+                })
+            })
+            .build()
+            .unwrap();
+        let mut collection = GpxCollection::new();
+        collection.push(PathBuf::from("rides/ride.gpx"), gpx);
+        collection
+    }
+
+    #[test]
+    fn test_route_api() {
+        let collection = collection();
+
+        let files: serde_json::Value =
+            serde_json::from_str(&route(&collection, "GET", "/api/files").body).unwrap();
+        assert_eq!(files[0]["file"], "ride.gpx");
+        assert_eq!(files[0]["name"], "Ride");
+
+        let stats = route(&collection, "GET", "/api/files/0/statistics");
+        assert_eq!(stats.status, 200);
+        assert!(stats.body.contains("\"elevation_gain\":50.0"));
+
+        let geojson = route(&collection, "GET", "/api/files/0/geojson?x=1");
+        assert_eq!(geojson.content_type, "application/geo+json");
+        assert!(geojson.body.contains("MultiLineString"));
+
+        let profile: Vec<(f64, f64)> =
+            serde_json::from_str(&route(&collection, "GET", "/api/files/0/profile").body).unwrap();
+        assert_eq!(profile.len(), 2);
+
+        assert_eq!(route(&collection, "GET", "/").content_type, "text/html");
+        assert_eq!(
+            route(&collection, "GET", "/api/files/1/geojson").status,
+            404
+        );
+        assert_eq!(route(&collection, "GET", "/api/files/0/kml").status, 404);
+        assert_eq!(route(&collection, "POST", "/api/files").status, 405);
+    }
+
+    #[test]
+    fn test_handle_limits_request_size() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let request = |bytes: Vec<u8>| {
+            let mut client = TcpStream::connect(address).unwrap();
+            client.write_all(&bytes).unwrap();
+            let (stream, _) = listener.accept().unwrap();
+            handle(stream, &collection()).unwrap();
+            let mut response = String::new();
+            client.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = request(b"GET /api/files HTTP/1.1\r\nHost: x\r\n\r\n".to_vec());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+
+        let mut huge = b"GET /api/files HTTP/1.1\r\nX-Padding: ".to_vec();
+        huge.resize(usize::try_from(MAX_REQUEST_BYTES).unwrap(), b'a');
+        let response = request(huge);
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::route::parse_distance;
+use clap::{Args, ValueEnum};
+use gpx_extractor::{Gpx, SimplifyAlgorithm, WriteOptions};
+use std::path::PathBuf;
+
+/// Reduce the number of points of a GPX file
+#[derive(Args)]
+pub struct SimplifyArgs {
+    /// GPX file to simplify
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Largest deviation allowed, e.g. `10m` (plain numbers are meters)
+    #[arg(long, value_name = "DISTANCE", value_parser = parse_distance)]
+    tolerance: f64,
+
+    /// Simplification algorithm
+    #[arg(long, value_enum, default_value = "rdp")]
+    algorithm: Algorithm,
+
+    /// Write the result to this file instead of standard output
+    #[arg(short, long, value_name = "FILE")]
+    out: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Algorithm {
+    /// Ramer–Douglas–Peucker, keeps the overall shape
+    Rdp,
+    /// Visvalingam–Whyatt, smoother lines on maps
+    Visvalingam,
+}
+
+impl From<Algorithm> for SimplifyAlgorithm {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Rdp => Self::RamerDouglasPeucker,
+            Algorithm::Visvalingam => Self::VisvalingamWhyatt,
+        }
+    }
+}
+
+pub fn run(args: &SimplifyArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let gpx = Gpx::from_file(&args.path)?;
+    let simplified = gpx.simplify_with(args.tolerance, args.algorithm.into());
+    // Por stderr, para no mezclarlo con el documento en stdout
+    eprintln!(
+        "Kept {} of {} points",
+        simplified.total_points(),
+        gpx.total_points()
+    );
+    let xml = simplified.to_xml_with(&WriteOptions::pretty())?;
+    crate::write_output(args.out.as_deref(), &xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        simplify: SimplifyArgs,
+    }
+
+    #[test]
+    fn test_parse_simplify() {
+        let cli = TestCli::try_parse_from(["simplify", "ride.gpx", "--tolerance", "10m"]).unwrap();
+        assert_eq!(cli.simplify.tolerance, 10.0);
+        assert_eq!(
+            SimplifyAlgorithm::from(cli.simplify.algorithm),
+            SimplifyAlgorithm::RamerDouglasPeucker
+        );
+
+        let cli = TestCli::try_parse_from([
+            "simplify",
+            "ride.gpx",
+            "--tolerance",
+            "5",
+            "--algorithm",
+            "visvalingam",
+        ])
+        .unwrap();
+        assert_eq!(cli.simplify.algorithm, Algorithm::Visvalingam);
+        assert!(TestCli::try_parse_from(["simplify", "ride.gpx"]).is_err());
+    }
+}
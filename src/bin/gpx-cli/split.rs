@@ -0,0 +1,171 @@
+use crate::edit::parse_offset;
+use crate::route::parse_distance;
+use chrono::Duration;
+use clap::{ArgGroup, Args};
+use colored::Colorize;
+use gpx_extractor::{Gpx, NameTemplate, SplitStrategy, WriteOptions};
+use std::path::{Path, PathBuf};
+
+/// Split a recording into several GPX files
+#[derive(Args)]
+#[command(group(ArgGroup::new("strategy").required(true)))]
+pub struct SplitArgs {
+    /// GPX file to split
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Cut at pauses longer than this, e.g. `30m` or `1h30m` (units: h, m, s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_gap, group = "strategy")]
+    gap: Option<Duration>,
+
+    /// Cut every given distance, e.g. `10km` (plain numbers are meters)
+    #[arg(long, value_name = "DISTANCE", value_parser = parse_distance, group = "strategy")]
+    every: Option<f64>,
+
+    /// Cut at midnight UTC, one file per day
+    #[arg(long, group = "strategy")]
+    per_day: bool,
+
+    /// Directory for the pieces (defaults to the directory of FILE)
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+
+    /// File name of each piece, without extension; besides the document values
+    /// (`{date}`, `{distance_km:.1}`...) it gets `{stem}` of FILE and the piece number `{n}`
+    #[arg(long, value_name = "TEMPLATE", default_value = "{stem}-{n}", value_parser = parse_template)]
+    name: NameTemplate,
+}
+
+/// Parsea la plantilla de nombres de fichero
+fn parse_template(text: &str) -> Result<NameTemplate, String> {
+    text.parse()
+        .map_err(|e: gpx_extractor::GpxError| e.to_string())
+}
+
+/// Parsea la duración mínima de una pausa; debe ser positiva
+fn parse_gap(text: &str) -> Result<Duration, String> {
+    let gap = parse_offset(text)?;
+    if gap <= Duration::zero() {
+        return Err(format!("pause length must be positive, got '{text}'"));
+    }
+    Ok(gap)
+}
+
+impl SplitArgs {
+    /// Estrategia de corte elegida con los flags
+    fn strategy(&self) -> SplitStrategy {
+        match (self.gap, self.every) {
+            (Some(gap), _) => SplitStrategy::TimeGap(gap),
+            (None, Some(meters)) => SplitStrategy::every_km(meters / 1000.0),
+            (None, None) => SplitStrategy::per_day(),
+        }
+    }
+}
+
+/// Ruta de la pieza `n` dentro de `dir`, con el nombre que da la plantilla
+fn piece_path(
+    dir: &Path,
+    template: &NameTemplate,
+    source: &Path,
+    piece: &Gpx,
+    n: usize,
+) -> PathBuf {
+    let stem = source
+        .file_stem()
+        .map_or_else(|| "split".into(), |stem| stem.to_string_lossy());
+    let values = piece
+        .template_values()
+        .with("stem", stem.as_ref())
+        .with("n", n);
+    // Los separadores de ruta o los dos puntos de las horas no valen en un nombre de fichero
+    let name = template.render(&values).replace(['/', '\\', ':'], "-");
+    dir.join(format!("{name}.gpx"))
+}
+
+pub fn run(args: &SplitArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let gpx = Gpx::from_file(&args.path)?;
+    let pieces = gpx.split(args.strategy());
+    if pieces.is_empty() {
+        println!("{}", "No track points; nothing to split".yellow());
+        return Ok(());
+    }
+
+    let dir = args
+        .out_dir
+        .clone()
+        .or_else(|| args.path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    std::fs::create_dir_all(&dir)?;
+    for (i, piece) in pieces.iter().enumerate() {
+        let path = piece_path(&dir, &args.name, &args.path, piece, i + 1);
+        piece.save_to_file_with(&path.to_string_lossy(), &WriteOptions::pretty())?;
+        println!("{}", format!("✅ Saved {}", path.display()).green());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        split: SplitArgs,
+    }
+
+    #[test]
+    fn test_parse_strategies() {
+        let cli = TestCli::try_parse_from(["split", "hike.gpx", "--gap", "1h30m"]).unwrap();
+        assert_eq!(
+            cli.split.strategy(),
+            SplitStrategy::TimeGap(Duration::minutes(90))
+        );
+        let cli = TestCli::try_parse_from(["split", "hike.gpx", "--every", "10km"]).unwrap();
+        assert_eq!(cli.split.strategy(), SplitStrategy::Distance(10.0));
+        let cli = TestCli::try_parse_from(["split", "hike.gpx", "--per-day"]).unwrap();
+        assert_eq!(cli.split.strategy(), SplitStrategy::per_day());
+
+        assert!(TestCli::try_parse_from(["split", "hike.gpx"]).is_err());
+        assert!(TestCli::try_parse_from(["split", "hike.gpx", "--gap", "-5m"]).is_err());
+        assert!(
+            TestCli::try_parse_from(["split", "hike.gpx", "--gap", "5m", "--per-day"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_piece_path() {
+        let cli = TestCli::try_parse_from(["split", "trips/hike.gpx", "--per-day"]).unwrap();
+        let piece = Gpx::new();
+        let path = piece_path(
+            Path::new("out"),
+            &cli.split.name,
+            &cli.split.path,
+            &piece,
+            2,
+        );
+        assert_eq!(path, Path::new("out").join("hike-2.gpx"));
+
+        let template = "{n}_{name}_{date}".parse().unwrap();
+        let mut piece = Gpx::try_from_str(
+            r#"<gpx><trk><name>Day/Hike</name><trkseg>
+            <trkpt lat="42.0" lon="1.0"><time>2024-07-01T16:00:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        piece.metadata_mut().time = Some("2024-07-01T16:00:00Z".to_string());
+        let path = piece_path(
+            Path::new("out"),
+            &template,
+            Path::new("hike.gpx"),
+            &piece,
+            1,
+        );
+        assert_eq!(
+            path,
+            Path::new("out").join("1_Day-Hike_2024-07-01T16-00-00Z.gpx")
+        );
+        assert!(TestCli::try_parse_from(["split", "a.gpx", "--per-day", "--name", "{n"]).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::edit::save_in_place;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use gpx_extractor::{Gpx, WaypointBuilder};
+use regex::Regex;
+use std::path::PathBuf;
+
+/// Add or remove waypoints of a GPX file in place, keeping a backup
+#[derive(Args)]
+pub struct WaypointArgs {
+    #[command(subcommand)]
+    action: WaypointAction,
+}
+
+#[derive(Subcommand)]
+enum WaypointAction {
+    /// Add a waypoint at the given coordinates
+    Add(AddArgs),
+    /// Remove every waypoint whose name matches a regular expression
+    Remove(RemoveArgs),
+}
+
+#[derive(Args)]
+struct AddArgs {
+    /// GPX file to edit
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Latitude in decimal degrees
+    #[arg(long, allow_negative_numbers = true)]
+    lat: f64,
+
+    /// Longitude in decimal degrees
+    #[arg(long, allow_negative_numbers = true)]
+    lon: f64,
+
+    /// Waypoint name
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Elevation in meters
+    #[arg(long, value_name = "METERS", allow_negative_numbers = true)]
+    ele: Option<f64>,
+
+    /// Symbol (`<sym>`), e.g. `Drinking Water`
+    #[arg(long, value_name = "SYMBOL")]
+    sym: Option<String>,
+
+    /// Type (`<type>`), e.g. `Geocache`
+    #[arg(long = "type", value_name = "TYPE")]
+    kind: Option<String>,
+
+    /// Do not keep a copy of the original file as `<FILE>.bak`
+    #[arg(long)]
+    no_backup: bool,
+}
+
+#[derive(Args)]
+struct RemoveArgs {
+    /// GPX file to edit
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+
+    /// Regular expression matched against the waypoint names
+    #[arg(long, value_name = "REGEX")]
+    name_regex: Regex,
+
+    /// Do not keep a copy of the original file as `<FILE>.bak`
+    #[arg(long)]
+    no_backup: bool,
+}
+
+/// Construye el waypoint pedido y lo añade al documento
+fn add(gpx: &mut Gpx, args: &AddArgs) -> Result<(), gpx_extractor::GpxError> {
+    let mut builder = WaypointBuilder::new(args.lat, args.lon);
+    if let Some(name) = &args.name {
+        builder = builder.name(name);
+    }
+    if let Some(ele) = args.ele {
+        builder = builder.elevation(ele);
+    }
+    if let Some(sym) = &args.sym {
+        builder = builder.symbol(sym);
+    }
+    if let Some(kind) = &args.kind {
+        builder = builder.kind(kind);
+    }
+    gpx.add_waypoint(builder.build()?);
+    Ok(())
+}
+
+/// Quita los waypoints cuyo nombre encaja con la expresión; devuelve cuántos
+fn remove(gpx: &mut Gpx, pattern: &Regex) -> usize {
+    gpx.filter_waypoints_by(|w| !w.name.as_deref().is_some_and(|n| pattern.is_match(n)))
+}
+
+pub fn run(args: &WaypointArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match &args.action {
+        WaypointAction::Add(args) => {
+            let mut gpx = Gpx::from_file(&args.path)?;
+            add(&mut gpx, args)?;
+            save_in_place(&gpx, &args.path, !args.no_backup)
+        }
+        WaypointAction::Remove(args) => {
+            let mut gpx = Gpx::from_file(&args.path)?;
+            let removed = remove(&mut gpx, &args.name_regex);
+            if removed == 0 {
+                println!(
+                    "{}",
+                    "No waypoint names matched; file left unchanged".yellow()
+                );
+                return Ok(());
+            }
+            println!("Removed {removed} waypoint(s)");
+            save_in_place(&gpx, &args.path, !args.no_backup)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        waypoint: WaypointArgs,
+    }
+
+    fn parse(args: &[&str]) -> WaypointAction {
+        let mut argv = vec!["waypoint"];
+        argv.extend_from_slice(args);
+        TestCli::try_parse_from(argv).unwrap().waypoint.action
+    }
+
+    #[test]
+    fn test_add_and_remove_waypoints() {
+        let mut gpx = Gpx::new();
+        let WaypointAction::Add(water) = parse(&[
+            "add",
+            "ride.gpx",
+            "--lat",
+            "40.4",
+            "--lon",
+            "-3.7",
+            "--name",
+            "Water",
+            "--sym",
+            "Drinking Water",
+        ]) else {
+            panic!("expected add");
+        };
+        add(&mut gpx, &water).unwrap();
+        let WaypointAction::Add(summit) = parse(&[
+            "add", "ride.gpx", "--lat", "40.5", "--lon", "-3.8", "--name", "Summit", "--ele",
+            "1800",
+        ]) else {
+            panic!("expected add");
+        };
+        add(&mut gpx, &summit).unwrap();
+        assert_eq!(gpx.waypoints[0].lon, -3.7);
+        assert_eq!(gpx.waypoints[0].symbol.as_deref(), Some("Drinking Water"));
+        assert_eq!(gpx.waypoints[1].elevation, Some(1800.0));
+
+        let WaypointAction::Remove(args) = parse(&["remove", "ride.gpx", "--name-regex", "^Wat"])
+        else {
+            panic!("expected remove");
+        };
+        assert_eq!(remove(&mut gpx, &args.name_regex), 1);
+        assert_eq!(gpx.waypoint_names(), vec!["Summit"]);
+    }
+
+    #[test]
+    fn test_add_rejects_invalid_coordinates() {
+        let WaypointAction::Add(args) = parse(&["add", "ride.gpx", "--lat", "91", "--lon", "0"])
+        else {
+            panic!("expected add");
+        };
+        assert!(add(&mut Gpx::new(), &args).is_err());
+        assert!(
+            TestCli::try_parse_from(["waypoint", "remove", "ride.gpx", "--name-regex", "("])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use crate::gpx::{
+    parser::{start_time, Gpx},
+    tiles::Tile,
+};
+use chrono::{DateTime, Datelike, IsoWeek, Utc};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashSet};
+
+/// Zoom of the heatmap cells used by [`ActivityLog::from_gpx_iter`] (about 2.4 km at the equator)
+pub const DEFAULT_HEATMAP_ZOOM: u8 = 14;
+
+/// Totals of the activities in one period
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PeriodTotals {
+    /// Number of activities
+    pub activities: usize,
+    /// Distance in kilometers
+    pub distance_km: f64,
+    /// Moving and stopped time, from the first to the last timestamp of each activity
+    pub duration_seconds: i64,
+    /// Elevation gain in meters
+    pub elevation_gain_m: f64,
+}
+
+impl PeriodTotals {
+    /// Suma una actividad al periodo
+    fn add(&mut self, distance_km: f64, duration_seconds: i64, elevation_gain_m: f64) {
+        self.activities += 1;
+        self.distance_km += distance_km;
+        self.duration_seconds += duration_seconds;
+        self.elevation_gain_m += elevation_gain_m;
+    }
+}
+
+/// The activity holding a personal record
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalRecord {
+    /// Position of the activity in the input, starting at 0
+    pub index: usize,
+    /// Document name, or the name of its first named track
+    pub name: Option<String>,
+    /// Time of the first timed point
+    pub start: Option<DateTime<Utc>>,
+    /// Record value, in the unit of the record (km, seconds or meters)
+    pub value: f64,
+}
+
+/// Best activities of an [`ActivityLog`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PersonalRecords {
+    /// Longest distance, in kilometers
+    pub longest_distance: Option<PersonalRecord>,
+    /// Longest duration, in seconds
+    pub longest_duration: Option<PersonalRecord>,
+    /// Biggest elevation gain, in meters
+    pub biggest_climb: Option<PersonalRecord>,
+}
+
+/// Training summary of many activities: weekly and monthly totals, personal
+/// records and a visit-count grid for heatmaps
+///
+/// Activities without timestamps count in [`ActivityLog::totals`] and the
+/// records, but not in the weekly or monthly totals. Each heatmap cell is a
+/// slippy-map [`Tile`](crate::tiles::Tile) and counts the activities that
+/// passed through it, so a daily commute shows up hotter than a single long ride.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::aggregate::ActivityLog;
+/// use gpx_extractor::Gpx;
+///
+/// let ride = Gpx::try_from_str(
+///     r#"<gpx><trk><trkseg>
+///         <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+///         <trkpt lat="40.1" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt>
+///     </trkseg></trk></gpx>"#,
+/// )
+/// .unwrap();
+///
+/// let log = ActivityLog::from_gpx_iter([&ride, &ride]);
+/// assert_eq!(log.totals.activities, 2);
+/// assert_eq!(log.monthly[&(2024, 7)].duration_seconds, 7200);
+/// assert_eq!(log.heatmap.values().max(), Some(&2));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityLog {
+    /// Totals of every activity
+    pub totals: PeriodTotals,
+    /// Totals per ISO week of the start time
+    pub weekly: BTreeMap<IsoWeek, PeriodTotals>,
+    /// Totals per `(year, month)` of the start time
+    pub monthly: BTreeMap<(i32, u32), PeriodTotals>,
+    /// Personal records
+    pub records: PersonalRecords,
+    /// Number of activities that visited each cell
+    pub heatmap: BTreeMap<Tile, usize>,
+    /// Zoom level of the heatmap cells
+    pub heatmap_zoom: u8,
+}
+
+impl ActivityLog {
+    /// Crea un registro vacío con celdas de mapa de calor del nivel `heatmap_zoom`
+    pub fn new(heatmap_zoom: u8) -> Self {
+        Self {
+            totals: PeriodTotals::default(),
+            weekly: BTreeMap::new(),
+            monthly: BTreeMap::new(),
+            records: PersonalRecords::default(),
+            heatmap: BTreeMap::new(),
+            heatmap_zoom,
+        }
+    }
+
+    /// Builds the log of a set of activities, with [`DEFAULT_HEATMAP_ZOOM`] cells
+    pub fn from_gpx_iter<I>(activities: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Gpx>,
+    {
+        let mut log = Self::new(DEFAULT_HEATMAP_ZOOM);
+        for gpx in activities {
+            log.add(gpx.borrow());
+        }
+        log
+    }
+
+    /// Adds one activity to the totals, records and heatmap
+    pub fn add(&mut self, gpx: &Gpx) {
+        let index = self.totals.activities;
+        let stats = gpx.statistics();
+        let duration_seconds = stats.duration_seconds.unwrap_or(0);
+        let elevation_gain_m = stats.elevation_gain.unwrap_or(0.0);
+        let totals = (stats.total_distance_km, duration_seconds, elevation_gain_m);
+        self.totals.add(totals.0, totals.1, totals.2);
+
+        let start = start_time(gpx);
+        if let Some(start) = start {
+            let weekly = self.weekly.entry(start.iso_week()).or_default();
+            weekly.add(totals.0, totals.1, totals.2);
+            let monthly = self
+                .monthly
+                .entry((start.year(), start.month()))
+                .or_default();
+            monthly.add(totals.0, totals.1, totals.2);
+        }
+
+        let record = |value: f64| PersonalRecord {
+            index,
+            name: activity_name(gpx),
+            start,
+            value,
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let candidates = [
+            (&mut self.records.longest_distance, totals.0),
+            (&mut self.records.longest_duration, totals.1 as f64),
+            (&mut self.records.biggest_climb, totals.2),
+        ];
+        for (best, value) in candidates {
+            // Un récord a cero no dice nada (sin tiempo, sin altitud...)
+            if value > 0.0 && best.as_ref().map_or(true, |best| value > best.value) {
+                *best = Some(record(value));
+            }
+        }
+
+        let cells: HashSet<Tile> = gpx
+            .points()
+            .map(|point| Tile::containing(point.lat, point.lon, self.heatmap_zoom))
+            .collect();
+        for cell in cells {
+            *self.heatmap.entry(cell).or_default() += 1;
+        }
+    }
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_HEATMAP_ZOOM)
+    }
+}
+
+/// Nombre del documento o, si no tiene, el del primer track con nombre
+fn activity_name(gpx: &Gpx) -> Option<String> {
+    gpx.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.name.clone())
+        .or_else(|| gpx.tracks.iter().find_map(|track| track.name.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(name: &str, start: &str, end: &str, points: &[(f64, f64, f64)]) -> Gpx {
+        let mut xml = format!("<gpx><trk><name>{name}</name><trkseg>");
+        for (i, (lat, lon, ele)) in points.iter().enumerate() {
+            let time = if i == 0 { start } else { end };
+            xml.push_str(&format!(
+                r#"<trkpt lat="{lat}" lon="{lon}"><ele>{ele}</ele><time>{time}</time></trkpt>"#
+            ));
+        }
+        xml.push_str("</trkseg></trk></gpx>");
+        Gpx::try_from_str(&xml).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_monthly_totals_and_records() {
+        let commute = activity(
+            "Commute",
+            "2024-07-01T08:00:00Z",
+            "2024-07-01T08:30:00Z",
+            &[(40.0, -3.0, 600.0), (40.05, -3.0, 620.0)],
+        );
+        let climb = activity(
+            "Col",
+            "2024-07-31T07:00:00Z",
+            "2024-07-31T11:00:00Z",
+            &[(42.0, 1.0, 800.0), (42.02, 1.0, 2100.0)],
+        );
+        let long_ride = activity(
+            "Coast",
+            "2024-08-04T06:00:00Z",
+            "2024-08-04T12:00:00Z",
+            &[(40.0, -3.0, 600.0), (41.0, -3.0, 590.0)],
+        );
+        let log = ActivityLog::from_gpx_iter(vec![commute.clone(), climb, long_ride, commute]);
+
+        assert_eq!(log.totals.activities, 4);
+        assert_eq!(log.monthly.len(), 2);
+        assert_eq!(log.monthly[&(2024, 7)].activities, 3);
+        assert_eq!(log.monthly[&(2024, 8)].elevation_gain_m, 0.0);
+        // El 31 de julio y el 4 de agosto de 2024 caen en la misma semana ISO
+        let weeks: Vec<(u32, usize)> = log
+            .weekly
+            .iter()
+            .map(|(week, totals)| (week.week(), totals.activities))
+            .collect();
+        assert_eq!(weeks, vec![(27, 2), (31, 2)]);
+
+        let records = &log.records;
+        let longest = records.longest_distance.as_ref().unwrap();
+        assert_eq!((longest.index, longest.name.as_deref()), (2, Some("Coast")));
+        assert_eq!(records.longest_duration.as_ref().unwrap().value, 21600.0);
+        let climb = records.biggest_climb.as_ref().unwrap();
+        assert_eq!((climb.name.as_deref(), climb.value), (Some("Col"), 1300.0));
+    }
+
+    #[test]
+    fn test_heatmap_counts_each_activity_once_per_cell() {
+        let mut log = ActivityLog::new(10);
+        let ride = activity(
+            "Loop",
+            "2024-07-01T08:00:00Z",
+            "2024-07-01T08:30:00Z",
+            &[
+                (40.0, -3.0, 600.0),
+                (40.0001, -3.0, 600.0),
+                (40.0002, -3.0, 600.0),
+            ],
+        );
+        log.add(&ride);
+        log.add(&ride);
+        log.add(&Gpx::new());
+
+        let cell = Tile::containing(40.0, -3.0, 10);
+        assert_eq!(log.heatmap.len(), 1);
+        assert_eq!(log.heatmap[&cell], 2);
+        assert_eq!(log.totals.activities, 3);
+        assert!(log.weekly.values().all(|week| week.activities == 2));
+    }
+}
@@ -0,0 +1,548 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, Point},
+    track::{Track, TrackSegment},
+};
+use chrono::Duration;
+
+/// Values derived from a track point and the one before it in its segment
+///
+/// Metrics that need the previous point (speed, grade, vertical speed, heading)
+/// are `None` for the first point of every segment, and when the timestamps or
+/// elevations they depend on are missing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PointMetrics {
+    /// Distance from the first point, in kilometers; gaps between segments are not counted
+    pub distance_km: f64,
+    /// Speed from the previous point, in km/h
+    pub speed_kmh: Option<f64>,
+    /// Slope from the previous point, in percent (positive uphill)
+    pub grade_percent: Option<f64>,
+    /// Rate of climb from the previous point, in meters per second (negative descending)
+    pub vertical_speed_mps: Option<f64>,
+    /// Course from the previous point, in degrees clockwise from north; `None` when standing still
+    pub heading_degrees: Option<f64>,
+}
+
+/// Giro con signo, entre -180 y 180 grados, para pasar del rumbo `from` a `to` (positivo a la derecha)
+pub(crate) fn turn_degrees(from: f64, to: f64) -> f64 {
+    (to - from + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Calcula las métricas de una secuencia de puntos partiendo de una distancia acumulada
+fn metrics_from(points: &[Point], start_km: f64) -> Vec<PointMetrics> {
+    let mut distance_km = start_km;
+    let mut metrics = Vec::with_capacity(points.len());
+    let mut previous: Option<&Point> = None;
+
+    for point in points {
+        let mut current = PointMetrics::default();
+        if let Some(previous) = previous {
+            let step_km = haversine_distance(previous, point);
+            distance_km += step_km;
+
+            #[allow(clippy::cast_precision_loss)]
+            let seconds = match (previous.time, point.time) {
+                (Some(from), Some(to)) => Some((to - from).num_milliseconds() as f64 / 1000.0),
+                _ => None,
+            }
+            .filter(|seconds| *seconds > 0.0);
+            let climb_m = previous.elevation.zip(point.elevation).map(|(a, b)| b - a);
+
+            current.speed_kmh = seconds.map(|s| step_km / (s / 3600.0));
+            current.grade_percent = climb_m
+                .filter(|_| step_km > 0.0)
+                .map(|climb| climb / (step_km * 1000.0) * 100.0);
+            current.vertical_speed_mps = climb_m.zip(seconds).map(|(climb, s)| climb / s);
+            current.heading_degrees = (step_km > 0.0).then(|| previous.bearing_to(point));
+        }
+        current.distance_km = distance_km;
+        metrics.push(current);
+        previous = Some(point);
+    }
+    metrics
+}
+
+/// Métricas de varios segmentos seguidos, con la distancia acumulada entre ellos
+fn metrics_for_segments<'a>(
+    segments: impl IntoIterator<Item = &'a TrackSegment>,
+) -> Vec<PointMetrics> {
+    let mut metrics: Vec<PointMetrics> = Vec::new();
+    for segment in segments {
+        let start_km = metrics.last().map_or(0.0, |m| m.distance_km);
+        metrics.extend(metrics_from(&segment.points, start_km));
+    }
+    metrics
+}
+
+impl TrackSegment {
+    /// Derived metrics for every point of the segment, in the same order as `points`
+    pub fn point_metrics(&self) -> Vec<PointMetrics> {
+        metrics_from(&self.points, 0.0)
+    }
+}
+
+impl Track {
+    /// Derived metrics aligned with [`Track::get_all_points`]
+    pub fn point_metrics(&self) -> Vec<PointMetrics> {
+        metrics_for_segments(&self.segments)
+    }
+
+    /// Sum of every change of heading along the track, in degrees, whatever its direction
+    ///
+    /// Headings come from [`PointMetrics::heading_degrees`]; points that did not
+    /// move are skipped, and the turn between two segments is not counted. A
+    /// straight line scores 0 and a lap around a block about 360, which makes
+    /// this a simple measure of how twisty a route is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// // Norte, este y sur: dos giros de 90 grados a la derecha
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[
+    ///     (0.0, 0.0),
+    ///     (0.01, 0.0),
+    ///     (0.01, 0.01),
+    ///     (0.0, 0.01),
+    /// ]));
+    /// assert!((track.total_turning_degrees() - 180.0).abs() < 0.01);
+    /// ```
+    pub fn total_turning_degrees(&self) -> f64 {
+        self.segments
+            .iter()
+            .map(|segment| {
+                let headings: Vec<f64> = segment
+                    .point_metrics()
+                    .iter()
+                    .filter_map(|metrics| metrics.heading_degrees)
+                    .collect();
+                headings
+                    .windows(2)
+                    .map(|pair| turn_degrees(pair[0], pair[1]).abs())
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+}
+
+impl Gpx {
+    /// Derived metrics aligned with [`Gpx::get_all_points`]
+    ///
+    /// The cumulative distance runs through every track of the document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.000" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.009" lon="-3.0"><ele>650</ele><time>2024-07-01T08:05:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let metrics = gpx.point_metrics();
+    /// assert_eq!(metrics.len(), 2);
+    /// assert_eq!(metrics[0].speed_kmh, None);
+    /// assert!((metrics[1].distance_km - 1.0).abs() < 0.001);
+    /// assert!((metrics[1].speed_kmh.unwrap() - 12.0).abs() < 0.1);
+    /// assert!((metrics[1].grade_percent.unwrap() - 5.0).abs() < 0.1);
+    /// ```
+    pub fn point_metrics(&self) -> Vec<PointMetrics> {
+        metrics_for_segments(self.tracks.iter().flat_map(|track| &track.segments))
+    }
+}
+
+/// Stretch of a track covering a fixed distance, see [`Track::windows_by_distance`]
+#[derive(Debug, Clone)]
+pub struct DistanceWindow<'a> {
+    /// Index of the first point in [`Track::get_all_points`] order
+    pub start_index: usize,
+    /// Distance from the start of the track to the first point, in kilometers
+    pub start_km: f64,
+    /// Distance from the start of the track to the last point, in kilometers
+    pub end_km: f64,
+    /// Points inside the window, borrowed from the track
+    pub points: Vec<&'a Point>,
+}
+
+impl DistanceWindow<'_> {
+    /// Distancia cubierta por la ventana en kilómetros
+    pub fn distance_km(&self) -> f64 {
+        self.end_km - self.start_km
+    }
+
+    /// Segundos entre el primer y el último punto, si ambos tienen tiempo
+    pub fn duration_seconds(&self) -> Option<f64> {
+        let from = self.points.first()?.time?;
+        let to = self.points.last()?.time?;
+        #[allow(clippy::cast_precision_loss)]
+        let seconds = (to - from).num_milliseconds() as f64 / 1000.0;
+        Some(seconds)
+    }
+}
+
+/// Iterator returned by [`Track::windows_by_distance`]
+#[derive(Debug, Clone)]
+pub struct DistanceWindows<'a> {
+    points: Vec<&'a Point>,
+    distances: Vec<f64>,
+    window_km: f64,
+    step_km: f64,
+    // Número de la próxima ventana y primer punto candidato
+    next: usize,
+    first: usize,
+}
+
+impl<'a> Iterator for DistanceWindows<'a> {
+    type Item = DistanceWindow<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let total = self.distances.last().copied()?;
+        if self.window_km <= 0.0 || self.step_km <= 0.0 {
+            return None;
+        }
+        loop {
+            // Multiplicar en vez de acumular evita la deriva de redondeo
+            #[allow(clippy::cast_precision_loss)]
+            let start = self.next as f64 * self.step_km;
+            let end = start + self.window_km;
+            if end > total + 1e-9 {
+                return None;
+            }
+            self.next += 1;
+
+            while self.distances[self.first] < start - 1e-9 {
+                self.first += 1;
+            }
+            let last = self.distances.partition_point(|&d| d <= end + 1e-9) - 1;
+            // Ventanas más cortas que la separación entre puntos no tienen tramo
+            if last > self.first {
+                return Some(DistanceWindow {
+                    start_index: self.first,
+                    start_km: self.distances[self.first],
+                    end_km: self.distances[last],
+                    points: self.points[self.first..=last].to_vec(),
+                });
+            }
+        }
+    }
+}
+
+impl Track {
+    /// Overlapping stretches of `window_km` starting every `step_km` along the track
+    ///
+    /// Each window holds the points between its start and end distance, without
+    /// interpolation, so its actual length can be slightly shorter than
+    /// `window_km`. Only complete windows are produced, and gaps between segments
+    /// are not counted (see [`Track::point_metrics`]). Use it to build rolling
+    /// statistics or best-effort searches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// // Un punto cada ~111 m durante ~1.1 km
+    /// let coords: Vec<(f64, f64)> = (0..=10).map(|i| (40.0 + f64::from(i) * 0.001, -3.0)).collect();
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&coords));
+    ///
+    /// let windows: Vec<_> = track.windows_by_distance(0.5, 0.25).collect();
+    /// assert_eq!(windows.len(), 3);
+    /// assert_eq!(windows[1].start_index, 3);
+    /// assert_eq!(windows[1].points.len(), 4);
+    /// ```
+    pub fn windows_by_distance(&self, window_km: f64, step_km: f64) -> DistanceWindows<'_> {
+        DistanceWindows {
+            points: self.get_all_points(),
+            distances: self.point_metrics().iter().map(|m| m.distance_km).collect(),
+            window_km,
+            step_km,
+            next: 0,
+            first: 0,
+        }
+    }
+}
+
+/// Quantity averaged by [`best_window`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Speed, in km/h, from the distance covered
+    Speed,
+    /// Heart rate, in beats per minute
+    HeartRate,
+    /// Power, in watts
+    Power,
+}
+
+/// Best rolling-window effort found by [`best_window`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BestWindow {
+    /// Index of the first point, in [`Gpx::get_all_points`] order
+    pub start_index: usize,
+    /// Index of the last point, in [`Gpx::get_all_points`] order
+    pub end_index: usize,
+    /// Time-weighted average of the metric over the window
+    pub average: f64,
+    /// Seconds between the first and the last point, at least the requested duration
+    pub duration_seconds: f64,
+}
+
+/// Muestra para la ventana: índice global, segundos desde el inicio del
+/// segmento y acumulado de la métrica por segundos (integral en el tiempo)
+type WindowSample = (usize, f64, f64);
+
+/// Muestras de un segmento con la métrica disponible
+fn window_samples(segment: &TrackSegment, offset: usize, metric: Metric) -> Vec<WindowSample> {
+    let start = segment.points.iter().find_map(|point| point.time);
+    let metrics = segment.point_metrics();
+    let mut samples: Vec<WindowSample> = Vec::new();
+    let mut previous_km = 0.0;
+    for (i, point) in segment.points.iter().enumerate() {
+        let (Some(time), Some(start)) = (point.time, start) else {
+            continue;
+        };
+        let value = match metric {
+            Metric::Speed => Some(0.0),
+            Metric::HeartRate => point.extensions.heart_rate.map(f64::from),
+            Metric::Power => point.extensions.power.map(f64::from),
+        };
+        let Some(value) = value else {
+            continue;
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let seconds = (time - start).num_milliseconds() as f64 / 1000.0;
+        let distance_km = metrics[i].distance_km;
+        let total = match samples.last() {
+            // Cada punto aporta su valor durante el intervalo que cierra
+            Some(&(_, before, total)) => match metric {
+                Metric::Speed => total + (distance_km - previous_km) * 3600.0,
+                Metric::HeartRate | Metric::Power => total + value * (seconds - before),
+            },
+            None => 0.0,
+        };
+        samples.push((offset + i, seconds, total));
+        previous_km = distance_km;
+    }
+    samples
+}
+
+/// Finds the stretch of at least `duration` with the best average, like the
+/// "best 20 min power" of training platforms
+///
+/// The window is the shortest run of consecutive points of one segment spanning
+/// `duration`; the average is weighted by time, each point counting for the
+/// interval since the previous one. Points without time, or without heart
+/// rate or power for those metrics, are skipped. Returns `None` when no segment
+/// lasts long enough.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::Duration;
+/// use gpx_extractor::{analysis::{best_window, Metric}, Gpx};
+///
+/// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+///     <trkpt lat="40.00" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+///     <trkpt lat="40.05" lon="-3.0"><time>2024-07-01T08:20:00Z</time></trkpt>
+///     <trkpt lat="40.15" lon="-3.0"><time>2024-07-01T08:40:00Z</time></trkpt>
+///     <trkpt lat="40.20" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt>
+/// </trkseg></trk></gpx>"#).unwrap();
+///
+/// let best = best_window(&gpx, Duration::minutes(20), Metric::Speed).unwrap();
+/// assert_eq!((best.start_index, best.end_index), (1, 2));
+/// assert!((best.average - 33.4).abs() < 0.1);
+/// ```
+pub fn best_window(gpx: &Gpx, duration: Duration, metric: Metric) -> Option<BestWindow> {
+    #[allow(clippy::cast_precision_loss)]
+    let window = duration.num_milliseconds() as f64 / 1000.0;
+    let mut best: Option<BestWindow> = None;
+    let mut offset = 0;
+    for segment in gpx.tracks.iter().flat_map(|track| &track.segments) {
+        let samples = window_samples(segment, offset, metric);
+        offset += segment.points.len();
+        let mut end = 0;
+        for &(start_index, from, before) in &samples {
+            while end < samples.len() && samples[end].1 - from < window {
+                end += 1;
+            }
+            let Some(&(end_index, to, total)) = samples.get(end) else {
+                break;
+            };
+            if to <= from {
+                continue;
+            }
+            let average = (total - before) / (to - from);
+            if best.map_or(true, |best| average > best.average) {
+                best = Some(BestWindow {
+                    start_index,
+                    end_index,
+                    average,
+                    duration_seconds: to - from,
+                });
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_point_metrics_climb() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let segment = TrackSegment::with_points(vec![
+            Point::with_time(40.0, -3.0, Some(600.0), start),
+            Point::with_time(40.0045, -3.0, Some(650.0), start + Duration::seconds(100)),
+            Point::with_time(40.009, -3.0, None, start + Duration::seconds(100)),
+        ]);
+        let metrics = segment.point_metrics();
+
+        assert_eq!(metrics[0], PointMetrics::default());
+        let climb = metrics[1];
+        assert!((climb.distance_km - 0.5004).abs() < 0.001);
+        assert!((climb.grade_percent.unwrap() - 9.99).abs() < 0.05);
+        assert!((climb.vertical_speed_mps.unwrap() - 0.5).abs() < 1e-9);
+        assert!((climb.speed_kmh.unwrap() - climb.distance_km * 36.0).abs() < 1e-9);
+
+        // Sin elevación ni tiempo transcurrido no hay pendiente ni velocidades
+        let last = metrics[2];
+        assert!(last.distance_km > climb.distance_km);
+        assert_eq!(last.speed_kmh, None);
+        assert_eq!(last.grade_percent, None);
+        assert_eq!(last.vertical_speed_mps, None);
+    }
+
+    #[test]
+    fn test_track_metrics_skip_segment_gaps() {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_elevation(40.0, -3.0, 600.0),
+            Point::with_elevation(40.01, -3.0, 580.0),
+        ]));
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_elevation(41.0, -3.0, 900.0),
+            Point::with_elevation(41.01, -3.0, 900.0),
+        ]));
+        let metrics = track.point_metrics();
+
+        assert_eq!(metrics.len(), track.get_all_points().len());
+        assert!(metrics[1].grade_percent.unwrap() < 0.0);
+        // El primer punto del segundo segmento no suma el salto de un grado
+        assert_eq!(metrics[2].distance_km, metrics[1].distance_km);
+        assert_eq!(metrics[2].grade_percent, None);
+        assert_eq!(metrics[3].grade_percent, Some(0.0));
+        assert!((metrics[3].distance_km - 2.0 * metrics[1].distance_km).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_headings_and_total_turning() {
+        let mut track = Track::new();
+        // Ida hacia el norte, un punto repetido y vuelta atrás; luego un zigzag
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (0.0, 0.0),
+            (0.01, 0.0),
+            (0.01, 0.0),
+            (0.0, 0.0),
+        ]));
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (1.0, 1.0),
+            (1.01, 1.01),
+            (1.02, 1.0),
+            (1.03, 1.01),
+        ]));
+
+        let headings: Vec<Option<f64>> = track
+            .point_metrics()
+            .iter()
+            .map(|m| m.heading_degrees.map(f64::round))
+            .collect();
+        assert_eq!(headings[..4], [None, Some(0.0), None, Some(180.0)]);
+        assert_eq!(headings[4], None);
+
+        // 180 del cambio de sentido y dos giros de unos 90 en el zigzag
+        let turning = track.total_turning_degrees();
+        assert!((turning - 360.0).abs() < 0.1, "{turning}");
+        assert_eq!(turn_degrees(350.0, 10.0), 20.0);
+        assert_eq!(turn_degrees(10.0, 350.0), -20.0);
+    }
+
+    #[test]
+    fn test_windows_by_distance() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        // Puntos cada ~111 m y 30 s
+        let points = (0..=20)
+            .map(|i| {
+                Point::with_time(
+                    40.0 + f64::from(i) * 0.001,
+                    -3.0,
+                    None,
+                    start + Duration::seconds(i64::from(i) * 30),
+                )
+            })
+            .collect();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(points));
+
+        let windows: Vec<_> = track.windows_by_distance(1.0, 0.5).collect();
+        // 2.22 km dan ventanas que empiezan en 0, 0.5 y 1.0 km
+        assert_eq!(windows.len(), 3);
+        for window in &windows {
+            assert!(window.distance_km() <= 1.0);
+            assert!(window.distance_km() > 0.85);
+            assert_eq!(window.points.len(), 9);
+        }
+        assert_eq!(windows[2].start_index, 9);
+        assert_eq!(windows[0].duration_seconds(), Some(240.0));
+
+        // Ventanas más cortas que la separación o pasos no positivos no producen nada
+        assert_eq!(track.windows_by_distance(0.05, 0.05).count(), 0);
+        assert_eq!(track.windows_by_distance(1.0, 0.0).count(), 0);
+        assert_eq!(track.windows_by_distance(5.0, 1.0).count(), 0);
+        assert_eq!(Track::new().windows_by_distance(1.0, 1.0).count(), 0);
+    }
+
+    #[test]
+    fn test_best_window_weights_by_time() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |seconds: i64, power: Option<u16>| {
+            let mut point = Point::with_time(40.0, -3.0, None, start + Duration::seconds(seconds));
+            point.extensions.power = power;
+            point
+        };
+        let mut gpx = Gpx::new();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            at(0, Some(100)),
+            at(60, Some(200)),
+            at(90, None),
+            at(120, Some(300)),
+            at(420, Some(250)),
+        ]));
+        gpx.add_track(track);
+
+        // Desde el minuto 1 no hay punto a los 3 minutos: la ventana llega al último
+        let best = best_window(&gpx, Duration::minutes(2), Metric::Power).unwrap();
+        assert_eq!((best.start_index, best.end_index), (1, 4));
+        assert!((best.average - (300.0 * 60.0 + 250.0 * 300.0) / 360.0).abs() < 1e-9);
+        assert_eq!(best.duration_seconds, 360.0);
+
+        let whole = best_window(&gpx, Duration::minutes(7), Metric::Power).unwrap();
+        assert_eq!((whole.start_index, whole.end_index), (0, 4));
+        assert!(
+            (whole.average - (200.0 * 60.0 + 300.0 * 60.0 + 250.0 * 300.0) / 420.0).abs() < 1e-9
+        );
+
+        assert_eq!(best_window(&gpx, Duration::minutes(8), Metric::Power), None);
+        assert_eq!(
+            best_window(&gpx, Duration::minutes(1), Metric::HeartRate),
+            None
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::gpx::{error::GpxError, parser::Gpx};
+use std::path::Path;
+use tokio::io::{AsyncBufRead, AsyncReadExt};
+
+impl Gpx {
+    /// Reads and parses a GPX file without blocking the async runtime (requires the `async` feature)
+    ///
+    /// Only the reading is asynchronous; parsing the text in memory is as fast
+    /// as with [`Gpx::from_file`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or for the same reasons as
+    /// [`Gpx::try_from_str`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gpx_extractor::Gpx;
+    ///
+    /// # async fn handler() -> Result<(), gpx_extractor::GpxError> {
+    /// let gpx = Gpx::from_file_async("uploads/ride.gpx").await?;
+    /// gpx.save_to_file_async("archive/ride.gpx").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_file_async(path: impl AsRef<Path>) -> Result<Self, GpxError> {
+        let file = tokio::fs::File::open(path).await?;
+        Self::from_reader_async(tokio::io::BufReader::new(file)).await
+    }
+
+    /// Parses a GPX document from an async buffered reader, such as an HTTP request body
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or for the same reasons as
+    /// [`Gpx::try_from_str`]
+    pub async fn from_reader_async<R>(mut reader: R) -> Result<Self, GpxError>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml).await?;
+        Self::try_from_str(&xml)
+    }
+
+    /// Saves the GPX to a file without blocking the async runtime
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub async fn save_to_file_async(&self, path: impl AsRef<Path>) -> Result<(), GpxError> {
+        tokio::fs::write(path, self.to_xml()).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_async_round_trip() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ride.gpx");
+
+        let xml = b"\xef\xbb\xbf<gpx><trk><name>Ride</name><trkseg><trkpt lat=\"40.0\" lon=\"-3.0\"/></trkseg></trk></gpx>";
+        let gpx = runtime.block_on(Gpx::from_reader_async(&xml[..])).unwrap();
+        runtime.block_on(gpx.save_to_file_async(&path)).unwrap();
+        let reopened = runtime.block_on(Gpx::from_file_async(&path)).unwrap();
+        assert_eq!(reopened.track_names(), vec!["Ride"]);
+
+        let missing = runtime.block_on(Gpx::from_file_async(dir.path().join("missing.gpx")));
+        assert!(matches!(missing, Err(GpxError::Io(_))));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::gpx::parser::Gpx;
+
+/// Geographic bounding box in decimal degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bounds {
+    /// Southernmost latitude
+    pub min_lat: f64,
+    /// Westernmost longitude
+    pub min_lon: f64,
+    /// Northernmost latitude
+    pub max_lat: f64,
+    /// Easternmost longitude
+    pub max_lon: f64,
+}
+
+impl Bounds {
+    /// Crea una caja que contiene un único punto
+    pub fn from_point(lat: f64, lon: f64) -> Self {
+        Self {
+            min_lat: lat,
+            min_lon: lon,
+            max_lat: lat,
+            max_lon: lon,
+        }
+    }
+
+    /// Smallest box containing every `(lat, lon)` pair, or `None` if there are none
+    pub fn from_coordinates<I>(coordinates: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+    {
+        let mut coordinates = coordinates.into_iter();
+        let (lat, lon) = coordinates.next()?;
+        let mut bounds = Self::from_point(lat, lon);
+        for (lat, lon) in coordinates {
+            bounds.extend(lat, lon);
+        }
+        Some(bounds)
+    }
+
+    /// Amplía la caja para incluir el punto
+    pub fn extend(&mut self, lat: f64, lon: f64) {
+        self.min_lat = self.min_lat.min(lat);
+        self.min_lon = self.min_lon.min(lon);
+        self.max_lat = self.max_lat.max(lat);
+        self.max_lon = self.max_lon.max(lon);
+    }
+
+    /// Verifica si el punto está dentro de la caja (bordes incluidos)
+    pub fn contains(self, lat: f64, lon: f64) -> bool {
+        (self.min_lat..=self.max_lat).contains(&lat) && (self.min_lon..=self.max_lon).contains(&lon)
+    }
+}
+
+impl Gpx {
+    /// Bounding box of every track point and waypoint, or `None` for an empty document
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_coordinates(
+            self.get_all_points()
+                .into_iter()
+                .map(|p| (p.lat, p.lon))
+                .chain(self.waypoints.iter().map(|w| (w.lat, w.lon))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GpxBuilder;
+
+    #[test]
+    fn test_gpx_bounds_include_points_and_waypoints() {
+        let gpx = GpxBuilder::new()
+            .track(|t| t.segment(|s| s.point(40.0, -3.5).point(40.2, -3.0)))
+            .waypoint_at(39.9, -3.2)
+            .build()
+            .unwrap();
+
+        let bounds = gpx.bounds().unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                min_lat: 39.9,
+                min_lon: -3.5,
+                max_lat: 40.2,
+                max_lon: -3.0,
+            }
+        );
+        assert!(bounds.contains(40.0, -3.2));
+        assert!(!bounds.contains(40.3, -3.2));
+        assert!(Gpx::new().bounds().is_none());
+    }
+
+    #[test]
+    fn test_bounds_written_on_output_and_parsed_back() {
+        let gpx = GpxBuilder::new()
+            .track(|t| t.segment(|s| s.point(40.0, -3.5).point(40.2, -3.0)))
+            .build()
+            .unwrap();
+        assert!(gpx.metadata.is_none());
+
+        let xml = gpx.to_xml();
+        assert!(xml.contains(
+            r#"<metadata><bounds minlat="40" minlon="-3.5" maxlat="40.2" maxlon="-3"/></metadata>"#
+        ));
+        let parsed = Gpx::try_from_str(&xml).unwrap();
+        assert_eq!(parsed.metadata.unwrap().bounds, gpx.bounds());
+
+        let options = crate::WriteOptions {
+            bounds: false,
+            ..crate::WriteOptions::default()
+        };
+        assert!(!gpx.to_xml_with(&options).unwrap().contains("<bounds"));
+        assert!(!Gpx::new().to_xml().contains("<metadata"));
+    }
+}
@@ -0,0 +1,357 @@
+use crate::gpx::{
+    error::GpxError,
+    extensions::Color,
+    metadata::Metadata,
+    parser::Gpx,
+    point::Point,
+    track::{Track, TrackSegment},
+    waypoint::Waypoint,
+};
+
+/// Fluent builder for [`Gpx`] documents
+///
+/// Coordinates are validated when [`GpxBuilder::build`] is called, so a chain of
+/// calls never panics and reports the first out-of-range point as an error.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::GpxBuilder;
+///
+/// let gpx = GpxBuilder::new()
+///     .creator("myapp")
+///     .track(|t| {
+///         t.name("Run").segment(|s| {
+///             s.point(40.4168, -3.7038)
+///                 .point_with_elevation(40.4170, -3.7040, 650.0)
+///         })
+///     })
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(gpx.total_points(), 2);
+/// assert!(GpxBuilder::new().waypoint_at(95.0, 0.0).build().is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GpxBuilder {
+    creator: Option<String>,
+    metadata: Option<Metadata>,
+    tracks: Vec<TrackBuilder>,
+    waypoints: Vec<Waypoint>,
+}
+
+impl GpxBuilder {
+    /// Crea un builder vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `creator` attribute written on output
+    #[must_use]
+    pub fn creator(mut self, creator: impl Into<String>) -> Self {
+        self.creator = Some(creator.into());
+        self
+    }
+
+    /// Sets the metadata timestamp (ISO 8601)
+    #[must_use]
+    pub fn time(mut self, time: impl Into<String>) -> Self {
+        self.metadata.get_or_insert_with(Metadata::default).time = Some(time.into());
+        self
+    }
+
+    /// Adds a track configured by `build`
+    #[must_use]
+    pub fn track<F>(mut self, build: F) -> Self
+    where
+        F: FnOnce(TrackBuilder) -> TrackBuilder,
+    {
+        self.tracks.push(build(TrackBuilder::new()));
+        self
+    }
+
+    /// Adds a waypoint
+    #[must_use]
+    pub fn waypoint(mut self, waypoint: Waypoint) -> Self {
+        self.waypoints.push(waypoint);
+        self
+    }
+
+    /// Adds an unnamed waypoint at the given coordinates
+    #[must_use]
+    pub fn waypoint_at(self, lat: f64, lon: f64) -> Self {
+        self.waypoint(Waypoint::new(lat, lon))
+    }
+
+    /// Builds the document
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidCoordinate`] for the first point or waypoint
+    /// outside the WGS84 range
+    pub fn build(self) -> Result<Gpx, GpxError> {
+        for waypoint in &self.waypoints {
+            Point::check_coordinates(waypoint.lat, waypoint.lon)?;
+        }
+
+        let mut gpx = Gpx::new();
+        gpx.creator = self.creator;
+        gpx.metadata = self.metadata;
+        gpx.waypoints = self.waypoints;
+        gpx.tracks = self
+            .tracks
+            .into_iter()
+            .map(TrackBuilder::build)
+            .collect::<Result<_, _>>()?;
+        Ok(gpx)
+    }
+}
+
+/// Fluent builder for a [`Track`], usually obtained through [`GpxBuilder::track`]
+#[derive(Debug, Clone, Default)]
+pub struct TrackBuilder {
+    name: Option<String>,
+    kind: Option<String>,
+    color: Option<Color>,
+    segments: Vec<SegmentBuilder>,
+}
+
+impl TrackBuilder {
+    /// Crea un builder de track vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the track name
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the activity type, such as `cycling`
+    #[must_use]
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    /// Sets the display color
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Adds a segment configured by `build`
+    #[must_use]
+    pub fn segment<F>(mut self, build: F) -> Self
+    where
+        F: FnOnce(SegmentBuilder) -> SegmentBuilder,
+    {
+        self.segments.push(build(SegmentBuilder::new()));
+        self
+    }
+
+    /// Builds the track
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidCoordinate`] for the first point outside the WGS84 range
+    pub fn build(self) -> Result<Track, GpxError> {
+        let mut track = Track::new();
+        track.name = self.name;
+        track.kind = self.kind;
+        if let Some(color) = self.color {
+            track.set_color(color);
+        }
+        for segment in self.segments {
+            track.add_segment(segment.build()?);
+        }
+        Ok(track)
+    }
+}
+
+/// Fluent builder for a [`TrackSegment`], usually obtained through [`TrackBuilder::segment`]
+#[derive(Debug, Clone, Default)]
+pub struct SegmentBuilder {
+    points: Vec<Point>,
+}
+
+impl SegmentBuilder {
+    /// Crea un builder de segmento vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a point with only coordinates
+    #[must_use]
+    pub fn point(self, lat: f64, lon: f64) -> Self {
+        self.push(Point::new(lat, lon))
+    }
+
+    /// Adds a point with elevation in meters
+    #[must_use]
+    pub fn point_with_elevation(self, lat: f64, lon: f64, elevation: f64) -> Self {
+        self.push(Point::with_elevation(lat, lon, elevation))
+    }
+
+    /// Adds an already constructed point
+    #[must_use]
+    pub fn push(mut self, point: Point) -> Self {
+        self.points.push(point);
+        self
+    }
+
+    /// Adds every point from an iterator
+    #[must_use]
+    pub fn points<I: IntoIterator<Item = Point>>(mut self, points: I) -> Self {
+        self.points.extend(points);
+        self
+    }
+
+    /// Builds the segment
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidCoordinate`] for the first point outside the WGS84 range
+    pub fn build(self) -> Result<TrackSegment, GpxError> {
+        for point in &self.points {
+            Point::check_coordinates(point.lat, point.lon)?;
+        }
+        Ok(TrackSegment::with_points(self.points))
+    }
+}
+
+/// Fluent builder for a [`Waypoint`]
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{GpxBuilder, WaypointBuilder};
+///
+/// let water = WaypointBuilder::new(40.4168, -3.7038)
+///     .name("Water")
+///     .symbol("Drinking Water")
+///     .build()
+///     .unwrap();
+/// let gpx = GpxBuilder::new().waypoint(water).build().unwrap();
+///
+/// assert_eq!(gpx.waypoint_names(), vec!["Water"]);
+/// assert!(WaypointBuilder::new(0.0, 181.0).build().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaypointBuilder {
+    waypoint: Waypoint,
+}
+
+impl WaypointBuilder {
+    /// Crea un builder de waypoint en las coordenadas dadas
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self {
+            waypoint: Waypoint::new(lat, lon),
+        }
+    }
+
+    /// Sets the waypoint name
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.waypoint.name = Some(name.into());
+        self
+    }
+
+    /// Sets the comment (`<cmt>`)
+    #[must_use]
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.waypoint.comment = Some(comment.into());
+        self
+    }
+
+    /// Sets the elevation in meters
+    #[must_use]
+    pub fn elevation(mut self, elevation: f64) -> Self {
+        self.waypoint.elevation = Some(elevation);
+        self
+    }
+
+    /// Sets the symbol (`<sym>`), such as `Summit`
+    #[must_use]
+    pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.waypoint.symbol = Some(symbol.into());
+        self
+    }
+
+    /// Sets the type (`<type>`), such as `Geocache`
+    #[must_use]
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.waypoint.kind = Some(kind.into());
+        self
+    }
+
+    /// Builds the waypoint
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidCoordinate`] if the position is outside the WGS84 range
+    pub fn build(self) -> Result<Waypoint, GpxError> {
+        Point::check_coordinates(self.waypoint.lat, self.waypoint.lon)?;
+        Ok(self.waypoint)
+    }
+}
+
+impl Gpx {
+    /// Starts a [`GpxBuilder`]
+    pub fn builder() -> GpxBuilder {
+        GpxBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_creates_nested_structure() {
+        let gpx = Gpx::builder()
+            .creator("myapp")
+            .time("2024-07-11T10:00:00Z")
+            .track(|t| {
+                t.name("Run")
+                    .color(Color::rgb(0xFF, 0, 0))
+                    .segment(|s| s.point(40.0, -3.0).point(40.001, -3.0))
+                    .segment(|s| s.point_with_elevation(40.002, -3.0, 700.0))
+            })
+            .waypoint(Waypoint::with_name(40.0, -3.0, "Start".to_string()))
+            .build()
+            .unwrap();
+
+        assert_eq!(gpx.creator.as_deref(), Some("myapp"));
+        assert_eq!(gpx.date(), Some("2024-07-11T10:00:00Z"));
+        assert_eq!(gpx.track_names(), vec!["Run"]);
+        assert_eq!(gpx.tracks[0].color(), Some(Color::rgb(0xFF, 0, 0)));
+        assert_eq!(gpx.total_segments(), 2);
+        assert_eq!(gpx.total_points(), 3);
+        assert_eq!(gpx.waypoint_names(), vec!["Start"]);
+        assert!(gpx.to_xml().contains("creator=\"myapp\""));
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_coordinates() {
+        let result = GpxBuilder::new()
+            .track(|t| t.segment(|s| s.point(40.0, 0.0).point(40.0, 200.0)))
+            .build();
+        assert!(matches!(
+            result,
+            Err(GpxError::InvalidCoordinate { lon, .. }) if lon == 200.0
+        ));
+
+        assert!(GpxBuilder::new().waypoint_at(-91.0, 0.0).build().is_err());
+    }
+
+    #[test]
+    fn test_empty_builder() {
+        let gpx = GpxBuilder::new().build().unwrap();
+        assert!(gpx.is_empty());
+        assert!(gpx.creator.is_none());
+    }
+}
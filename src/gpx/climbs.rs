@@ -0,0 +1,320 @@
+use crate::gpx::parser::Gpx;
+use std::fmt;
+
+/// Distancia sobre la que se mide la pendiente al recortar el inicio de una subida
+const TRIM_WINDOW_KM: f64 = 0.1;
+
+/// Options for [`Gpx::detect_climbs`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClimbOptions {
+    /// Minimum average grade, in percent
+    pub min_grade_percent: f64,
+    /// Minimum length, in kilometers
+    pub min_length_km: f64,
+    /// Largest drop, in meters, tolerated inside a climb before it is considered over
+    pub max_dip_m: f64,
+    /// Whether to report descents too, with negative gain and grade
+    pub descents: bool,
+}
+
+impl Default for ClimbOptions {
+    fn default() -> Self {
+        Self {
+            min_grade_percent: 3.0,
+            min_length_km: 0.5,
+            max_dip_m: 10.0,
+            descents: false,
+        }
+    }
+}
+
+/// Climb category as used by cycling sites, from the product of length and grade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ClimbCategory {
+    /// Category 4, the easiest categorized climbs
+    Category4,
+    /// Category 3
+    Category3,
+    /// Category 2
+    Category2,
+    /// Category 1
+    Category1,
+    /// Hors catégorie, the hardest climbs
+    HorsCategorie,
+}
+
+impl ClimbCategory {
+    /// Categoriza una subida según longitud (m) × pendiente (%); `None` si no llega a categoría 4
+    pub fn from_score(score: f64) -> Option<Self> {
+        match score {
+            s if s >= 80_000.0 => Some(Self::HorsCategorie),
+            s if s >= 64_000.0 => Some(Self::Category1),
+            s if s >= 32_000.0 => Some(Self::Category2),
+            s if s >= 16_000.0 => Some(Self::Category3),
+            s if s >= 8_000.0 => Some(Self::Category4),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ClimbCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Category4 => "Cat 4",
+            Self::Category3 => "Cat 3",
+            Self::Category2 => "Cat 2",
+            Self::Category1 => "Cat 1",
+            Self::HorsCategorie => "HC",
+        })
+    }
+}
+
+/// A climb or descent found by [`Gpx::detect_climbs`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Climb {
+    /// Index of the first point, in [`Gpx::get_all_points`] order
+    pub start_index: usize,
+    /// Index of the last point, in [`Gpx::get_all_points`] order
+    pub end_index: usize,
+    /// Length in kilometers
+    pub length_km: f64,
+    /// Elevation difference between the ends, in meters (negative for descents)
+    pub elevation_gain_m: f64,
+    /// Average grade, in percent (negative for descents)
+    pub average_grade_percent: f64,
+    /// Category of the climb; always `None` for descents
+    pub category: Option<ClimbCategory>,
+}
+
+impl Climb {
+    /// Verifica si es un descenso
+    pub fn is_descent(self) -> bool {
+        self.elevation_gain_m < 0.0
+    }
+}
+
+/// Punto del perfil: índice global, distancia acumulada (km) y elevación (m)
+type ProfilePoint = (usize, f64, f64);
+
+/// Busca subidas en un perfil; con `sign` negativo busca descensos
+fn detect(profile: &[ProfilePoint], options: ClimbOptions, sign: f64, out: &mut Vec<Climb>) {
+    let elevation = |i: usize| profile[i].2 * sign;
+    let (mut start, mut top) = (0, 0);
+    for i in 1..profile.len() {
+        if elevation(i) > elevation(top) {
+            top = i;
+        } else if elevation(top) - elevation(i) > options.max_dip_m {
+            emit(profile, options, sign, start, top, out);
+            (start, top) = (i, i);
+            continue;
+        }
+        if elevation(i) < elevation(start) {
+            (start, top) = (i, i);
+        }
+    }
+    emit(profile, options, sign, start, top, out);
+}
+
+fn emit(
+    profile: &[ProfilePoint],
+    options: ClimbOptions,
+    sign: f64,
+    mut start: usize,
+    end: usize,
+    out: &mut Vec<Climb>,
+) {
+    let elevation = |i: usize| profile[i].2 * sign;
+    let grade = |from: usize, to: usize| {
+        let length_m = (profile[to].1 - profile[from].1) * 1000.0;
+        if length_m > 0.0 {
+            (elevation(to) - elevation(from)) / length_m * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    // El punto más bajo puede estar al final de un llano: se descarta la parte sin pendiente
+    while start < end {
+        let window = (start + 1..end)
+            .find(|&j| profile[j].1 - profile[start].1 >= TRIM_WINDOW_KM)
+            .unwrap_or(end);
+        if grade(start, window) >= options.min_grade_percent {
+            break;
+        }
+        start += 1;
+    }
+
+    let length_km = profile[end].1 - profile[start].1;
+    let average_grade = grade(start, end);
+    if start >= end
+        || length_km < options.min_length_km
+        || average_grade < options.min_grade_percent
+    {
+        return;
+    }
+
+    let category = (sign > 0.0)
+        .then(|| ClimbCategory::from_score(length_km * 1000.0 * average_grade))
+        .flatten();
+    out.push(Climb {
+        start_index: profile[start].0,
+        end_index: profile[end].0,
+        length_km,
+        elevation_gain_m: (elevation(end) - elevation(start)) * sign,
+        average_grade_percent: average_grade * sign,
+        category,
+    });
+}
+
+impl Gpx {
+    /// Finds the climbs (and optionally descents) of the recording
+    ///
+    /// A climb runs from a low point to the highest point reached before the
+    /// elevation drops more than `max_dip_m`; flat ground before it is trimmed.
+    /// It is reported if it is long and steep enough. Climbs never span two
+    /// segments and points without elevation are ignored. Results are ordered by
+    /// `start_index`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{ClimbCategory, ClimbOptions, GpxBuilder};
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| {
+    ///         t.segment(|s| {
+    ///             s.point_with_elevation(40.00, -3.0, 600.0)
+    ///                 .point_with_elevation(40.01, -3.0, 600.0)
+    ///                 .point_with_elevation(40.02, -3.0, 700.0)
+    ///                 .point_with_elevation(40.03, -3.0, 800.0)
+    ///         })
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let climbs = gpx.detect_climbs(ClimbOptions::default());
+    /// assert_eq!(climbs.len(), 1);
+    /// assert_eq!((climbs[0].start_index, climbs[0].end_index), (1, 3));
+    /// assert!((climbs[0].average_grade_percent - 9.0).abs() < 0.1);
+    /// assert_eq!(climbs[0].category, Some(ClimbCategory::Category3));
+    /// ```
+    pub fn detect_climbs(&self, options: ClimbOptions) -> Vec<Climb> {
+        let metrics = self.point_metrics();
+        let mut climbs = Vec::new();
+        let mut index = 0;
+
+        for segment in self.tracks.iter().flat_map(|track| &track.segments) {
+            let profile: Vec<ProfilePoint> = segment
+                .points
+                .iter()
+                .enumerate()
+                .filter_map(|(i, point)| {
+                    let global = index + i;
+                    point
+                        .elevation
+                        .map(|ele| (global, metrics[global].distance_km, ele))
+                })
+                .collect();
+            index += segment.points.len();
+
+            if profile.len() < 2 {
+                continue;
+            }
+            detect(&profile, options, 1.0, &mut climbs);
+            if options.descents {
+                detect(&profile, options, -1.0, &mut climbs);
+            }
+        }
+
+        climbs.sort_by_key(|climb| climb.start_index);
+        climbs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{
+        point::Point,
+        track::{Track, TrackSegment},
+    };
+
+    /// Perfil a lo largo de un meridiano con puntos cada 0.001° (~111 m)
+    fn profile(elevations: &[f64]) -> Gpx {
+        let points = elevations
+            .iter()
+            .enumerate()
+            .map(|(i, &ele)| {
+                #[allow(clippy::cast_precision_loss)]
+                let lat = 40.0 + i as f64 * 0.001;
+                Point::with_elevation(lat, -3.0, ele)
+            })
+            .collect();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(points));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_climb_tolerates_small_dips_and_trims_flat_start() {
+        // Llano con ruido, subida con un bache de 5 m, cima y bajada larga
+        let mut elevations = vec![100.0, 101.0, 99.0, 100.0, 100.0];
+        elevations.extend([110.0, 120.0, 115.0, 130.0, 140.0, 150.0, 160.0]);
+        elevations.extend([150.0, 135.0, 120.0, 105.0, 100.0, 95.0]);
+        let gpx = profile(&elevations);
+
+        let climbs = gpx.detect_climbs(ClimbOptions::default());
+        assert_eq!(climbs.len(), 1);
+        let climb = climbs[0];
+        assert_eq!((climb.start_index, climb.end_index), (4, 11));
+        assert!((climb.elevation_gain_m - 60.0).abs() < 1e-9);
+        assert!((climb.length_km - 0.7784).abs() < 0.001);
+        assert!(climb.average_grade_percent > 7.5);
+        assert_eq!(climb.category, None);
+        assert!(!climb.is_descent());
+
+        let options = ClimbOptions {
+            descents: true,
+            ..ClimbOptions::default()
+        };
+        let both = gpx.detect_climbs(options);
+        assert_eq!(both.len(), 2);
+        let descent = both[1];
+        assert!(descent.is_descent());
+        assert_eq!((descent.start_index, descent.end_index), (11, 17));
+        assert!((descent.elevation_gain_m + 65.0).abs() < 1e-9);
+        assert!(descent.average_grade_percent < -3.0);
+        assert_eq!(descent.category, None);
+    }
+
+    #[test]
+    fn test_short_or_gentle_climbs_are_ignored() {
+        let gentle = profile(&[100.0, 102.0, 104.0, 106.0, 108.0, 110.0, 112.0]);
+        assert!(gentle.detect_climbs(ClimbOptions::default()).is_empty());
+
+        let short = profile(&[100.0, 120.0, 140.0]);
+        assert!(short.detect_climbs(ClimbOptions::default()).is_empty());
+        let relaxed = ClimbOptions {
+            min_length_km: 0.2,
+            ..ClimbOptions::default()
+        };
+        assert_eq!(short.detect_climbs(relaxed).len(), 1);
+    }
+
+    #[test]
+    fn test_climb_categories() {
+        assert_eq!(ClimbCategory::from_score(7_999.0), None);
+        assert_eq!(
+            ClimbCategory::from_score(10_000.0),
+            Some(ClimbCategory::Category4)
+        );
+        assert_eq!(
+            ClimbCategory::from_score(90_000.0),
+            Some(ClimbCategory::HorsCategorie)
+        );
+        assert_eq!(ClimbCategory::Category1.to_string(), "Cat 1");
+        assert!(ClimbCategory::HorsCategorie > ClimbCategory::Category1);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, Point},
+    waypoint::Waypoint,
+};
+use std::collections::HashSet;
+
+/// Prefijos de los nombres que ponen los GPS por defecto ("WPT 12", "POI007"...)
+const GENERIC_NAME_PREFIXES: [&str; 4] = ["waypoint", "wpt", "wp", "poi"];
+
+/// Calidad del nombre de un waypoint: sin nombre, genérico o de verdad
+fn name_rank(waypoint: &Waypoint) -> (u8, usize) {
+    let Some(name) = waypoint
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    else {
+        return (0, 0);
+    };
+    let lower = name.to_lowercase();
+    let rest = GENERIC_NAME_PREFIXES
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .unwrap_or(&lower);
+    let generic = !name.chars().any(char::is_alphabetic)
+        || rest
+            .trim_start_matches([' ', '-', '_'])
+            .chars()
+            .all(|c| c.is_ascii_digit());
+    (if generic { 1 } else { 2 }, name.chars().count())
+}
+
+/// Completa los datos que le faltan a `kept` con los de `other`
+fn fill_missing(kept: &mut Waypoint, other: &Waypoint) {
+    let fill = |field: &mut Option<String>, from: &Option<String>| {
+        if field.is_none() {
+            field.clone_from(from);
+        }
+    };
+    fill(&mut kept.comment, &other.comment);
+    fill(&mut kept.description, &other.description);
+    fill(&mut kept.symbol, &other.symbol);
+    fill(&mut kept.kind, &other.kind);
+    kept.elevation = kept.elevation.or(other.elevation);
+    kept.time = kept.time.or(other.time);
+    if kept.links.is_empty() {
+        kept.links.clone_from(&other.links);
+    }
+}
+
+impl Gpx {
+    /// Merges waypoints closer than `radius_m` meters, returning how many were removed
+    ///
+    /// Waypoints are grouped in document order: each one joins the first group
+    /// whose first waypoint is within `radius_m`, or starts a new group. Every
+    /// group keeps its best-named waypoint, at its own position: a real name
+    /// beats a generic one such as `"WPT 012"`, which beats no name at all, and
+    /// longer names win ties. The fields it lacks (description, symbol,
+    /// elevation...) are taken from the rest of the group. Useful when combining
+    /// points of interest from several sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, Waypoint};
+    ///
+    /// let mut gpx = Gpx::new();
+    /// gpx.add_waypoint(Waypoint::with_name(40.0, -3.0, "WPT001".to_string()));
+    /// gpx.add_waypoint(Waypoint::with_name(40.000_1, -3.0, "Fuente del Cura".to_string()));
+    /// gpx.add_waypoint(Waypoint::with_name(40.1, -3.0, "Refugio".to_string()));
+    ///
+    /// assert_eq!(gpx.cluster_waypoints(25.0), 1);
+    /// assert_eq!(gpx.waypoint_names(), vec!["Fuente del Cura", "Refugio"]);
+    /// ```
+    pub fn cluster_waypoints(&mut self, radius_m: f64) -> usize {
+        let mut groups: Vec<Vec<Waypoint>> = Vec::new();
+        for waypoint in std::mem::take(&mut self.waypoints) {
+            let position = Point::new(waypoint.lat, waypoint.lon);
+            let group = groups.iter_mut().find(|group| {
+                let seed = Point::new(group[0].lat, group[0].lon);
+                haversine_distance(&seed, &position) * 1000.0 <= radius_m
+            });
+            match group {
+                Some(group) => group.push(waypoint),
+                None => groups.push(vec![waypoint]),
+            }
+        }
+
+        let before: usize = groups.iter().map(Vec::len).sum();
+        self.waypoints = groups
+            .into_iter()
+            .map(|mut group| {
+                // En caso de empate gana el primero
+                let best = (0..group.len())
+                    .rev()
+                    .max_by_key(|&i| name_rank(&group[i]))
+                    .unwrap_or(0);
+                let mut kept = group.swap_remove(best);
+                for other in &group {
+                    fill_missing(&mut kept, other);
+                }
+                kept
+            })
+            .collect();
+        before - self.waypoints.len()
+    }
+
+    /// Removes repeated waypoints, returning how many were removed
+    ///
+    /// A waypoint is repeated when an earlier one has the same coordinates and
+    /// the same name, ignoring case and surrounding spaces. The first copy is
+    /// kept.
+    pub fn dedupe_waypoints(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        self.filter_waypoints_by(|waypoint| {
+            seen.insert((
+                waypoint.lat.to_bits(),
+                waypoint.lon.to_bits(),
+                waypoint
+                    .name
+                    .as_deref()
+                    .map(|name| name.trim().to_lowercase()),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_rank() {
+        let named = |name: &str| Waypoint::with_name(0.0, 0.0, name.to_string());
+        assert_eq!(name_rank(&Waypoint::new(0.0, 0.0)), (0, 0));
+        assert_eq!(name_rank(&named("  ")), (0, 0));
+        assert_eq!(name_rank(&named("WPT 012")).0, 1);
+        assert_eq!(name_rank(&named("007")).0, 1);
+        assert_eq!(name_rank(&named("Waypoint-3")).0, 1);
+        assert_eq!(name_rank(&named("Poinsettia")).0, 2);
+        assert_eq!(name_rank(&named("Fuente")), (2, 6));
+    }
+
+    #[test]
+    fn test_cluster_keeps_best_name_and_fills_fields() {
+        let mut gpx = Gpx::new();
+        let mut unnamed = Waypoint::new(40.0, -3.0);
+        unnamed.symbol = Some("Drinking Water".to_string());
+        gpx.add_waypoint(unnamed);
+        let mut spring = Waypoint::with_name(40.000_1, -3.0, "Fuente".to_string());
+        spring.elevation = Some(950.0);
+        gpx.add_waypoint(spring);
+        // Empata en nombre con "Fuente", que va antes
+        gpx.add_waypoint(Waypoint::with_name(40.000_2, -3.0, "Source".to_string()));
+        // A más de 25 m del primero del grupo, aunque cerca del último
+        gpx.add_waypoint(Waypoint::with_name(40.000_35, -3.0, "Mirador".to_string()));
+
+        assert_eq!(gpx.cluster_waypoints(25.0), 2);
+        assert_eq!(gpx.waypoint_names(), vec!["Fuente", "Mirador"]);
+        let kept = &gpx.waypoints[0];
+        assert_eq!((kept.lat, kept.elevation), (40.000_1, Some(950.0)));
+        assert_eq!(kept.symbol.as_deref(), Some("Drinking Water"));
+    }
+
+    #[test]
+    fn test_dedupe_waypoints() {
+        let mut gpx = Gpx::new();
+        gpx.add_waypoint(Waypoint::with_name(40.0, -3.0, "Summit".to_string()));
+        gpx.add_waypoint(Waypoint::with_name(40.0, -3.0, " summit ".to_string()));
+        gpx.add_waypoint(Waypoint::with_name(40.0, -3.0, "Hut".to_string()));
+        gpx.add_waypoint(Waypoint::new(40.0, -3.0));
+        gpx.add_waypoint(Waypoint::new(40.0, -3.0));
+
+        assert_eq!(gpx.dedupe_waypoints(), 2);
+        assert_eq!(gpx.waypoints.len(), 3);
+        assert_eq!(gpx.waypoints[0].name.as_deref(), Some("Summit"));
+    }
+}
@@ -0,0 +1,408 @@
+use crate::gpx::{
+    error::GpxError,
+    extensions::Color,
+    fingerprint::fnv1a,
+    formats::{detect, FormatRegistry},
+    parser::{start_time, Gpx},
+    template::NameTemplate,
+};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A document loaded into a [`GpxCollection`], together with its source path
+#[derive(Debug, Clone)]
+pub struct CollectionEntry {
+    /// File the document was read from
+    pub path: PathBuf,
+    /// Parsed document
+    pub gpx: Gpx,
+}
+
+/// Options for [`GpxCollection::into_single_gpx`]
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    /// Name of the merged document (`<metadata><name>`)
+    pub name: Option<String>,
+    /// Template for each track name; receives the values of
+    /// [`Gpx::template_values`] plus `file` (file stem) and `n` (1-based index).
+    /// When `None`, the activity's own track name or the file stem is used.
+    pub track_name: Option<NameTemplate>,
+    /// Colors assigned to the tracks in turn; empty keeps the original colors
+    pub palette: Vec<Color>,
+    /// Drop activities whose points are identical to an earlier one
+    pub deduplicate: bool,
+    /// Copy the waypoints of every file (exact duplicates are dropped)
+    pub include_waypoints: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            name: None,
+            track_name: None,
+            palette: Vec::new(),
+            deduplicate: true,
+            include_waypoints: true,
+        }
+    }
+}
+
+/// A set of documents loaded from a directory
+///
+/// Files are recognized by content (see [`detect`]) rather than by extension,
+/// so directories mixing GPX with other supported formats load transparently.
+/// Files in an unrecognized format, or in a format without a registered reader,
+/// are skipped; files that look supported but fail to decode are reported in
+/// [`GpxCollection::failures`].
+#[derive(Debug, Default)]
+pub struct GpxCollection {
+    /// Successfully loaded documents, sorted by path
+    pub entries: Vec<CollectionEntry>,
+    /// Files that were recognized but could not be decoded
+    pub failures: Vec<(PathBuf, GpxError)>,
+    /// Directory the collection was loaded from, if any
+    pub root: Option<PathBuf>,
+}
+
+impl GpxCollection {
+    /// Crea una colección vacía
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every supported file in a directory (non-recursive)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be listed
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, GpxError> {
+        Self::from_dir_with(dir, &FormatRegistry::default())
+    }
+
+    /// Loads every file in a directory that `registry` can read (non-recursive)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be listed
+    pub fn from_dir_with<P: AsRef<Path>>(
+        dir: P,
+        registry: &FormatRegistry,
+    ) -> Result<Self, GpxError> {
+        let mut collection = Self {
+            root: Some(dir.as_ref().to_path_buf()),
+            ..Self::new()
+        };
+        for path in list_files(dir.as_ref())? {
+            match std::fs::read(&path) {
+                Ok(bytes) => collection.load(path, &bytes, registry),
+                Err(e) => collection.failures.push((path, e.into())),
+            }
+        }
+
+        Ok(collection)
+    }
+
+    /// Decodifica un archivo si su formato es reconocido y tiene lector registrado
+    pub(crate) fn load(&mut self, path: PathBuf, bytes: &[u8], registry: &FormatRegistry) {
+        let Some(format) = detect(bytes) else {
+            return;
+        };
+        let Some(reader) = registry.reader_for_extension(format.extension()) else {
+            return;
+        };
+
+        match reader.read(bytes) {
+            Ok(gpx) => self.push(path, gpx),
+            Err(e) => self.failures.push((path, e)),
+        }
+    }
+
+    /// Agrega un documento a la colección
+    pub fn push(&mut self, path: PathBuf, gpx: Gpx) {
+        self.entries.push(CollectionEntry { path, gpx });
+    }
+
+    /// Número de documentos cargados
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Verifica si la colección está vacía
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the loaded documents
+    pub fn iter(&self) -> impl Iterator<Item = &Gpx> {
+        self.entries.iter().map(|entry| &entry.gpx)
+    }
+
+    /// Distancia total de todos los documentos en kilómetros
+    pub fn total_distance_km(&self) -> f64 {
+        self.iter().map(|gpx| gpx.total_distance_km()).sum()
+    }
+
+    /// Merges every document into a single GPX with one track per activity
+    ///
+    /// Activities are sorted by start time (undated ones last) and each becomes one
+    /// track holding all of its segments. The start dates are stored as metadata
+    /// keywords and the metadata bounds cover the whole result, so map viewers can
+    /// open the "all my 2024 rides" file directly.
+    pub fn into_single_gpx(self, options: &MergeOptions) -> Gpx {
+        let mut activities: Vec<(Option<DateTime<Utc>>, CollectionEntry)> = self
+            .entries
+            .into_iter()
+            .map(|entry| (start_time(&entry.gpx), entry))
+            .collect();
+        // Las actividades sin fecha van al final, manteniendo el orden de archivo
+        activities.sort_by_key(|(start, _)| (start.is_none(), *start));
+
+        let mut merged = Gpx::new();
+        let mut seen_activities = HashSet::new();
+        let mut seen_waypoints = HashSet::new();
+        let mut dates = BTreeSet::new();
+
+        for (start, entry) in activities {
+            if entry.gpx.tracks.is_empty() && entry.gpx.waypoints.is_empty() {
+                continue;
+            }
+            if options.deduplicate && !seen_activities.insert(points_fingerprint(&entry.gpx)) {
+                continue;
+            }
+            if let Some(start) = start {
+                dates.insert(start.format("%Y-%m-%d").to_string());
+            }
+
+            if options.include_waypoints {
+                for waypoint in &entry.gpx.waypoints {
+                    let key = (
+                        waypoint.lat.to_bits(),
+                        waypoint.lon.to_bits(),
+                        waypoint.name.clone(),
+                    );
+                    if seen_waypoints.insert(key) {
+                        merged.waypoints.push(waypoint.clone());
+                    }
+                }
+            }
+            if entry.gpx.tracks.is_empty() {
+                continue;
+            }
+
+            let index = merged.tracks.len();
+            let mut track = entry.gpx.tracks[0].header();
+            track.name = Some(activity_name(&entry, index, options));
+            if !options.palette.is_empty() {
+                track.set_color(options.palette[index % options.palette.len()]);
+            }
+            track.segments = entry
+                .gpx
+                .tracks
+                .into_iter()
+                .flat_map(|track| track.segments)
+                .collect();
+            merged.add_track(track);
+        }
+
+        if let Some(name) = &options.name {
+            merged.set_name(name.clone());
+        }
+        if !dates.is_empty() {
+            merged.set_keywords(&dates);
+        }
+        if merged.bounds().is_some() {
+            merged.update_metadata_bounds();
+        }
+        merged
+    }
+}
+
+/// Archivos regulares del directorio, ordenados por ruta
+pub(crate) fn list_files(dir: &Path) -> Result<Vec<PathBuf>, GpxError> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+    Ok(paths)
+}
+
+/// Huella exacta de los puntos de una actividad, para detectar exportaciones duplicadas
+fn points_fingerprint(gpx: &Gpx) -> u64 {
+    // Los valores ausentes usan marcas que ningún dato real produce
+    let points = gpx.get_all_points().into_iter().flat_map(|point| {
+        let time = point.time.map_or((i64::MIN, u32::MAX), |time| {
+            (time.timestamp(), time.timestamp_subsec_nanos())
+        });
+        [
+            point.lat.to_bits(),
+            point.lon.to_bits(),
+            point.elevation.map_or(u64::MAX, f64::to_bits),
+            u64::from_le_bytes(time.0.to_le_bytes()),
+            u64::from(time.1),
+        ]
+    });
+    let waypoints = gpx
+        .waypoints
+        .iter()
+        .flat_map(|waypoint| [waypoint.lat.to_bits(), waypoint.lon.to_bits()]);
+    fnv1a(points.chain(waypoints).flat_map(u64::to_le_bytes))
+}
+
+fn activity_name(entry: &CollectionEntry, index: usize, options: &MergeOptions) -> String {
+    let stem = entry
+        .path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    match &options.track_name {
+        Some(template) => {
+            let mut values = entry.gpx.template_values();
+            values.set("file", stem);
+            values.set("n", index + 1);
+            template.render(&values)
+        }
+        None => entry
+            .gpx
+            .tracks
+            .iter()
+            .find_map(|track| track.name.clone())
+            .unwrap_or(stem),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> tempfile::TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_from_dir_sniffs_content_and_skips_unknown_files() {
+        let dir = temp_dir();
+        std::fs::write(
+            dir.path().join("a.gpx"),
+            r"<gpx><trk><name>A</name></trk></gpx>",
+        )
+        .unwrap();
+        // Extensión engañosa: el contenido es GPX
+        std::fs::write(
+            dir.path().join("b.xml"),
+            r"<gpx><trk><name>B</name></trk></gpx>",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a track").unwrap();
+        std::fs::write(dir.path().join("c.kml"), "<kml></kml>").unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+
+        let collection = GpxCollection::from_dir(dir.path()).unwrap();
+        assert_eq!(collection.len(), 2);
+        assert_eq!(collection.entries[0].gpx.track_names(), vec!["A"]);
+        assert_eq!(collection.entries[1].gpx.track_names(), vec!["B"]);
+        assert!(collection.failures.is_empty());
+    }
+
+    #[test]
+    fn test_from_dir_reports_broken_files() {
+        let dir = temp_dir();
+        std::fs::write(dir.path().join("broken.gpx"), "<gpx><trk></gpx>").unwrap();
+
+        let collection = GpxCollection::from_dir(dir.path()).unwrap();
+        assert!(collection.is_empty());
+        assert_eq!(collection.failures.len(), 1);
+        assert!(collection.failures[0].0.ends_with("broken.gpx"));
+    }
+
+    #[test]
+    fn test_from_dir_missing_directory() {
+        let result = GpxCollection::from_dir("/nonexistent-gpx-dir");
+        assert!(matches!(result, Err(GpxError::Io(_))));
+    }
+
+    fn ride(date: &str, lat: f64) -> String {
+        format!(
+            r#"<gpx version="1.1" creator="test"><trk><name>Ride {date}</name><trkseg>
+            <trkpt lat="{lat}" lon="-3.0"><time>{date}T08:00:00Z</time></trkpt>
+            <trkpt lat="{lat}" lon="-3.1"><time>{date}T09:00:00Z</time></trkpt>
+            </trkseg><trkseg><trkpt lat="{lat}" lon="-3.2"/></trkseg></trk>
+            <wpt lat="40.0" lon="-3.0"><name>Home</name></wpt></gpx>"#
+        )
+    }
+
+    #[test]
+    fn test_into_single_gpx_merges_sorted_and_deduplicated() {
+        let dir = temp_dir();
+        std::fs::write(dir.path().join("a.gpx"), ride("2024-05-02", 40.2)).unwrap();
+        std::fs::write(dir.path().join("b.gpx"), ride("2024-03-01", 40.1)).unwrap();
+        // Misma actividad exportada dos veces
+        std::fs::write(dir.path().join("c.gpx"), ride("2024-03-01", 40.1)).unwrap();
+
+        let merged = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .into_single_gpx(&MergeOptions {
+                name: Some("Rides 2024".to_string()),
+                palette: vec![Color::rgb(0xFF, 0, 0), Color::rgb(0, 0, 0xFF)],
+                ..MergeOptions::default()
+            });
+
+        assert_eq!(
+            merged.track_names(),
+            vec!["Ride 2024-03-01", "Ride 2024-05-02"]
+        );
+        assert_eq!(merged.tracks[0].segments.len(), 2);
+        assert_eq!(merged.tracks[1].color(), Some(Color::rgb(0, 0, 0xFF)));
+        assert_eq!(merged.waypoints.len(), 1);
+
+        let metadata = merged.metadata.as_ref().unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Rides 2024"));
+        assert_eq!(metadata.keyword_list(), vec!["2024-03-01", "2024-05-02"]);
+        assert_eq!(metadata.bounds.unwrap().max_lat, 40.2);
+    }
+
+    #[test]
+    fn test_into_single_gpx_with_template_and_duplicates() {
+        let dir = temp_dir();
+        std::fs::write(dir.path().join("a.gpx"), ride("2024-05-02", 40.2)).unwrap();
+        std::fs::write(dir.path().join("b.gpx"), ride("2024-05-02", 40.2)).unwrap();
+
+        let merged = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .into_single_gpx(&MergeOptions {
+                track_name: Some("#{n} {file} {distance_km:.1} km".parse().unwrap()),
+                deduplicate: false,
+                include_waypoints: false,
+                ..MergeOptions::default()
+            });
+
+        assert_eq!(merged.track_names(), vec!["#1 a 8.5 km", "#2 b 8.5 km"]);
+        assert!(merged.waypoints.is_empty());
+    }
+    #[test]
+    fn test_points_fingerprint_is_stable() {
+        let gpx = Gpx::try_from_str(&ride("2024-03-01", 40.1)).unwrap();
+        // Valor fijo: la huella no puede cambiar entre versiones ni plataformas
+        assert_eq!(points_fingerprint(&gpx), 0x84ca_a061_f809_f3f7);
+
+        let mut moved = gpx.clone();
+        moved.tracks[0].segments[0].points[0].elevation = Some(0.0);
+        assert_ne!(points_fingerprint(&moved), points_fingerprint(&gpx));
+    }
+
+    #[test]
+    fn test_into_single_gpx_keeps_the_track_header() {
+        let mut gpx = Gpx::try_from_str(&ride("2024-05-02", 40.2)).unwrap();
+        gpx.tracks[0].description = Some("Sunny".to_string());
+        gpx.tracks[0].user_data.insert("bike", "gravel");
+        let mut collection = GpxCollection::new();
+        collection.push(PathBuf::from("a.gpx"), gpx);
+
+        let merged = collection.into_single_gpx(&MergeOptions::default());
+        let track = &merged.tracks[0];
+        assert_eq!(track.description.as_deref(), Some("Sunny"));
+        assert_eq!(track.user_data.get("bike"), Some("gravel"));
+    }
+}
@@ -0,0 +1,514 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{Point, EARTH_RADIUS_KM},
+    track::Track,
+};
+
+const EARTH_RADIUS_M: f64 = EARTH_RADIUS_KM * 1000.0;
+
+/// Curve distance used by [`Track::similarity`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DistanceMetric {
+    /// Largest distance from a point of either track to the nearest stretch of the
+    /// other. Ignores direction and order, so a route ridden backwards still matches.
+    #[default]
+    Hausdorff,
+    /// Discrete Fréchet distance: like walking both tracks at once, it also takes
+    /// the order of the points into account. Detects loops ridden in the other
+    /// direction or out-and-backs cut short.
+    DiscreteFrechet,
+}
+
+/// Options for [`Track::similarity`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompareOptions {
+    /// Curve distance to compute
+    pub metric: DistanceMetric,
+    /// Distance, in meters, within which a point counts as overlapping the other track
+    pub overlap_tolerance_m: f64,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            metric: DistanceMetric::Hausdorff,
+            overlap_tolerance_m: 25.0,
+        }
+    }
+}
+
+/// How close two tracks are, see [`Track::similarity`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Similarity {
+    /// Curve distance between the tracks in meters, with the chosen [`DistanceMetric`]
+    pub distance_m: f64,
+    /// Share of the points of this track lying near the other one, in percent
+    pub overlap_percent: f64,
+    /// Share of the points of the other track lying near this one, in percent
+    pub other_overlap_percent: f64,
+}
+
+/// Stretch of a recording that left the planned route, see [`Gpx::compare_to_route`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deviation {
+    /// Index of the first off-route point in [`Gpx::get_all_points`] order
+    pub start_index: usize,
+    /// Index of the last off-route point in [`Gpx::get_all_points`] order
+    pub end_index: usize,
+    /// Largest distance from the route, in meters
+    pub max_distance_m: f64,
+    /// Distance travelled off the route, in kilometers
+    pub distance_km: f64,
+    /// Time spent off the route, in seconds; `None` without timestamps
+    pub duration_seconds: Option<i64>,
+}
+
+/// Elevation of two routes at the same distance from their start, see [`elevation_overlay`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlaySample {
+    /// Distance from the start, in kilometers
+    pub distance_km: f64,
+    /// Elevation of the first route, in meters; `None` past its end or without elevation data
+    pub a: Option<f64>,
+    /// Elevation of the second route, in meters; `None` past its end or without elevation data
+    pub b: Option<f64>,
+}
+
+/// Coordenadas planas (metros) sobre una proyección equirectangular común
+type Xy = (f64, f64);
+
+fn to_xy(point: &Point, cos_lat: f64) -> Xy {
+    (
+        point.lon.to_radians() * cos_lat * EARTH_RADIUS_M,
+        point.lat.to_radians() * EARTH_RADIUS_M,
+    )
+}
+
+fn distance(a: Xy, b: Xy) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Distancia de `p` al tramo `a`–`b`
+fn distance_to_segment(p: Xy, a: Xy, b: Xy) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    if length_sq == 0.0 {
+        return distance(p, a);
+    }
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0);
+    distance(p, (a.0 + t * dx, a.1 + t * dy))
+}
+
+/// Puntos proyectados de un track, agrupados por segmento
+fn project(track: &Track, cos_lat: f64) -> Vec<Vec<Xy>> {
+    track
+        .segments
+        .iter()
+        .map(|segment| segment.points.iter().map(|p| to_xy(p, cos_lat)).collect())
+        .filter(|points: &Vec<Xy>| !points.is_empty())
+        .collect()
+}
+
+/// Distancia de `p` a la polilínea formada por los segmentos de un track
+fn distance_to_track(p: Xy, track: &[Vec<Xy>]) -> f64 {
+    track
+        .iter()
+        .map(|points| {
+            if points.len() == 1 {
+                distance(p, points[0])
+            } else {
+                points
+                    .windows(2)
+                    .map(|pair| distance_to_segment(p, pair[0], pair[1]))
+                    .fold(f64::INFINITY, f64::min)
+            }
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Hausdorff contra las polilíneas, para no depender de la frecuencia de muestreo
+fn hausdorff(a: &[Vec<Xy>], b: &[Vec<Xy>]) -> f64 {
+    let directed = |from: &[Vec<Xy>], to: &[Vec<Xy>]| {
+        from.iter()
+            .flatten()
+            .map(|&p| distance_to_track(p, to))
+            .fold(0.0, f64::max)
+    };
+    directed(a, b).max(directed(b, a))
+}
+
+/// Distancia de Fréchet discreta con programación dinámica en dos filas
+fn discrete_frechet(a: &[Xy], b: &[Xy]) -> f64 {
+    let mut previous: Vec<f64> = Vec::with_capacity(b.len());
+    for (i, &p) in a.iter().enumerate() {
+        let mut row: Vec<f64> = Vec::with_capacity(b.len());
+        for (j, &q) in b.iter().enumerate() {
+            let reach = match (i, j) {
+                (0, 0) => 0.0,
+                (0, _) => row[j - 1],
+                (_, 0) => previous[0],
+                _ => previous[j].min(previous[j - 1]).min(row[j - 1]),
+            };
+            row.push(reach.max(distance(p, q)));
+        }
+        previous = row;
+    }
+    previous.last().copied().unwrap_or(0.0)
+}
+
+/// Porcentaje de puntos de `from` a menos de `tolerance` de `to`
+fn overlap(from: &[Vec<Xy>], to: &[Vec<Xy>], tolerance: f64) -> f64 {
+    let points: Vec<Xy> = from.iter().flatten().copied().collect();
+    let near = points
+        .iter()
+        .filter(|&&p| distance_to_track(p, to) <= tolerance)
+        .count();
+    #[allow(clippy::cast_precision_loss)]
+    let percent = near as f64 / points.len() as f64 * 100.0;
+    percent
+}
+
+impl Track {
+    /// Compares the shape of two tracks, to spot duplicate uploads or check that a
+    /// ride followed a planned route
+    ///
+    /// Returns the curve distance between them and how much of each one lies
+    /// within `overlap_tolerance_m` of the other (measured to the nearest stretch,
+    /// so different sampling rates do not matter). Timestamps and elevation are
+    /// ignored. Returns `None` if either track has no points.
+    ///
+    /// The discrete Fréchet distance only looks at the recorded points, so it grows
+    /// with the spacing between them. Both metrics compare every point with the
+    /// whole other track; for long recordings, [`Track::simplify_with`] or [`Track::resample`] them first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::compare::CompareOptions;
+    /// use gpx_extractor::{Track, TrackSegment};
+    ///
+    /// let mut planned = Track::new();
+    /// planned.add_segment(TrackSegment::from_latlon_pairs(&[(40.0, -3.0), (40.01, -3.0)]));
+    /// let mut ridden = Track::new();
+    /// ridden.add_segment(TrackSegment::from_latlon_pairs(&[
+    ///     (40.0, -3.0001),
+    ///     (40.005, -3.0001),
+    ///     (40.01, -3.0001),
+    /// ]));
+    ///
+    /// let similarity = planned.similarity(&ridden, CompareOptions::default()).unwrap();
+    /// assert!(similarity.distance_m < 10.0);
+    /// assert_eq!(similarity.overlap_percent, 100.0);
+    /// assert_eq!(similarity.other_overlap_percent, 100.0);
+    /// ```
+    pub fn similarity(&self, other: &Track, options: CompareOptions) -> Option<Similarity> {
+        let all = self.get_all_points();
+        let all_other = other.get_all_points();
+        if all.is_empty() || all_other.is_empty() {
+            return None;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let mean_lat = all.iter().chain(&all_other).map(|p| p.lat).sum::<f64>()
+            / (all.len() + all_other.len()) as f64;
+        let cos_lat = mean_lat.to_radians().cos();
+        let mine = project(self, cos_lat);
+        let theirs = project(other, cos_lat);
+
+        let distance_m = match options.metric {
+            DistanceMetric::Hausdorff => hausdorff(&mine, &theirs),
+            DistanceMetric::DiscreteFrechet => {
+                let flat_mine: Vec<Xy> = mine.iter().flatten().copied().collect();
+                let flat_theirs: Vec<Xy> = theirs.iter().flatten().copied().collect();
+                discrete_frechet(&flat_mine, &flat_theirs)
+            }
+        };
+
+        Some(Similarity {
+            distance_m,
+            overlap_percent: overlap(&mine, &theirs, options.overlap_tolerance_m),
+            other_overlap_percent: overlap(&theirs, &mine, options.overlap_tolerance_m),
+        })
+    }
+}
+
+/// Perfil (distancia en metros, elevación) de los puntos con elevación de un track
+fn elevation_profile(track: &Track) -> (Vec<(f64, f64)>, f64) {
+    let metrics = track.point_metrics();
+    let profile = track
+        .get_all_points()
+        .iter()
+        .zip(&metrics)
+        .filter_map(|(p, m)| p.elevation.map(|ele| (m.distance_km * 1000.0, ele)))
+        .collect();
+    (
+        profile,
+        metrics.last().map_or(0.0, |m| m.distance_km * 1000.0),
+    )
+}
+
+/// Elevación interpolada a `meters` del inicio; `None` fuera del perfil
+fn elevation_at(profile: &[(f64, f64)], total_m: f64, meters: f64) -> Option<f64> {
+    if meters > total_m {
+        return None;
+    }
+    let after = profile.partition_point(|&(d, _)| d < meters);
+    match (after.checked_sub(1).map(|i| profile[i]), profile.get(after)) {
+        (Some((d0, e0)), Some(&(d1, e1))) => Some(e0 + (e1 - e0) * (meters - d0) / (d1 - d0)),
+        // Antes del primer o después del último punto con elevación se mantiene el valor
+        (Some((_, ele)), None) | (None, Some(&(_, ele))) => Some(ele),
+        (None, None) => None,
+    }
+}
+
+/// Aligns the elevation profiles of two routes by distance, for "route A vs B"
+/// profile charts
+///
+/// Both routes are sampled every `sample_m` meters from their start, up to the
+/// end of the longer one, with the elevation interpolated linearly between
+/// points; the last sample lies at the end of the longer route. Past the end
+/// of the shorter route its elevation is `None`. Returns no samples if
+/// `sample_m` is not positive or both routes are empty.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::compare::elevation_overlay;
+/// use gpx_extractor::{Track, TrackSegment};
+///
+/// let mut a = Track::new();
+/// a.add_segment(TrackSegment::from_latlon_ele(&[(40.0, -3.0, 600.0), (40.009, -3.0, 700.0)]));
+/// let mut b = Track::new();
+/// b.add_segment(TrackSegment::from_latlon_ele(&[(41.0, -3.0, 100.0), (41.0045, -3.0, 150.0)]));
+///
+/// let overlay = elevation_overlay(&a, &b, 250.0);
+/// assert_eq!(overlay.len(), 6);
+/// assert_eq!(overlay[0].a, Some(600.0));
+/// assert!((overlay[2].a.unwrap() - 650.0).abs() < 0.1);
+/// assert!((overlay[1].b.unwrap() - 125.0).abs() < 0.1);
+/// assert_eq!(overlay[3].b, None);
+/// ```
+pub fn elevation_overlay(a: &Track, b: &Track, sample_m: f64) -> Vec<OverlaySample> {
+    let (profile_a, total_a) = elevation_profile(a);
+    let (profile_b, total_b) = elevation_profile(b);
+    let total = total_a.max(total_b);
+    if sample_m <= 0.0 || (a.get_all_points().is_empty() && b.get_all_points().is_empty()) {
+        return Vec::new();
+    }
+
+    let sample = |meters: f64| OverlaySample {
+        distance_km: meters / 1000.0,
+        a: elevation_at(&profile_a, total_a, meters),
+        b: elevation_at(&profile_b, total_b, meters),
+    };
+    let mut samples = Vec::new();
+    for k in 0_usize.. {
+        #[allow(clippy::cast_precision_loss)]
+        let meters = sample_m * k as f64;
+        // Se evita una muestra casi duplicada justo antes del final
+        if meters >= total - sample_m * 1e-6 {
+            break;
+        }
+        samples.push(sample(meters));
+    }
+    samples.push(sample(total));
+    samples
+}
+
+impl Gpx {
+    /// Finds the stretches of the recording that strayed more than `threshold_m`
+    /// meters from a planned route, e.g. to validate an audax or brevet ride
+    ///
+    /// The route is any document with the planned course as tracks (such as the
+    /// one exported by a route planner); the distance to it is measured to the
+    /// nearest stretch, so sampling rates do not matter. Distance and duration of
+    /// each deviation run from the last point on the route before it to the first
+    /// one after it, within the same segment. Returns no deviations if the route
+    /// has no points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let route = Gpx::from_polyline_coords(&[(40.0, -3.0), (40.03, -3.0)]);
+    /// let ride = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.00" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.01" lon="-3.0"><time>2024-07-01T08:02:00Z</time></trkpt>
+    ///     <trkpt lat="40.015" lon="-3.005"><time>2024-07-01T08:03:00Z</time></trkpt>
+    ///     <trkpt lat="40.02" lon="-3.0"><time>2024-07-01T08:04:00Z</time></trkpt>
+    ///     <trkpt lat="40.03" lon="-3.0"><time>2024-07-01T08:06:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let deviations = ride.compare_to_route(&route, 100.0);
+    /// assert_eq!(deviations.len(), 1);
+    /// assert_eq!(deviations[0].start_index, 2);
+    /// assert!((deviations[0].max_distance_m - 426.0).abs() < 1.0);
+    /// assert_eq!(deviations[0].duration_seconds, Some(120));
+    /// ```
+    pub fn compare_to_route(&self, route: &Gpx, threshold_m: f64) -> Vec<Deviation> {
+        let route_points = route.get_all_points();
+        if route_points.is_empty() {
+            return Vec::new();
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let cos_lat = (route_points.iter().map(|p| p.lat).sum::<f64>() / route_points.len() as f64)
+            .to_radians()
+            .cos();
+        let planned: Vec<Vec<Xy>> = route
+            .tracks
+            .iter()
+            .flat_map(|track| project(track, cos_lat))
+            .collect();
+
+        // Punto, distancia a la ruta y posición (track, segmento) para no cruzar huecos
+        let mut points: Vec<(&Point, f64, (usize, usize))> = Vec::new();
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (s, segment) in track.segments.iter().enumerate() {
+                points.extend(
+                    segment
+                        .points
+                        .iter()
+                        .map(|p| (p, distance_to_track(to_xy(p, cos_lat), &planned), (t, s))),
+                );
+            }
+        }
+        let metrics = self.point_metrics();
+
+        let mut deviations = Vec::new();
+        let mut i = 0;
+        while i < points.len() {
+            if points[i].1 <= threshold_m {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i + 1 < points.len()
+                && points[i + 1].1 > threshold_m
+                && points[i + 1].2 == points[start].2
+            {
+                i += 1;
+            }
+            let end = i;
+            // Se extiende a los puntos sobre la ruta que la rodean, si son del mismo segmento
+            let from = if start > 0 && points[start - 1].2 == points[start].2 {
+                start - 1
+            } else {
+                start
+            };
+            let to = if end + 1 < points.len() && points[end + 1].2 == points[end].2 {
+                end + 1
+            } else {
+                end
+            };
+            deviations.push(Deviation {
+                start_index: start,
+                end_index: end,
+                max_distance_m: points[start..=end].iter().map(|p| p.1).fold(0.0, f64::max),
+                distance_km: metrics[to].distance_km - metrics[from].distance_km,
+                duration_seconds: points[from]
+                    .0
+                    .time
+                    .zip(points[to].0.time)
+                    .map(|(a, b)| (b - a).num_seconds()),
+            });
+            i += 1;
+        }
+        deviations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::TrackSegment;
+
+    fn track(coords: &[(f64, f64)]) -> Track {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::from_latlon_pairs(coords));
+        track
+    }
+
+    #[test]
+    fn test_frechet_detects_reversed_direction() {
+        let out = track(&[(40.0, -3.0), (40.005, -3.0), (40.01, -3.0)]);
+        let back = track(&[(40.01, -3.0), (40.005, -3.0), (40.0, -3.0)]);
+
+        let hausdorff = out.similarity(&back, CompareOptions::default()).unwrap();
+        assert!(hausdorff.distance_m < 1e-6);
+        assert_eq!(hausdorff.overlap_percent, 100.0);
+
+        let options = CompareOptions {
+            metric: DistanceMetric::DiscreteFrechet,
+            ..CompareOptions::default()
+        };
+        let frechet = out.similarity(&back, options).unwrap();
+        // Recorrido al revés: hay que separarse toda la longitud del track
+        assert!((frechet.distance_m - 1112.0).abs() < 1.0);
+        assert!(out.similarity(&out, options).unwrap().distance_m < 1e-6);
+    }
+
+    #[test]
+    fn test_partial_overlap() {
+        // La ruta planeada sigue 1.1 km más allá del punto donde se abandonó
+        let planned = track(&[(40.0, -3.0), (40.01, -3.0), (40.02, -3.0)]);
+        let ridden = track(&[(40.0, -3.0), (40.005, -3.0), (40.01, -3.0)]);
+        let similarity = planned
+            .similarity(&ridden, CompareOptions::default())
+            .unwrap();
+
+        assert!((similarity.overlap_percent - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(similarity.other_overlap_percent, 100.0);
+        assert!((similarity.distance_m - 1112.0).abs() < 1.0);
+        assert!(planned
+            .similarity(&Track::new(), CompareOptions::default())
+            .is_none());
+    }
+
+    #[test]
+    fn test_compare_to_route_splits_deviations_by_segment() {
+        let route = Gpx::from_polyline_coords(&[(40.0, -3.0), (40.05, -3.0)]);
+        let mut ride = Gpx::new();
+        let mut first = track(&[(40.0, -3.0), (40.01, -3.01), (40.02, -3.0)]);
+        first.add_segment(TrackSegment::from_latlon_pairs(&[
+            (40.03, -3.01),
+            (40.04, -3.0),
+        ]));
+        ride.add_track(first);
+
+        let deviations = ride.compare_to_route(&route, 50.0);
+        assert_eq!(deviations.len(), 2);
+        assert_eq!((deviations[0].start_index, deviations[0].end_index), (1, 1));
+        assert!((deviations[0].max_distance_m - 852.0).abs() < 1.0);
+        // Ida y vuelta a 852 m de la ruta, unos 2.8 km
+        assert!((deviations[0].distance_km - 2.80).abs() < 0.01);
+        assert_eq!(deviations[0].duration_seconds, None);
+        // El segundo segmento empieza fuera de la ruta: sin punto anterior
+        assert_eq!(deviations[1].start_index, 3);
+        assert!((deviations[1].distance_km - 1.40).abs() < 0.01);
+
+        assert!(ride.compare_to_route(&ride, 1.0).is_empty());
+        assert!(ride.compare_to_route(&Gpx::new(), 50.0).is_empty());
+    }
+
+    #[test]
+    fn test_elevation_overlay_fills_gaps_and_handles_flat_routes() {
+        let mut hilly = Track::new();
+        hilly.add_segment(TrackSegment::with_points(vec![
+            Point::with_elevation(40.0, -3.0, 100.0),
+            Point::new(40.001, -3.0),
+            Point::with_elevation(40.002, -3.0, 120.0),
+        ]));
+        let flat = track(&[(40.0, -3.0), (40.002, -3.0)]);
+
+        let overlay = elevation_overlay(&hilly, &flat, 100.0);
+        assert_eq!(overlay.len(), 4);
+        // El punto sin elevación se salta y se interpola entre sus vecinos
+        assert!((overlay[1].a.unwrap() - 109.0).abs() < 0.1);
+        assert!(overlay.iter().all(|sample| sample.b.is_none()));
+        assert!((overlay[3].distance_km - 0.2224).abs() < 1e-4);
+
+        assert!(elevation_overlay(&hilly, &flat, 0.0).is_empty());
+        assert!(elevation_overlay(&Track::new(), &Track::new(), 10.0).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, interpolate, Point},
+    split::Piece,
+};
+use chrono::{DateTime, Utc};
+
+/// Interval of time selected by [`Gpx::crop`]; a missing end leaves that side open
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use gpx_extractor::TimeRange;
+///
+/// let start = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+/// let range = TimeRange::since(start);
+/// assert!(range.contains(start));
+/// assert!(!range.contains(start - chrono::Duration::seconds(1)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TimeRange {
+    /// First instant included, if any
+    pub start: Option<DateTime<Utc>>,
+    /// Last instant included, if any
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    /// Intervalo cerrado entre `start` y `end`
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start: Some(start),
+            end: Some(end),
+        }
+    }
+
+    /// Todo lo que ocurre desde `start`
+    pub fn since(start: DateTime<Utc>) -> Self {
+        Self {
+            start: Some(start),
+            end: None,
+        }
+    }
+
+    /// Todo lo que ocurre hasta `end`
+    pub fn until(end: DateTime<Utc>) -> Self {
+        Self {
+            start: None,
+            end: Some(end),
+        }
+    }
+
+    /// Si el instante cae dentro del intervalo
+    pub fn contains(self, time: DateTime<Utc>) -> bool {
+        self.start.map_or(true, |start| start <= time) && self.end.map_or(true, |end| time <= end)
+    }
+}
+
+/// Milisegundos desde la época, como posición para recortar por tiempo
+#[allow(clippy::cast_precision_loss)]
+fn millis(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64
+}
+
+/// Puntos de un segmento entre `from` y `to`, según su posición (tiempo o
+/// distancia), con un punto interpolado en cada borde que cae entre dos puntos
+fn cut(positioned: &[(f64, &Point)], from: f64, to: f64) -> Vec<Point> {
+    let inside = |position: f64| from <= position && position <= to;
+    let mut points = Vec::new();
+    for pair in positioned.windows(2) {
+        let ((a, before), (b, after)) = (pair[0], pair[1]);
+        if inside(a) {
+            points.push(before.clone());
+        }
+        for edge in [from, to] {
+            if a < edge && edge < b {
+                points.push(interpolate(before, after, (edge - a) / (b - a)));
+            }
+        }
+    }
+    if let Some(&(position, point)) = positioned.last() {
+        if inside(position) {
+            points.push(point.clone());
+        }
+    }
+    points
+}
+
+impl Gpx {
+    /// Copy of the document with only the points recorded inside `range`
+    ///
+    /// Where the range starts or ends between two points, a point is interpolated
+    /// at the exact instant. Untimed points are left out, as are segments and
+    /// tracks left empty. Waypoints are kept when they fall inside the bounds of
+    /// the selected points, and the metadata time becomes the new start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use gpx_extractor::{Gpx, TimeRange};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.2" lon="-3.0"><time>2024-07-01T08:20:00Z</time></trkpt>
+    ///     <trkpt lat="40.3" lon="-3.0"><time>2024-07-01T10:20:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// // Sin el trayecto en coche de los primeros 10 minutos
+    /// let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 10, 0).unwrap();
+    /// let hike = gpx.crop(TimeRange::since(start));
+    /// assert_eq!(hike.total_points(), 3);
+    /// assert!((hike.tracks[0].segments[0].points[0].lat - 40.1).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn crop(&self, range: TimeRange) -> Gpx {
+        let from = range.start.map_or(f64::NEG_INFINITY, millis);
+        let to = range.end.map_or(f64::INFINITY, millis);
+        self.select(from, to, |_, point| point.time.map(millis))
+    }
+
+    /// Copy of the document between two distances from the start, in kilometers
+    ///
+    /// Distances run along the tracks and their segments in order, as in
+    /// [`Gpx::total_distance_km`]. Points are interpolated at both cut
+    /// distances; waypoints and metadata are handled as in [`Gpx::crop`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="0.0" lon="0.0"/>
+    ///     <trkpt lat="0.0" lon="0.1"/>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let middle = gpx.trim_distance(2.0, 5.0);
+    /// assert_eq!(middle.total_points(), 2);
+    /// assert!((middle.total_distance_km() - 3.0).abs() < 1e-6);
+    /// ```
+    #[must_use]
+    pub fn trim_distance(&self, start_km: f64, end_km: f64) -> Gpx {
+        let mut travelled = 0.0;
+        self.select(start_km, end_km, |previous, point| {
+            if let Some(previous) = previous {
+                travelled += haversine_distance(previous, point);
+            }
+            Some(travelled)
+        })
+    }
+
+    /// Recorta cada segmento con la posición que `position` da a cada punto a
+    /// partir del anterior del mismo segmento; los puntos sin posición se descartan
+    fn select<F>(&self, from: f64, to: f64, mut position: F) -> Gpx
+    where
+        F: FnMut(Option<&Point>, &Point) -> Option<f64>,
+    {
+        let mut piece = Piece::new(self);
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (s, segment) in track.segments.iter().enumerate() {
+                let positioned: Vec<(f64, &Point)> = segment
+                    .points
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, point)| {
+                        let previous = i.checked_sub(1).map(|i| &segment.points[i]);
+                        position(previous, point).map(|at| (at, point))
+                    })
+                    .collect();
+                for point in cut(&positioned, from, to) {
+                    piece.push((t, s), track, point);
+                }
+            }
+        }
+        self.finish_piece(piece.gpx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{
+        track::{Track, TrackSegment},
+        waypoint::Waypoint,
+    };
+    use chrono::{Duration, TimeZone};
+
+    fn drive_and_hike() -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |lat: f64, minutes: i64| {
+            Point::with_time(lat, -3.0, Some(600.0), start + Duration::minutes(minutes))
+        };
+        let mut track = Track::with_name("Day out".to_string());
+        track.add_segment(TrackSegment::with_points(vec![at(40.0, 0), at(40.2, 20)]));
+        track.add_segment(TrackSegment::with_points(vec![
+            at(40.2, 30),
+            Point::new(40.21, -3.0),
+            at(40.22, 90),
+            at(40.24, 150),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx.add_waypoint(Waypoint::with_name(40.1, -3.0, "Car park".to_string()));
+        gpx.add_waypoint(Waypoint::with_name(40.225, -3.0, "Summit".to_string()));
+        gpx
+    }
+
+    #[test]
+    fn test_crop_interpolates_at_both_ends() {
+        let gpx = drive_and_hike();
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 25, 0).unwrap();
+        let cropped = gpx.crop(TimeRange::new(start, start + Duration::minutes(95)));
+
+        // El primer segmento queda fuera entero; el segundo pierde el punto sin hora
+        assert_eq!(cropped.tracks.len(), 1);
+        assert_eq!(cropped.tracks[0].name.as_deref(), Some("Day out"));
+        let points = &cropped.tracks[0].segments[0].points;
+        let lats: Vec<f64> = points.iter().map(|p| (p.lat * 1000.0).round()).collect();
+        assert_eq!(lats, vec![40200.0, 40220.0, 40230.0]);
+        assert_eq!(points[2].time, Some(start + Duration::minutes(95)));
+        assert_eq!(points[2].elevation, Some(600.0));
+        assert_eq!(cropped.date(), Some("2024-07-01T08:30:00Z"));
+        assert_eq!(cropped.waypoint_names(), vec!["Summit"]);
+
+        // El original no cambia
+        assert_eq!(gpx.total_points(), 6);
+        assert!(gpx
+            .crop(TimeRange::until(start - Duration::hours(1)))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_trim_distance_spans_segments() {
+        let gpx = drive_and_hike();
+        let total = gpx.total_distance_km();
+        let trimmed = gpx.trim_distance(10.0, total - 1.0);
+
+        assert!((trimmed.total_distance_km() - (total - 11.0)).abs() < 1e-6);
+        assert_eq!(trimmed.tracks[0].segments.len(), 2);
+        assert_eq!(trimmed.tracks[0].segments[1].points.len(), 4);
+    }
+}
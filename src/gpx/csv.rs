@@ -0,0 +1,284 @@
+use crate::gpx::{
+    analysis::PointMetrics, error::GpxError, formats::FormatWriter, parser::Gpx, point::Point,
+};
+use chrono::SecondsFormat;
+use std::io::Write;
+
+/// A column of the point table written by [`Gpx::to_csv`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsvColumn {
+    /// Index of the track the point belongs to
+    Track,
+    /// Index of the segment inside its track
+    Segment,
+    /// Latitude in decimal degrees
+    Lat,
+    /// Longitude in decimal degrees
+    Lon,
+    /// Elevation in meters
+    Elevation,
+    /// RFC 3339 timestamp in UTC
+    Time,
+    /// Distance from the first point of the document, in kilometers
+    DistanceKm,
+    /// Speed from the previous point of the segment, in km/h
+    SpeedKmh,
+    /// Heart rate in beats per minute
+    HeartRate,
+    /// Cadence per minute
+    Cadence,
+    /// Power in watts
+    Power,
+}
+
+impl CsvColumn {
+    /// Nombre de la columna en la cabecera
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Track => "track",
+            Self::Segment => "segment",
+            Self::Lat => "lat",
+            Self::Lon => "lon",
+            Self::Elevation => "elevation",
+            Self::Time => "time",
+            Self::DistanceKm => "distance_km",
+            Self::SpeedKmh => "speed_kmh",
+            Self::HeartRate => "heart_rate",
+            Self::Cadence => "cadence",
+            Self::Power => "power",
+        }
+    }
+}
+
+/// Options for [`Gpx::to_csv`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Field separator, `,` by default (use `;` or `\t` for spreadsheets in other locales)
+    pub delimiter: char,
+    /// Whether to write a header row with the column names
+    pub header: bool,
+    /// Columns to write, in order; `None` writes track, segment, position, time,
+    /// distance and speed, plus the sensor columns present in the document
+    pub columns: Option<Vec<CsvColumn>>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: true,
+            columns: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Opciones por defecto con las columnas indicadas
+    pub fn with_columns(columns: &[CsvColumn]) -> Self {
+        Self {
+            columns: Some(columns.to_vec()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Writer for point tables (`.csv`), using the given [`CsvOptions`]
+#[derive(Debug, Clone, Default)]
+pub struct CsvFormat {
+    /// Delimiter, header and columns of the table
+    pub options: CsvOptions,
+}
+
+impl FormatWriter for CsvFormat {
+    fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+        let mut csv = Vec::new();
+        gpx.to_csv(&mut csv, &self.options)?;
+        Ok(csv)
+    }
+}
+
+/// Fila en construcción: un punto y lo calculado hasta él
+struct Row<'a> {
+    track: usize,
+    segment: usize,
+    point: &'a Point,
+    metrics: PointMetrics,
+}
+
+impl Row<'_> {
+    fn field(&self, column: CsvColumn) -> String {
+        let point = self.point;
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        match column {
+            CsvColumn::Track => self.track.to_string(),
+            CsvColumn::Segment => self.segment.to_string(),
+            CsvColumn::Lat => point.lat.to_string(),
+            CsvColumn::Lon => point.lon.to_string(),
+            CsvColumn::Elevation => optional(point.elevation.map(|e| e.to_string())),
+            CsvColumn::Time => optional(
+                point
+                    .time
+                    .map(|t| t.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            ),
+            CsvColumn::DistanceKm => self.metrics.distance_km.to_string(),
+            CsvColumn::SpeedKmh => optional(self.metrics.speed_kmh.map(|s| s.to_string())),
+            CsvColumn::HeartRate => optional(point.heart_rate().map(|v| v.to_string())),
+            CsvColumn::Cadence => optional(point.cadence().map(|v| v.to_string())),
+            CsvColumn::Power => optional(point.power().map(|v| v.to_string())),
+        }
+    }
+}
+
+impl Gpx {
+    /// Writes one row per track point, ready to load into pandas, Polars or a spreadsheet
+    ///
+    /// Missing values (no elevation, no time, speed of the first point of a segment)
+    /// are written as empty fields. The cumulative distance does not include the
+    /// gaps between segments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Io`] if writing fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{CsvColumn, CsvOptions, GpxBuilder};
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.segment(|s| s.point_with_elevation(40.0, -3.0, 650.0).point(40.1, -3.0)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut csv = Vec::new();
+    /// let options = CsvOptions {
+    ///     delimiter: ';',
+    ///     ..CsvOptions::with_columns(&[CsvColumn::Lat, CsvColumn::Lon, CsvColumn::Elevation])
+    /// };
+    /// gpx.to_csv(&mut csv, &options).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "lat;lon;elevation\n40;-3;650\n40.1;-3;\n");
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W, options: &CsvOptions) -> Result<(), GpxError> {
+        let columns = options
+            .columns
+            .clone()
+            .unwrap_or_else(|| self.default_csv_columns());
+        let delimiter = options.delimiter.to_string();
+
+        if options.header {
+            let header: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+            writeln!(writer, "{}", header.join(&delimiter))?;
+        }
+
+        let mut metrics = self.point_metrics().into_iter();
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (s, segment) in track.segments.iter().enumerate() {
+                for (point, metrics) in segment.points.iter().zip(metrics.by_ref()) {
+                    let row = Row {
+                        track: t,
+                        segment: s,
+                        point,
+                        metrics,
+                    };
+                    let fields: Vec<String> = columns.iter().map(|&c| row.field(c)).collect();
+                    writeln!(writer, "{}", fields.join(&delimiter))?;
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn default_csv_columns(&self) -> Vec<CsvColumn> {
+        let mut columns = vec![
+            CsvColumn::Track,
+            CsvColumn::Segment,
+            CsvColumn::Lat,
+            CsvColumn::Lon,
+            CsvColumn::Elevation,
+            CsvColumn::Time,
+            CsvColumn::DistanceKm,
+            CsvColumn::SpeedKmh,
+        ];
+        let points = self.get_all_points();
+        let sensors = [
+            (
+                CsvColumn::HeartRate,
+                points.iter().any(|p| p.heart_rate().is_some()),
+            ),
+            (
+                CsvColumn::Cadence,
+                points.iter().any(|p| p.cadence().is_some()),
+            ),
+            (CsvColumn::Power, points.iter().any(|p| p.power().is_some())),
+        ];
+        columns.extend(
+            sensors
+                .iter()
+                .filter(|(_, present)| *present)
+                .map(|(c, _)| *c),
+        );
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::{TimeZone, Utc};
+
+    fn ride() -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let mut first = Point::with_time(40.0, -3.0, Some(600.0), start);
+        first.extensions.heart_rate = Some(110);
+        let mut second = Point::with_time(40.01, -3.0, None, start + chrono::Duration::minutes(2));
+        second.extensions.heart_rate = Some(125);
+
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![first, second]));
+        track.add_segment(TrackSegment::with_points(vec![Point::new(40.02, -3.0)]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_to_csv_default_columns() {
+        let mut out = Vec::new();
+        ride().to_csv(&mut out, &CsvOptions::default()).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "track,segment,lat,lon,elevation,time,distance_km,speed_kmh,heart_rate"
+        );
+        assert_eq!(lines[1], "0,0,40,-3,600,2024-07-01T08:00:00Z,0,,110");
+
+        let second: Vec<&str> = lines[2].split(',').collect();
+        let distance: f64 = second[6].parse().unwrap();
+        let speed: f64 = second[7].parse().unwrap();
+        assert!((distance - 1.112).abs() < 0.001);
+        assert!((speed - distance * 30.0).abs() < 1e-9);
+
+        // El hueco entre segmentos no suma distancia
+        assert_eq!(lines[3], format!("0,1,40.02,-3,,,{distance},,"));
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn test_to_csv_column_selection_without_header() {
+        let options = CsvOptions {
+            delimiter: '\t',
+            header: false,
+            columns: Some(vec![CsvColumn::HeartRate, CsvColumn::Power, CsvColumn::Lat]),
+        };
+        let mut out = Vec::new();
+        ride().to_csv(&mut out, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "110\t\t40\n125\t\t40.01\n\t\t40.02\n"
+        );
+    }
+}
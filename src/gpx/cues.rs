@@ -0,0 +1,412 @@
+use crate::gpx::{
+    analysis::turn_degrees,
+    parser::Gpx,
+    point::{haversine_distance, interpolate, Point},
+    template::{NameTemplate, TemplateValues},
+    track::Track,
+    waypoint::Waypoint,
+};
+use quick_xml::escape::escape;
+use std::fmt;
+
+/// Options for [`generate`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CueOptions {
+    /// Smallest change of direction reported, in degrees
+    pub min_turn_degrees: f64,
+    /// Distance before and after each point over which the headings are measured,
+    /// in meters; longer distances smooth out GPS jitter but blur close turns
+    pub lookaround_m: f64,
+    /// Turns closer than this distance, in meters, become a single cue for the sharpest one
+    pub min_spacing_m: f64,
+}
+
+impl Default for CueOptions {
+    fn default() -> Self {
+        Self {
+            min_turn_degrees: 35.0,
+            lookaround_m: 25.0,
+            min_spacing_m: 50.0,
+        }
+    }
+}
+
+/// Kind of turn announced by a [`Cue`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CueDirection {
+    /// Up to 45 degrees to the left
+    SlightLeft,
+    /// Between 45 and 120 degrees to the left
+    Left,
+    /// Between 120 and 165 degrees to the left
+    SharpLeft,
+    /// Up to 45 degrees to the right
+    SlightRight,
+    /// Between 45 and 120 degrees to the right
+    Right,
+    /// Between 120 and 165 degrees to the right
+    SharpRight,
+    /// More than 165 degrees either way
+    UTurn,
+}
+
+impl CueDirection {
+    /// Direction for a signed turn in degrees, positive to the right
+    pub fn from_turn_degrees(turn: f64) -> Self {
+        let right = turn > 0.0;
+        match turn.abs() {
+            angle if angle > 165.0 => Self::UTurn,
+            angle if angle > 120.0 => {
+                if right {
+                    Self::SharpRight
+                } else {
+                    Self::SharpLeft
+                }
+            }
+            angle if angle > 45.0 => {
+                if right {
+                    Self::Right
+                } else {
+                    Self::Left
+                }
+            }
+            _ if right => Self::SlightRight,
+            _ => Self::SlightLeft,
+        }
+    }
+
+    /// Symbol written to `<sym>`, using the course point names of cycling computers
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::SlightLeft => "Slight Left",
+            Self::Left => "Left",
+            Self::SharpLeft => "Sharp Left",
+            Self::SlightRight => "Slight Right",
+            Self::Right => "Right",
+            Self::SharpRight => "Sharp Right",
+            Self::UTurn => "U-Turn",
+        }
+    }
+}
+
+impl fmt::Display for CueDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SlightLeft => "bear left",
+            Self::Left => "turn left",
+            Self::SharpLeft => "turn sharp left",
+            Self::SlightRight => "bear right",
+            Self::Right => "turn right",
+            Self::SharpRight => "turn sharp right",
+            Self::UTurn => "make a U-turn",
+        })
+    }
+}
+
+/// A turn instruction found by [`generate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// Track point where the turn happens
+    pub point: Point,
+    /// Index of the point, in [`Track::get_all_points`] order
+    pub index: usize,
+    /// Distance from the start of the track, in kilometers
+    pub distance_km: f64,
+    /// Distance from the previous cue (or the start), in kilometers
+    pub from_previous_km: f64,
+    /// Change of heading in degrees, positive to the right
+    pub turn_degrees: f64,
+    /// Kind of turn
+    pub direction: CueDirection,
+}
+
+impl Cue {
+    /// Waypoint for the cue, named after the instruction and with the direction symbol
+    pub fn to_waypoint(&self) -> Waypoint {
+        self.waypoint(self.to_string())
+    }
+
+    /// Waypoint for the cue named with `template` (see [`Cue::template_values`])
+    pub fn to_waypoint_named(&self, template: &NameTemplate) -> Waypoint {
+        self.waypoint(template.render(&self.template_values()))
+    }
+
+    /// Template values describing the cue
+    ///
+    /// Provides `direction` (e.g. `turn left`), `distance_km`, `from_previous_km`,
+    /// `turn_deg` and `index`.
+    pub fn template_values(&self) -> TemplateValues {
+        TemplateValues::new()
+            .with("direction", self.direction.to_string())
+            .with("distance_km", self.distance_km)
+            .with("from_previous_km", self.from_previous_km)
+            .with("turn_deg", self.turn_degrees)
+            .with("index", self.index)
+    }
+
+    fn waypoint(&self, name: String) -> Waypoint {
+        Waypoint {
+            name: Some(name),
+            elevation: self.point.elevation,
+            time: self.point.time,
+            symbol: Some(self.direction.symbol().to_string()),
+            kind: Some("Cue".to_string()),
+            ..Waypoint::new(self.point.lat, self.point.lon)
+        }
+    }
+
+    /// Elemento `<rtept>` del cue
+    fn to_route_point_xml(&self) -> String {
+        let elevation = self
+            .point
+            .elevation
+            .map(|elevation| format!("<ele>{elevation}</ele>"))
+            .unwrap_or_default();
+        format!(
+            r#"<rtept lat="{}" lon="{}">{elevation}<name>{}</name><sym>{}</sym><type>Cue</type></rtept>"#,
+            self.point.lat,
+            self.point.lon,
+            escape(&self.to_string()),
+            self.direction.symbol(),
+        )
+    }
+}
+
+impl fmt::Display for Cue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} after {:.1} km",
+            self.direction, self.from_previous_km
+        )
+    }
+}
+
+/// Posición a `meters` metros del inicio de un segmento, dadas sus distancias acumuladas
+fn position_at(points: &[Point], cumulative_m: &[f64], meters: f64) -> Point {
+    let i = cumulative_m.partition_point(|&at| at < meters);
+    match (i.checked_sub(1), points.get(i)) {
+        (Some(before), Some(after)) if cumulative_m[i] > cumulative_m[before] => {
+            let fraction =
+                (meters - cumulative_m[before]) / (cumulative_m[i] - cumulative_m[before]);
+            interpolate(&points[before], after, fraction)
+        }
+        (_, Some(point)) => point.clone(),
+        (_, None) => points[points.len() - 1].clone(),
+    }
+}
+
+/// Giro candidato: índice global, distancia desde el inicio (km) y giro con signo
+type Candidate = (usize, f64, f64);
+
+/// Generates turn-by-turn cues for the significant changes of direction of a track
+///
+/// At every point, the heading of the `lookaround_m` meters before it is compared
+/// with the heading of the `lookaround_m` meters after it; turns of at least
+/// `min_turn_degrees` become cues, keeping only the sharpest one among turns
+/// closer than `min_spacing_m`. Turns are never measured across two segments.
+/// Add the cues to a document with [`Gpx::add_cue_waypoints`] or
+/// [`Gpx::add_cue_route`].
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::cues::{generate, CueDirection, CueOptions};
+/// use gpx_extractor::{Track, TrackSegment};
+///
+/// let mut track = Track::new();
+/// // Hacia el norte y luego hacia el oeste
+/// track.add_segment(TrackSegment::from_latlon_pairs(&[
+///     (40.00, -3.00),
+///     (40.01, -3.00),
+///     (40.02, -3.00),
+///     (40.02, -3.01),
+///     (40.02, -3.02),
+/// ]));
+///
+/// let cues = generate(&track, CueOptions::default());
+/// assert_eq!(cues.len(), 1);
+/// assert_eq!(cues[0].direction, CueDirection::Left);
+/// assert_eq!(cues[0].to_string(), "turn left after 2.2 km");
+/// ```
+pub fn generate(track: &Track, options: CueOptions) -> Vec<Cue> {
+    let metrics = track.point_metrics();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut offset = 0;
+    for segment in &track.segments {
+        let points = &segment.points;
+        let mut cumulative_m = Vec::with_capacity(points.len());
+        let mut travelled = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                travelled += haversine_distance(&points[i - 1], point) * 1000.0;
+            }
+            cumulative_m.push(travelled);
+        }
+        for (i, point) in points.iter().enumerate() {
+            let at = cumulative_m[i];
+            let before = position_at(points, &cumulative_m, (at - options.lookaround_m).max(0.0));
+            let after = position_at(
+                points,
+                &cumulative_m,
+                (at + options.lookaround_m).min(travelled),
+            );
+            // En los extremos del segmento falta uno de los dos rumbos
+            if haversine_distance(&before, point) == 0.0 || haversine_distance(point, &after) == 0.0
+            {
+                continue;
+            }
+            let turn = turn_degrees(before.bearing_to(point), point.bearing_to(&after));
+            if turn.abs() >= options.min_turn_degrees {
+                candidates.push((offset + i, metrics[offset + i].distance_km, turn));
+            }
+        }
+        offset += points.len();
+    }
+
+    // Los candidatos cercanos son el mismo giro: se queda el más cerrado
+    let mut turns: Vec<Candidate> = Vec::new();
+    for candidate in candidates {
+        match turns.last_mut() {
+            Some(last) if (candidate.1 - last.1) * 1000.0 < options.min_spacing_m => {
+                if candidate.2.abs() > last.2.abs() {
+                    *last = candidate;
+                }
+            }
+            _ => turns.push(candidate),
+        }
+    }
+
+    let points = track.get_all_points();
+    let mut previous_km = 0.0;
+    turns
+        .into_iter()
+        .map(|(index, distance_km, turn)| {
+            let cue = Cue {
+                point: points[index].clone(),
+                index,
+                distance_km,
+                from_previous_km: distance_km - previous_km,
+                turn_degrees: turn,
+                direction: CueDirection::from_turn_degrees(turn),
+            };
+            previous_km = distance_km;
+            cue
+        })
+        .collect()
+}
+
+impl Gpx {
+    /// Adds every cue as a waypoint (see [`Cue::to_waypoint`])
+    pub fn add_cue_waypoints(&mut self, cues: &[Cue]) {
+        self.waypoints.extend(cues.iter().map(Cue::to_waypoint));
+    }
+
+    /// Adds every cue as a waypoint named with `template` (see [`Cue::template_values`])
+    pub fn add_cue_waypoints_named(&mut self, cues: &[Cue], template: &NameTemplate) {
+        self.waypoints
+            .extend(cues.iter().map(|cue| cue.to_waypoint_named(template)));
+    }
+
+    /// Adds the cues as a `<rte>` with one `<rtept>` per turn, for devices that follow routes
+    ///
+    /// Routes are not modeled by this crate, so the route is kept as raw XML
+    /// alongside any routes read from the original file and written at the end
+    /// of the document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::cues::{generate, CueOptions};
+    /// use gpx_extractor::{Gpx, Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[
+    ///     (40.00, -3.00),
+    ///     (40.01, -3.00),
+    ///     (40.01, -2.99),
+    /// ]));
+    /// let cues = generate(&track, CueOptions::default());
+    ///
+    /// let mut gpx = Gpx::new();
+    /// gpx.add_cue_route(Some("Sunday loop"), &cues);
+    /// let xml = gpx.to_xml();
+    /// assert!(xml.contains("<rte><name>Sunday loop</name><rtept"));
+    /// assert!(xml.contains("<name>turn right after 1.1 km</name><sym>Right</sym>"));
+    /// ```
+    pub fn add_cue_route(&mut self, name: Option<&str>, cues: &[Cue]) {
+        let name = name
+            .map(|name| format!("<name>{}</name>", escape(name)))
+            .unwrap_or_default();
+        let points: String = cues.iter().map(Cue::to_route_point_xml).collect();
+        self.unknown
+            .push_element(None, &format!("<rte>{name}{points}</rte>"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::TrackSegment;
+
+    #[test]
+    fn test_direction_thresholds() {
+        assert_eq!(
+            CueDirection::from_turn_degrees(-30.0),
+            CueDirection::SlightLeft
+        );
+        assert_eq!(CueDirection::from_turn_degrees(90.0), CueDirection::Right);
+        assert_eq!(
+            CueDirection::from_turn_degrees(-150.0),
+            CueDirection::SharpLeft
+        );
+        assert_eq!(CueDirection::from_turn_degrees(175.0), CueDirection::UTurn);
+        assert_eq!(CueDirection::SharpRight.to_string(), "turn sharp right");
+    }
+
+    #[test]
+    fn test_generate_ignores_jitter_and_merges_close_points() {
+        let mut track = Track::new();
+        // Norte con un desvío de GPS de unos 3 m, un giro a la derecha muestreado
+        // con varios puntos cerca de la esquina y un segundo segmento recto
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (40.000, -3.0),
+            (40.001, -3.0),
+            (40.002, -3.000_04),
+            (40.003, -3.0),
+            (40.004, -3.0),
+            (40.004_1, -3.0),
+            (40.004_1, -2.999_9),
+            (40.004_1, -2.999),
+            (40.004_1, -2.998),
+        ]));
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (41.0, -3.0),
+            (41.01, -3.0),
+        ]));
+
+        let cues = generate(&track, CueOptions::default());
+        assert_eq!(cues.len(), 1);
+        let cue = &cues[0];
+        assert_eq!(cue.direction, CueDirection::Right);
+        assert!((4..=6).contains(&cue.index));
+        assert!((cue.turn_degrees - 90.0).abs() < 10.0);
+        assert_eq!(cue.from_previous_km, cue.distance_km);
+
+        let waypoint = cue.to_waypoint();
+        assert_eq!(waypoint.symbol.as_deref(), Some("Right"));
+        assert_eq!(waypoint.name, Some(cue.to_string()));
+        let template = NameTemplate::parse("{direction} ({turn_deg:.0}°)").unwrap();
+        assert_eq!(
+            cue.to_waypoint_named(&template).name,
+            Some(format!("turn right ({:.0}°)", cue.turn_degrees))
+        );
+
+        let mut gpx = Gpx::new();
+        gpx.add_cue_waypoints(&cues);
+        gpx.add_cue_route(None, &cues);
+        assert_eq!(gpx.waypoints.len(), 1);
+        let reparsed = Gpx::try_from_str(&gpx.to_xml()).unwrap();
+        assert_eq!(reparsed.unknown.elements().count(), 1);
+    }
+}
@@ -0,0 +1,505 @@
+use crate::gpx::{error::GpxError, parser::Gpx, point::Point};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Valor de las celdas sin dato en los ficheros HGT
+const HGT_VOID: i16 = -32768;
+
+/// Source of terrain elevations, such as a digital elevation model on disk or a web service
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::error::GpxError;
+/// use gpx_extractor::{ElevationProvider, Gpx};
+///
+/// // Un modelo de juguete: el terreno sube 1 m por cada milésima de grado al norte
+/// struct Ramp;
+///
+/// impl ElevationProvider for Ramp {
+///     fn elevation_at(&self, lat: f64, _lon: f64) -> Result<Option<f64>, GpxError> {
+///         Ok(Some((lat - 40.0) * 1000.0))
+///     }
+/// }
+///
+/// let mut gpx = Gpx::try_from_str(
+///     r#"<gpx><trk><trkseg>
+///         <trkpt lat="40.0" lon="-3.0"/>
+///         <trkpt lat="40.1" lon="-3.0"><ele>95</ele></trkpt>
+///     </trkseg></trk></gpx>"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(gpx.fill_elevations(&Ramp).unwrap(), 1);
+/// assert_eq!(gpx.total_elevation_gain(), Some(95.0));
+/// ```
+pub trait ElevationProvider {
+    /// Elevation in meters at a coordinate, or `None` where the provider has no data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot be read
+    fn elevation_at(&self, lat: f64, lon: f64) -> Result<Option<f64>, GpxError>;
+}
+
+/// Which elevations [`Gpx::fill_elevations_with`] replaces
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FillMode {
+    /// Only points without elevation
+    #[default]
+    Missing,
+    /// Points without elevation, and recorded elevations that differ from the
+    /// provider by more than `tolerance_m` meters (barometer drift, GPS noise)
+    Deviating {
+        /// Largest difference in meters kept as recorded
+        tolerance_m: f64,
+    },
+    /// Every point the provider has data for
+    All,
+}
+
+impl FillMode {
+    /// Indica si hay que consultar el proveedor para un punto
+    fn wants(self, point: &Point) -> bool {
+        !matches!(self, Self::Missing) || point.elevation.is_none()
+    }
+
+    /// Sustituye la elevación del punto si el modo lo pide; indica si ha cambiado
+    fn apply(self, point: &mut Point, model: Option<f64>) -> bool {
+        let Some(model) = model else {
+            return false;
+        };
+        let replace = match (self, point.elevation) {
+            (_, None) | (Self::All, _) => true,
+            (Self::Missing, Some(_)) => false,
+            (Self::Deviating { tolerance_m }, Some(recorded)) => {
+                (recorded - model).abs() > tolerance_m
+            }
+        };
+        if replace {
+            point.elevation = Some(model);
+        }
+        replace
+    }
+}
+
+impl Gpx {
+    /// Fills the track points without elevation from `provider`
+    ///
+    /// Returns the number of points changed. Points where the provider has
+    /// no data keep their current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the provider; points filled before it keep
+    /// their new elevation
+    pub fn fill_elevations<P>(&mut self, provider: &P) -> Result<usize, GpxError>
+    where
+        P: ElevationProvider + ?Sized,
+    {
+        self.fill_elevations_with(provider, FillMode::Missing)
+    }
+
+    /// Replaces track point elevations from `provider` according to `mode`
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the provider; points filled before it keep
+    /// their new elevation
+    pub fn fill_elevations_with<P>(
+        &mut self,
+        provider: &P,
+        mode: FillMode,
+    ) -> Result<usize, GpxError>
+    where
+        P: ElevationProvider + ?Sized,
+    {
+        let mut changed = 0;
+        for point in self.points_mut().filter(|point| mode.wants(point)) {
+            let model = provider.elevation_at(point.lat, point.lon)?;
+            if mode.apply(point, model) {
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Celdas de una tesela HGT: filas de norte a sur y columnas de oeste a este
+#[derive(Debug)]
+struct HgtTile {
+    size: usize,
+    samples: Vec<i16>,
+}
+
+impl HgtTile {
+    /// Lee una tesela de 1201×1201 (SRTM3) o 3601×3601 (SRTM1) muestras
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let count = bytes.len() / 2;
+        let size = [1201, 3601].into_iter().find(|size| size * size == count)?;
+        let samples = bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        Some(Self { size, samples })
+    }
+
+    /// Interpolación bilineal; las celdas sin dato se ignoran
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn elevation(&self, lat_offset: f64, lon_offset: f64) -> Option<f64> {
+        let last = (self.size - 1) as f64;
+        let row = ((1.0 - lat_offset) * last).clamp(0.0, last);
+        let col = (lon_offset * last).clamp(0.0, last);
+        let (row0, col0) = (row.floor() as usize, col.floor() as usize);
+        let (row1, col1) = ((row0 + 1).min(self.size - 1), (col0 + 1).min(self.size - 1));
+        let (dy, dx) = (row - row0 as f64, col - col0 as f64);
+
+        let mut total = 0.0;
+        let mut weights = 0.0;
+        for (r, c, weight) in [
+            (row0, col0, (1.0 - dy) * (1.0 - dx)),
+            (row0, col1, (1.0 - dy) * dx),
+            (row1, col0, dy * (1.0 - dx)),
+            (row1, col1, dy * dx),
+        ] {
+            let sample = self.samples[r * self.size + c];
+            if sample != HGT_VOID && weight > 0.0 {
+                total += f64::from(sample) * weight;
+                weights += weight;
+            }
+        }
+        (weights > 0.0).then(|| total / weights)
+    }
+}
+
+/// Teselas cargadas por esquina suroeste; `None` si no hay fichero
+type TileCache = HashMap<(i32, i32), Option<Arc<HgtTile>>>;
+
+/// [`ElevationProvider`] reading SRTM `.hgt` tiles from a directory
+///
+/// Tiles are named after their south-west corner, such as `N40W004.hgt`, and
+/// both the 3 arc-second (1201×1201) and 1 arc-second (3601×3601) resolutions
+/// are accepted. Tiles are loaded on first use and kept in memory; coordinates
+/// without a tile in the directory have no data.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gpx_extractor::{Gpx, SrtmProvider};
+///
+/// let srtm = SrtmProvider::new("/data/srtm");
+/// let mut gpx = Gpx::from_file("hike.gpx").unwrap();
+/// let filled = gpx.fill_elevations(&srtm).unwrap();
+/// println!("{filled} points filled");
+/// ```
+#[derive(Debug)]
+pub struct SrtmProvider {
+    dir: PathBuf,
+    tiles: Mutex<TileCache>,
+}
+
+impl SrtmProvider {
+    /// Crea un proveedor para las teselas del directorio `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tiles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Nombre del fichero de la tesela con esquina suroeste en `(lat, lon)`
+    fn tile_name(lat: i32, lon: i32) -> String {
+        let ns = if lat < 0 { 'S' } else { 'N' };
+        let ew = if lon < 0 { 'W' } else { 'E' };
+        format!("{ns}{:02}{ew}{:03}.hgt", lat.abs(), lon.abs())
+    }
+
+    /// Tesela de la caché, cargándola del disco la primera vez
+    fn tile(&self, lat: i32, lon: i32) -> Result<Option<Arc<HgtTile>>, GpxError> {
+        let mut tiles = self
+            .tiles
+            .lock()
+            .map_err(|_| GpxError::Elevation("SRTM tile cache poisoned".to_string()))?;
+        if let Some(tile) = tiles.get(&(lat, lon)) {
+            return Ok(tile.clone());
+        }
+
+        let path = self.dir.join(Self::tile_name(lat, lon));
+        let tile = match std::fs::read(&path) {
+            Ok(bytes) => Some(Arc::new(HgtTile::parse(&bytes).ok_or_else(|| {
+                GpxError::Elevation(format!("{} is not an SRTM tile", path.display()))
+            })?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        tiles.insert((lat, lon), tile.clone());
+        Ok(tile)
+    }
+}
+
+impl ElevationProvider for SrtmProvider {
+    #[allow(clippy::cast_possible_truncation)]
+    fn elevation_at(&self, lat: f64, lon: f64) -> Result<Option<f64>, GpxError> {
+        Point::check_coordinates(lat, lon)?;
+        let (south, west) = (lat.floor(), lon.floor());
+        let Some(tile) = self.tile(south as i32, west as i32)? else {
+            return Ok(None);
+        };
+        Ok(tile.elevation(lat - south, lon - west))
+    }
+}
+
+/// Asynchronous client for elevation web services (requires the `elevation-http` feature)
+///
+/// Speaks the lookup API shared by [Open Topo Data](https://www.opentopodata.org)
+/// and Open-Elevation: `GET <url>?locations=lat,lon|lat,lon` answering
+/// `{"results": [{"elevation": 612.0}, ...]}`. Points are sent in batches, so a
+/// long track costs a few requests rather than one per point. It runs on the
+/// caller's tokio runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gpx_extractor::{FillMode, Gpx, HttpElevationProvider};
+///
+/// # async fn run() -> Result<(), gpx_extractor::GpxError> {
+/// let service = HttpElevationProvider::open_topo_data("srtm30m");
+/// let mut gpx = Gpx::from_file("ride.gpx")?;
+/// let filled = service.fill_elevations(&mut gpx, FillMode::Missing).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "elevation-http")]
+#[derive(Debug, Clone)]
+pub struct HttpElevationProvider {
+    client: reqwest::Client,
+    url: String,
+    batch_size: usize,
+}
+
+#[cfg(feature = "elevation-http")]
+impl HttpElevationProvider {
+    /// Points per request of [`HttpElevationProvider::new`], the limit of the public Open Topo Data API
+    pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+    /// Client for the lookup endpoint at `url`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Client for a dataset of the public Open Topo Data API, such as `srtm30m` or `eudem25m`
+    pub fn open_topo_data(dataset: &str) -> Self {
+        Self::new(format!("https://api.opentopodata.org/v1/{dataset}"))
+    }
+
+    /// Cambia el número de puntos por petición (al menos uno)
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Elevations of `(lat, lon)` pairs, in the same order
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Elevation`] if a request fails or the answer cannot be read
+    pub async fn elevations(
+        &self,
+        coordinates: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, GpxError> {
+        let mut elevations = Vec::with_capacity(coordinates.len());
+        for batch in coordinates.chunks(self.batch_size) {
+            let body = self
+                .client
+                .get(&self.url)
+                .query(&[("locations", locations_param(batch))])
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(|e| GpxError::Elevation(e.to_string()))?
+                .bytes()
+                .await
+                .map_err(|e| GpxError::Elevation(e.to_string()))?;
+            elevations.extend(parse_results(&body, batch.len())?);
+        }
+        Ok(elevations)
+    }
+
+    /// Replaces track point elevations with the service answers according to `mode`
+    ///
+    /// Returns the number of points changed. The document is only modified
+    /// once every batch has been answered.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Elevation`] if a request fails
+    pub async fn fill_elevations(&self, gpx: &mut Gpx, mode: FillMode) -> Result<usize, GpxError> {
+        let coordinates: Vec<(f64, f64)> = gpx
+            .points()
+            .filter(|point| mode.wants(point))
+            .map(|point| (point.lat, point.lon))
+            .collect();
+        let elevations = self.elevations(&coordinates).await?;
+
+        let mut changed = 0;
+        let points = gpx.points_mut().filter(|point| mode.wants(point));
+        for (point, model) in points.zip(elevations) {
+            if mode.apply(point, model) {
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Parámetro `locations` de la API: `lat,lon|lat,lon`
+#[cfg(feature = "elevation-http")]
+fn locations_param(batch: &[(f64, f64)]) -> String {
+    batch
+        .iter()
+        .map(|(lat, lon)| format!("{lat:.6},{lon:.6}"))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Lee `results[].elevation` de la respuesta; `null` significa sin dato
+#[cfg(feature = "elevation-http")]
+fn parse_results(body: &[u8], expected: usize) -> Result<Vec<Option<f64>>, GpxError> {
+    let value: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| GpxError::Elevation(e.to_string()))?;
+    let results = value
+        .get("results")
+        .and_then(serde_json::Value::as_array)
+        .filter(|results| results.len() == expected)
+        .ok_or_else(|| {
+            let detail = value.get("error").and_then(serde_json::Value::as_str);
+            GpxError::Elevation(detail.map_or_else(
+                || format!("expected {expected} results from the elevation service"),
+                str::to_string,
+            ))
+        })?;
+    Ok(results
+        .iter()
+        .map(|result| result.get("elevation").and_then(serde_json::Value::as_f64))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tesela SRTM3 con elevación `fila + columna` y un hueco sin dato
+    fn synthetic_tile(dir: &std::path::Path, name: &str) {
+        let mut bytes = Vec::with_capacity(1201 * 1201 * 2);
+        for row in 0..1201_i16 {
+            for col in 0..1201_i16 {
+                let sample = if (row, col) == (600, 600) {
+                    HGT_VOID
+                } else {
+                    row + col
+                };
+                bytes.extend_from_slice(&sample.to_be_bytes());
+            }
+        }
+        std::fs::write(dir.join(name), bytes).unwrap();
+    }
+
+    #[test]
+    fn test_srtm_provider_interpolates_tiles() {
+        let dir = tempfile::tempdir().unwrap();
+        synthetic_tile(dir.path(), "N40W004.hgt");
+        let srtm = SrtmProvider::new(dir.path());
+
+        // Primera fila bajo el borde norte (que ya es de la tesela N41),
+        // esquina suroeste y un punto intermedio interpolado
+        let north = srtm
+            .elevation_at(41.0 - 1.0 / 1200.0, -4.0)
+            .unwrap()
+            .unwrap();
+        assert!((north - 1.0).abs() < 1e-9);
+        assert_eq!(srtm.elevation_at(40.0, -4.0).unwrap(), Some(1200.0));
+        let middle = srtm
+            .elevation_at(40.75, -3.75 + 0.5 / 1200.0)
+            .unwrap()
+            .unwrap();
+        assert!((middle - 600.5).abs() < 1e-9);
+        // El hueco se rellena con los vecinos
+        assert_eq!(srtm.elevation_at(40.5, -3.5).unwrap(), None);
+        assert!(srtm
+            .elevation_at(40.5, -3.5 + 0.5 / 1200.0)
+            .unwrap()
+            .is_some());
+        // Sin tesela no hay dato
+        assert_eq!(srtm.elevation_at(10.0, 10.0).unwrap(), None);
+
+        std::fs::write(dir.path().join("N10E010.hgt"), b"short").unwrap();
+        assert!(matches!(
+            SrtmProvider::new(dir.path()).elevation_at(10.5, 10.5),
+            Err(GpxError::Elevation(_))
+        ));
+    }
+
+    #[test]
+    fn test_fill_modes() {
+        struct Flat;
+        impl ElevationProvider for Flat {
+            fn elevation_at(&self, _lat: f64, _lon: f64) -> Result<Option<f64>, GpxError> {
+                Ok(Some(100.0))
+            }
+        }
+        let gpx = Gpx::try_from_str(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="40.0" lon="-3.0"/>
+                <trkpt lat="40.1" lon="-3.0"><ele>103</ele></trkpt>
+                <trkpt lat="40.2" lon="-3.0"><ele>140</ele></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let elevations = |gpx: &Gpx| gpx.points().map(|p| p.elevation).collect::<Vec<_>>();
+
+        let mut missing = gpx.clone();
+        assert_eq!(missing.fill_elevations(&Flat).unwrap(), 1);
+        assert_eq!(
+            elevations(&missing),
+            [Some(100.0), Some(103.0), Some(140.0)]
+        );
+
+        let mut deviating = gpx.clone();
+        let mode = FillMode::Deviating { tolerance_m: 5.0 };
+        assert_eq!(deviating.fill_elevations_with(&Flat, mode).unwrap(), 2);
+        assert_eq!(
+            elevations(&deviating),
+            [Some(100.0), Some(103.0), Some(100.0)]
+        );
+
+        let mut all = gpx;
+        assert_eq!(all.fill_elevations_with(&Flat, FillMode::All).unwrap(), 3);
+    }
+
+    #[cfg(feature = "elevation-http")]
+    #[test]
+    fn test_http_request_and_response_format() {
+        assert_eq!(
+            locations_param(&[(40.0, -3.0), (40.5, -3.25)]),
+            "40.000000,-3.000000|40.500000,-3.250000"
+        );
+        let body = br#"{"results": [{"elevation": 612.5}, {"elevation": null}], "status": "OK"}"#;
+        assert_eq!(parse_results(body, 2).unwrap(), vec![Some(612.5), None]);
+
+        let error = br#"{"error": "Too many locations", "status": "INVALID_REQUEST"}"#;
+        assert_eq!(
+            parse_results(error, 2).unwrap_err().to_string(),
+            "elevation lookup failed: Too many locations"
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, haversine_distance_with_radius, Point, EARTH_RADIUS_KM},
+    track::{Track, TrackSegment},
+};
+
+/// Semieje mayor del elipsoide WGS84, en metros
+const WGS84_A: f64 = 6_378_137.0;
+/// Achatamiento del elipsoide WGS84
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+/// Iteraciones máximas de Vincenty antes de darse por vencido (puntos casi antípodas)
+const VINCENTY_MAX_ITERATIONS: usize = 200;
+
+/// Body whose mean radius is used for great-circle distances
+///
+/// Statistics use it through [`StatisticsOptions::planet`](crate::StatisticsOptions::planet)
+/// and the models through [`DistanceModel::distance_km_on`]; everything else in
+/// the crate assumes [`Planet::Earth`].
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Planet, Point};
+///
+/// // Un grado de meridiano en Marte
+/// let a = Point::new(0.0, 0.0);
+/// let b = Point::new(1.0, 0.0);
+/// assert!((Planet::Mars.distance_km(&a, &b) - 59.16).abs() < 0.01);
+/// assert!((Planet::Earth.distance_km(&a, &b) - 111.19).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Planet {
+    /// The Earth, 6371 km
+    #[default]
+    Earth,
+    /// Mars, 3389.5 km
+    Mars,
+}
+
+impl Planet {
+    /// Radio medio en kilómetros
+    pub fn radius_km(self) -> f64 {
+        match self {
+            Self::Earth => EARTH_RADIUS_KM,
+            Self::Mars => 3389.5,
+        }
+    }
+
+    /// Distancia Haversine entre dos puntos sobre este planeta, en kilómetros
+    pub fn distance_km(self, p1: &Point, p2: &Point) -> f64 {
+        haversine_distance_with_radius(p1, p2, self.radius_km())
+    }
+}
+
+/// How the distance between two consecutive points is computed
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{DistanceModel, Point};
+///
+/// // Un grado de longitud sobre el ecuador
+/// let a = Point::new(0.0, 0.0);
+/// let b = Point::new(0.0, 1.0);
+/// assert!((DistanceModel::Haversine.distance_km(&a, &b) - 111.195).abs() < 0.001);
+/// assert!((DistanceModel::Vincenty.distance_km(&a, &b) - 111.319).abs() < 0.001);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DistanceModel {
+    /// Great circle on a sphere of the planet's radius ([`EARTH_RADIUS_KM`] by
+    /// default); fast, and within about 0.5% of the geodesic
+    #[default]
+    Haversine,
+    /// Geodesic on the WGS84 ellipsoid (Vincenty's inverse formula), accurate
+    /// to well under a millimeter; falls back to haversine for nearly antipodal
+    /// points, where the iteration does not converge, and on planets other than
+    /// the Earth, which have no reference ellipsoid here
+    Vincenty,
+    /// Straight line on an equirectangular projection centered on each pair of
+    /// points; the cheapest, and fine for points a few hundred meters apart
+    Projected,
+}
+
+impl DistanceModel {
+    /// Distance between two points, in kilometers
+    pub fn distance_km(self, p1: &Point, p2: &Point) -> f64 {
+        self.distance_km_on(Planet::Earth, p1, p2)
+    }
+
+    /// Distance between two points on `planet`, in kilometers
+    pub fn distance_km_on(self, planet: Planet, p1: &Point, p2: &Point) -> f64 {
+        match self {
+            Self::Vincenty if planet == Planet::Earth => {
+                vincenty_distance(p1, p2).unwrap_or_else(|| haversine_distance(p1, p2))
+            }
+            Self::Haversine | Self::Vincenty => planet.distance_km(p1, p2),
+            Self::Projected => projected_distance(p1, p2, planet.radius_km()),
+        }
+    }
+
+    /// Distance between two points in kilometers, including the elevation change
+    ///
+    /// The horizontal distance and the climb are combined as the sides of a right
+    /// triangle. When either point has no elevation, this is the horizontal distance.
+    pub fn distance_3d_km(self, p1: &Point, p2: &Point) -> f64 {
+        self.distance_3d_km_on(Planet::Earth, p1, p2)
+    }
+
+    /// Como [`DistanceModel::distance_3d_km`], sobre `planet`
+    pub fn distance_3d_km_on(self, planet: Planet, p1: &Point, p2: &Point) -> f64 {
+        let horizontal = self.distance_km_on(planet, p1, p2);
+        match (p1.elevation, p2.elevation) {
+            (Some(ele1), Some(ele2)) => horizontal.hypot((ele2 - ele1) / 1000.0),
+            _ => horizontal,
+        }
+    }
+}
+
+/// Geodesic distance on the WGS84 ellipsoid, in kilometers (Vincenty's inverse formula)
+///
+/// Returns `None` when the iteration does not converge, which only happens
+/// for nearly antipodal points.
+pub fn vincenty_distance(p1: &Point, p2: &Point) -> Option<f64> {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let l = (p2.lon - p1.lon).to_radians();
+    let u1 = ((1.0 - WGS84_F) * p1.lat.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * p2.lat.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..VINCENTY_MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            // Puntos coincidentes
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Sobre el ecuador cos²α es cero y el término no aplica
+        let cos_2sigma_m = if cos2_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+        };
+        let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+        if (lambda - previous).abs() > 1e-12 {
+            continue;
+        }
+
+        let u_sq = cos2_alpha * (WGS84_A * WGS84_A - b * b) / (b * b);
+        let big_a =
+            1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+        let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+        let delta_sigma = big_b
+            * sin_sigma
+            * (cos_2sigma_m
+                + big_b / 4.0
+                    * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                        - big_b / 6.0
+                            * cos_2sigma_m
+                            * (-3.0 + 4.0 * sin_sigma.powi(2))
+                            * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+        return Some(b * big_a * (sigma - delta_sigma) / 1000.0);
+    }
+    None
+}
+
+/// Distancia euclídea sobre una proyección equirectangular centrada en los dos puntos
+fn projected_distance(p1: &Point, p2: &Point, radius_km: f64) -> f64 {
+    let mean_lat = ((p1.lat + p2.lat) / 2.0).to_radians();
+    let x = (p2.lon - p1.lon).to_radians() * mean_lat.cos();
+    let y = (p2.lat - p1.lat).to_radians();
+    radius_km * x.hypot(y)
+}
+
+impl TrackSegment {
+    /// Calcula la distancia total del segmento en kilómetros con el modelo indicado
+    pub fn distance_km_with(&self, model: DistanceModel) -> f64 {
+        self.points
+            .windows(2)
+            .map(|window| model.distance_km(&window[0], &window[1]))
+            .sum()
+    }
+
+    /// Calcula la distancia total del segmento en kilómetros, contando el desnivel
+    pub fn distance_3d_km(&self) -> f64 {
+        self.distance_3d_km_with(DistanceModel::Haversine)
+    }
+
+    /// Calcula la distancia total del segmento en kilómetros, contando el desnivel, con el modelo indicado
+    pub fn distance_3d_km_with(&self, model: DistanceModel) -> f64 {
+        self.points
+            .windows(2)
+            .map(|window| model.distance_3d_km(&window[0], &window[1]))
+            .sum()
+    }
+}
+
+impl Track {
+    /// Calcula la distancia total del track en kilómetros, contando el desnivel
+    pub fn total_distance_3d_km(&self) -> f64 {
+        self.segments.iter().map(TrackSegment::distance_3d_km).sum()
+    }
+}
+
+impl Gpx {
+    /// Total distance in kilometers, including the elevation change between points
+    ///
+    /// Steep hikes are noticeably longer on the ground than on the map: a 30%
+    /// grade adds about 4% to the distance. Pairs of points without elevation
+    /// count their horizontal distance only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::try_from_str(
+    ///     r#"<gpx><trk><trkseg>
+    ///         <trkpt lat="46.0" lon="7.0"><ele>1000</ele></trkpt>
+    ///         <trkpt lat="46.009" lon="7.0"><ele>1300</ele></trkpt>
+    ///     </trkseg></trk></gpx>"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(gpx.total_distance_3d_km() > gpx.total_distance_km() * 1.03);
+    /// ```
+    pub fn total_distance_3d_km(&self) -> f64 {
+        self.tracks.iter().map(Track::total_distance_3d_km).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_scales_with_planet_radius() {
+        let madrid = Point::new(40.4168, -3.7038);
+        let new_york = Point::new(40.7128, -74.0060);
+
+        let earth = haversine_distance(&madrid, &new_york);
+        assert_eq!(Planet::default().distance_km(&madrid, &new_york), earth);
+        let mars = Planet::Mars.distance_km(&madrid, &new_york);
+        assert!((mars / earth - 3389.5 / 6371.0).abs() < 1e-12);
+        assert_eq!(haversine_distance_with_radius(&madrid, &new_york, 0.0), 0.0);
+
+        // Sin elipsoide de referencia, Vincenty usa la esfera del planeta
+        assert_eq!(
+            DistanceModel::Vincenty.distance_km_on(Planet::Mars, &madrid, &new_york),
+            mars
+        );
+        let projected = DistanceModel::Projected.distance_km(&madrid, &new_york);
+        let projected_mars =
+            DistanceModel::Projected.distance_km_on(Planet::Mars, &madrid, &new_york);
+        assert!((projected_mars / projected - 3389.5 / 6371.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_vincenty_reference_distance() {
+        // Ejemplo clásico de Vincenty: Flinders Peak a Buninyong, 54 972.271 m
+        let flinders = Point::new(-37.951_033_42, 144.424_867_89);
+        let buninyong = Point::new(-37.652_821_14, 143.926_495_54);
+        let km = vincenty_distance(&flinders, &buninyong).unwrap();
+        assert!((km - 54.972_271).abs() < 1e-6);
+        assert_eq!(vincenty_distance(&flinders, &flinders), Some(0.0));
+
+        // Casi antípodas: no converge y el modelo cae a haversine
+        let a = Point::new(0.0, 0.0);
+        let b = Point::new(0.5, 179.7);
+        assert_eq!(vincenty_distance(&a, &b), None);
+        assert_eq!(
+            DistanceModel::Vincenty.distance_km(&a, &b),
+            haversine_distance(&a, &b)
+        );
+    }
+
+    #[test]
+    fn test_projected_matches_haversine_at_short_range() {
+        let a = Point::new(45.0, 6.0);
+        let b = Point::new(45.003, 6.004);
+        let haversine = DistanceModel::Haversine.distance_km(&a, &b);
+        let projected = DistanceModel::Projected.distance_km(&a, &b);
+        assert!((haversine - projected).abs() < 1e-6);
+
+        let segment = TrackSegment::with_points(vec![a, b]);
+        assert_eq!(
+            segment.distance_km_with(DistanceModel::Haversine),
+            segment.distance_km()
+        );
+    }
+
+    #[test]
+    fn test_distance_3d_adds_the_climb() {
+        // 300 m de desnivel en 400 m horizontales: 500 m sobre el terreno
+        let a = Point::with_elevation(0.0, 0.0, 100.0);
+        let b = Point::with_elevation(0.0, 0.4 / 111.319_490_793, 400.0);
+        let projected = DistanceModel::Projected;
+        let horizontal = projected.distance_km(&a, &b);
+        assert!((projected.distance_3d_km(&a, &b) - horizontal.hypot(0.3)).abs() < 1e-12);
+
+        // Sin elevación en uno de los extremos cuenta solo la distancia horizontal
+        let c = Point::new(0.0, 0.01);
+        let segment = TrackSegment::with_points(vec![a, b, c]);
+        let flat = segment.distance_km();
+        let steep = segment.distance_3d_km();
+        assert!(steep > flat);
+        assert!((steep - flat - (horizontal.hypot(0.3) - horizontal)).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,491 @@
+use crate::gpx::{parser::Gpx, point::haversine_distance, track::TrackSegment};
+
+/// Filter applied to raw elevations before accumulating gain and loss
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ElevationFilter {
+    /// Leave elevations untouched; only the hysteresis threshold applies
+    None,
+    /// Centered moving average over [`ElevationOptions::window`] points
+    #[default]
+    MovingAverage,
+    /// One-dimensional Kalman filter, suited to noisy GPS altitude
+    Kalman {
+        /// Expected standard deviation of each reading in meters
+        measurement_noise_m: f64,
+        /// Expected real change of elevation between two points in meters
+        process_noise_m: f64,
+    },
+}
+
+/// Options for smoothed elevation gain and loss
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{ElevationOptions, GpxBuilder};
+///
+/// // Subida real de 40 m con ruido de ±2 m en cada lectura
+/// let gpx = GpxBuilder::new()
+///     .track(|t| {
+///         t.segment(|s| {
+///             (0..=40).fold(s, |s, i| {
+///                 let noise = if i % 2 == 0 { 2.0 } else { -2.0 };
+///                 s.point_with_elevation(40.0 + f64::from(i) * 1e-4, -3.0, 600.0 + f64::from(i) + noise)
+///             })
+///         })
+///     })
+///     .build()
+///     .unwrap();
+///
+/// let raw = gpx.total_elevation_gain().unwrap();
+/// let smoothed = gpx.elevation_gain_smoothed(ElevationOptions::default()).unwrap();
+/// assert!(raw >= 100.0);
+/// assert!((smoothed - 40.0).abs() < 5.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElevationOptions {
+    /// Filter applied before accumulating
+    pub filter: ElevationFilter,
+    /// Number of points of the moving average window
+    pub window: usize,
+    /// Minimum change in meters counted as ascent or descent (hysteresis)
+    pub min_threshold_m: f64,
+}
+
+impl Default for ElevationOptions {
+    fn default() -> Self {
+        Self {
+            filter: ElevationFilter::MovingAverage,
+            window: 5,
+            min_threshold_m: 3.0,
+        }
+    }
+}
+
+impl ElevationOptions {
+    /// Opciones que reproducen el cálculo sin suavizado
+    pub fn raw() -> Self {
+        Self {
+            filter: ElevationFilter::None,
+            window: 1,
+            min_threshold_m: 0.0,
+        }
+    }
+
+    /// Sin suavizado, contando solo cambios de al menos `meters` metros
+    pub fn threshold(meters: f64) -> Self {
+        Self {
+            min_threshold_m: meters,
+            ..Self::raw()
+        }
+    }
+
+    /// Media móvil centrada de `window` puntos, sin umbral
+    pub fn moving_average(window: usize) -> Self {
+        Self {
+            filter: ElevationFilter::MovingAverage,
+            window,
+            min_threshold_m: 0.0,
+        }
+    }
+
+    /// Applies the configured filter to a sequence of elevations
+    ///
+    /// Missing values stay `None` and are ignored by the filter.
+    pub fn smooth(self, elevations: &[Option<f64>]) -> Vec<Option<f64>> {
+        let known: Vec<f64> = elevations.iter().flatten().copied().collect();
+        let filtered = match self.filter {
+            ElevationFilter::None => known,
+            ElevationFilter::MovingAverage => moving_average(&known, self.window),
+            ElevationFilter::Kalman {
+                measurement_noise_m,
+                process_noise_m,
+            } => kalman(&known, measurement_noise_m, process_noise_m),
+        };
+
+        let mut filtered = filtered.into_iter();
+        elevations
+            .iter()
+            .map(|elevation| elevation.and_then(|_| filtered.next()))
+            .collect()
+    }
+
+    /// Smoothed `(gain, loss)` in meters of a sequence of elevations
+    ///
+    /// Returns `None` when fewer than two elevations are known.
+    pub fn gain_loss(self, elevations: &[Option<f64>]) -> Option<(f64, f64)> {
+        let smoothed: Vec<f64> = self.smooth(elevations).into_iter().flatten().collect();
+        let (&first, rest) = smoothed.split_first()?;
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (mut gain, mut loss) = (0.0, 0.0);
+        let mut reference = first;
+        for &elevation in rest {
+            let diff = elevation - reference;
+            // Histéresis: solo se acumula al superar el umbral desde la última referencia
+            if diff.abs() >= self.min_threshold_m {
+                if diff > 0.0 {
+                    gain += diff;
+                } else {
+                    loss -= diff;
+                }
+                reference = elevation;
+            }
+        }
+        Some((gain, loss))
+    }
+}
+
+/// A documented way of computing elevation gain, see [`Gpx::elevation_gain_strategies`]
+///
+/// Platforms disagree on ascent figures mostly because of how they treat GPS
+/// noise. Each strategy maps to an [`ElevationOptions`] preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElevationStrategy {
+    /// Every positive difference between consecutive points; the highest figure
+    Raw,
+    /// Changes of at least 3 m since the last counted point, without smoothing
+    Threshold3m,
+    /// Centered moving average over 5 points, without threshold
+    SmoothedWindow,
+    /// Moving average over 5 points plus the 3 m threshold (the crate default)
+    SmoothedThreshold,
+    /// Kalman filter for ±3 m GPS noise plus a 1 m threshold
+    Kalman,
+}
+
+impl ElevationStrategy {
+    /// Todas las estrategias, de la más a la menos sensible al ruido
+    pub const ALL: [Self; 5] = [
+        Self::Raw,
+        Self::Threshold3m,
+        Self::SmoothedWindow,
+        Self::SmoothedThreshold,
+        Self::Kalman,
+    ];
+
+    /// Nombre corto y estable, apto para tablas y la CLI
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Threshold3m => "threshold-3m",
+            Self::SmoothedWindow => "smoothed-window",
+            Self::SmoothedThreshold => "smoothed-threshold",
+            Self::Kalman => "kalman",
+        }
+    }
+
+    /// Opciones de suavizado equivalentes
+    pub fn options(self) -> ElevationOptions {
+        match self {
+            Self::Raw => ElevationOptions::raw(),
+            Self::Threshold3m => ElevationOptions::threshold(3.0),
+            Self::SmoothedWindow => ElevationOptions::moving_average(5),
+            Self::SmoothedThreshold => ElevationOptions::default(),
+            Self::Kalman => ElevationOptions {
+                filter: ElevationFilter::Kalman {
+                    measurement_noise_m: 3.0,
+                    process_noise_m: 1.0,
+                },
+                window: 1,
+                min_threshold_m: 1.0,
+            },
+        }
+    }
+}
+
+/// Gain and loss computed with one [`ElevationStrategy`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElevationEstimate {
+    /// Strategy used
+    pub strategy: ElevationStrategy,
+    /// Total ascent in meters, if at least two elevations are known in a segment
+    pub gain_m: Option<f64>,
+    /// Total descent in meters
+    pub loss_m: Option<f64>,
+}
+
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    if window <= 1 {
+        return values.to_vec();
+    }
+
+    let half = window / 2;
+    (0..values.len())
+        .map(|i| {
+            let start = i.saturating_sub(half);
+            let end = (i + half + 1).min(values.len());
+            let slice = &values[start..end];
+            #[allow(clippy::cast_precision_loss)]
+            let len = slice.len() as f64;
+            slice.iter().sum::<f64>() / len
+        })
+        .collect()
+}
+
+fn kalman(values: &[f64], measurement_noise_m: f64, process_noise_m: f64) -> Vec<f64> {
+    let r = measurement_noise_m * measurement_noise_m;
+    let q = process_noise_m * process_noise_m;
+    let Some(&(mut estimate)) = values.first() else {
+        return Vec::new();
+    };
+    let mut variance = r;
+
+    values
+        .iter()
+        .map(|&measurement| {
+            variance += q;
+            let gain = variance / (variance + r);
+            estimate += gain * (measurement - estimate);
+            variance *= 1.0 - gain;
+            estimate
+        })
+        .collect()
+}
+
+impl TrackSegment {
+    fn elevations(&self) -> Vec<Option<f64>> {
+        self.points.iter().map(|p| p.elevation).collect()
+    }
+
+    /// Smoothed `(gain, loss)` in meters of this segment
+    pub fn elevation_gain_loss_smoothed(&self, options: ElevationOptions) -> Option<(f64, f64)> {
+        options.gain_loss(&self.elevations())
+    }
+
+    /// Replaces the elevation of every point by its filtered value
+    pub fn smooth_elevation(&mut self, options: ElevationOptions) {
+        let smoothed = options.smooth(&self.elevations());
+        for (point, elevation) in self.points.iter_mut().zip(smoothed) {
+            point.elevation = elevation;
+        }
+    }
+}
+
+impl Gpx {
+    fn elevation_gain_loss_smoothed(&self, options: ElevationOptions) -> Option<(f64, f64)> {
+        self.tracks
+            .iter()
+            .flat_map(|track| &track.segments)
+            .filter_map(|segment| segment.elevation_gain_loss_smoothed(options))
+            .reduce(|(gain, loss), (g, l)| (gain + g, loss + l))
+    }
+
+    /// Total ascent in meters after smoothing, far less sensitive to GPS noise than
+    /// [`Gpx::total_elevation_gain`]
+    pub fn elevation_gain_smoothed(&self, options: ElevationOptions) -> Option<f64> {
+        self.elevation_gain_loss_smoothed(options)
+            .map(|(gain, _)| gain)
+    }
+
+    /// Total descent in meters after smoothing
+    pub fn elevation_loss_smoothed(&self, options: ElevationOptions) -> Option<f64> {
+        self.elevation_gain_loss_smoothed(options)
+            .map(|(_, loss)| loss)
+    }
+
+    /// Elevation gain and loss under every [`ElevationStrategy`], side by side
+    ///
+    /// Use it to find which strategy matches the figure reported by another
+    /// platform, then pass its [`ElevationStrategy::options`] to
+    /// [`Gpx::elevation_gain_smoothed`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{ElevationStrategy, GpxBuilder};
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| {
+    ///         t.segment(|s| {
+    ///             s.point_with_elevation(40.0, -3.0, 600.0)
+    ///                 .point_with_elevation(40.001, -3.0, 602.0)
+    ///                 .point_with_elevation(40.002, -3.0, 600.0)
+    ///                 .point_with_elevation(40.003, -3.0, 610.0)
+    ///         })
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let estimates = gpx.elevation_gain_strategies();
+    /// assert_eq!(estimates.len(), ElevationStrategy::ALL.len());
+    /// assert_eq!(estimates[0].strategy.name(), "raw");
+    /// assert_eq!(estimates[0].gain_m, Some(12.0));
+    /// assert_eq!(estimates[1].gain_m, Some(10.0));
+    /// ```
+    pub fn elevation_gain_strategies(&self) -> Vec<ElevationEstimate> {
+        ElevationStrategy::ALL
+            .iter()
+            .map(|&strategy| {
+                let totals = self.elevation_gain_loss_smoothed(strategy.options());
+                ElevationEstimate {
+                    strategy,
+                    gain_m: totals.map(|(gain, _)| gain),
+                    loss_m: totals.map(|(_, loss)| loss),
+                }
+            })
+            .collect()
+    }
+
+    /// Elevation profile as `(distance_km, elevation_m)` pairs
+    ///
+    /// Distance accumulates along every segment of every track, without counting
+    /// the gaps between segments; points without elevation are skipped.
+    pub fn elevation_profile(&self) -> Vec<(f64, f64)> {
+        let mut profile = Vec::new();
+        let mut distance = 0.0;
+        for segment in self.tracks.iter().flat_map(|track| &track.segments) {
+            for (i, point) in segment.points.iter().enumerate() {
+                if i > 0 {
+                    distance += haversine_distance(&segment.points[i - 1], point);
+                }
+                if let Some(elevation) = point.elevation {
+                    profile.push((distance, elevation));
+                }
+            }
+        }
+        profile
+    }
+
+    /// Replaces every track point elevation by its filtered value
+    ///
+    /// Useful before exporting, so other tools compute sensible ascent figures.
+    pub fn smooth_elevation(&mut self, options: ElevationOptions) {
+        for segment in self.tracks.iter_mut().flat_map(|track| &mut track.segments) {
+            segment.smooth_elevation(options);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::point::Point;
+
+    fn noisy_segment() -> TrackSegment {
+        // Terreno llano con ruido de ±1.5 m y luego una subida real de 30 m
+        let flat = (0..20).map(|i| if i % 2 == 0 { 601.5 } else { 598.5 });
+        let climb = (1..=30).map(|i| 600.0 + f64::from(i));
+        TrackSegment::with_points(
+            flat.chain(climb)
+                .map(|ele| Point::with_elevation(40.0, -3.0, ele))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_smoothed_gain_filters_noise() {
+        let segment = noisy_segment();
+        let mut gpx = Gpx::new();
+        let mut track = crate::gpx::track::Track::new();
+        track.add_segment(segment);
+        gpx.add_track(track);
+
+        assert!(gpx.total_elevation_gain().unwrap() > 55.0);
+
+        let options = ElevationOptions::default();
+        let gain = gpx.elevation_gain_smoothed(options).unwrap();
+        assert!((gain - 30.0).abs() < 3.0, "gain = {gain}");
+        assert!(gpx.elevation_loss_smoothed(options).unwrap() < 1.0);
+
+        let raw = ElevationOptions::raw();
+        assert_eq!(gpx.elevation_gain_smoothed(raw), gpx.total_elevation_gain());
+    }
+
+    #[test]
+    fn test_kalman_and_threshold() {
+        let elevations: Vec<Option<f64>> =
+            noisy_segment().points.iter().map(|p| p.elevation).collect();
+        let options = ElevationOptions {
+            filter: ElevationFilter::Kalman {
+                measurement_noise_m: 2.0,
+                process_noise_m: 1.0,
+            },
+            ..ElevationOptions::default()
+        };
+        let (gain, loss) = options.gain_loss(&elevations).unwrap();
+        assert!(gain > 20.0 && gain < 35.0, "gain = {gain}");
+        assert!(loss < 3.0);
+
+        let threshold_only = ElevationOptions {
+            filter: ElevationFilter::None,
+            window: 1,
+            min_threshold_m: 5.0,
+        };
+        assert_eq!(
+            threshold_only.gain_loss(&[Some(0.0), Some(4.0), Some(0.0), Some(6.0)]),
+            Some((6.0, 0.0))
+        );
+        assert_eq!(threshold_only.gain_loss(&[Some(1.0), None]), None);
+    }
+
+    #[test]
+    fn test_elevation_profile_skips_gaps_and_missing_values() {
+        let gpx = crate::GpxBuilder::new()
+            .track(|t| {
+                t.segment(|s| s.point_with_elevation(40.0, -3.0, 600.0).point(40.01, -3.0))
+                    .segment(|s| s.point_with_elevation(41.0, -3.0, 650.0))
+            })
+            .build()
+            .unwrap();
+
+        let profile = gpx.elevation_profile();
+        assert_eq!(profile.len(), 2);
+        assert_eq!(profile[0], (0.0, 600.0));
+        assert!((profile[1].0 - 1.112).abs() < 0.01);
+        assert_eq!(profile[1].1, 650.0);
+    }
+
+    #[test]
+    fn test_smooth_elevation_keeps_missing_values() {
+        let mut segment = TrackSegment::with_points(vec![
+            Point::with_elevation(40.0, -3.0, 100.0),
+            Point::new(40.0, -3.0),
+            Point::with_elevation(40.0, -3.0, 110.0),
+        ]);
+        segment.smooth_elevation(ElevationOptions {
+            window: 3,
+            ..ElevationOptions::default()
+        });
+
+        let elevations: Vec<Option<f64>> = segment.points.iter().map(|p| p.elevation).collect();
+        assert_eq!(elevations, vec![Some(105.0), None, Some(105.0)]);
+    }
+
+    #[test]
+    fn test_elevation_gain_strategies_side_by_side() {
+        let mut gpx = Gpx::new();
+        let mut track = crate::gpx::track::Track::new();
+        track.add_segment(noisy_segment());
+        gpx.add_track(track);
+
+        let estimates = gpx.elevation_gain_strategies();
+        let names: Vec<&str> = estimates.iter().map(|e| e.strategy.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "raw",
+                "threshold-3m",
+                "smoothed-window",
+                "smoothed-threshold",
+                "kalman"
+            ]
+        );
+        assert_eq!(estimates[0].gain_m, gpx.total_elevation_gain());
+        assert_eq!(
+            estimates[3].gain_m,
+            gpx.elevation_gain_smoothed(ElevationOptions::default())
+        );
+
+        // Las estrategias con filtro o umbral se acercan a los 30 m reales
+        let raw = estimates[0].gain_m.unwrap();
+        for estimate in &estimates[1..] {
+            let gain = estimate.gain_m.unwrap();
+            assert!(gain < raw, "{}: {gain}", estimate.strategy.name());
+            assert!(gain >= 25.0, "{}: {gain}", estimate.strategy.name());
+        }
+        assert!(Gpx::new().elevation_gain_strategies()[0].gain_m.is_none());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::gpx::{error::GpxError, parser::Gpx};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+
+/// Cabecera de los ficheros cifrados: firma y versión del formato
+const MAGIC: &[u8; 8] = b"GPXENC\x00\x01";
+
+/// Longitud del nonce de ChaCha20-Poly1305
+const NONCE_LEN: usize = 12;
+
+/// Size in bytes of the keys accepted by the encryption helpers
+pub const KEY_LEN: usize = 32;
+
+fn cipher(key: &[u8]) -> Result<ChaCha20Poly1305, GpxError> {
+    ChaCha20Poly1305::new_from_slice(key).map_err(|_| {
+        GpxError::Encryption(format!(
+            "key must be {KEY_LEN} bytes long, got {}",
+            key.len()
+        ))
+    })
+}
+
+impl Gpx {
+    /// Encrypts the document with ChaCha20-Poly1305
+    ///
+    /// The output is a short header, a random 96-bit nonce and the authenticated
+    /// ciphertext of the GPX XML. The header is authenticated as well, so any
+    /// tampering is detected by [`Gpx::from_encrypted_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Encryption`] if the key is not [`KEY_LEN`] bytes long
+    pub fn to_encrypted_bytes(&self, key: &[u8]) -> Result<Vec<u8>, GpxError> {
+        let cipher = cipher(key)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let xml = self.to_xml();
+        let payload = Payload {
+            msg: xml.as_bytes(),
+            aad: MAGIC,
+        };
+        let ciphertext = cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| GpxError::Encryption("cannot encrypt document".to_string()))?;
+
+        let mut bytes = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+
+    /// Decrypts and parses bytes produced by [`Gpx::to_encrypted_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Encryption`] if the bytes are not an encrypted document,
+    /// the key is wrong or has the wrong length or the content was modified, or a parse error if the
+    /// decrypted document is not valid GPX
+    pub fn from_encrypted_bytes(bytes: &[u8], key: &[u8]) -> Result<Self, GpxError> {
+        let body = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .filter(|body| body.len() >= NONCE_LEN)
+            .ok_or_else(|| GpxError::Encryption("not an encrypted GPX document".to_string()))?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+
+        let cipher = cipher(key)?;
+        let payload = Payload {
+            msg: ciphertext,
+            aad: MAGIC,
+        };
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| GpxError::Encryption("wrong key or corrupted document".to_string()))?;
+
+        let xml = String::from_utf8(plaintext)
+            .map_err(|e| GpxError::Schema(format!("document is not valid UTF-8: {e}")))?;
+        Self::try_from_str(&xml)
+    }
+
+    /// Saves the GPX encrypted with a 32-byte key, for archives kept on untrusted storage
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be encrypted or the file cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::GpxBuilder;
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # let path = dir.path().join("ride.gpx.enc");
+    /// # let path = path.to_str().unwrap();
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.name("Home").segment(|s| s.point(40.0, -3.0)))
+    ///     .build()
+    ///     .unwrap();
+    /// let key = [7u8; 32];
+    ///
+    /// gpx.save_to_file_encrypted(path, &key).unwrap();
+    /// let opened = gpx_extractor::Gpx::open_encrypted(path, &key).unwrap();
+    /// assert_eq!(opened.track_names(), vec!["Home"]);
+    /// ```
+    pub fn save_to_file_encrypted(&self, path: &str, key: &[u8]) -> Result<(), GpxError> {
+        std::fs::write(path, self.to_encrypted_bytes(key)?)?;
+        Ok(())
+    }
+
+    /// Opens a file written by [`Gpx::save_to_file_encrypted`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, the key is wrong, the file was
+    /// modified, or the decrypted content is not valid GPX
+    pub fn open_encrypted(path: &str, key: &[u8]) -> Result<Self, GpxError> {
+        Self::from_encrypted_bytes(&std::fs::read(path)?, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GpxBuilder;
+
+    const KEY: [u8; KEY_LEN] = [42; KEY_LEN];
+
+    fn sample() -> Gpx {
+        GpxBuilder::new()
+            .track(|t| {
+                t.name("Secret")
+                    .segment(|s| s.point(40.4168, -3.7038).point(40.42, -3.70))
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encrypted_roundtrip_hides_plaintext() {
+        let bytes = sample().to_encrypted_bytes(&KEY).unwrap();
+        assert!(bytes.starts_with(MAGIC));
+        assert!(!bytes.windows(6).any(|w| w == b"Secret"));
+
+        let gpx = Gpx::from_encrypted_bytes(&bytes, &KEY).unwrap();
+        assert_eq!(gpx.track_names(), vec!["Secret"]);
+        assert_eq!(gpx.total_points(), 2);
+
+        // Cada cifrado usa un nonce distinto
+        assert_ne!(bytes, sample().to_encrypted_bytes(&KEY).unwrap());
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering_are_rejected() {
+        let mut bytes = sample().to_encrypted_bytes(&KEY).unwrap();
+        let wrong = Gpx::from_encrypted_bytes(&bytes, &[0; KEY_LEN]);
+        assert!(matches!(wrong, Err(GpxError::Encryption(_))));
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            Gpx::from_encrypted_bytes(&bytes, &KEY),
+            Err(GpxError::Encryption(_))
+        ));
+
+        assert!(matches!(
+            sample().to_encrypted_bytes(b"short"),
+            Err(GpxError::Encryption(msg)) if msg.contains("32 bytes")
+        ));
+
+        let plain = sample().to_xml();
+        assert!(matches!(
+            Gpx::from_encrypted_bytes(plain.as_bytes(), &KEY),
+            Err(GpxError::Encryption(msg)) if msg.contains("not an encrypted")
+        ));
+    }
+
+    #[test]
+    fn test_save_and_open_encrypted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.gpx.enc");
+        let path = path.to_str().unwrap();
+
+        sample().save_to_file_encrypted(path, &KEY).unwrap();
+        assert!(!std::fs::read(path).unwrap().starts_with(b"<?xml"));
+        assert_eq!(Gpx::open_encrypted(path, &KEY).unwrap().total_points(), 2);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::gpx::validate::ValidationIssue;
+use std::fmt;
+
+/// Errors produced by GPX parsing, validation and IO operations
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GpxError {
+    /// The input is not well-formed XML
+    Xml(quick_xml::Error),
+    /// The XML is well-formed but does not follow the GPX schema
+    /// (e.g. a point without `lat`/`lon`, or a non-numeric elevation)
+    Schema(String),
+    /// A latitude or longitude lies outside the WGS84 range
+    InvalidCoordinate {
+        /// Offending latitude in decimal degrees
+        lat: f64,
+        /// Offending longitude in decimal degrees
+        lon: f64,
+    },
+    /// Reading or writing a file failed
+    Io(std::io::Error),
+    /// The document declares a GPX version this crate does not understand
+    UnsupportedVersion(String),
+    /// A naming template is malformed (e.g. an unclosed `{placeholder`)
+    InvalidTemplate(String),
+    /// An archive manifest could not be parsed
+    InvalidManifest(String),
+    /// A storage backend rejected a key or failed an operation
+    Store(String),
+    /// Encrypting or decrypting a document failed (wrong key, tampered content)
+    Encryption(String),
+    /// A signed document has no signature or was modified after signing
+    InvalidSignature(String),
+    /// An elevation provider could not answer (unreadable tile, failed request)
+    Elevation(String),
+    /// No registered reader or writer handles the format (an extension, a MIME
+    /// type or content that [`detect`](crate::formats::detect) does not recognize)
+    UnknownFormat(String),
+    /// A FIT activity file is malformed (bad signature or CRC, truncated records)
+    Fit(String),
+    /// Writing was refused because [`Gpx::validate`](crate::Gpx::validate) found errors
+    Validation(Vec<ValidationIssue>),
+}
+
+impl fmt::Display for GpxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xml(e) => write!(f, "XML syntax error: {e}"),
+            Self::Schema(msg) => write!(f, "invalid GPX document: {msg}"),
+            Self::InvalidCoordinate { lat, lon } => {
+                write!(f, "invalid coordinate: lat={lat}, lon={lon}")
+            }
+            Self::Io(e) => write!(f, "IO error: {e}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported GPX version: {version}")
+            }
+            Self::InvalidTemplate(msg) => write!(f, "invalid template: {msg}"),
+            Self::InvalidManifest(msg) => write!(f, "invalid manifest: {msg}"),
+            Self::Store(msg) => write!(f, "storage error: {msg}"),
+            Self::Encryption(msg) => write!(f, "encryption error: {msg}"),
+            Self::InvalidSignature(msg) => write!(f, "invalid signature: {msg}"),
+            Self::Elevation(msg) => write!(f, "elevation lookup failed: {msg}"),
+            Self::UnknownFormat(msg) => write!(f, "unknown format: {msg}"),
+            Self::Fit(msg) => write!(f, "invalid FIT file: {msg}"),
+            Self::Validation(issues) => match issues.first() {
+                Some(first) if issues.len() > 1 => {
+                    write!(f, "{} validation errors, first: {first}", issues.len())
+                }
+                Some(first) => write!(f, "validation failed: {first}"),
+                None => f.write_str("validation failed"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for GpxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Xml(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<quick_xml::DeError> for GpxError {
+    fn from(e: quick_xml::DeError) -> Self {
+        match e {
+            quick_xml::DeError::InvalidXml(e) => Self::from(e),
+            other => Self::Schema(other.to_string()),
+        }
+    }
+}
+
+impl From<quick_xml::Error> for GpxError {
+    fn from(e: quick_xml::Error) -> Self {
+        match e {
+            quick_xml::Error::Io(io) => Self::Io(std::io::Error::new(io.kind(), io.to_string())),
+            other => Self::Xml(other),
+        }
+    }
+}
+
+#[cfg(feature = "s3")]
+impl From<object_store::Error> for GpxError {
+    fn from(e: object_store::Error) -> Self {
+        Self::Store(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for GpxError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_messages() {
+        let error = GpxError::UnsupportedVersion("2.0".to_string());
+        assert_eq!(error.to_string(), "unsupported GPX version: 2.0");
+
+        let error = GpxError::InvalidCoordinate {
+            lat: 100.0,
+            lon: 0.0,
+        };
+        assert!(error.to_string().contains("lat=100"));
+    }
+
+    #[test]
+    fn test_from_io_error_keeps_source() {
+        use std::error::Error;
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing.gpx");
+        let error = GpxError::from(io);
+        assert!(matches!(error, GpxError::Io(_)));
+        assert!(error.source().is_some());
+    }
+}
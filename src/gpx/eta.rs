@@ -0,0 +1,342 @@
+use crate::gpx::{
+    error::GpxError,
+    pace::Pace,
+    parser::{start_time, Gpx},
+    point::{haversine_distance, Point},
+};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// Segundos de ascenso por metro según Naismith (una hora cada 600 m)
+const NAISMITH_SECONDS_PER_M: f64 = 6.0;
+
+/// How long the route is expected to take, for [`Gpx::annotate_waypoints_with_eta`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaceModel {
+    /// The same pace over the whole route
+    Constant(Pace),
+    /// Naismith's rule for hiking: the given pace on the flat plus one hour per
+    /// 600 m of climbing
+    Naismith(Pace),
+}
+
+impl PaceModel {
+    /// Segundos previstos para recorrer `distance_km` subiendo `climb_m`
+    fn seconds(self, distance_km: f64, climb_m: f64) -> f64 {
+        match self {
+            Self::Constant(pace) => distance_km * pace.seconds_per_km(),
+            Self::Naismith(pace) => {
+                distance_km * pace.seconds_per_km() + climb_m * NAISMITH_SECONDS_PER_M
+            }
+        }
+    }
+}
+
+/// Redondea segundos a minutos enteros
+// Tiempos de ruta: muy lejos de los límites de i64
+#[allow(clippy::cast_possible_truncation)]
+fn whole_minutes(seconds: f64) -> i64 {
+    (seconds / 60.0).round().max(0.0) as i64
+}
+
+/// Predicted arrival at one checkpoint, see [`Gpx::predict_splits`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointSplit {
+    /// Index of the checkpoint in [`Gpx::waypoints`]
+    pub waypoint_index: usize,
+    /// Name of the checkpoint (see [`Waypoint::display_name`](crate::Waypoint::display_name))
+    pub name: String,
+    /// Distance from the start along the route, in kilometers
+    pub distance_km: f64,
+    /// Elevation climbed from the start, in meters
+    pub climb_m: f64,
+    /// Predicted time from the start, in whole minutes
+    pub elapsed_minutes: i64,
+    /// Predicted clock time of arrival, if the start time is known
+    pub arrival: Option<DateTime<Utc>>,
+}
+
+impl CheckpointSplit {
+    /// Tiempo previsto desde la salida como `"h:mm"`
+    pub fn elapsed_formatted(&self) -> String {
+        format!(
+            "{}:{:02}",
+            self.elapsed_minutes / 60,
+            self.elapsed_minutes % 60
+        )
+    }
+}
+
+/// Predicted splits for every checkpoint of a planned route, in race order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PacingChart {
+    /// One split per checkpoint, sorted by distance
+    pub splits: Vec<CheckpointSplit>,
+}
+
+impl PacingChart {
+    /// Writes the chart as CSV (`checkpoint,distance_km,climb_m,elapsed,arrival`)
+    ///
+    /// Names containing commas, quotes or line breaks are quoted; the arrival
+    /// column is empty when the start time is unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Io`] if writing fails
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> Result<(), GpxError> {
+        writeln!(writer, "checkpoint,distance_km,climb_m,elapsed,arrival")?;
+        for split in &self.splits {
+            let name = if split.name.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", split.name.replace('"', "\"\""))
+            } else {
+                split.name.clone()
+            };
+            let arrival = split
+                .arrival
+                .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_default();
+            writeln!(
+                writer,
+                "{name},{:.2},{:.0},{},{arrival}",
+                split.distance_km,
+                split.climb_m,
+                split.elapsed_formatted()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Gpx {
+    /// Predicts when each waypoint of a planned route will be reached
+    ///
+    /// Waypoints act as checkpoints and are placed at the nearest track point; the
+    /// time to reach them comes from the pace model, the distance and the climb
+    /// along the track. Arrival clock times are computed from `start`, or from the
+    /// first timestamp of the route when `start` is `None`. Write the result with
+    /// [`PacingChart::to_csv`], or use [`Gpx::annotate_waypoints_with_eta`] for an
+    /// annotated GPX. The chart is empty if the document has no track points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use gpx_extractor::{GpxBuilder, Pace, PaceModel, Waypoint};
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.segment(|s| s.point(40.0, -3.0).point(40.1, -3.0).point(40.2, -3.0)))
+    ///     .waypoint(Waypoint::with_name(40.2, -3.0, "Finish".to_string()))
+    ///     .waypoint(Waypoint::with_name(40.1, -3.0, "Aid 1".to_string()))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let pace = Pace::from_speed_kmh(10.0).unwrap();
+    /// let start = Utc.with_ymd_and_hms(2024, 9, 1, 7, 0, 0).unwrap();
+    /// let chart = gpx.predict_splits(PaceModel::Constant(pace), Some(start));
+    ///
+    /// assert_eq!(chart.splits[0].name, "Aid 1");
+    /// assert_eq!(chart.splits[1].elapsed_formatted(), "2:13");
+    ///
+    /// let mut csv = Vec::new();
+    /// chart.to_csv(&mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// assert!(csv.ends_with("Finish,22.24,0,2:13,2024-09-01T09:13:00Z\n"));
+    /// ```
+    pub fn predict_splits(&self, model: PaceModel, start: Option<DateTime<Utc>>) -> PacingChart {
+        let start = start.or_else(|| start_time(self));
+        let mut splits: Vec<CheckpointSplit> = self
+            .checkpoints(model)
+            .into_iter()
+            .enumerate()
+            .map(
+                |(i, (distance_km, climb_m, elapsed_minutes))| CheckpointSplit {
+                    waypoint_index: i,
+                    name: self.waypoints[i].display_name(),
+                    distance_km,
+                    climb_m,
+                    elapsed_minutes,
+                    arrival: start.map(|start| start + Duration::minutes(elapsed_minutes)),
+                },
+            )
+            .collect();
+        splits.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+        PacingChart { splits }
+    }
+
+    /// Appends the distance from the start and the estimated arrival time to the
+    /// comment (`<cmt>`) of every waypoint, turning the GPX into a cue sheet
+    ///
+    /// Each waypoint is placed at the nearest track point. The note reads
+    /// `"12.4 km, ETA +2:05"`; when the recording has a start time, the arrival
+    /// clock time in UTC is added (`"… (10:05Z)"`). Existing comments are kept and
+    /// the note goes on a new line. Returns the number of annotated waypoints,
+    /// which is zero if the document has no track points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{GpxBuilder, Pace, PaceModel};
+    ///
+    /// let mut gpx = GpxBuilder::new()
+    ///     .track(|t| t.segment(|s| s.point(40.0, -3.0).point(40.1, -3.0)))
+    ///     .waypoint_at(40.1, -3.0)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let pace = Pace::from_speed_kmh(20.0).unwrap();
+    /// assert_eq!(gpx.annotate_waypoints_with_eta(PaceModel::Constant(pace)), 1);
+    /// assert_eq!(gpx.waypoints[0].comment.as_deref(), Some("11.1 km, ETA +0:33"));
+    /// ```
+    pub fn annotate_waypoints_with_eta(&mut self, model: PaceModel) -> usize {
+        let splits = self.predict_splits(model, None).splits;
+        for split in &splits {
+            let mut note = format!(
+                "{:.1} km, ETA +{}",
+                split.distance_km,
+                split.elapsed_formatted()
+            );
+            if let Some(arrival) = split.arrival {
+                let _ = write!(note, " ({}Z)", arrival.format("%H:%M"));
+            }
+            let waypoint = &mut self.waypoints[split.waypoint_index];
+            waypoint.comment = Some(match waypoint.comment.take() {
+                Some(comment) if !comment.is_empty() => format!("{comment}\n{note}"),
+                _ => note,
+            });
+        }
+        splits.len()
+    }
+
+    /// Distancia, ascenso acumulado y minutos previstos hasta cada waypoint, en su orden
+    fn checkpoints(&self, model: PaceModel) -> Vec<(f64, f64, i64)> {
+        let points = self.get_all_points();
+        let metrics = self.point_metrics();
+        if points.is_empty() {
+            return Vec::new();
+        }
+
+        // Ascenso acumulado hasta cada punto, sin contar los saltos entre segmentos
+        let mut climb_m = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 && metrics[i].distance_km > metrics[i - 1].distance_km {
+                if let (Some(from), Some(to)) = (points[i - 1].elevation, point.elevation) {
+                    total += (to - from).max(0.0);
+                }
+            }
+            climb_m.push(total);
+        }
+
+        self.waypoints
+            .iter()
+            .map(|waypoint| {
+                let position = Point::new(waypoint.lat, waypoint.lon);
+                let nearest = points
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| {
+                        haversine_distance(a, &position)
+                            .total_cmp(&haversine_distance(b, &position))
+                    })
+                    .map_or(0, |(i, _)| i);
+
+                let distance_km = metrics[nearest].distance_km;
+                let climb = climb_m[nearest];
+                (
+                    distance_km,
+                    climb,
+                    whole_minutes(model.seconds(distance_km, climb)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{
+        track::{Track, TrackSegment},
+        waypoint::Waypoint,
+    };
+    use chrono::TimeZone;
+
+    fn hike() -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(42.00, 1.0, Some(1000.0), start),
+            Point::with_elevation(42.05, 1.0, 1600.0),
+            Point::with_elevation(42.10, 1.0, 1200.0),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        let mut summit = Waypoint::with_name(42.0501, 1.0002, "Summit".to_string());
+        summit.comment = Some("Water source".to_string());
+        gpx.add_waypoint(summit);
+        gpx.add_waypoint(Waypoint::new(42.10, 1.0));
+        gpx
+    }
+
+    #[test]
+    fn test_naismith_eta_includes_climb_and_clock_time() {
+        let mut gpx = hike();
+        let pace = Pace::from_speed_kmh(5.0).unwrap();
+        assert_eq!(
+            gpx.annotate_waypoints_with_eta(PaceModel::Naismith(pace)),
+            2
+        );
+
+        // 5.56 km a 12 min/km son 1:07, más una hora por los 600 m de subida
+        assert_eq!(
+            gpx.waypoints[0].comment.as_deref(),
+            Some("Water source\n5.6 km, ETA +2:07 (10:07Z)")
+        );
+        // La bajada no añade tiempo
+        assert_eq!(
+            gpx.waypoints[1].comment.as_deref(),
+            Some("11.1 km, ETA +3:13 (11:13Z)")
+        );
+    }
+
+    #[test]
+    fn test_eta_without_track_points() {
+        let mut gpx = Gpx::new();
+        gpx.add_waypoint(Waypoint::new(42.0, 1.0));
+        let pace = Pace::from_speed_kmh(5.0).unwrap();
+        assert_eq!(
+            gpx.annotate_waypoints_with_eta(PaceModel::Constant(pace)),
+            0
+        );
+        assert_eq!(gpx.waypoints[0].comment, None);
+        assert!(gpx
+            .predict_splits(PaceModel::Constant(pace), None)
+            .splits
+            .is_empty());
+    }
+
+    #[test]
+    fn test_predict_splits_in_race_order_and_csv() {
+        let mut gpx = hike();
+        gpx.waypoints[1].name = Some("Hut, \"Refugi\"".to_string());
+        gpx.waypoints.swap(0, 1);
+        let pace = Pace::from_speed_kmh(5.0).unwrap();
+        let chart = gpx.predict_splits(PaceModel::Naismith(pace), None);
+
+        assert_eq!(chart.splits.len(), 2);
+        assert_eq!(chart.splits[0].name, "Summit");
+        assert_eq!(chart.splits[0].waypoint_index, 1);
+        assert!((chart.splits[0].climb_m - 600.0).abs() < 1e-9);
+        assert_eq!(chart.splits[1].elapsed_formatted(), "3:13");
+
+        let mut csv = Vec::new();
+        chart.to_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "checkpoint,distance_km,climb_m,elapsed,arrival");
+        assert_eq!(lines[1], "Summit,5.56,600,2:07,2024-07-01T10:07:00Z");
+        assert!(lines[2].starts_with("\"Hut, \"\"Refugi\"\"\",11.12,600,3:13,"));
+    }
+}
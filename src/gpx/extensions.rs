@@ -0,0 +1,345 @@
+use crate::gpx::unknown::{UnknownXml, Within, RAW_MARKER};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use std::fmt;
+
+/// Namespace of the `gpx_style` extension (line color, opacity, width)
+pub const GPX_STYLE_NAMESPACE: &str = "http://www.topografix.com/GPX/gpx_style/0/2";
+
+/// Namespace of Garmin's GPX extensions v3 (`gpxx`)
+pub const GARMIN_GPXX_NAMESPACE: &str = "http://www.garmin.com/xmlschemas/GpxExtensions/v3";
+
+/// Namespace of Garmin's track point extension v1 (`gpxtpx`: heart rate, cadence)
+pub const GARMIN_TPX_NAMESPACE: &str = "http://www.garmin.com/xmlschemas/TrackPointExtension/v1";
+
+/// Paleta `DisplayColor_t` de Garmin, con su equivalente RGB aproximado
+const GARMIN_PALETTE: [(&str, Color); 16] = [
+    ("Black", Color::rgb(0x00, 0x00, 0x00)),
+    ("DarkRed", Color::rgb(0x8B, 0x00, 0x00)),
+    ("DarkGreen", Color::rgb(0x00, 0x64, 0x00)),
+    ("DarkYellow", Color::rgb(0x8B, 0x8B, 0x00)),
+    ("DarkBlue", Color::rgb(0x00, 0x00, 0x8B)),
+    ("DarkMagenta", Color::rgb(0x8B, 0x00, 0x8B)),
+    ("DarkCyan", Color::rgb(0x00, 0x8B, 0x8B)),
+    ("LightGray", Color::rgb(0xD3, 0xD3, 0xD3)),
+    ("DarkGray", Color::rgb(0xA9, 0xA9, 0xA9)),
+    ("Red", Color::rgb(0xFF, 0x00, 0x00)),
+    ("Green", Color::rgb(0x00, 0xFF, 0x00)),
+    ("Yellow", Color::rgb(0xFF, 0xFF, 0x00)),
+    ("Blue", Color::rgb(0x00, 0x00, 0xFF)),
+    ("Magenta", Color::rgb(0xFF, 0x00, 0xFF)),
+    ("Cyan", Color::rgb(0x00, 0xFF, 0xFF)),
+    ("White", Color::rgb(0xFF, 0xFF, 0xFF)),
+];
+
+/// An RGB color used to display a track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// Red component
+    pub r: u8,
+    /// Green component
+    pub g: u8,
+    /// Blue component
+    pub b: u8,
+}
+
+impl Color {
+    /// Crea un color a partir de sus componentes RGB
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parses a `RRGGBB` hex string, with or without a leading `#`
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let component = |range| u8::from_str_radix(&hex[range], 16).ok();
+        Some(Self::rgb(
+            component(0..2)?,
+            component(2..4)?,
+            component(4..6)?,
+        ))
+    }
+
+    /// Formats the color as uppercase `RRGGBB`, as used by `gpx_style`
+    pub fn to_hex(self) -> String {
+        format!("{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    /// Looks up a Garmin `DisplayColor` name (e.g. `"DarkRed"`)
+    pub fn from_garmin_name(name: &str) -> Option<Self> {
+        GARMIN_PALETTE
+            .iter()
+            .find(|(garmin, _)| garmin.eq_ignore_ascii_case(name.trim()))
+            .map(|&(_, color)| color)
+    }
+
+    /// Closest Garmin `DisplayColor` name, since Garmin devices only support a fixed palette
+    pub fn garmin_name(self) -> &'static str {
+        GARMIN_PALETTE
+            .iter()
+            .min_by_key(|(_, color)| self.distance_squared(*color))
+            .map_or("Black", |&(name, _)| name)
+    }
+
+    fn distance_squared(self, other: Self) -> u32 {
+        let diff = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+        diff(self.r, other.r) + diff(self.g, other.g) + diff(self.b, other.b)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.to_hex())
+    }
+}
+
+/// Extensions attached to a `<trk>` element
+///
+/// On input, the color is read from the `gpx_style:line` extension, falling back to
+/// Garmin's `gpxx:DisplayColor`. On output both are written, so the color survives in
+/// planning tools as well as on Garmin devices.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(from = "RawTrackExtensions")]
+pub struct TrackExtensions {
+    /// Display color of the track
+    pub color: Option<Color>,
+    /// Extensions not modeled by this crate (line width, other vendors), written back as read
+    pub unknown: UnknownXml,
+}
+
+impl TrackExtensions {
+    /// Verifica si no hay ninguna extensión que escribir
+    pub fn is_empty(&self) -> bool {
+        self.color.is_none() && self.unknown.is_empty()
+    }
+}
+
+impl Serialize for TrackExtensions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("extensions", 5)?;
+        let line = PrefixedLine {
+            color: self.color.map(Color::to_hex),
+            unknown: self.unknown.within(Some("line")),
+        };
+        let garmin = PrefixedGarminTrack {
+            display_color: self.color.map(Color::garmin_name),
+            unknown: self.unknown.within(Some("TrackExtension")),
+        };
+        let write_line = line.color.is_some() || !line.unknown.is_empty();
+        let write_garmin = garmin.display_color.is_some() || !garmin.unknown.is_empty();
+        if write_line {
+            state.serialize_field("@xmlns:gpx_style", GPX_STYLE_NAMESPACE)?;
+        }
+        if write_garmin {
+            state.serialize_field("@xmlns:gpxx", GARMIN_GPXX_NAMESPACE)?;
+        }
+        if write_line {
+            state.serialize_field("gpx_style:line", &line)?;
+        }
+        if write_garmin {
+            state.serialize_field("gpxx:TrackExtension", &garmin)?;
+        }
+        if !self.unknown.within(None).is_empty() {
+            state.serialize_field(RAW_MARKER, &self.unknown)?;
+        }
+        state.end()
+    }
+}
+
+// quick-xml compara por nombre local al deserializar, así que los prefijos
+// (`gpx_style:`, `gpxx:`, o los que use cada exportador) no importan al leer.
+#[derive(Deserialize)]
+struct RawTrackExtensions {
+    #[serde(rename = "line")]
+    line: Option<RawLine>,
+    #[serde(rename = "TrackExtension")]
+    garmin: Option<RawGarminTrack>,
+}
+
+#[derive(Deserialize)]
+struct RawLine {
+    #[serde(rename = "color")]
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawGarminTrack {
+    #[serde(rename = "DisplayColor")]
+    display_color: Option<String>,
+}
+
+impl From<RawTrackExtensions> for TrackExtensions {
+    fn from(raw: RawTrackExtensions) -> Self {
+        let style_color = raw
+            .line
+            .and_then(|line| line.color)
+            .and_then(|hex| Color::from_hex(&hex));
+        let garmin_color = raw
+            .garmin
+            .and_then(|garmin| garmin.display_color)
+            .and_then(|name| Color::from_garmin_name(&name));
+
+        Self {
+            color: style_color.or(garmin_color),
+            unknown: UnknownXml::default(),
+        }
+    }
+}
+
+/// Extensions attached to a `<trkpt>` element
+///
+/// Heart rate and cadence are read from and written to Garmin's
+/// `gpxtpx:TrackPointExtension`, which is what most devices and training platforms
+/// use in GPX exports. Power goes in a plain `<power>` element, as written by Strava
+/// and most cycling tools.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "RawPointExtensions")]
+pub struct PointExtensions {
+    /// Heart rate in beats per minute
+    pub heart_rate: Option<u8>,
+    /// Cadence in revolutions (or steps per foot) per minute
+    pub cadence: Option<u8>,
+    /// Power in watts
+    pub power: Option<u16>,
+    /// Extensions not modeled by this crate (temperature, other vendors), written back as read
+    pub unknown: UnknownXml,
+}
+
+impl PointExtensions {
+    /// Verifica si no hay ninguna extensión que escribir
+    pub fn is_empty(&self) -> bool {
+        self.heart_rate.is_none()
+            && self.cadence.is_none()
+            && self.power.is_none()
+            && self.unknown.is_empty()
+    }
+}
+
+impl Serialize for PointExtensions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("extensions", 4)?;
+        let garmin = PrefixedTrackPoint {
+            heart_rate: self.heart_rate,
+            cadence: self.cadence,
+            unknown: self.unknown.within(Some("TrackPointExtension")),
+        };
+        if garmin.heart_rate.is_some() || garmin.cadence.is_some() || !garmin.unknown.is_empty() {
+            state.serialize_field("@xmlns:gpxtpx", GARMIN_TPX_NAMESPACE)?;
+            state.serialize_field("gpxtpx:TrackPointExtension", &garmin)?;
+        }
+        if let Some(power) = self.power {
+            state.serialize_field("power", &power)?;
+        }
+        if !self.unknown.within(None).is_empty() {
+            state.serialize_field(RAW_MARKER, &self.unknown)?;
+        }
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+struct RawPointExtensions {
+    #[serde(rename = "TrackPointExtension")]
+    garmin: Option<RawGarminTrackPoint>,
+    #[serde(rename = "power")]
+    power: Option<String>,
+}
+
+// Se leen como texto para que un valor mal formado no invalide todo el documento
+#[derive(Deserialize)]
+struct RawGarminTrackPoint {
+    #[serde(rename = "hr")]
+    heart_rate: Option<String>,
+    #[serde(rename = "cad")]
+    cadence: Option<String>,
+}
+
+impl From<RawPointExtensions> for PointExtensions {
+    fn from(raw: RawPointExtensions) -> Self {
+        fn parse<T: std::str::FromStr>(value: Option<String>) -> Option<T> {
+            value.and_then(|v| v.trim().parse().ok())
+        }
+        let (heart_rate, cadence) = raw
+            .garmin
+            .map_or((None, None), |g| (parse(g.heart_rate), parse(g.cadence)));
+
+        Self {
+            heart_rate,
+            cadence,
+            power: parse(raw.power),
+            unknown: UnknownXml::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PrefixedTrackPoint<'a> {
+    #[serde(rename = "gpxtpx:hr", skip_serializing_if = "Option::is_none")]
+    heart_rate: Option<u8>,
+    #[serde(rename = "gpxtpx:cad", skip_serializing_if = "Option::is_none")]
+    cadence: Option<u8>,
+    #[serde(rename = "gpx_extractor_raw")]
+    unknown: Within<'a>,
+}
+
+#[derive(Serialize)]
+struct PrefixedLine<'a> {
+    #[serde(rename = "gpx_style:color", skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(rename = "gpx_extractor_raw")]
+    unknown: Within<'a>,
+}
+
+#[derive(Serialize)]
+struct PrefixedGarminTrack<'a> {
+    #[serde(rename = "gpxx:DisplayColor", skip_serializing_if = "Option::is_none")]
+    display_color: Option<&'static str>,
+    #[serde(rename = "gpx_extractor_raw")]
+    unknown: Within<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_hex_roundtrip() {
+        let color = Color::from_hex("#1a2B3c").unwrap();
+        assert_eq!(color, Color::rgb(0x1A, 0x2B, 0x3C));
+        assert_eq!(color.to_hex(), "1A2B3C");
+        assert_eq!(color.to_string(), "#1A2B3C");
+
+        assert!(Color::from_hex("12345").is_none());
+        assert!(Color::from_hex("GG0000").is_none());
+    }
+
+    #[test]
+    fn test_color_garmin_names() {
+        assert_eq!(
+            Color::from_garmin_name("darkred"),
+            Some(Color::rgb(0x8B, 0, 0))
+        );
+        assert_eq!(Color::from_garmin_name("Transparent"), None);
+        assert_eq!(Color::rgb(0xF0, 0x10, 0x10).garmin_name(), "Red");
+        assert_eq!(Color::rgb(0x10, 0x10, 0x90).garmin_name(), "DarkBlue");
+    }
+
+    #[test]
+    fn test_raw_extensions_prefer_gpx_style() {
+        let raw = RawTrackExtensions {
+            line: Some(RawLine {
+                color: Some("123456".to_string()),
+            }),
+            garmin: Some(RawGarminTrack {
+                display_color: Some("Red".to_string()),
+            }),
+        };
+        assert_eq!(
+            TrackExtensions::from(raw).color,
+            Some(Color::rgb(0x12, 0x34, 0x56))
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use crate::gpx::{error::GpxError, parser::Gpx};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+thread_local! {
+    /// Último error de este hilo, para `gpx_last_error`
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Guarda el error del hilo actual; los bytes nulos internos se eliminan
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Olvida el error del hilo actual tras una llamada correcta
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Convierte el resultado en un puntero propio, o en nulo guardando el error
+fn into_handle(result: Result<Gpx, GpxError>) -> *mut Gpx {
+    match result {
+        Ok(gpx) => {
+            clear_last_error();
+            Box::into_raw(Box::new(gpx))
+        }
+        Err(e) => {
+            set_last_error(&e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Lee una cadena C como UTF-8, guardando el error si no lo es
+unsafe fn read_str<'a>(text: *const c_char, what: &str) -> Option<&'a str> {
+    if text.is_null() {
+        set_last_error(&format!("{what} is NULL"));
+        return None;
+    }
+    match CStr::from_ptr(text).to_str() {
+        Ok(text) => Some(text),
+        Err(e) => {
+            set_last_error(&format!("{what} is not UTF-8: {e}"));
+            None
+        }
+    }
+}
+
+/// Statistics of a document, as returned by [`gpx_statistics`]
+///
+/// Figures that cannot be computed are `NaN` (floating point) or `-1` (duration).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpxCStatistics {
+    /// Number of tracks
+    pub total_tracks: usize,
+    /// Number of waypoints
+    pub total_waypoints: usize,
+    /// Number of track segments
+    pub total_segments: usize,
+    /// Number of track points
+    pub total_points: usize,
+    /// Distance in kilometers
+    pub total_distance_km: f64,
+    /// Lowest elevation in meters, or `NaN`
+    pub min_elevation_m: f64,
+    /// Highest elevation in meters, or `NaN`
+    pub max_elevation_m: f64,
+    /// Elevation gain in meters, or `NaN`
+    pub elevation_gain_m: f64,
+    /// Elevation loss in meters, or `NaN`
+    pub elevation_loss_m: f64,
+    /// Duration in seconds, or `-1`
+    pub duration_seconds: i64,
+    /// Average speed in km/h, or `NaN`
+    pub average_speed_kmh: f64,
+}
+
+impl From<&Gpx> for GpxCStatistics {
+    fn from(gpx: &Gpx) -> Self {
+        let stats = gpx.statistics();
+        let (min, max) = stats.elevation_range.unwrap_or((f64::NAN, f64::NAN));
+        Self {
+            total_tracks: stats.total_tracks,
+            total_waypoints: stats.total_waypoints,
+            total_segments: stats.total_segments,
+            total_points: stats.total_points,
+            total_distance_km: stats.total_distance_km,
+            min_elevation_m: min,
+            max_elevation_m: max,
+            elevation_gain_m: stats.elevation_gain.unwrap_or(f64::NAN),
+            elevation_loss_m: stats.elevation_loss.unwrap_or(f64::NAN),
+            duration_seconds: stats.duration_seconds.unwrap_or(-1),
+            average_speed_kmh: stats.average_speed_kmh.unwrap_or(f64::NAN),
+        }
+    }
+}
+
+/// Parses a NUL-terminated UTF-8 GPX document
+///
+/// Returns a document to release with [`gpx_free`], or `NULL` on error (see
+/// [`gpx_last_error`]).
+///
+/// # Safety
+///
+/// `xml` must be `NULL` or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gpx_parse(xml: *const c_char) -> *mut Gpx {
+    match read_str(xml, "xml") {
+        Some(xml) => into_handle(Gpx::try_from_str(xml)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Reads and parses a GPX file
+///
+/// Returns a document to release with [`gpx_free`], or `NULL` on error (see
+/// [`gpx_last_error`]).
+///
+/// # Safety
+///
+/// `path` must be `NULL` or point to a NUL-terminated UTF-8 path.
+#[no_mangle]
+pub unsafe extern "C" fn gpx_parse_file(path: *const c_char) -> *mut Gpx {
+    match read_str(path, "path") {
+        Some(path) => into_handle(Gpx::from_file(path)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Statistics of a parsed document
+///
+/// A `NULL` document gives zero counts and unknown figures.
+///
+/// # Safety
+///
+/// `gpx` must be `NULL` or a document returned by [`gpx_parse`] or
+/// [`gpx_parse_file`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn gpx_statistics(gpx: *const Gpx) -> GpxCStatistics {
+    match gpx.as_ref() {
+        Some(gpx) => GpxCStatistics::from(gpx),
+        None => GpxCStatistics::from(&Gpx::new()),
+    }
+}
+
+/// Converts a parsed document to a GeoJSON `FeatureCollection`
+///
+/// Returns a string to release with [`gpx_string_free`], or `NULL` on error (see
+/// [`gpx_last_error`]).
+///
+/// # Safety
+///
+/// `gpx` must be `NULL` or a document returned by [`gpx_parse`] or
+/// [`gpx_parse_file`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn gpx_to_geojson(gpx: *const Gpx) -> *mut c_char {
+    let Some(gpx) = gpx.as_ref() else {
+        set_last_error("gpx is NULL");
+        return ptr::null_mut();
+    };
+    match CString::new(gpx.to_geojson()) {
+        Ok(geojson) => {
+            clear_last_error();
+            geojson.into_raw()
+        }
+        Err(e) => {
+            set_last_error(&format!("GeoJSON contains a NUL byte: {e}"));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a document returned by [`gpx_parse`] or [`gpx_parse_file`]
+///
+/// # Safety
+///
+/// `gpx` must be `NULL` or a document not yet freed; it must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn gpx_free(gpx: *mut Gpx) {
+    if !gpx.is_null() {
+        drop(Box::from_raw(gpx));
+    }
+}
+
+/// Releases a string returned by this library, such as [`gpx_to_geojson`]
+///
+/// # Safety
+///
+/// `text` must be `NULL` or a string returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn gpx_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Message of the error of the last fallible call on the calling thread, or
+/// `NULL` if that call succeeded
+///
+/// The string belongs to the library and stays valid until the next call to
+/// `gpx_parse`, `gpx_parse_file` or `gpx_to_geojson` on the same thread.
+#[no_mangle]
+pub extern "C" fn gpx_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_statistics_and_free() {
+        let xml = CString::new(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="40.0" lon="-3.0"><ele>600</ele></trkpt>
+                <trkpt lat="40.1" lon="-3.0"><ele>650</ele></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        unsafe {
+            let gpx = gpx_parse(xml.as_ptr());
+            assert!(!gpx.is_null());
+
+            let stats = gpx_statistics(gpx);
+            assert_eq!((stats.total_tracks, stats.total_points), (1, 2));
+            assert_eq!(stats.elevation_gain_m, 50.0);
+            assert_eq!(stats.duration_seconds, -1);
+            assert!(stats.average_speed_kmh.is_nan());
+
+            let geojson = gpx_to_geojson(gpx);
+            let text = CStr::from_ptr(geojson).to_str().unwrap();
+            assert!(text.contains("FeatureCollection"));
+            gpx_string_free(geojson);
+            gpx_free(gpx);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        let broken = CString::new("<gpx><trk>").unwrap();
+        unsafe {
+            assert!(gpx_parse(broken.as_ptr()).is_null());
+            let error = CStr::from_ptr(gpx_last_error()).to_str().unwrap();
+            assert!(!error.is_empty());
+
+            assert!(gpx_parse(ptr::null()).is_null());
+            assert_eq!(CStr::from_ptr(gpx_last_error()).to_str(), Ok("xml is NULL"));
+            assert!(gpx_to_geojson(ptr::null()).is_null());
+            assert_eq!(CStr::from_ptr(gpx_last_error()).to_str(), Ok("gpx is NULL"));
+
+            // Una llamada correcta borra el error anterior
+            let empty = CString::new("<gpx/>").unwrap();
+            let gpx = gpx_parse(empty.as_ptr());
+            assert!(gpx_last_error().is_null());
+            gpx_free(gpx);
+            assert_eq!(gpx_statistics(ptr::null()).total_points, 0);
+            gpx_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::gpx::{
+    compare::{CompareOptions, DistanceMetric},
+    parser::{start_time, Gpx},
+    track::{Track, TrackSegment},
+};
+
+/// Tolerancia, en metros, de la simplificación previa a la huella
+const FINGERPRINT_SIMPLIFY_M: f64 = 50.0;
+
+/// Caracteres de geohash por punto de la huella (celdas de unos 1,2 × 0,6 km)
+const FINGERPRINT_GEOHASH_PRECISION: usize = 6;
+
+/// Tamaño, en segundos, de los intervalos en que se agrupa la hora de inicio
+const START_BUCKET_SECONDS: i64 = 15 * 60;
+
+/// Diferencia máxima, en segundos, entre las horas de inicio de dos duplicados
+const DUPLICATE_MAX_START_OFFSET_SECONDS: i64 = 5 * 60;
+
+/// FNV-1a de 64 bits: estable entre versiones y plataformas, a diferencia de `DefaultHasher`
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl Gpx {
+    /// Stable hash of the recorded geometry and start time, to spot the same activity in an archive
+    ///
+    /// The track points are simplified, reduced to the sequence of geohash
+    /// cells they cross (about 1.2 × 0.6 km each) and hashed together with the
+    /// start time rounded down to 15 minutes. Names, metadata, sensor data and
+    /// the sampling rate do not change the result, so the same ride exported by
+    /// two services usually gets the same fingerprint. Routes and waypoints are
+    /// ignored. The hash (FNV-1a) is stable across versions and platforms.
+    ///
+    /// Recordings crossing a cell or bucket boundary at slightly different
+    /// points can still differ; use [`Gpx::is_duplicate_of`] to confirm a match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let strava = Gpx::try_from_str(r#"<gpx creator="Strava"><trk><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.1" lon="-3.0"><time>2024-07-01T08:30:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    /// let garmin = Gpx::try_from_str(r#"<gpx creator="Garmin"><trk><name>Morning ride</name><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:01Z</time></trkpt>
+    ///     <trkpt lat="40.05" lon="-3.0"><time>2024-07-01T08:15:00Z</time></trkpt>
+    ///     <trkpt lat="40.1" lon="-3.0"><time>2024-07-01T08:30:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// assert_eq!(strava.fingerprint().len(), 16);
+    /// assert_eq!(strava.fingerprint(), garmin.fingerprint());
+    /// assert!(strava.is_duplicate_of(&garmin, 50.0));
+    /// ```
+    pub fn fingerprint(&self) -> String {
+        let points: Vec<_> = self.get_all_points().into_iter().cloned().collect();
+        let simplified = TrackSegment::with_points(points).simplify(FINGERPRINT_SIMPLIFY_M);
+        let mut cells: Vec<String> = simplified
+            .points
+            .iter()
+            .map(|point| point.geohash(FINGERPRINT_GEOHASH_PRECISION))
+            .collect();
+        cells.dedup();
+
+        let bucket = start_time(self)
+            .map(|start| {
+                start
+                    .timestamp()
+                    .div_euclid(START_BUCKET_SECONDS)
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let hash = fnv1a(
+            cells
+                .iter()
+                .flat_map(|cell| cell.bytes().chain([b',']))
+                .chain(bucket.into_bytes()),
+        );
+        format!("{hash:016x}")
+    }
+
+    /// Whether both documents record the same activity, within `tolerance_m` meters
+    ///
+    /// The tracks of each document are compared as a whole with the Hausdorff
+    /// distance (see [`Track::similarity`]), which must not exceed
+    /// `tolerance_m`. When both have timestamps, their start times must also be
+    /// at most 5 minutes apart, so the same commute on two different days is not
+    /// a duplicate. Documents without track points are never duplicates.
+    pub fn is_duplicate_of(&self, other: &Gpx, tolerance_m: f64) -> bool {
+        if let (Some(mine), Some(theirs)) = (start_time(self), start_time(other)) {
+            if (mine - theirs).num_seconds().abs() > DUPLICATE_MAX_START_OFFSET_SECONDS {
+                return false;
+            }
+        }
+        // Simplificar con la mitad de la tolerancia no mueve la forma más de lo permitido
+        let merged = |gpx: &Gpx| {
+            let mut track = Track::new();
+            for segment in gpx.tracks.iter().flat_map(|track| &track.segments) {
+                track.add_segment(segment.simplify(tolerance_m / 2.0));
+            }
+            track
+        };
+        let options = CompareOptions {
+            metric: DistanceMetric::Hausdorff,
+            ..CompareOptions::default()
+        };
+        merged(self)
+            .similarity(&merged(other), options)
+            .is_some_and(|similarity| similarity.distance_m <= tolerance_m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::point::Point;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn ride(offset_minutes: i64, lon_shift: f64, every_seconds: i64) -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        // Un lazo de unos 8 km muestreado cada `every_seconds`
+        let points = (0..=3600 / every_seconds)
+            .map(|i| {
+                #[allow(clippy::cast_precision_loss)]
+                let angle = (i * every_seconds) as f64 / 3600.0 * std::f64::consts::TAU;
+                Point::with_time(
+                    40.0 + 0.01 * angle.sin(),
+                    -3.0 + lon_shift + 0.013 * (1.0 - angle.cos()),
+                    None,
+                    start
+                        + Duration::minutes(offset_minutes)
+                        + Duration::seconds(i * every_seconds),
+                )
+            })
+            .collect();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(points));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_stable_fingerprint() {
+        // Valor fijo: la huella no debe cambiar entre versiones
+        let gpx = ride(0, 0.0, 10);
+        assert_eq!(gpx.fingerprint(), "d62079960b1e46b7");
+        assert_eq!(gpx.fingerprint(), ride(0, 0.0, 10).fingerprint());
+        assert_eq!(gpx.fingerprint(), ride(2, 0.0, 5).fingerprint());
+        assert_ne!(gpx.fingerprint(), ride(24 * 60, 0.0, 10).fingerprint());
+        assert_eq!(Gpx::new().fingerprint(), format!("{:016x}", fnv1a([])));
+    }
+
+    #[test]
+    fn test_is_duplicate_of() {
+        let gpx = ride(0, 0.0, 10);
+        // Otro servicio: muestreo distinto, un minuto de desfase y 10 m de error
+        assert!(gpx.is_duplicate_of(&ride(1, 0.000_12, 3), 25.0));
+        // La misma ruta otro día
+        assert!(!gpx.is_duplicate_of(&ride(24 * 60, 0.0, 10), 25.0));
+        // Otra ruta a la misma hora
+        assert!(!gpx.is_duplicate_of(&ride(0, 0.01, 10), 25.0));
+        assert!(!gpx.is_duplicate_of(&Gpx::new(), 25.0));
+    }
+}
@@ -0,0 +1,486 @@
+use crate::gpx::{
+    error::GpxError,
+    extensions::PointExtensions,
+    formats::FormatReader,
+    parser::Gpx,
+    point::Point,
+    track::{Track, TrackSegment},
+    unknown::UnknownXml,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
+
+/// Segundos entre la época Unix y la época FIT (1989-12-31T00:00:00Z)
+const FIT_EPOCH_OFFSET: i64 = 631_065_600;
+
+// Números de mensaje globales del perfil FIT
+const MESG_FILE_ID: u16 = 0;
+const MESG_SESSION: u16 = 18;
+const MESG_LAP: u16 = 19;
+const MESG_RECORD: u16 = 20;
+
+// Números de campo usados de cada mensaje
+const FIELD_TIMESTAMP: u8 = 253;
+const FIELD_TIME_CREATED: u8 = 4;
+const FIELD_SESSION_SPORT: u8 = 5;
+const FIELD_POSITION_LAT: u8 = 0;
+const FIELD_POSITION_LONG: u8 = 1;
+const FIELD_ALTITUDE: u8 = 2;
+const FIELD_HEART_RATE: u8 = 3;
+const FIELD_CADENCE: u8 = 4;
+const FIELD_POWER: u8 = 7;
+const FIELD_ENHANCED_ALTITUDE: u8 = 78;
+
+/// Reader for Garmin FIT activity files (`.fit`)
+///
+/// Record messages become track points: position, altitude (enhanced when
+/// available), timestamp, heart rate, cadence and power. Each lap closes a
+/// segment, and the track is named after the session sport. Records without a
+/// position (e.g. indoor sensor samples) are skipped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FitFormat;
+
+impl FormatReader for FitFormat {
+    fn read(&self, bytes: &[u8]) -> Result<Gpx, GpxError> {
+        Gpx::from_fit(bytes)
+    }
+}
+
+/// Definición de un tipo de mensaje local
+struct Definition {
+    global: u16,
+    big_endian: bool,
+    /// Número y tamaño de cada campo, en orden
+    fields: Vec<(u8, usize)>,
+    /// Bytes de campos de desarrollador, que se saltan
+    developer_size: usize,
+}
+
+/// Valores de un mensaje de datos, indexados por número de campo
+struct Message<'a> {
+    definition: &'a Definition,
+    values: HashMap<u8, &'a [u8]>,
+}
+
+impl Message<'_> {
+    /// Lee un entero sin signo; `None` si falta o tiene el valor inválido del tipo
+    fn unsigned(&self, field: u8) -> Option<u32> {
+        let bytes = self.values.get(&field)?;
+        let value = match bytes.len() {
+            1 => u32::from(bytes[0]),
+            2 => {
+                let raw = [bytes[0], bytes[1]];
+                u32::from(if self.definition.big_endian {
+                    u16::from_be_bytes(raw)
+                } else {
+                    u16::from_le_bytes(raw)
+                })
+            }
+            4 => {
+                let raw = [bytes[0], bytes[1], bytes[2], bytes[3]];
+                if self.definition.big_endian {
+                    u32::from_be_bytes(raw)
+                } else {
+                    u32::from_le_bytes(raw)
+                }
+            }
+            _ => return None,
+        };
+        let invalid = match bytes.len() {
+            1 => 0xFF,
+            2 => 0xFFFF,
+            _ => 0xFFFF_FFFF,
+        };
+        (value != invalid).then_some(value)
+    }
+
+    /// Lee un `sint32` en semicírculos y lo convierte a grados
+    fn degrees(&self, field: u8) -> Option<f64> {
+        let bytes = self.values.get(&field)?;
+        if bytes.len() != 4 {
+            return None;
+        }
+        let raw = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let value = if self.definition.big_endian {
+            i32::from_be_bytes(raw)
+        } else {
+            i32::from_le_bytes(raw)
+        };
+        (value != i32::MAX).then(|| f64::from(value) * (180.0 / 2_147_483_648.0))
+    }
+
+    fn timestamp(&self, field: u8) -> Option<DateTime<Utc>> {
+        let seconds = i64::from(self.unsigned(field)?);
+        DateTime::from_timestamp(seconds + FIT_EPOCH_OFFSET, 0)
+    }
+
+    /// Punto de un mensaje `record`; `None` si no tiene posición
+    fn point(&self, timestamp: u32) -> Result<Option<Point>, GpxError> {
+        let time = DateTime::from_timestamp(i64::from(timestamp) + FIT_EPOCH_OFFSET, 0)
+            .filter(|_| timestamp != 0);
+        let narrow = |field| self.unsigned(field).and_then(|v| v.try_into().ok());
+        let (Some(lat), Some(lon)) = (
+            self.degrees(FIELD_POSITION_LAT),
+            self.degrees(FIELD_POSITION_LONG),
+        ) else {
+            return Ok(None);
+        };
+        // Un sint32 en semicírculos llega hasta ±180°, fuera de rango para la latitud
+        Point::check_coordinates(lat, lon)?;
+        Ok(Some(Point {
+            elevation: self.altitude(),
+            time,
+            extensions: PointExtensions {
+                heart_rate: narrow(FIELD_HEART_RATE),
+                cadence: narrow(FIELD_CADENCE),
+                power: self.unsigned(FIELD_POWER).and_then(|v| v.try_into().ok()),
+                unknown: UnknownXml::default(),
+            },
+            ..Point::new(lat, lon)
+        }))
+    }
+
+    /// Altitud con escala 5 y desplazamiento 500 m
+    fn altitude(&self) -> Option<f64> {
+        self.unsigned(FIELD_ENHANCED_ALTITUDE)
+            .or_else(|| self.unsigned(FIELD_ALTITUDE))
+            .map(|raw| f64::from(raw) / 5.0 - 500.0)
+    }
+}
+
+/// Nombre legible de los valores más comunes del enum `sport`
+fn sport_name(sport: u32) -> Option<&'static str> {
+    Some(match sport {
+        1 => "Running",
+        2 => "Cycling",
+        5 => "Swimming",
+        10 => "Training",
+        11 => "Walking",
+        13 => "Alpine skiing",
+        17 => "Hiking",
+        _ => return None,
+    })
+}
+
+/// CRC-16 del protocolo FIT
+fn crc(bytes: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+        0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+    bytes.iter().fold(0, |crc, &byte| {
+        let crc = (crc >> 4) ^ TABLE[usize::from(crc & 0xF)] ^ TABLE[usize::from(byte & 0xF)];
+        (crc >> 4) ^ TABLE[usize::from(crc & 0xF)] ^ TABLE[usize::from(byte >> 4)]
+    })
+}
+
+fn fit_error(msg: &str) -> GpxError {
+    GpxError::Fit(msg.to_string())
+}
+
+/// Cursor sobre los registros de datos del fichero
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], GpxError> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.saturating_add(len))
+            .ok_or_else(|| fit_error("truncated record"))?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, GpxError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn definition(&mut self, developer: bool) -> Result<Definition, GpxError> {
+        let header = self.take(5)?;
+        let big_endian = header[1] == 1;
+        let raw = [header[2], header[3]];
+        let global = if big_endian {
+            u16::from_be_bytes(raw)
+        } else {
+            u16::from_le_bytes(raw)
+        };
+        let fields = self
+            .take(usize::from(header[4]) * 3)?
+            .chunks_exact(3)
+            .map(|field| (field[0], usize::from(field[1])))
+            .collect();
+        let mut developer_size = 0;
+        if developer {
+            let count = usize::from(self.byte()?);
+            developer_size = self
+                .take(count * 3)?
+                .chunks_exact(3)
+                .map(|field| usize::from(field[1]))
+                .sum();
+        }
+        Ok(Definition {
+            global,
+            big_endian,
+            fields,
+            developer_size,
+        })
+    }
+
+    fn message<'d>(&mut self, definition: &'d Definition) -> Result<Message<'d>, GpxError>
+    where
+        'a: 'd,
+    {
+        let mut values = HashMap::new();
+        for &(number, size) in &definition.fields {
+            values.insert(number, self.take(size)?);
+        }
+        self.take(definition.developer_size)?;
+        Ok(Message { definition, values })
+    }
+}
+
+impl Gpx {
+    /// Decodes a FIT activity file
+    ///
+    /// Only the messages needed for a track are read (`record`, `lap`, `session`
+    /// and `file_id`); everything else is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Fit`] if the bytes are not a FIT file, are truncated,
+    /// fail the CRC check, use an undefined local message type or overflow a
+    /// compressed timestamp, and [`GpxError::InvalidCoordinate`] if a record lies
+    /// outside the WGS84 range
+    pub fn from_fit(bytes: &[u8]) -> Result<Self, GpxError> {
+        let header_size = usize::from(*bytes.first().ok_or_else(|| fit_error("empty file"))?);
+        if header_size < 12 || bytes.get(8..12) != Some(b".FIT".as_slice()) {
+            return Err(fit_error("missing .FIT signature"));
+        }
+        let data_size = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        let (content, trailer) = header_size
+            .checked_add(data_size)
+            .and_then(|end| bytes.get(..end).zip(bytes.get(end..end.checked_add(2)?)))
+            .ok_or_else(|| fit_error("file is shorter than its header says"))?;
+        if crc(content) != u16::from_le_bytes([trailer[0], trailer[1]]) {
+            return Err(fit_error("CRC mismatch"));
+        }
+
+        let mut cursor = Cursor {
+            data: &content[header_size..],
+            position: 0,
+        };
+        let mut definitions: HashMap<u8, Definition> = HashMap::new();
+        let mut track = Track::new();
+        let mut segment = TrackSegment::new();
+        let mut created = None;
+        let mut last_timestamp: u32 = 0;
+
+        while cursor.position < cursor.data.len() {
+            let header = cursor.byte()?;
+            if header & 0x40 != 0 && header & 0x80 == 0 {
+                let definition = cursor.definition(header & 0x20 != 0)?;
+                definitions.insert(header & 0x0F, definition);
+                continue;
+            }
+
+            // Cabecera compacta: tipo local en los bits 5-6 y desfase de 5 bits
+            let (local, time_offset) = if header & 0x80 == 0 {
+                (header & 0x0F, None)
+            } else {
+                ((header >> 5) & 0x03, Some(u32::from(header & 0x1F)))
+            };
+            let definition = definitions
+                .get(&local)
+                .ok_or_else(|| fit_error("data message without definition"))?;
+            let message = cursor.message(definition)?;
+
+            if let Some(timestamp) = message.unsigned(FIELD_TIMESTAMP) {
+                last_timestamp = timestamp;
+            } else if let Some(offset) = time_offset {
+                let base = last_timestamp & !0x1F;
+                let rollover = if offset >= last_timestamp & 0x1F {
+                    0
+                } else {
+                    0x20
+                };
+                last_timestamp = (base + offset)
+                    .checked_add(rollover)
+                    .ok_or_else(|| fit_error("compressed timestamp overflows"))?;
+            }
+
+            match definition.global {
+                MESG_FILE_ID => created = message.timestamp(FIELD_TIME_CREATED),
+                MESG_SESSION => {
+                    if let Some(name) = message.unsigned(FIELD_SESSION_SPORT).and_then(sport_name) {
+                        track.name = Some(name.to_string());
+                    }
+                }
+                MESG_LAP if !segment.points.is_empty() => {
+                    track.add_segment(std::mem::take(&mut segment));
+                }
+                MESG_RECORD => {
+                    if let Some(point) = message.point(last_timestamp)? {
+                        segment.add_point(point);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !segment.points.is_empty() {
+            track.add_segment(segment);
+        }
+
+        let mut gpx = Gpx::new();
+        let start = track
+            .segments
+            .iter()
+            .flat_map(|s| &s.points)
+            .find_map(|p| p.time)
+            .or(created);
+        if let Some(start) = start {
+            gpx.metadata_mut().time = Some(start.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        if !track.segments.is_empty() {
+            gpx.add_track(track);
+        }
+        Ok(gpx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::formats::{detect, Format, FormatRegistry};
+
+    /// Construye un fichero FIT mínimo con cabecera de 14 bytes y CRC
+    fn fit_file(records: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![14, 0x10, 0x08, 0x08];
+        bytes.extend_from_slice(&u32::try_from(records.len()).unwrap().to_le_bytes());
+        bytes.extend_from_slice(b".FIT");
+        let header_crc = crc(&bytes);
+        bytes.extend_from_slice(&header_crc.to_le_bytes());
+        bytes.extend_from_slice(records);
+        let file_crc = crc(&bytes);
+        bytes.extend_from_slice(&file_crc.to_le_bytes());
+        bytes
+    }
+
+    fn semicircles(degrees: f64) -> [u8; 4] {
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (degrees / (180.0 / 2_147_483_648.0)).round() as i32;
+        value.to_le_bytes()
+    }
+
+    fn record(timestamp: u32, lat: f64, hr: u8, power: u16) -> Vec<u8> {
+        let mut bytes = vec![0x00];
+        bytes.extend_from_slice(&timestamp.to_le_bytes());
+        bytes.extend_from_slice(&semicircles(lat));
+        bytes.extend_from_slice(&semicircles(-3.0));
+        bytes.extend_from_slice(&3250u16.to_le_bytes()); // 3250 / 5 - 500 = 150 m
+        bytes.push(hr);
+        bytes.extend_from_slice(&power.to_le_bytes());
+        bytes
+    }
+
+    fn activity() -> Vec<u8> {
+        // Definición local 0: record con timestamp, lat, long, altitude, hr y power
+        let mut records = vec![0x40, 0, 0, 20, 0, 6];
+        records.extend_from_slice(&[253, 4, 0x86, 0, 4, 0x85, 1, 4, 0x85]);
+        records.extend_from_slice(&[2, 2, 0x84, 3, 1, 0x02, 7, 2, 0x84]);
+        records.extend(record(1_000_000_000, 40.0, 120, 200));
+        records.extend(record(1_000_000_060, 40.001, 0xFF, 0xFFFF));
+        // Definición local 1: lap vacío (solo timestamp) y session con sport
+        records.extend_from_slice(&[0x41, 0, 0, 19, 0, 1, 253, 4, 0x86]);
+        records.push(0x01);
+        records.extend_from_slice(&1_000_000_060u32.to_le_bytes());
+        records.extend(record(1_000_000_120, 40.002, 130, 210));
+        // Cabecera compacta: tipo local 0 y desfase de tiempo
+        let mut compressed = record(0, 40.003, 131, 220);
+        compressed[0] = 0x80 | 0x1F;
+        compressed[1..5].copy_from_slice(&u32::MAX.to_le_bytes());
+        records.extend(compressed);
+        records.extend_from_slice(&[0x42, 0, 0, 18, 0, 1, 5, 1, 0x00, 0x02, 1]);
+        fit_file(&records)
+    }
+
+    #[test]
+    fn test_from_fit_records_and_laps() {
+        let gpx = Gpx::from_fit(&activity()).unwrap();
+
+        assert_eq!(gpx.track_names(), vec!["Running"]);
+        let track = &gpx.tracks[0];
+        assert_eq!(track.segments.len(), 2);
+        assert_eq!(track.segments[0].points.len(), 2);
+
+        let first = &track.segments[0].points[0];
+        assert!((first.lat - 40.0).abs() < 1e-7);
+        assert!((first.lon + 3.0).abs() < 1e-7);
+        assert_eq!(first.elevation, Some(150.0));
+        assert_eq!(first.heart_rate(), Some(120));
+        assert_eq!(first.power(), Some(200));
+        // 1_000_000_000 s después de la época FIT
+        assert_eq!(gpx.date(), Some("2021-09-08T01:46:40Z"));
+
+        // Los valores inválidos del tipo (0xFF, 0xFFFF) se leen como ausentes
+        let second = &track.segments[0].points[1];
+        assert_eq!(second.heart_rate(), None);
+        assert_eq!(second.power(), None);
+
+        // 1_000_000_120 = ...1111000 en binario: el desfase 31 suma 7 segundos
+        let compressed = &track.segments[1].points[1];
+        assert_eq!(
+            (compressed.time.unwrap() - track.segments[1].points[0].time.unwrap()).num_seconds(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_from_fit_rejects_corrupt_files() {
+        let mut bytes = activity();
+        let last = bytes.len() - 3;
+        bytes[last] ^= 0xFF;
+        assert!(matches!(Gpx::from_fit(&bytes), Err(GpxError::Fit(msg)) if msg.contains("CRC")));
+
+        assert!(Gpx::from_fit(b"<gpx/>").is_err());
+        assert!(Gpx::from_fit(&activity()[..30]).is_err());
+
+        let undefined = fit_file(&[0x03, 0, 0]);
+        assert!(
+            matches!(Gpx::from_fit(&undefined), Err(GpxError::Fit(msg)) if msg.contains("definition"))
+        );
+    }
+
+    #[test]
+    fn test_from_fit_rejects_overflowing_timestamps_and_coordinates() {
+        let definition = [0x40, 0, 0, 20, 0, 6, 253, 4, 0x86, 0, 4, 0x85, 1, 4, 0x85];
+        let definition = [&definition[..], &[2, 2, 0x84, 3, 1, 0x02, 7, 2, 0x84]].concat();
+
+        let mut records = definition.clone();
+        records.extend(record(u32::MAX - 1, 40.0, 120, 200));
+        let mut compressed = record(0, 40.001, 120, 200);
+        compressed[0] = 0x80;
+        compressed[1..5].copy_from_slice(&u32::MAX.to_le_bytes());
+        records.extend(compressed);
+        assert!(
+            matches!(Gpx::from_fit(&fit_file(&records)), Err(GpxError::Fit(msg)) if msg.contains("timestamp"))
+        );
+
+        let mut records = definition;
+        records.extend(record(1_000_000_000, 100.0, 120, 200));
+        assert!(matches!(
+            Gpx::from_fit(&fit_file(&records)),
+            Err(GpxError::InvalidCoordinate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_default_registry_reads_fit() {
+        let bytes = activity();
+        assert_eq!(detect(&bytes), Some(Format::Fit));
+        let gpx = FormatRegistry::default().read_detected(&bytes).unwrap();
+        assert_eq!(gpx.total_points(), 4);
+    }
+}
@@ -0,0 +1,399 @@
+use crate::gpx::{
+    pace::Pace,
+    parser::Gpx,
+    point::{haversine_distance, Point},
+};
+use std::fmt;
+
+/// Pendiente máxima (en tanto por uno) en la que es válido el modelo de Minetti
+const MINETTI_MAX_GRADE: f64 = 0.45;
+
+/// Coste energético de correr en llano según Minetti, en J/(kg·m)
+const FLAT_RUNNING_COST: f64 = 3.6;
+
+/// Velocidad, en km/h, por debajo de la cual un intervalo cuenta como reposo
+const RESTING_MAX_KMH: f64 = 1.0;
+
+/// Desnivel positivo por kilómetro a partir del cual andar es senderismo
+const HIKE_MIN_GAIN_M_PER_KM: f64 = 50.0;
+
+/// Segundos en movimiento por debajo de los cuales la suposición es poco fiable
+const SHORT_RECORDING_SECONDS: f64 = 600.0;
+
+/// Rangos de velocidad típica en movimiento (km/h) de cada actividad, de menor a mayor
+const SPEED_BANDS: [(ActivityKind, f64, f64); 4] = [
+    (ActivityKind::Walk, 1.0, 7.0),
+    (ActivityKind::Run, 7.0, 15.0),
+    (ActivityKind::Ride, 15.0, 40.0),
+    (ActivityKind::Drive, 40.0, 130.0),
+];
+
+/// Kind of activity a recording comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActivityKind {
+    /// Walking on roads or easy paths
+    Walk,
+    /// Hiking cross-country or on mountain trails
+    Hike,
+    /// Running
+    Run,
+    /// Cycling
+    Ride,
+    /// Driving a car or riding a motorbike
+    Drive,
+}
+
+impl ActivityKind {
+    /// Metabolic equivalent (MET) of the activity at the given speed
+    ///
+    /// Values follow the Compendium of Physical Activities; running scales with
+    /// speed (about 1 MET per km/h).
+    pub fn met(self, speed_kmh: f64) -> f64 {
+        match self {
+            Self::Walk => match speed_kmh {
+                s if s < 3.2 => 2.0,
+                s if s < 4.0 => 2.8,
+                s if s < 4.8 => 3.0,
+                s if s < 5.6 => 3.5,
+                s if s < 6.4 => 4.3,
+                _ => 5.0,
+            },
+            Self::Hike => 6.0,
+            Self::Run => speed_kmh.max(6.0),
+            Self::Ride => match speed_kmh {
+                s if s < 16.0 => 4.0,
+                s if s < 19.2 => 6.8,
+                s if s < 22.4 => 8.0,
+                s if s < 25.6 => 10.0,
+                s if s < 30.6 => 12.0,
+                _ => 15.8,
+            },
+            Self::Drive => 2.5,
+        }
+    }
+}
+
+impl fmt::Display for ActivityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Walk => "walk",
+            Self::Hike => "hike",
+            Self::Run => "run",
+            Self::Ride => "ride",
+            Self::Drive => "drive",
+        })
+    }
+}
+
+/// Result of [`Gpx::activity_guess`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActivityGuess {
+    /// Most likely activity
+    pub kind: ActivityKind,
+    /// How sure the guess is, from 0 (no data) to 1
+    pub confidence: f64,
+}
+
+/// Athlete data for [`Gpx::estimated_calories`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileParams {
+    /// Body weight, in kilograms
+    pub weight_kg: f64,
+    /// What the recording is
+    pub activity: ActivityKind,
+}
+
+/// Coste relativo de correr con la pendiente dada frente a hacerlo en llano
+/// (polinomio de Minetti et al., 2002)
+fn minetti_factor(grade: f64) -> f64 {
+    let i = grade.clamp(-MINETTI_MAX_GRADE, MINETTI_MAX_GRADE);
+    let cost = 155.4 * i.powi(5) - 30.4 * i.powi(4) - 43.3 * i.powi(3)
+        + 46.3 * i.powi(2)
+        + 19.5 * i
+        + FLAT_RUNNING_COST;
+    cost / FLAT_RUNNING_COST
+}
+
+/// Cuantil de valores ponderados `(valor, peso)`; los ordena por valor
+fn weighted_quantile(samples: &mut [(f64, f64)], quantile: f64) -> Option<f64> {
+    samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let total: f64 = samples.iter().map(|&(_, weight)| weight).sum();
+    let mut seen = 0.0;
+    for &(value, weight) in samples.iter() {
+        seen += weight;
+        if seen >= total * quantile {
+            return Some(value);
+        }
+    }
+    samples.last().map(|&(value, _)| value)
+}
+
+impl Gpx {
+    /// Pares de puntos consecutivos de cada segmento con tiempo creciente, con
+    /// su distancia en km y los segundos transcurridos
+    fn timed_steps(&self) -> impl Iterator<Item = (&Point, &Point, f64, f64)> {
+        self.tracks
+            .iter()
+            .flat_map(|track| &track.segments)
+            .flat_map(|segment| segment.points.windows(2))
+            .filter_map(|pair| {
+                let (from, to) = (pair[0].time?, pair[1].time?);
+                #[allow(clippy::cast_precision_loss)]
+                let seconds = (to - from).num_milliseconds() as f64 / 1000.0;
+                (seconds > 0.0).then(|| {
+                    let km = haversine_distance(&pair[0], &pair[1]);
+                    (&pair[0], &pair[1], km, seconds)
+                })
+            })
+    }
+
+    /// Grade-adjusted pace: the pace the same effort would give on flat ground
+    ///
+    /// Each stretch between timed points is weighted by the energy cost of
+    /// running on its grade (Minetti et al., 2002), so climbs count as longer
+    /// and gentle descents as shorter. Grades are capped at ±45%; stretches
+    /// without elevation count as flat. Returns `None` without timed movement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.000" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.009" lon="-3.0"><ele>700</ele><time>2024-07-01T08:07:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// // 1 km al 10% en 7 minutos equivale a correr en llano a menos de 5 min/km
+    /// let gap = gpx.grade_adjusted_pace().unwrap();
+    /// assert_eq!(gap.to_string(), "4:13 /km");
+    /// ```
+    pub fn grade_adjusted_pace(&self) -> Option<Pace> {
+        let (mut flat_km, mut seconds) = (0.0, 0.0);
+        for (from, to, km, step_seconds) in self.timed_steps() {
+            let factor = match (from.elevation, to.elevation) {
+                (Some(a), Some(b)) if km > 0.0 => minetti_factor((b - a) / (km * 1000.0)),
+                _ => 1.0,
+            };
+            flat_km += km * factor;
+            seconds += step_seconds;
+        }
+        Pace::from_seconds_per_km(seconds / flat_km)
+    }
+
+    /// Most likely activity of the recording (see [`Gpx::activity_guess`])
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{ActivityKind, Gpx};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.00" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.05" lon="-3.0"><time>2024-07-01T08:30:00Z</time></trkpt>
+    ///     <trkpt lat="40.10" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// // Unos 11 km/h
+    /// assert_eq!(gpx.guess_activity(), ActivityKind::Run);
+    /// ```
+    pub fn guess_activity(&self) -> ActivityKind {
+        self.activity_guess().kind
+    }
+
+    /// Guesses whether the recording is a walk, hike, run, ride or drive, with a confidence
+    ///
+    /// The typical moving speed (time-weighted median, ignoring stops) picks
+    /// the activity, and the confidence grows with its distance from the
+    /// neighbouring speed ranges. A running cadence (140 steps per minute or
+    /// more) makes it a run; slow recordings climbing more than 50 m per
+    /// kilometer are hikes. Short recordings halve the confidence. Without
+    /// timed movement the guess is a walk with a confidence of 0.
+    pub fn activity_guess(&self) -> ActivityGuess {
+        let mut speeds: Vec<(f64, f64)> = self
+            .timed_steps()
+            .map(|(_, _, km, seconds)| (km / (seconds / 3600.0), seconds))
+            .filter(|&(speed, _)| speed >= RESTING_MAX_KMH)
+            .collect();
+        let moving_seconds: f64 = speeds.iter().map(|&(_, seconds)| seconds).sum();
+        let Some(speed) = weighted_quantile(&mut speeds, 0.5) else {
+            return ActivityGuess {
+                kind: ActivityKind::Walk,
+                confidence: 0.0,
+            };
+        };
+
+        let (mut kind, low, high) = SPEED_BANDS
+            .into_iter()
+            .find(|&(_, _, high)| speed < high)
+            .unwrap_or(SPEED_BANDS[SPEED_BANDS.len() - 1]);
+        // 1 en el centro del rango y 0 en sus bordes
+        let margin = (1.0 - ((speed - low) / (high - low) * 2.0 - 1.0).abs()).clamp(0.0, 1.0);
+        let mut confidence = 0.5 + 0.4 * margin;
+
+        let mut cadences: Vec<(f64, f64)> = self
+            .get_all_points()
+            .iter()
+            .filter_map(|point| point.extensions.cadence)
+            .map(|cadence| (f64::from(cadence), 1.0))
+            .collect();
+        if let Some(cadence) = weighted_quantile(&mut cadences, 0.5) {
+            if cadence >= 140.0 && kind != ActivityKind::Drive {
+                confidence = if kind == ActivityKind::Run {
+                    confidence + 0.1
+                } else {
+                    0.8
+                };
+                kind = ActivityKind::Run;
+            }
+        }
+
+        if kind == ActivityKind::Walk {
+            let km = self.total_distance_km();
+            let gain = self.statistics().elevation_gain.unwrap_or(0.0);
+            if km > 0.0 && gain / km >= HIKE_MIN_GAIN_M_PER_KM {
+                kind = ActivityKind::Hike;
+            }
+        }
+        if moving_seconds < SHORT_RECORDING_SECONDS {
+            confidence /= 2.0;
+        }
+        ActivityGuess {
+            kind,
+            confidence: confidence.min(1.0),
+        }
+    }
+
+    /// Estimated energy spent, in kilocalories, from MET values
+    ///
+    /// Each stretch between timed points adds `MET × weight × hours`, with the
+    /// MET of the activity at the stretch's speed (see [`ActivityKind::met`]);
+    /// stretches slower than 1 km/h count as rest (1 MET). Returns `None`
+    /// without timed points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{ActivityKind, Gpx, ProfileParams};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.00" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.09" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// // Una hora corriendo a 10 km/h con 70 kg
+    /// let params = ProfileParams { weight_kg: 70.0, activity: ActivityKind::Run };
+    /// let kcal = gpx.estimated_calories(params).unwrap();
+    /// assert!((kcal - 700.5).abs() < 0.1);
+    /// ```
+    pub fn estimated_calories(&self, params: ProfileParams) -> Option<f64> {
+        let mut kcal = None;
+        for (_, _, km, seconds) in self.timed_steps() {
+            let hours = seconds / 3600.0;
+            let speed = km / hours;
+            let met = if speed < RESTING_MAX_KMH {
+                1.0
+            } else {
+                params.activity.met(speed)
+            };
+            *kcal.get_or_insert(0.0) += met * params.weight_kg * hours;
+        }
+        kcal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_minetti_factor() {
+        assert_eq!(minetti_factor(0.0), 1.0);
+        // Bajar suave cuesta menos que llano; bajar muy empinado, más
+        assert!(minetti_factor(-0.1) < 1.0);
+        assert!(minetti_factor(-0.4) > 1.0);
+        assert!((minetti_factor(0.1) - 1.658).abs() < 0.001);
+        assert_eq!(minetti_factor(0.9), minetti_factor(MINETTI_MAX_GRADE));
+    }
+
+    #[test]
+    fn test_calories_count_stops_as_rest() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |lat: f64, minutes: i64| {
+            Point::with_time(lat, -3.0, None, start + Duration::minutes(minutes))
+        };
+        let mut track = Track::new();
+        // Media hora a unos 22 km/h y otra media hora parado
+        track.add_segment(TrackSegment::with_points(vec![
+            at(40.0, 0),
+            at(40.1, 30),
+            at(40.1, 60),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        let ride = ProfileParams {
+            weight_kg: 80.0,
+            activity: ActivityKind::Ride,
+        };
+        let kcal = gpx.estimated_calories(ride).unwrap();
+        assert!((kcal - (8.0 * 80.0 * 0.5 + 80.0 * 0.5)).abs() < 1e-9);
+        assert!(gpx.grade_adjusted_pace().is_some());
+
+        assert_eq!(Gpx::new().estimated_calories(ride), None);
+        assert_eq!(Gpx::new().grade_adjusted_pace(), None);
+    }
+
+    #[test]
+    fn test_activity_guess() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        // Un punto por minuto a la velocidad dada, con la elevación y cadencia dadas
+        let recording = |kmh: f64, minutes: i32, climb_m: f64, cadence: Option<u8>| {
+            let points = (0..=minutes)
+                .map(|i| {
+                    let km = kmh * f64::from(i) / 60.0;
+                    let mut point = Point::with_time(
+                        40.0 + km / 111.195,
+                        -3.0,
+                        Some(600.0 + climb_m * f64::from(i) / f64::from(minutes)),
+                        start + Duration::minutes(i64::from(i)),
+                    );
+                    point.extensions.cadence = cadence;
+                    point
+                })
+                .collect();
+            let mut track = Track::new();
+            track.add_segment(TrackSegment::with_points(points));
+            let mut gpx = Gpx::new();
+            gpx.add_track(track);
+            gpx
+        };
+
+        let walk = recording(4.5, 60, 0.0, None).activity_guess();
+        assert_eq!(walk.kind, ActivityKind::Walk);
+        assert!(walk.confidence > 0.8);
+        assert_eq!(
+            recording(4.0, 120, 600.0, None).guess_activity(),
+            ActivityKind::Hike
+        );
+        assert_eq!(
+            recording(24.0, 90, 0.0, None).guess_activity(),
+            ActivityKind::Ride
+        );
+        assert_eq!(
+            recording(90.0, 60, 0.0, None).guess_activity(),
+            ActivityKind::Drive
+        );
+
+        // Trote lento que por velocidad sería andar, pero con cadencia de carrera
+        let jog = recording(6.8, 40, 0.0, Some(165)).activity_guess();
+        assert_eq!(jog.kind, ActivityKind::Run);
+        assert!((jog.confidence - 0.8).abs() < 1e-9);
+
+        // En el borde entre rangos y durante pocos minutos la confianza es baja
+        assert!(recording(15.0, 5, 0.0, None).activity_guess().confidence < 0.3);
+        assert_eq!(Gpx::new().activity_guess().confidence, 0.0);
+    }
+}
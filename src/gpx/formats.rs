@@ -0,0 +1,519 @@
+use crate::gpx::{
+    csv::CsvFormat, error::GpxError, kml::KmlFormat, parser::Gpx, tcx::TcxFormat,
+    write::WriteOptions,
+};
+use quick_xml::{events::Event, Reader};
+use std::path::Path;
+
+/// File formats recognized by [`detect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// GPS Exchange Format (`<gpx>`)
+    Gpx,
+    /// Keyhole Markup Language (`<kml>`)
+    Kml,
+    /// Garmin Training Center XML (`<TrainingCenterDatabase>`)
+    Tcx,
+    /// Garmin Flexible and Interoperable Data Transfer (binary)
+    Fit,
+    /// GeoJSON (`{"type": ...}`)
+    GeoJson,
+}
+
+impl Format {
+    /// Usual file extension, matching the keys used by the default registry
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gpx => "gpx",
+            Self::Kml => "kml",
+            Self::Tcx => "tcx",
+            Self::Fit => "fit",
+            Self::GeoJson => "geojson",
+        }
+    }
+
+    /// Registered MIME type of the format
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Gpx => "application/gpx+xml",
+            Self::Kml => "application/vnd.google-earth.kml+xml",
+            Self::Tcx => "application/vnd.garmin.tcx+xml",
+            Self::Fit => "application/vnd.ant.fit",
+            Self::GeoJson => "application/geo+json",
+        }
+    }
+}
+
+/// Guesses the format of a document from its content
+///
+/// FIT files are recognized by their `.FIT` header signature, GeoJSON by a JSON
+/// object with a `"type"` member, and XML formats by the local name of their root
+/// element. Returns `None` when the content matches none of them.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::formats::{detect, Format};
+///
+/// assert_eq!(detect(br#"<?xml version="1.0"?><gpx version="1.1"/>"#), Some(Format::Gpx));
+/// assert_eq!(detect(br#"{"type": "FeatureCollection", "features": []}"#), Some(Format::GeoJson));
+/// assert_eq!(detect(b"lat,lon\n1,2"), None);
+/// ```
+pub fn detect(bytes: &[u8]) -> Option<Format> {
+    if bytes.len() >= 12 && matches!(bytes[0], 12 | 14) && &bytes[8..12] == b".FIT" {
+        return Some(Format::Fit);
+    }
+
+    let content = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let start = content.iter().position(|b| !b.is_ascii_whitespace())?;
+    let content = &content[start..];
+
+    match content[0] {
+        b'{' => {
+            let head = &content[..content.len().min(4096)];
+            head.windows(6)
+                .any(|window| window == b"\"type\"")
+                .then_some(Format::GeoJson)
+        }
+        b'<' => match root_element(content)?.as_str() {
+            "gpx" => Some(Format::Gpx),
+            "kml" => Some(Format::Kml),
+            "TrainingCenterDatabase" => Some(Format::Tcx),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Nombre local del elemento raíz de un documento XML
+fn root_element(xml: &[u8]) -> Option<String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(element) | Event::Empty(element) => {
+                return Some(String::from_utf8_lossy(element.local_name().as_ref()).into_owned());
+            }
+            Event::Eof => return None,
+            _ => buf.clear(),
+        }
+    }
+}
+
+/// Decodes a document in some format into the [`Gpx`] model
+pub trait FormatReader: Send + Sync {
+    /// Parses the raw bytes of a document
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid document for this format
+    fn read(&self, bytes: &[u8]) -> Result<Gpx, GpxError>;
+}
+
+/// Encodes the [`Gpx`] model into some format
+pub trait FormatWriter: Send + Sync {
+    /// Serializes the document to raw bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be represented in this format
+    fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError>;
+}
+
+/// Reader and writer for the native GPX XML format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpxXmlFormat;
+
+impl FormatReader for GpxXmlFormat {
+    fn read(&self, bytes: &[u8]) -> Result<Gpx, GpxError> {
+        let xml = std::str::from_utf8(bytes)
+            .map_err(|e| GpxError::Schema(format!("document is not valid UTF-8: {e}")))?;
+        Gpx::try_from_str(xml)
+    }
+}
+
+impl FormatWriter for GpxXmlFormat {
+    fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+        Ok(gpx.to_xml_with(&WriteOptions::default())?.into_bytes())
+    }
+}
+
+/// A format known to a [`FormatRegistry`]: its lookup keys plus optional reader/writer
+pub struct FormatRegistration {
+    name: String,
+    extensions: Vec<String>,
+    mime_types: Vec<String>,
+    reader: Option<Box<dyn FormatReader>>,
+    writer: Option<Box<dyn FormatWriter>>,
+}
+
+impl FormatRegistration {
+    /// Crea un registro vacío para el formato indicado
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            extensions: Vec::new(),
+            mime_types: Vec::new(),
+            reader: None,
+            writer: None,
+        }
+    }
+
+    /// Adds file extensions (without the leading dot) handled by this format
+    #[must_use]
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions
+            .extend(extensions.iter().map(|ext| normalize_extension(ext)));
+        self
+    }
+
+    /// Adds MIME types handled by this format
+    #[must_use]
+    pub fn mime_types(mut self, mime_types: &[&str]) -> Self {
+        self.mime_types
+            .extend(mime_types.iter().map(|mime| mime.to_ascii_lowercase()));
+        self
+    }
+
+    /// Sets the reader used to import this format
+    #[must_use]
+    pub fn reader(mut self, reader: impl FormatReader + 'static) -> Self {
+        self.reader = Some(Box::new(reader));
+        self
+    }
+
+    /// Sets the writer used to export this format
+    #[must_use]
+    pub fn writer(mut self, writer: impl FormatWriter + 'static) -> Self {
+        self.writer = Some(Box::new(writer));
+        self
+    }
+
+    /// Nombre del formato
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Lookup table of readers and writers keyed by file extension and MIME type
+///
+/// [`FormatRegistry::default`] comes with the GPX and TCX formats and KML and CSV
+/// writers registered (plus a GeoJSON writer with the `geojson` feature and a FIT
+/// reader with the `fit` feature); other formats plug in
+/// through [`FormatRegistry::register`]. When two registrations claim the same key,
+/// the most recent one wins.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::formats::FormatRegistry;
+///
+/// let registry = FormatRegistry::default();
+/// let xml = br#"<gpx><trk><name>Run</name></trk></gpx>"#;
+///
+/// let gpx = registry.read_as("gpx", xml).unwrap();
+/// assert_eq!(gpx.tracks.len(), 1);
+/// ```
+pub struct FormatRegistry {
+    formats: Vec<FormatRegistration>,
+}
+
+impl FormatRegistry {
+    /// Crea un registro sin ningún formato
+    pub fn new() -> Self {
+        Self {
+            formats: Vec::new(),
+        }
+    }
+
+    /// Registers a format, taking precedence over earlier registrations
+    pub fn register(&mut self, format: FormatRegistration) -> &mut Self {
+        self.formats.push(format);
+        self
+    }
+
+    /// Names of all registered formats, in registration order
+    pub fn format_names(&self) -> Vec<&str> {
+        self.formats.iter().map(|format| format.name()).collect()
+    }
+
+    /// Finds the writer registered under a format name (case-insensitive)
+    pub fn writer_for_name(&self, name: &str) -> Option<&dyn FormatWriter> {
+        self.find(|format| format.name.eq_ignore_ascii_case(name) && format.writer.is_some())
+            .and_then(|format| format.writer.as_deref())
+    }
+
+    /// Finds the reader registered for a file extension (case-insensitive, dot optional)
+    pub fn reader_for_extension(&self, extension: &str) -> Option<&dyn FormatReader> {
+        let extension = normalize_extension(extension);
+        self.find(|format| format.extensions.contains(&extension) && format.reader.is_some())
+            .and_then(|format| format.reader.as_deref())
+    }
+
+    /// Finds the writer registered for a file extension (case-insensitive, dot optional)
+    pub fn writer_for_extension(&self, extension: &str) -> Option<&dyn FormatWriter> {
+        let extension = normalize_extension(extension);
+        self.find(|format| format.extensions.contains(&extension) && format.writer.is_some())
+            .and_then(|format| format.writer.as_deref())
+    }
+
+    /// Finds the reader registered for a MIME type
+    pub fn reader_for_mime(&self, mime_type: &str) -> Option<&dyn FormatReader> {
+        let mime_type = mime_type.to_ascii_lowercase();
+        self.find(|format| format.mime_types.contains(&mime_type) && format.reader.is_some())
+            .and_then(|format| format.reader.as_deref())
+    }
+
+    /// Finds the writer registered for a MIME type
+    pub fn writer_for_mime(&self, mime_type: &str) -> Option<&dyn FormatWriter> {
+        let mime_type = mime_type.to_ascii_lowercase();
+        self.find(|format| format.mime_types.contains(&mime_type) && format.writer.is_some())
+            .and_then(|format| format.writer.as_deref())
+    }
+
+    /// Reads bytes using the reader registered for `extension`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::UnknownFormat`] if no reader handles the extension, or the
+    /// reader's own error if decoding fails
+    pub fn read_as(&self, extension: &str, bytes: &[u8]) -> Result<Gpx, GpxError> {
+        self.reader_for_extension(extension)
+            .ok_or_else(|| unknown_format(extension))?
+            .read(bytes)
+    }
+
+    /// Writes a document using the writer registered for `extension`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::UnknownFormat`] if no writer handles the extension, or the
+    /// writer's own error if encoding fails
+    pub fn write_as(&self, extension: &str, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+        self.writer_for_extension(extension)
+            .ok_or_else(|| unknown_format(extension))?
+            .write(gpx)
+    }
+
+    /// Reads bytes using the reader for the format sniffed by [`detect`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::UnknownFormat`] if the format is not recognized or has no
+    /// registered reader, or the reader's own error if decoding fails
+    pub fn read_detected(&self, bytes: &[u8]) -> Result<Gpx, GpxError> {
+        let format = detect(bytes)
+            .ok_or_else(|| GpxError::UnknownFormat("unrecognized document content".to_string()))?;
+        self.read_as(format.extension(), bytes)
+    }
+
+    /// Reads a file, picking the reader from its extension
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has no registered extension,
+    /// or cannot be decoded
+    pub fn read_path<P: AsRef<Path>>(&self, path: P) -> Result<Gpx, GpxError> {
+        let path = path.as_ref();
+        let extension = extension_of(path)?;
+        let bytes = std::fs::read(path)?;
+        self.read_as(extension, &bytes)
+    }
+
+    /// Writes a file, picking the writer from its extension
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path has no registered extension, the document
+    /// cannot be encoded, or the file cannot be written
+    pub fn write_path<P: AsRef<Path>>(&self, path: P, gpx: &Gpx) -> Result<(), GpxError> {
+        let path = path.as_ref();
+        let bytes = self.write_as(extension_of(path)?, gpx)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn find(&self, predicate: impl Fn(&FormatRegistration) -> bool) -> Option<&FormatRegistration> {
+        self.formats.iter().rev().find(|format| predicate(format))
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            FormatRegistration::new("gpx")
+                .extensions(&["gpx"])
+                .mime_types(&["application/gpx+xml"])
+                .reader(GpxXmlFormat)
+                .writer(GpxXmlFormat),
+        );
+        registry.register(
+            FormatRegistration::new("tcx")
+                .extensions(&["tcx"])
+                .mime_types(&["application/vnd.garmin.tcx+xml"])
+                .reader(TcxFormat)
+                .writer(TcxFormat),
+        );
+        registry.register(
+            FormatRegistration::new("kml")
+                .extensions(&["kml"])
+                .mime_types(&["application/vnd.google-earth.kml+xml"])
+                .writer(KmlFormat),
+        );
+        #[cfg(feature = "geojson")]
+        registry.register(
+            FormatRegistration::new("geojson")
+                .extensions(&["geojson"])
+                .mime_types(&["application/geo+json"])
+                .writer(crate::gpx::geojson::GeoJsonFormat),
+        );
+        registry.register(
+            FormatRegistration::new("csv")
+                .extensions(&["csv"])
+                .mime_types(&["text/csv"])
+                .writer(CsvFormat::default()),
+        );
+        #[cfg(feature = "fit")]
+        registry.register(
+            FormatRegistration::new("fit")
+                .extensions(&["fit"])
+                .mime_types(&["application/vnd.ant.fit"])
+                .reader(crate::gpx::fit::FitFormat),
+        );
+        registry
+    }
+}
+
+fn normalize_extension(extension: &str) -> String {
+    extension.trim_start_matches('.').to_ascii_lowercase()
+}
+
+fn extension_of(path: &Path) -> Result<&str, GpxError> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .ok_or_else(|| unknown_format(&path.display().to_string()))
+}
+
+fn unknown_format(key: &str) -> GpxError {
+    GpxError::UnknownFormat(format!("no registered format handles `{key}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::Track;
+
+    struct NamesWriter;
+
+    impl FormatWriter for NamesWriter {
+        fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+            Ok(gpx.track_names().join("\n").into_bytes())
+        }
+    }
+
+    #[test]
+    fn test_default_registry_formats() {
+        let registry = FormatRegistry::default();
+        let mut expected = vec!["gpx", "tcx", "kml"];
+        if cfg!(feature = "geojson") {
+            expected.push("geojson");
+        }
+        expected.push("csv");
+        if cfg!(feature = "fit") {
+            expected.push("fit");
+        }
+        assert_eq!(registry.format_names(), expected);
+        assert!(registry.writer_for_name("CSV").is_some());
+        assert!(registry.writer_for_name("fit").is_none());
+        assert!(registry.writer_for_mime("text/csv").is_some());
+        assert!(registry
+            .writer_for_mime("application/vnd.garmin.tcx+xml")
+            .is_some());
+        assert!(registry.reader_for_extension(".GPX").is_some());
+        assert!(registry.writer_for_mime("application/gpx+xml").is_some());
+        assert!(registry.reader_for_extension("kml").is_none());
+    }
+
+    #[test]
+    fn test_registry_roundtrip_through_gpx_format() {
+        let registry = FormatRegistry::default();
+        let mut gpx = Gpx::new();
+        gpx.add_track(Track::with_name("Roundtrip".to_string()));
+
+        let bytes = registry.write_as("gpx", &gpx).unwrap();
+        let parsed = registry.read_as("gpx", &bytes).unwrap();
+        assert_eq!(parsed.track_names(), vec!["Roundtrip".to_string()]);
+    }
+
+    #[test]
+    fn test_registry_custom_format_and_precedence() {
+        let mut registry = FormatRegistry::default();
+        registry.register(
+            FormatRegistration::new("names")
+                .extensions(&["txt"])
+                .mime_types(&["text/plain"])
+                .writer(NamesWriter),
+        );
+
+        let mut gpx = Gpx::new();
+        gpx.add_track(Track::with_name("A".to_string()));
+        gpx.add_track(Track::with_name("B".to_string()));
+
+        assert_eq!(registry.write_as("txt", &gpx).unwrap(), b"A\nB".to_vec());
+        assert!(registry.reader_for_extension("txt").is_none());
+        assert!(registry.read_as("txt", b"A").is_err());
+    }
+
+    #[test]
+    fn test_registry_unknown_extension_error() {
+        let registry = FormatRegistry::new();
+        let result = registry.read_as("gpx", b"<gpx/>");
+        assert!(matches!(result, Err(GpxError::UnknownFormat(msg)) if msg.contains("gpx")));
+        assert!(matches!(
+            FormatRegistry::default().read_detected(b"hello"),
+            Err(GpxError::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_detect_xml_formats_by_root_element() {
+        let gpx =
+            b"\xEF\xBB\xBF  <?xml version=\"1.0\"?>\n<!-- exported --><gpx version=\"1.1\"></gpx>";
+        assert_eq!(detect(gpx), Some(Format::Gpx));
+
+        let kml = br#"<?xml version="1.0"?><kml xmlns="http://www.opengis.net/kml/2.2"/>"#;
+        assert_eq!(detect(kml), Some(Format::Kml));
+
+        let tcx = br#"<ns:TrainingCenterDatabase xmlns:ns="x"></ns:TrainingCenterDatabase>"#;
+        assert_eq!(detect(tcx), Some(Format::Tcx));
+
+        assert_eq!(detect(b"<html></html>"), None);
+    }
+
+    #[test]
+    fn test_detect_binary_and_json_formats() {
+        let mut fit = vec![14, 0x10, 0, 0, 0, 0, 0, 0];
+        fit.extend_from_slice(b".FIT");
+        fit.extend_from_slice(&[0, 0]);
+        assert_eq!(detect(&fit), Some(Format::Fit));
+
+        assert_eq!(detect(br#" {"type":"Feature"}"#), Some(Format::GeoJson));
+        assert_eq!(detect(br#"{"name":"x"}"#), None);
+        assert_eq!(detect(b""), None);
+        assert_eq!(detect(b"   "), None);
+    }
+
+    #[test]
+    fn test_read_detected() {
+        let registry = FormatRegistry::default();
+        let gpx = registry
+            .read_detected(br"<gpx><trk><name>Sniffed</name></trk></gpx>")
+            .unwrap();
+        assert_eq!(gpx.tracks.len(), 1);
+
+        assert!(registry.read_detected(br"<kml/>").is_err());
+        assert!(registry.read_detected(b"plain text").is_err());
+    }
+}
@@ -0,0 +1,338 @@
+use crate::gpx::{
+    error::GpxError,
+    parser::Gpx,
+    point::{haversine_distance, Point},
+};
+
+/// Turns coordinates into place names, for naming tracks and waypoints
+///
+/// Implement it over a web service (Nominatim, a commercial API) or use the
+/// bundled [`OfflineGeocoder`].
+pub trait Geocoder {
+    /// Name of the place at a coordinate, or `None` if it is unknown
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup itself fails
+    fn place_name(&self, lat: f64, lon: f64) -> Result<Option<String>, GpxError>;
+}
+
+/// [`Geocoder`] backed by a small bundled list of cities, with no network access
+///
+/// A coordinate is named after the nearest city within
+/// [`OfflineGeocoder::city_radius_km`], or else after the country of the
+/// nearest city within [`OfflineGeocoder::country_radius_km`]. The list covers
+/// the main cities of Spain and the largest cities of the world, so names are
+/// approximate: enough for "Madrid → Toledo", not for street addresses.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Geocoder, OfflineGeocoder};
+///
+/// let geocoder = OfflineGeocoder::default();
+/// let name = geocoder.place_name(40.45, -3.70).unwrap();
+/// assert_eq!(name.as_deref(), Some("Madrid"));
+/// let name = geocoder.place_name(42.2, -6.5).unwrap();
+/// assert_eq!(name.as_deref(), Some("Spain"));
+/// assert_eq!(geocoder.place_name(-60.0, -30.0).unwrap(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OfflineGeocoder {
+    /// Largest distance in kilometers to name a coordinate after a city
+    pub city_radius_km: f64,
+    /// Largest distance in kilometers to name a coordinate after the country of a city
+    pub country_radius_km: f64,
+}
+
+impl Default for OfflineGeocoder {
+    fn default() -> Self {
+        Self {
+            city_radius_km: 30.0,
+            country_radius_km: 300.0,
+        }
+    }
+}
+
+impl Geocoder for OfflineGeocoder {
+    fn place_name(&self, lat: f64, lon: f64) -> Result<Option<String>, GpxError> {
+        Point::check_coordinates(lat, lon)?;
+        let here = Point::new(lat, lon);
+        let nearest = PLACES
+            .iter()
+            .map(|&(city, country, lat, lon)| {
+                (
+                    city,
+                    country,
+                    haversine_distance(&here, &Point::new(lat, lon)),
+                )
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        Ok(nearest.and_then(|(city, country, km)| {
+            if km <= self.city_radius_km {
+                Some(city.to_string())
+            } else if km <= self.country_radius_km {
+                Some(country.to_string())
+            } else {
+                None
+            }
+        }))
+    }
+}
+
+impl Gpx {
+    /// Names every unnamed track after the places where it starts and ends
+    ///
+    /// A track from one place to another is named `"Madrid → Toledo"`; one that
+    /// starts and ends in the same place, or where only one end is known, gets
+    /// that single name. Tracks that already have a name, or whose ends are
+    /// unknown to the geocoder, are left alone. Returns the number of tracks named.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the geocoder
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, OfflineGeocoder};
+    ///
+    /// let mut gpx = Gpx::try_from_str(
+    ///     r#"<gpx><trk><trkseg>
+    ///         <trkpt lat="40.4168" lon="-3.7038"/>
+    ///         <trkpt lat="39.8628" lon="-4.0273"/>
+    ///     </trkseg></trk></gpx>"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(gpx.auto_name_tracks(&OfflineGeocoder::default()).unwrap(), 1);
+    /// assert_eq!(gpx.tracks[0].name.as_deref(), Some("Madrid → Toledo"));
+    /// ```
+    pub fn auto_name_tracks<G>(&mut self, geocoder: &G) -> Result<usize, GpxError>
+    where
+        G: Geocoder + ?Sized,
+    {
+        let mut named = 0;
+        for track in self.tracks.iter_mut().filter(|track| track.name.is_none()) {
+            let (Some(first), Some(last)) = (track.points().next(), track.points().next_back())
+            else {
+                continue;
+            };
+            let start = geocoder.place_name(first.lat, first.lon)?;
+            let end = geocoder.place_name(last.lat, last.lon)?;
+            track.name = match (start, end) {
+                (Some(start), Some(end)) if start != end => Some(format!("{start} → {end}")),
+                (Some(place), _) | (None, Some(place)) => Some(place),
+                (None, None) => None,
+            };
+            if track.name.is_some() {
+                named += 1;
+            }
+        }
+        Ok(named)
+    }
+
+    /// Names every unnamed waypoint after the place where it lies
+    ///
+    /// Returns the number of waypoints named.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the geocoder
+    pub fn auto_name_waypoints<G>(&mut self, geocoder: &G) -> Result<usize, GpxError>
+    where
+        G: Geocoder + ?Sized,
+    {
+        let mut named = 0;
+        for waypoint in self.waypoints.iter_mut().filter(|w| w.name.is_none()) {
+            waypoint.name = geocoder.place_name(waypoint.lat, waypoint.lon)?;
+            if waypoint.name.is_some() {
+                named += 1;
+            }
+        }
+        Ok(named)
+    }
+}
+
+/// Ciudades incluidas: `(ciudad, país, latitud, longitud)` del centro urbano
+const PLACES: &[(&str, &str, f64, f64)] = &[
+    // España
+    ("A Coruña", "Spain", 43.3623, -8.4115),
+    ("Albacete", "Spain", 38.9943, -1.8585),
+    ("Alicante", "Spain", 38.3452, -0.4810),
+    ("Almería", "Spain", 36.8340, -2.4637),
+    ("Ávila", "Spain", 40.6565, -4.6818),
+    ("Badajoz", "Spain", 38.8794, -6.9707),
+    ("Barcelona", "Spain", 41.3874, 2.1686),
+    ("Bilbao", "Spain", 43.2630, -2.9350),
+    ("Burgos", "Spain", 42.3439, -3.6969),
+    ("Cáceres", "Spain", 39.4753, -6.3724),
+    ("Cádiz", "Spain", 36.5271, -6.2886),
+    ("Castellón de la Plana", "Spain", 39.9864, -0.0513),
+    ("Ciudad Real", "Spain", 38.9848, -3.9274),
+    ("Córdoba", "Spain", 37.8882, -4.7794),
+    ("Cuenca", "Spain", 40.0704, -2.1374),
+    ("Girona", "Spain", 41.9794, 2.8214),
+    ("Granada", "Spain", 37.1773, -3.5986),
+    ("Guadalajara", "Spain", 40.6326, -3.1602),
+    ("Huelva", "Spain", 37.2614, -6.9447),
+    ("Huesca", "Spain", 42.1401, -0.4089),
+    ("Jaén", "Spain", 37.7796, -3.7849),
+    ("Las Palmas de Gran Canaria", "Spain", 28.1235, -15.4363),
+    ("León", "Spain", 42.5987, -5.5671),
+    ("Lleida", "Spain", 41.6176, 0.6200),
+    ("Logroño", "Spain", 42.4627, -2.4450),
+    ("Lugo", "Spain", 43.0097, -7.5568),
+    ("Madrid", "Spain", 40.4168, -3.7038),
+    ("Málaga", "Spain", 36.7213, -4.4214),
+    ("Murcia", "Spain", 37.9922, -1.1307),
+    ("Ourense", "Spain", 42.3358, -7.8639),
+    ("Oviedo", "Spain", 43.3614, -5.8593),
+    ("Palencia", "Spain", 42.0095, -4.5288),
+    ("Palma", "Spain", 39.5696, 2.6502),
+    ("Pamplona", "Spain", 42.8125, -1.6458),
+    ("Pontevedra", "Spain", 42.4310, -8.6444),
+    ("Salamanca", "Spain", 40.9701, -5.6635),
+    ("San Sebastián", "Spain", 43.3183, -1.9812),
+    ("Santa Cruz de Tenerife", "Spain", 28.4636, -16.2518),
+    ("Santander", "Spain", 43.4623, -3.8099),
+    ("Segovia", "Spain", 40.9429, -4.1088),
+    ("Sevilla", "Spain", 37.3891, -5.9845),
+    ("Soria", "Spain", 41.7636, -2.4649),
+    ("Tarragona", "Spain", 41.1189, 1.2445),
+    ("Teruel", "Spain", 40.3456, -1.1065),
+    ("Toledo", "Spain", 39.8628, -4.0273),
+    ("Valencia", "Spain", 39.4699, -0.3763),
+    ("Valladolid", "Spain", 41.6523, -4.7245),
+    ("Vigo", "Spain", 42.2406, -8.7207),
+    ("Vitoria-Gasteiz", "Spain", 42.8467, -2.6716),
+    ("Zamora", "Spain", 41.5034, -5.7446),
+    ("Zaragoza", "Spain", 41.6488, -0.8891),
+    // Europa
+    ("Amsterdam", "Netherlands", 52.3676, 4.9041),
+    ("Athens", "Greece", 37.9838, 23.7275),
+    ("Berlin", "Germany", 52.5200, 13.4050),
+    ("Bern", "Switzerland", 46.9480, 7.4474),
+    ("Bordeaux", "France", 44.8378, -0.5792),
+    ("Brussels", "Belgium", 50.8503, 4.3517),
+    ("Bucharest", "Romania", 44.4268, 26.1025),
+    ("Budapest", "Hungary", 47.4979, 19.0402),
+    ("Copenhagen", "Denmark", 55.6761, 12.5683),
+    ("Dublin", "Ireland", 53.3498, -6.2603),
+    ("Edinburgh", "United Kingdom", 55.9533, -3.1883),
+    ("Geneva", "Switzerland", 46.2044, 6.1432),
+    ("Hamburg", "Germany", 53.5511, 9.9937),
+    ("Helsinki", "Finland", 60.1699, 24.9384),
+    ("Innsbruck", "Austria", 47.2692, 11.4041),
+    ("Istanbul", "Turkey", 41.0082, 28.9784),
+    ("Lisbon", "Portugal", 38.7223, -9.1393),
+    ("London", "United Kingdom", 51.5072, -0.1276),
+    ("Lyon", "France", 45.7640, 4.8357),
+    ("Manchester", "United Kingdom", 53.4808, -2.2426),
+    ("Marseille", "France", 43.2965, 5.3698),
+    ("Milan", "Italy", 45.4642, 9.1900),
+    ("Munich", "Germany", 48.1351, 11.5820),
+    ("Naples", "Italy", 40.8518, 14.2681),
+    ("Nice", "France", 43.7102, 7.2620),
+    ("Oslo", "Norway", 59.9139, 10.7522),
+    ("Paris", "France", 48.8566, 2.3522),
+    ("Porto", "Portugal", 41.1579, -8.6291),
+    ("Prague", "Czechia", 50.0755, 14.4378),
+    ("Reykjavík", "Iceland", 64.1466, -21.9426),
+    ("Rome", "Italy", 41.9028, 12.4964),
+    ("Stockholm", "Sweden", 59.3293, 18.0686),
+    ("Toulouse", "France", 43.6047, 1.4442),
+    ("Turin", "Italy", 45.0703, 7.6869),
+    ("Vienna", "Austria", 48.2082, 16.3738),
+    ("Warsaw", "Poland", 52.2297, 21.0122),
+    ("Zürich", "Switzerland", 47.3769, 8.5417),
+    // América
+    ("Bogotá", "Colombia", 4.7110, -74.0721),
+    ("Buenos Aires", "Argentina", -34.6037, -58.3816),
+    ("Chicago", "United States", 41.8781, -87.6298),
+    ("Denver", "United States", 39.7392, -104.9903),
+    ("Lima", "Peru", -12.0464, -77.0428),
+    ("Los Angeles", "United States", 34.0522, -118.2437),
+    ("Mexico City", "Mexico", 19.4326, -99.1332),
+    ("Montreal", "Canada", 45.5019, -73.5674),
+    ("New York", "United States", 40.7128, -74.0060),
+    ("Rio de Janeiro", "Brazil", -22.9068, -43.1729),
+    ("San Francisco", "United States", 37.7749, -122.4194),
+    ("Santiago", "Chile", -33.4489, -70.6693),
+    ("São Paulo", "Brazil", -23.5505, -46.6333),
+    ("Seattle", "United States", 47.6062, -122.3321),
+    ("Toronto", "Canada", 43.6532, -79.3832),
+    ("Vancouver", "Canada", 49.2827, -123.1207),
+    // África, Asia y Oceanía
+    ("Auckland", "New Zealand", -36.8485, 174.7633),
+    ("Bangkok", "Thailand", 13.7563, 100.5018),
+    ("Beijing", "China", 39.9042, 116.4074),
+    ("Cairo", "Egypt", 30.0444, 31.2357),
+    ("Cape Town", "South Africa", -33.9249, 18.4241),
+    ("Casablanca", "Morocco", 33.5731, -7.5898),
+    ("Delhi", "India", 28.7041, 77.1025),
+    ("Dubai", "United Arab Emirates", 25.2048, 55.2708),
+    ("Hong Kong", "China", 22.3193, 114.1694),
+    ("Kathmandu", "Nepal", 27.7172, 85.3240),
+    ("Marrakesh", "Morocco", 31.6295, -7.9811),
+    ("Melbourne", "Australia", -37.8136, 144.9631),
+    ("Mumbai", "India", 19.0760, 72.8777),
+    ("Nairobi", "Kenya", -1.2921, 36.8219),
+    ("Seoul", "South Korea", 37.5665, 126.9780),
+    ("Shanghai", "China", 31.2304, 121.4737),
+    ("Singapore", "Singapore", 1.3521, 103.8198),
+    ("Sydney", "Australia", -33.8688, 151.2093),
+    ("Tokyo", "Japan", 35.6762, 139.6503),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::waypoint::Waypoint;
+
+    #[test]
+    fn test_auto_name_tracks_and_waypoints() {
+        let mut gpx = Gpx::try_from_str(
+            r#"<gpx>
+                <wpt lat="41.39" lon="2.17"/>
+                <wpt lat="41.39" lon="2.17"><name>Sagrada Família</name></wpt>
+                <trk><trkseg>
+                    <trkpt lat="43.30" lon="-1.98"/><trkpt lat="43.32" lon="-1.97"/>
+                </trkseg></trk>
+                <trk><name>Kept</name><trkseg><trkpt lat="40.41" lon="-3.70"/></trkseg></trk>
+                <trk><trkseg>
+                    <trkpt lat="-60.0" lon="-30.0"/><trkpt lat="37.39" lon="-5.98"/>
+                </trkseg></trk>
+                <trk><trkseg></trkseg></trk>
+            </gpx>"#,
+        )
+        .unwrap();
+        let geocoder = OfflineGeocoder::default();
+
+        assert_eq!(gpx.auto_name_tracks(&geocoder).unwrap(), 2);
+        let names: Vec<Option<&str>> = gpx.tracks.iter().map(|t| t.name.as_deref()).collect();
+        assert_eq!(
+            names,
+            [Some("San Sebastián"), Some("Kept"), Some("Sevilla"), None]
+        );
+
+        assert_eq!(gpx.auto_name_waypoints(&geocoder).unwrap(), 1);
+        let names: Vec<String> = gpx.waypoints.iter().map(Waypoint::display_name).collect();
+        assert_eq!(names, ["Barcelona", "Sagrada Família"]);
+    }
+
+    #[test]
+    fn test_offline_geocoder_radii() {
+        let strict = OfflineGeocoder {
+            city_radius_km: 1.0,
+            country_radius_km: 0.0,
+        };
+        assert_eq!(
+            strict.place_name(40.4168, -3.7038).unwrap().as_deref(),
+            Some("Madrid")
+        );
+        assert_eq!(strict.place_name(40.6, -3.7).unwrap(), None);
+        assert!(strict.place_name(95.0, 0.0).is_err());
+    }
+}
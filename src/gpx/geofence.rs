@@ -0,0 +1,321 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{Point, EARTH_RADIUS_KM},
+};
+use chrono::Duration;
+
+const EARTH_RADIUS_M: f64 = EARTH_RADIUS_KM * 1000.0;
+
+/// An area to test tracks against, see [`Gpx::intersects`] and [`Gpx::time_inside`]
+///
+/// Distances are measured on a local flat projection around the area, which is
+/// accurate for parks, cities and other regions up to a few hundred kilometers
+/// across.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Geofence, Point};
+///
+/// let park = Geofence::polygon(vec![
+///     Point::new(40.0, -3.0),
+///     Point::new(40.0, -2.9),
+///     Point::new(40.1, -2.9),
+///     Point::new(40.1, -3.0),
+/// ]);
+/// assert!(park.contains(&Point::new(40.05, -2.95)));
+///
+/// let around_home = Geofence::circle(Point::new(40.0, -3.0), 500.0);
+/// assert!(!around_home.contains(&Point::new(40.05, -2.95)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geofence {
+    /// Every position within `radius_m` meters of `center`
+    Circle {
+        /// Center of the circle
+        center: Point,
+        /// Radius, in meters
+        radius_m: f64,
+    },
+    /// Area enclosed by the vertices, in order; the last one joins back to the first
+    Polygon(Vec<Point>),
+}
+
+/// Coordenadas planas (metros) respecto a la latitud de referencia del área
+type Xy = (f64, f64);
+
+impl Geofence {
+    /// Círculo de `radius_m` metros alrededor de `center`
+    pub fn circle(center: Point, radius_m: f64) -> Self {
+        Self::Circle { center, radius_m }
+    }
+
+    /// Polígono con los vértices dados
+    pub fn polygon(vertices: Vec<Point>) -> Self {
+        Self::Polygon(vertices)
+    }
+
+    /// Whether the position lies inside the area (or on its border)
+    ///
+    /// Polygons with fewer than three vertices contain nothing.
+    pub fn contains(&self, point: &Point) -> bool {
+        self.contains_xy(self.project(point))
+    }
+
+    /// Coseno de la latitud de referencia de la proyección
+    fn cos_lat(&self) -> f64 {
+        let lat = match self {
+            Self::Circle { center, .. } => center.lat,
+            #[allow(clippy::cast_precision_loss)]
+            Self::Polygon(vertices) => {
+                vertices.iter().map(|p| p.lat).sum::<f64>() / vertices.len().max(1) as f64
+            }
+        };
+        lat.to_radians().cos()
+    }
+
+    fn project(&self, point: &Point) -> Xy {
+        (
+            point.lon.to_radians() * self.cos_lat() * EARTH_RADIUS_M,
+            point.lat.to_radians() * EARTH_RADIUS_M,
+        )
+    }
+
+    fn contains_xy(&self, p: Xy) -> bool {
+        match self {
+            Self::Circle { center, radius_m } => {
+                let c = self.project(center);
+                (p.0 - c.0).hypot(p.1 - c.1) <= *radius_m
+            }
+            Self::Polygon(vertices) if vertices.len() >= 3 => {
+                // Regla par-impar con un rayo hacia el este
+                let projected: Vec<Xy> = vertices.iter().map(|v| self.project(v)).collect();
+                let mut inside = false;
+                let mut j = projected.len() - 1;
+                for (i, &a) in projected.iter().enumerate() {
+                    let b = projected[j];
+                    if (a.1 > p.1) != (b.1 > p.1)
+                        && p.0 < a.0 + (p.1 - a.1) * (b.0 - a.0) / (b.1 - a.1)
+                    {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+            Self::Polygon(_) => false,
+        }
+    }
+
+    /// Posiciones (0 a 1) en que el tramo `a`–`b` cruza el borde del área
+    fn crossings(&self, a: Xy, b: Xy) -> Vec<f64> {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        match self {
+            Self::Circle { center, radius_m } => {
+                let c = self.project(center);
+                let (fx, fy) = (a.0 - c.0, a.1 - c.1);
+                let qa = dx * dx + dy * dy;
+                let qb = 2.0 * (fx * dx + fy * dy);
+                let qc = fx * fx + fy * fy - radius_m * radius_m;
+                let discriminant = qb * qb - 4.0 * qa * qc;
+                if qa == 0.0 || discriminant < 0.0 {
+                    return Vec::new();
+                }
+                let root = discriminant.sqrt();
+                vec![(-qb - root) / (2.0 * qa), (-qb + root) / (2.0 * qa)]
+            }
+            Self::Polygon(vertices) => {
+                let projected: Vec<Xy> = vertices.iter().map(|v| self.project(v)).collect();
+                let edges = projected
+                    .iter()
+                    .zip(projected.iter().cycle().skip(1))
+                    .take(projected.len());
+                edges
+                    .filter_map(|(&q0, &q1)| {
+                        let (ex, ey) = (q1.0 - q0.0, q1.1 - q0.1);
+                        let denominator = dx * ey - dy * ex;
+                        if denominator == 0.0 {
+                            return None;
+                        }
+                        let (wx, wy) = (q0.0 - a.0, q0.1 - a.1);
+                        let t = (wx * ey - wy * ex) / denominator;
+                        let u = (wx * dy - wy * dx) / denominator;
+                        (0.0..=1.0).contains(&u).then_some(t)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Fracción del tramo `a`–`b` que queda dentro del área
+    fn inside_fraction(&self, a: &Point, b: &Point) -> f64 {
+        let (a, b) = (self.project(a), self.project(b));
+        let mut cuts: Vec<f64> = self
+            .crossings(a, b)
+            .into_iter()
+            .filter(|t| 0.0 < *t && *t < 1.0)
+            .collect();
+        cuts.extend([0.0, 1.0]);
+        cuts.sort_by(f64::total_cmp);
+        cuts.windows(2)
+            .filter(|pair| {
+                let middle = (pair[0] + pair[1]) / 2.0;
+                self.contains_xy((a.0 + (b.0 - a.0) * middle, a.1 + (b.1 - a.1) * middle))
+            })
+            .map(|pair| pair[1] - pair[0])
+            .sum()
+    }
+
+    /// Si el tramo `a`–`b` toca el área en algún punto
+    fn touches(&self, a: &Point, b: &Point) -> bool {
+        self.contains(a) || self.contains(b) || self.inside_fraction(a, b) > 0.0
+    }
+}
+
+impl Gpx {
+    /// Whether any track passes through the area, including between two recorded points
+    ///
+    /// Routes and waypoints are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Geofence, Gpx, Point};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"/>
+    ///     <trkpt lat="40.2" lon="-3.0"/>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// // Ningún punto cae dentro, pero la línea entre ellos sí
+    /// assert!(gpx.intersects(&Geofence::circle(Point::new(40.1, -3.0), 100.0)));
+    /// assert!(gpx.intersects_circle(&Point::new(40.1, -3.0), 100.0));
+    /// assert!(!gpx.intersects_circle(&Point::new(40.1, -2.9), 100.0));
+    /// ```
+    pub fn intersects(&self, area: &Geofence) -> bool {
+        self.tracks
+            .iter()
+            .flat_map(|track| &track.segments)
+            .any(|segment| match segment.points.as_slice() {
+                [point] => area.contains(point),
+                points => points
+                    .windows(2)
+                    .any(|pair| area.touches(&pair[0], &pair[1])),
+            })
+    }
+
+    /// Whether any track comes within `radius_m` meters of `center`
+    pub fn intersects_circle(&self, center: &Point, radius_m: f64) -> bool {
+        self.intersects(&Geofence::circle(center.clone(), radius_m))
+    }
+
+    /// Whether any track enters the polygon with the given vertices
+    pub fn intersects_polygon(&self, vertices: &[Point]) -> bool {
+        self.intersects(&Geofence::polygon(vertices.to_vec()))
+    }
+
+    /// Time spent by the tracks inside the area
+    ///
+    /// Between two timed points of a segment the track is assumed to move in a
+    /// straight line at constant speed, so entering or leaving the area between
+    /// them counts the matching share of the interval. Untimed points and the
+    /// gaps between segments are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use gpx_extractor::{Geofence, Gpx, Point};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.00" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.10" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let park = Geofence::polygon(vec![
+    ///     Point::new(40.05, -3.1),
+    ///     Point::new(40.05, -2.9),
+    ///     Point::new(40.20, -2.9),
+    ///     Point::new(40.20, -3.1),
+    /// ]);
+    /// assert_eq!(gpx.time_inside(&park), Duration::minutes(30));
+    /// ```
+    pub fn time_inside(&self, area: &Geofence) -> Duration {
+        let mut millis = 0.0;
+        for segment in self.tracks.iter().flat_map(|track| &track.segments) {
+            let timed: Vec<_> = segment
+                .points
+                .iter()
+                .filter_map(|point| point.time.map(|time| (point, time)))
+                .collect();
+            for pair in timed.windows(2) {
+                let ((a, from), (b, to)) = (pair[0], pair[1]);
+                #[allow(clippy::cast_precision_loss)]
+                let interval = (to - from).num_milliseconds() as f64;
+                millis += interval * area.inside_fraction(a, b);
+            }
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Duration::milliseconds(millis.round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_time_inside_circle_with_exit_and_return() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |lon: f64, minutes: i64| {
+            Point::with_time(0.0, lon, None, start + Duration::minutes(minutes))
+        };
+        // Sobre el ecuador 0,01° son unos 1112 m; el círculo cubre de -0,005° a 0,005°
+        let area = Geofence::circle(Point::new(0.0, 0.0), 0.005 * 111_194.93);
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            at(-0.01, 0),
+            at(0.01, 20),
+            Point::new(0.0, 0.0),
+            at(0.0, 40),
+        ]));
+        track.add_segment(TrackSegment::with_points(vec![at(0.02, 50)]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        // Cruza por el centro (10 de 20 minutos dentro) y vuelve de 0,01° a 0° (10 de 20)
+        let inside = gpx.time_inside(&area);
+        assert!((inside - Duration::minutes(20)).num_milliseconds().abs() <= 10);
+        assert!(gpx.intersects(&area));
+        assert!(!gpx.intersects_circle(&Point::new(0.1, 0.0), 100.0));
+    }
+
+    #[test]
+    fn test_polygon_crossed_without_points_inside() {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (40.0, -3.2),
+            (40.0, -2.8),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        // Un rombo atravesado por su diagonal
+        let diamond = [
+            Point::new(39.9, -3.0),
+            Point::new(40.0, -2.9),
+            Point::new(40.1, -3.0),
+            Point::new(40.0, -3.1),
+        ];
+        assert!(gpx.intersects_polygon(&diamond));
+        assert!(!gpx.intersects_polygon(&diamond[..2]));
+        let far = diamond.clone().map(|p| Point::new(p.lat + 1.0, p.lon));
+        assert!(!gpx.intersects_polygon(&far));
+        assert_eq!(
+            gpx.time_inside(&Geofence::polygon(diamond.to_vec())),
+            Duration::zero()
+        );
+    }
+}
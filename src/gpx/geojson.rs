@@ -0,0 +1,129 @@
+use crate::gpx::{
+    error::GpxError, formats::FormatWriter, parser::Gpx, point::Point, track::Track,
+    waypoint::Waypoint,
+};
+use serde_json::{json, Value};
+
+/// Writer for GeoJSON `FeatureCollection` documents (`.geojson`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GeoJsonFormat;
+
+impl FormatWriter for GeoJsonFormat {
+    fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+        Ok(gpx.to_geojson().into_bytes())
+    }
+}
+
+/// Posición GeoJSON: longitud, latitud y elevación opcional
+fn position(point: &Point) -> Value {
+    match point.elevation {
+        Some(elevation) => json!([point.lon, point.lat, elevation]),
+        None => json!([point.lon, point.lat]),
+    }
+}
+
+fn track_feature(track: &Track) -> Value {
+    let lines: Vec<Vec<Value>> = track
+        .segments
+        .iter()
+        .map(|segment| segment.points.iter().map(position).collect())
+        .collect();
+
+    let mut properties = json!({
+        "name": track.name,
+        "distance_km": track.total_distance_km(),
+    });
+    if let Some(color) = track.color() {
+        properties["color"] = json!(color.to_string());
+    }
+
+    json!({
+        "type": "Feature",
+        "geometry": { "type": "MultiLineString", "coordinates": lines },
+        "properties": properties,
+    })
+}
+
+fn waypoint_feature(waypoint: &Waypoint) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [waypoint.lon, waypoint.lat] },
+        "properties": { "name": waypoint.name },
+    })
+}
+
+impl Gpx {
+    /// Converts the document to a GeoJSON `FeatureCollection` (RFC 7946)
+    ///
+    /// Each track becomes a `MultiLineString` feature with one line per segment and
+    /// `name`, `distance_km` and `color` properties; each waypoint becomes a `Point`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::GpxBuilder;
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.name("Run").segment(|s| s.point(40.0, -3.0).point(40.1, -3.0)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let geojson = gpx.to_geojson();
+    /// assert!(geojson.contains(r#""type":"FeatureCollection""#));
+    /// assert!(geojson.contains("[-3.0,40.0]"));
+    /// ```
+    pub fn to_geojson(&self) -> String {
+        let features: Vec<Value> = self
+            .tracks
+            .iter()
+            .map(track_feature)
+            .chain(self.waypoints.iter().map(waypoint_feature))
+            .collect();
+
+        json!({ "type": "FeatureCollection", "features": features }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gpx::{extensions::Color, waypoint::Waypoint};
+    use crate::GpxBuilder;
+    use serde_json::Value;
+
+    #[test]
+    fn test_to_geojson_tracks_and_waypoints() {
+        let gpx = GpxBuilder::new()
+            .track(|t| {
+                t.name("Ride")
+                    .color(Color::rgb(0xFF, 0, 0))
+                    .segment(|s| s.point_with_elevation(40.0, -3.0, 650.0).point(40.1, -3.0))
+                    .segment(|s| s.point(40.2, -3.1))
+            })
+            .waypoint(Waypoint::with_name(40.0, -3.0, "Start".to_string()))
+            .build()
+            .unwrap();
+
+        let geojson: Value = serde_json::from_str(&gpx.to_geojson()).unwrap();
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+
+        let track = &features[0];
+        assert_eq!(track["geometry"]["type"], "MultiLineString");
+        assert_eq!(
+            track["geometry"]["coordinates"][0][0],
+            serde_json::json!([-3.0, 40.0, 650.0])
+        );
+        assert_eq!(
+            track["geometry"]["coordinates"][1][0],
+            serde_json::json!([-3.1, 40.2])
+        );
+        assert_eq!(track["properties"]["name"], "Ride");
+        assert_eq!(
+            track["properties"]["color"],
+            Color::rgb(0xFF, 0, 0).to_string()
+        );
+
+        assert_eq!(features[1]["geometry"]["type"], "Point");
+        assert_eq!(features[1]["properties"]["name"], "Start");
+    }
+}
@@ -0,0 +1,116 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{interpolate, Point},
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Longest time, in seconds, between two points that [`Gpx::position_at`] interpolates across
+pub const DEFAULT_MAX_GAP_SECONDS: i64 = 1800;
+
+impl Gpx {
+    /// Position of the recording at the given instant, e.g. to geotag a photo
+    ///
+    /// Same as [`Gpx::position_at_with_gap`] with a gap of
+    /// [`DEFAULT_MAX_GAP_SECONDS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.1" lon="-3.0"><ele>700</ele><time>2024-07-01T08:10:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let photo = Utc.with_ymd_and_hms(2024, 7, 1, 8, 2, 30).unwrap();
+    /// let point = gpx.position_at(photo).unwrap();
+    /// assert!((point.lat - 40.025).abs() < 1e-9);
+    /// assert_eq!(point.elevation, Some(625.0));
+    /// assert!(gpx.position_at(photo - chrono::Duration::hours(1)).is_none());
+    /// ```
+    pub fn position_at(&self, time: DateTime<Utc>) -> Option<Point> {
+        self.position_at_with_gap(time, Duration::seconds(DEFAULT_MAX_GAP_SECONDS))
+    }
+
+    /// Position at the given instant, interpolated between the two surrounding points
+    ///
+    /// Position, elevation and time are interpolated linearly; a point recorded
+    /// exactly at `time` is returned as is, sensor data included. Returns `None`
+    /// when `time` falls outside the recording, between two segments, or between
+    /// two points more than `max_gap` apart (the device was probably off).
+    pub fn position_at_with_gap(&self, time: DateTime<Utc>, max_gap: Duration) -> Option<Point> {
+        for segment in self.tracks.iter().flat_map(|track| &track.segments) {
+            let mut previous: Option<(&Point, DateTime<Utc>)> = None;
+            for point in &segment.points {
+                let Some(current) = point.time else {
+                    continue;
+                };
+                if current == time {
+                    return Some(point.clone());
+                }
+                if let Some((before, start)) = previous {
+                    if start < time && time < current && current - start <= max_gap {
+                        #[allow(clippy::cast_precision_loss)]
+                        let fraction = (time - start).num_milliseconds() as f64
+                            / (current - start).num_milliseconds() as f64;
+                        return Some(interpolate(before, point, fraction));
+                    }
+                }
+                previous = Some((point, current));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_position_at_respects_gaps_and_segments() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+        let mut exact = Point::with_time(40.01, -3.0, None, at(1));
+        exact.extensions.heart_rate = Some(140);
+
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(40.0, -3.0, None, at(0)),
+            exact,
+            Point::new(40.5, -3.0),
+            Point::with_time(40.02, -3.0, None, at(2)),
+            Point::with_time(40.03, -3.0, None, at(62)),
+        ]));
+        track.add_segment(TrackSegment::with_points(vec![Point::with_time(
+            41.0,
+            -3.0,
+            None,
+            at(70),
+        )]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        assert_eq!(gpx.position_at(at(1)).unwrap().heart_rate(), Some(140));
+        // Los puntos sin tiempo se ignoran
+        let middle = gpx.position_at(start + Duration::seconds(90)).unwrap();
+        assert!((middle.lat - 40.015).abs() < 1e-9);
+        assert_eq!(middle.time, Some(start + Duration::seconds(90)));
+
+        // Hueco de una hora: solo con un margen mayor
+        assert!(gpx.position_at(at(30)).is_none());
+        let far = gpx
+            .position_at_with_gap(at(32), Duration::hours(2))
+            .unwrap();
+        assert!((far.lat - 40.025).abs() < 1e-9);
+
+        // Entre segmentos o fuera de la grabación no hay posición
+        assert!(gpx.position_at(at(65)).is_none());
+        assert!(gpx.position_at(at(-1)).is_none());
+        assert_eq!(gpx.position_at(at(70)).unwrap().lat, 41.0);
+    }
+}
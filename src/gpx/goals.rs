@@ -0,0 +1,410 @@
+use crate::gpx::parser::GpxStatistics;
+use std::fmt;
+use std::str::FromStr;
+
+/// A statistic a [`Goal`] can be checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GoalMetric {
+    /// Total distance, in kilometers
+    Distance,
+    /// Total duration, in seconds
+    Duration,
+    /// Total elevation gain, in meters
+    ElevationGain,
+    /// Total elevation loss, in meters
+    ElevationLoss,
+    /// Average speed, in km/h
+    AverageSpeed,
+}
+
+impl GoalMetric {
+    /// Nombre usado al parsear y mostrar la meta
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Distance => "distance",
+            Self::Duration => "duration",
+            Self::ElevationGain => "gain",
+            Self::ElevationLoss => "loss",
+            Self::AverageSpeed => "speed",
+        }
+    }
+
+    /// Unidad en la que se expresan los valores de la métrica
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Distance => "km",
+            Self::Duration => "s",
+            Self::ElevationGain | Self::ElevationLoss => "m",
+            Self::AverageSpeed => "km/h",
+        }
+    }
+
+    /// Value of the metric in `stats`, if it could be computed
+    #[allow(clippy::cast_precision_loss)]
+    pub fn value(self, stats: &GpxStatistics) -> Option<f64> {
+        match self {
+            Self::Distance => Some(stats.total_distance_km),
+            Self::Duration => stats.duration_seconds.map(|s| s as f64),
+            Self::ElevationGain => stats.elevation_gain,
+            Self::ElevationLoss => stats.elevation_loss,
+            Self::AverageSpeed => stats.average_speed_kmh,
+        }
+    }
+
+    fn parse_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "distance" | "dist" => Some(Self::Distance),
+            "duration" | "time" => Some(Self::Duration),
+            "gain" | "elevation_gain" | "ascent" => Some(Self::ElevationGain),
+            "loss" | "elevation_loss" | "descent" => Some(Self::ElevationLoss),
+            "speed" | "average_speed" => Some(Self::AverageSpeed),
+            _ => None,
+        }
+    }
+
+    /// Convierte "100km", "4h", "1500m"... a la unidad base de la métrica
+    fn parse_value(self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        if self == Self::Duration && text.contains(':') {
+            return parse_clock(text);
+        }
+
+        let split = text
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+        let number: f64 = number.parse().ok()?;
+
+        let factor = match (self, unit.trim().to_ascii_lowercase().as_str()) {
+            (Self::Distance, "" | "km")
+            | (Self::Duration, "" | "s")
+            | (Self::ElevationGain | Self::ElevationLoss, "" | "m")
+            | (Self::AverageSpeed, "" | "kmh" | "km/h") => 1.0,
+            (Self::Distance, "m") => 0.001,
+            (Self::Distance, "mi") | (Self::AverageSpeed, "mph") => 1.609_344,
+            (Self::Duration, "min") => 60.0,
+            (Self::Duration, "h") => 3600.0,
+            (Self::ElevationGain | Self::ElevationLoss, "ft") => 0.3048,
+            (Self::ElevationGain | Self::ElevationLoss, "km") => 1000.0,
+            _ => return None,
+        };
+        Some(number * factor)
+    }
+}
+
+fn parse_clock(text: &str) -> Option<f64> {
+    text.split(':')
+        .try_fold((0.0, 0), |(total, parts), part| {
+            part.trim()
+                .parse::<f64>()
+                .ok()
+                .map(|value| (total * 60.0 + value, parts + 1))
+        })
+        .filter(|&(_, parts)| parts <= 3)
+        .map(|(total, _)| total)
+}
+
+/// Comparison applied between the actual value and the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    /// `>=`
+    AtLeast,
+    /// `>`
+    MoreThan,
+    /// `<=`
+    AtMost,
+    /// `<`
+    LessThan,
+}
+
+impl Comparison {
+    fn holds(self, actual: f64, target: f64) -> bool {
+        match self {
+            Self::AtLeast => actual >= target,
+            Self::MoreThan => actual > target,
+            Self::AtMost => actual <= target,
+            Self::LessThan => actual < target,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::AtLeast => ">=",
+            Self::MoreThan => ">",
+            Self::AtMost => "<=",
+            Self::LessThan => "<",
+        }
+    }
+}
+
+/// A single target, such as "distance of at least 100 km"
+///
+/// Goals parse from expressions like `distance>=100km`, `duration<=4h`,
+/// `duration<=03:30:00`, `gain>=1500m` or `speed>25kmh`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Goal {
+    /// Statistic being checked
+    pub metric: GoalMetric,
+    /// How the actual value must compare to the target
+    pub comparison: Comparison,
+    /// Target value, in the metric's unit (see [`GoalMetric::unit`])
+    pub target: f64,
+}
+
+impl Goal {
+    /// Crea una meta a partir de sus partes
+    pub fn new(metric: GoalMetric, comparison: Comparison, target: f64) -> Self {
+        Self {
+            metric,
+            comparison,
+            target,
+        }
+    }
+
+    /// Checks the goal against computed statistics
+    ///
+    /// A goal whose metric cannot be computed (e.g. duration without timestamps) fails.
+    pub fn check(self, stats: &GpxStatistics) -> GoalResult {
+        let actual = self.metric.value(stats);
+        GoalResult {
+            goal: self,
+            actual,
+            passed: actual.is_some_and(|actual| self.comparison.holds(actual, self.target)),
+        }
+    }
+}
+
+impl FromStr for Goal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Los operadores de dos caracteres primero, para no cortar ">=" en ">"
+        let (index, comparison, len) = [
+            (">=", Comparison::AtLeast),
+            ("<=", Comparison::AtMost),
+            (">", Comparison::MoreThan),
+            ("<", Comparison::LessThan),
+        ]
+        .into_iter()
+        .find_map(|(op, comparison)| s.find(op).map(|index| (index, comparison, op.len())))
+        .ok_or_else(|| format!("expected a comparison (>=, <=, >, <) in '{s}'"))?;
+
+        let metric = GoalMetric::parse_name(&s[..index])
+            .ok_or_else(|| format!("unknown goal metric '{}'", s[..index].trim()))?;
+        let target = metric
+            .parse_value(&s[index + len..])
+            .ok_or_else(|| format!("invalid {} value in '{s}'", metric.name()))?;
+
+        Ok(Self::new(metric, comparison, target))
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.metric.name(),
+            self.comparison.symbol(),
+            self.target,
+            self.metric.unit()
+        )
+    }
+}
+
+/// A set of goals checked together, e.g. the rules of a challenge
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Goals {
+    /// Individual goals; all of them must pass
+    pub goals: Vec<Goal>,
+}
+
+impl Goals {
+    /// Crea un conjunto de metas vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an arbitrary goal
+    #[must_use]
+    pub fn with(mut self, goal: Goal) -> Self {
+        self.goals.push(goal);
+        self
+    }
+
+    /// Requires a minimum total distance in kilometers
+    #[must_use]
+    pub fn min_distance_km(self, km: f64) -> Self {
+        self.with(Goal::new(GoalMetric::Distance, Comparison::AtLeast, km))
+    }
+
+    /// Requires a maximum total duration in seconds
+    #[must_use]
+    pub fn max_duration_seconds(self, seconds: f64) -> Self {
+        self.with(Goal::new(GoalMetric::Duration, Comparison::AtMost, seconds))
+    }
+
+    /// Requires a minimum total elevation gain in meters
+    #[must_use]
+    pub fn min_elevation_gain(self, meters: f64) -> Self {
+        self.with(Goal::new(
+            GoalMetric::ElevationGain,
+            Comparison::AtLeast,
+            meters,
+        ))
+    }
+}
+
+impl FromIterator<Goal> for Goals {
+    fn from_iter<I: IntoIterator<Item = Goal>>(iter: I) -> Self {
+        Self {
+            goals: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Outcome of a single [`Goal`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoalResult {
+    /// Goal that was checked
+    pub goal: Goal,
+    /// Actual value of the metric, if it could be computed
+    pub actual: Option<f64>,
+    /// Whether the goal was met
+    pub passed: bool,
+}
+
+impl GoalResult {
+    /// Difference between the actual value and the target (`actual - target`)
+    pub fn delta(self) -> Option<f64> {
+        self.actual.map(|actual| actual - self.goal.target)
+    }
+}
+
+impl fmt::Display for GoalResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.passed { "PASS" } else { "FAIL" };
+        match (self.actual, self.delta()) {
+            (Some(actual), Some(delta)) => write!(
+                f,
+                "{status} {}: actual {actual:.2} {} ({delta:+.2})",
+                self.goal,
+                self.goal.metric.unit()
+            ),
+            _ => write!(f, "{status} {}: not available", self.goal),
+        }
+    }
+}
+
+/// Outcome of checking [`Goals`] against statistics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoalReport {
+    /// One result per goal, in the order the goals were given
+    pub results: Vec<GoalResult>,
+}
+
+impl GoalReport {
+    /// Verifica si todas las metas se cumplieron
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.passed)
+    }
+
+    /// Results of the goals that were not met
+    pub fn failures(&self) -> impl Iterator<Item = &GoalResult> {
+        self.results.iter().filter(|result| !result.passed)
+    }
+}
+
+impl GpxStatistics {
+    /// Checks the statistics against a set of goals
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Goals, Gpx};
+    ///
+    /// let stats = Gpx::new().statistics();
+    /// let report = stats.against(&Goals::new().min_distance_km(100.0));
+    /// assert!(!report.passed());
+    /// assert_eq!(report.results[0].delta(), Some(-100.0));
+    /// ```
+    pub fn against(&self, goals: &Goals) -> GoalReport {
+        GoalReport {
+            results: goals.goals.iter().map(|goal| goal.check(self)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gpx, Point, Track, TrackSegment};
+    use chrono::TimeZone;
+
+    fn ride() -> GpxStatistics {
+        let start = chrono::Utc.with_ymd_and_hms(2024, 7, 11, 8, 0, 0).unwrap();
+        let mut track = Track::with_name("Ride".to_string());
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(40.0, -3.0, Some(600.0), start),
+            Point::with_time(40.5, -3.0, Some(900.0), start + chrono::Duration::hours(2)),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx.statistics()
+    }
+
+    #[test]
+    fn test_parse_goals() {
+        let goal: Goal = "distance>=100km".parse().unwrap();
+        assert_eq!(
+            goal,
+            Goal::new(GoalMetric::Distance, Comparison::AtLeast, 100.0)
+        );
+
+        let goal: Goal = "duration <= 4h".parse().unwrap();
+        assert_eq!(goal.target, 14_400.0);
+        assert_eq!(goal.comparison, Comparison::AtMost);
+
+        let goal: Goal = "duration<03:30:00".parse().unwrap();
+        assert_eq!(goal.target, 12_600.0);
+
+        let goal: Goal = "gain>1500".parse().unwrap();
+        assert_eq!(goal.metric, GoalMetric::ElevationGain);
+        assert_eq!(goal.to_string(), "gain > 1500 m");
+
+        assert!("distance".parse::<Goal>().is_err());
+        assert!("heartrate>=150".parse::<Goal>().is_err());
+        assert!("distance>=100kg".parse::<Goal>().is_err());
+    }
+
+    #[test]
+    fn test_against_reports_pass_fail_and_deltas() {
+        let stats = ride();
+        let goals = Goals::new()
+            .min_distance_km(50.0)
+            .max_duration_seconds(3.0 * 3600.0)
+            .min_elevation_gain(500.0);
+        let report = stats.against(&goals);
+
+        assert!(!report.passed());
+        assert!(report.results[0].passed);
+        assert!(report.results[0].delta().unwrap() > 5.0);
+        assert!(report.results[1].passed);
+        assert_eq!(report.results[1].delta(), Some(-3600.0));
+        assert!(!report.results[2].passed);
+        assert_eq!(report.results[2].delta(), Some(-200.0));
+        assert_eq!(report.failures().count(), 1);
+        assert!(report.results[2]
+            .to_string()
+            .starts_with("FAIL gain >= 500 m"));
+    }
+
+    #[test]
+    fn test_missing_metric_fails() {
+        let stats = Gpx::new().statistics();
+        let report = stats.against(&Goals::from_iter(["speed>=10".parse().unwrap()]));
+        assert!(!report.passed());
+        assert_eq!(report.results[0].actual, None);
+        assert!(report.results[0].to_string().contains("not available"));
+    }
+}
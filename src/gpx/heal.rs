@@ -0,0 +1,127 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, Point},
+    track::{Track, TrackSegment},
+};
+use chrono::Duration;
+
+/// Si el hueco entre el final de un segmento y el inicio del siguiente es una
+/// pérdida de señal y no una pausa de verdad
+fn is_spurious_break(
+    last: Option<&Point>,
+    first: Option<&Point>,
+    max_gap: Duration,
+    max_distance_m: f64,
+) -> bool {
+    let (Some(last), Some(first)) = (last, first) else {
+        // Un segmento vacío no separa nada
+        return true;
+    };
+    if haversine_distance(last, first) * 1000.0 > max_distance_m {
+        return false;
+    }
+    match (last.time, first.time) {
+        (Some(end), Some(start)) => {
+            let gap = start - end;
+            gap >= Duration::zero() && gap <= max_gap
+        }
+        _ => true,
+    }
+}
+
+impl Track {
+    /// Merges consecutive segments split by short GPS dropouts, returning the number of joins
+    ///
+    /// Two segments are joined when the first point of the second is at most
+    /// `max_distance_m` meters and `max_gap` after the last point of the first.
+    /// When either of those points has no time, only the distance is checked.
+    /// Empty segments are always absorbed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx><trk>
+    ///     <trkseg><trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt></trkseg>
+    ///     <trkseg><trkpt lat="40.0001" lon="-3.0"><time>2024-07-01T08:00:20Z</time></trkpt></trkseg>
+    ///     <trkseg><trkpt lat="40.1" lon="-3.0"><time>2024-07-01T09:00:00Z</time></trkpt></trkseg>
+    /// </trk></gpx>"#).unwrap();
+    ///
+    /// let joins = gpx.tracks[0].heal_segments(Duration::seconds(60), 50.0);
+    /// assert_eq!(joins, 1);
+    /// assert_eq!(gpx.tracks[0].segments.len(), 2);
+    /// ```
+    pub fn heal_segments(&mut self, max_gap: Duration, max_distance_m: f64) -> usize {
+        let mut joins = 0;
+        let mut healed: Vec<TrackSegment> = Vec::with_capacity(self.segments.len());
+        for segment in std::mem::take(&mut self.segments) {
+            if let Some(previous) = healed.last_mut() {
+                if is_spurious_break(
+                    previous.points.last(),
+                    segment.points.first(),
+                    max_gap,
+                    max_distance_m,
+                ) {
+                    previous.points.extend(segment.points);
+                    joins += 1;
+                    continue;
+                }
+            }
+            healed.push(segment);
+        }
+        self.segments = healed;
+        joins
+    }
+}
+
+impl Gpx {
+    /// Merges segments split by short GPS dropouts in every track (see [`Track::heal_segments`])
+    pub fn heal_segments(&mut self, max_gap: Duration, max_distance_m: f64) -> usize {
+        self.tracks
+            .iter_mut()
+            .map(|track| track.heal_segments(max_gap, max_distance_m))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_heal_segments_joins_only_short_dropouts() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |lat: f64, seconds: i64| {
+            Point::with_time(lat, -3.0, None, start + Duration::seconds(seconds))
+        };
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            at(40.0, 0),
+            at(40.0001, 10),
+        ]));
+        // 20 s y unos 11 m después: pérdida de señal
+        track.add_segment(TrackSegment::with_points(vec![at(40.0002, 30)]));
+        track.add_segment(TrackSegment::new());
+        // Demasiado lejos
+        track.add_segment(TrackSegment::with_points(vec![at(40.01, 40)]));
+        // Cerca, pero tras una pausa de una hora
+        track.add_segment(TrackSegment::with_points(vec![at(40.01, 3640)]));
+        // Sin hora: solo cuenta la distancia
+        track.add_segment(TrackSegment::with_points(vec![Point::new(40.01, -3.0)]));
+
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        assert_eq!(gpx.heal_segments(Duration::minutes(1), 50.0), 3);
+
+        let sizes: Vec<usize> = gpx.tracks[0]
+            .segments
+            .iter()
+            .map(TrackSegment::point_count)
+            .collect();
+        assert_eq!(sizes, vec![3, 1, 2]);
+        assert_eq!(gpx.total_points(), 6);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::gpx::{
+    collection::{list_files, GpxCollection},
+    error::GpxError,
+    formats::{detect, FormatRegistry},
+    manifest::sha256_hex,
+    parser::{start_time, Gpx, GpxStatistics},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Versión del formato de la caché; las cachés de otra versión se descartan
+const CACHE_VERSION: u32 = 1;
+
+/// Summary of one file kept in a [`CollectionIndex`]
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    /// Path of the file, relative to the indexed directory
+    pub file: PathBuf,
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+    /// Name of the first named track
+    pub name: Option<String>,
+    /// Document date as found in the file (metadata time)
+    pub date: Option<String>,
+    /// Timestamp of the first timed track point
+    pub start_time: Option<DateTime<Utc>>,
+    /// Statistics of the whole document
+    pub statistics: GpxStatistics,
+}
+
+/// Lightweight index of a directory of activities, created by [`GpxCollection::open_cached`]
+///
+/// Only summaries are kept in memory, so the index of a large archive is cheap to
+/// build once the cache is warm.
+#[derive(Debug, Default)]
+pub struct CollectionIndex {
+    /// Indexed files, sorted by path
+    pub entries: Vec<IndexEntry>,
+    /// Files that were recognized but could not be decoded
+    pub failures: Vec<(PathBuf, GpxError)>,
+    /// Number of files parsed in this run because they were not in the cache
+    pub parsed: usize,
+}
+
+impl CollectionIndex {
+    /// Número de archivos indexados
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Verifica si el índice está vacío
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Distancia total de todos los archivos en kilómetros
+    pub fn total_distance_km(&self) -> f64 {
+        self.entries
+            .iter()
+            .map(|entry| entry.statistics.total_distance_km)
+            .sum()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedStatistics {
+    total_tracks: usize,
+    total_waypoints: usize,
+    total_segments: usize,
+    total_points: usize,
+    total_distance_km: f64,
+    elevation_range: Option<(f64, f64)>,
+    elevation_gain: Option<f64>,
+    elevation_loss: Option<f64>,
+    duration_seconds: Option<i64>,
+    average_speed_kmh: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedEntry {
+    file: String,
+    sha256: String,
+    name: Option<String>,
+    date: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    statistics: CachedStatistics,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheDocument {
+    version: u32,
+    files: Vec<CachedEntry>,
+}
+
+impl From<&GpxStatistics> for CachedStatistics {
+    fn from(stats: &GpxStatistics) -> Self {
+        Self {
+            total_tracks: stats.total_tracks,
+            total_waypoints: stats.total_waypoints,
+            total_segments: stats.total_segments,
+            total_points: stats.total_points,
+            total_distance_km: stats.total_distance_km,
+            elevation_range: stats.elevation_range,
+            elevation_gain: stats.elevation_gain,
+            elevation_loss: stats.elevation_loss,
+            duration_seconds: stats.duration_seconds,
+            average_speed_kmh: stats.average_speed_kmh,
+        }
+    }
+}
+
+impl From<CachedStatistics> for GpxStatistics {
+    fn from(stats: CachedStatistics) -> Self {
+        Self {
+            total_tracks: stats.total_tracks,
+            total_waypoints: stats.total_waypoints,
+            total_segments: stats.total_segments,
+            total_points: stats.total_points,
+            total_distance_km: stats.total_distance_km,
+            elevation_range: stats.elevation_range,
+            elevation_gain: stats.elevation_gain,
+            elevation_loss: stats.elevation_loss,
+            duration_seconds: stats.duration_seconds,
+            average_speed_kmh: stats.average_speed_kmh,
+        }
+    }
+}
+
+impl From<&IndexEntry> for CachedEntry {
+    fn from(entry: &IndexEntry) -> Self {
+        Self {
+            file: entry.file.to_string_lossy().into_owned(),
+            sha256: entry.sha256.clone(),
+            name: entry.name.clone(),
+            date: entry.date.clone(),
+            start_time: entry.start_time,
+            statistics: CachedStatistics::from(&entry.statistics),
+        }
+    }
+}
+
+impl From<CachedEntry> for IndexEntry {
+    fn from(entry: CachedEntry) -> Self {
+        Self {
+            file: PathBuf::from(entry.file),
+            sha256: entry.sha256,
+            name: entry.name,
+            date: entry.date,
+            start_time: entry.start_time,
+            statistics: entry.statistics.into(),
+        }
+    }
+}
+
+impl IndexEntry {
+    fn from_gpx(file: PathBuf, sha256: String, gpx: &Gpx) -> Self {
+        Self {
+            file,
+            sha256,
+            name: gpx.tracks.iter().find_map(|track| track.name.clone()),
+            date: gpx.date().map(str::to_string),
+            start_time: start_time(gpx),
+            statistics: gpx.statistics(),
+        }
+    }
+}
+
+/// Lee la caché indexada por hash; una caché ausente o corrupta equivale a una vacía
+fn read_cache(path: &Path) -> HashMap<String, IndexEntry> {
+    let Ok(json) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    match serde_json::from_str::<CacheDocument>(&json) {
+        Ok(document) if document.version == CACHE_VERSION => document
+            .files
+            .into_iter()
+            .map(|entry| (entry.sha256.clone(), IndexEntry::from(entry)))
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+fn write_cache(path: &Path, entries: &[IndexEntry]) -> Result<(), GpxError> {
+    let document = CacheDocument {
+        version: CACHE_VERSION,
+        files: entries.iter().map(CachedEntry::from).collect(),
+    };
+    let json = serde_json::to_string(&document)
+        .map_err(|e| GpxError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+impl GpxCollection {
+    /// Indexes a directory, reusing the summaries stored in `cache_path`
+    ///
+    /// Files are hashed on every run but only parsed when their SHA-256 is not in
+    /// the cache, so repeated summaries of a large archive skip almost all of the
+    /// XML work. The cache is rewritten with the current files afterwards; a
+    /// missing or unreadable cache is simply rebuilt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be listed or the cache cannot be written
+    pub fn open_cached<P, C>(dir: P, cache_path: C) -> Result<CollectionIndex, GpxError>
+    where
+        P: AsRef<Path>,
+        C: AsRef<Path>,
+    {
+        Self::open_cached_with(dir, cache_path, &FormatRegistry::default())
+    }
+
+    /// Like [`GpxCollection::open_cached`], reading files with `registry`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be listed or the cache cannot be written
+    pub fn open_cached_with<P, C>(
+        dir: P,
+        cache_path: C,
+        registry: &FormatRegistry,
+    ) -> Result<CollectionIndex, GpxError>
+    where
+        P: AsRef<Path>,
+        C: AsRef<Path>,
+    {
+        let dir = dir.as_ref();
+        let cache_path = cache_path.as_ref();
+        let mut cache = read_cache(cache_path);
+        let mut index = CollectionIndex::default();
+
+        for path in list_files(dir)? {
+            if path == cache_path {
+                continue;
+            }
+            let bytes = match std::fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    index.failures.push((path, e.into()));
+                    continue;
+                }
+            };
+            let file = path.strip_prefix(dir).unwrap_or(&path).to_path_buf();
+            let sha256 = sha256_hex(&bytes);
+
+            // Un archivo renombrado conserva su entrada: la clave es el contenido
+            if let Some(mut entry) = cache.remove(&sha256) {
+                entry.file = file;
+                index.entries.push(entry);
+                continue;
+            }
+
+            let Some(format) = detect(&bytes) else {
+                continue;
+            };
+            let Some(reader) = registry.reader_for_extension(format.extension()) else {
+                continue;
+            };
+            match reader.read(&bytes) {
+                Ok(gpx) => {
+                    index.entries.push(IndexEntry::from_gpx(file, sha256, &gpx));
+                    index.parsed += 1;
+                }
+                Err(e) => index.failures.push((path, e)),
+            }
+        }
+
+        write_cache(cache_path, &index.entries)?;
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RIDE: &str = r#"<gpx version="1.1" creator="test"><trk><name>Ride</name><trkseg>
+        <trkpt lat="40.0" lon="-3.0"><ele>600</ele><time>2024-05-02T08:00:00Z</time></trkpt>
+        <trkpt lat="40.1" lon="-3.0"><ele>650</ele><time>2024-05-02T09:00:00Z</time></trkpt>
+        </trkseg></trk></gpx>"#;
+
+    #[test]
+    fn test_open_cached_only_parses_new_or_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(dir.path().join("a.gpx"), RIDE).unwrap();
+        std::fs::write(dir.path().join("b.gpx"), RIDE.replace("40.1", "40.2")).unwrap();
+        std::fs::write(dir.path().join("broken.gpx"), "<gpx><trk>").unwrap();
+
+        let first = GpxCollection::open_cached(dir.path(), cache.path()).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first.parsed, 2);
+        assert_eq!(first.failures.len(), 1);
+        assert_eq!(first.entries[0].file, PathBuf::from("a.gpx"));
+        assert_eq!(first.entries[0].name.as_deref(), Some("Ride"));
+        assert_eq!(first.entries[0].statistics.elevation_gain, Some(50.0));
+
+        let second = GpxCollection::open_cached(dir.path(), cache.path()).unwrap();
+        assert_eq!(second.parsed, 0);
+        assert_eq!(second.total_distance_km(), first.total_distance_km());
+        assert_eq!(second.entries[0].start_time, first.entries[0].start_time);
+
+        std::fs::write(dir.path().join("b.gpx"), RIDE.replace("40.1", "40.3")).unwrap();
+        std::fs::rename(dir.path().join("a.gpx"), dir.path().join("c.gpx")).unwrap();
+        let third = GpxCollection::open_cached(dir.path(), cache.path()).unwrap();
+        assert_eq!(third.parsed, 1);
+        assert_eq!(third.entries[1].file, PathBuf::from("c.gpx"));
+        assert!(third.total_distance_km() > second.total_distance_km());
+    }
+
+    #[test]
+    fn test_open_cached_rebuilds_corrupt_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("index.json");
+        std::fs::write(dir.path().join("a.gpx"), RIDE).unwrap();
+        std::fs::write(&cache, "not json").unwrap();
+
+        let index = GpxCollection::open_cached(dir.path(), &cache).unwrap();
+        assert_eq!(index.parsed, 1);
+        assert_eq!(index.len(), 1);
+        assert!(std::fs::read_to_string(&cache)
+            .unwrap()
+            .contains("\"version\":1"));
+    }
+}
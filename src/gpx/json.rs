@@ -0,0 +1,508 @@
+//! Representación JSON del modelo, independiente de la XML
+//!
+//! Los tipos del modelo ya implementan serde con los nombres de GPX (`@lat`,
+//! `trkseg`, `gpxtpx:hr`...), así que `Gpx` se (de)serializa a través de estas
+//! estructuras espejo con nombres de campo de Rust.
+
+use crate::gpx::{
+    bounds::Bounds,
+    extensions::{Color, PointExtensions, TrackExtensions},
+    metadata::{Copyright, Link, Metadata, Person},
+    parser::Gpx,
+    point::Point,
+    track::{Track, TrackSegment},
+    unknown::UnknownXml,
+    user_data::UserData,
+    waypoint::Waypoint,
+};
+use chrono::{DateTime, Utc};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Copia las anotaciones a un mapa serializable
+fn user_data_map(user_data: &UserData) -> BTreeMap<String, String> {
+    user_data
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonGpx {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    creator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<JsonMetadata>,
+    #[serde(default)]
+    tracks: Vec<JsonTrack>,
+    #[serde(default)]
+    waypoints: Vec<JsonWaypoint>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<JsonPerson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    copyright: Option<JsonCopyright>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    links: Vec<JsonLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keywords: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bounds: Option<Bounds>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPerson {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    link: Option<JsonLink>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonCopyright {
+    author: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    year: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonLink {
+    href: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonTrack {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    links: Vec<JsonLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    /// Color en hexadecimal `RRGGBB`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    user_data: BTreeMap<String, String>,
+    #[serde(default)]
+    segments: Vec<Vec<JsonPoint>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPoint {
+    lat: f64,
+    lon: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    elevation: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    heart_rate: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cadence: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    power: Option<u16>,
+    #[serde(flatten)]
+    accuracy: JsonAccuracy,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    user_data: BTreeMap<String, String>,
+}
+
+/// Campos de precisión del receptor, comunes a puntos y waypoints
+#[derive(Serialize, Deserialize)]
+struct JsonAccuracy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    satellites: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hdop: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vdop: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pdop: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dgps_age: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dgps_id: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonWaypoint {
+    lat: f64,
+    lon: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    links: Vec<JsonLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    elevation: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(flatten)]
+    accuracy: JsonAccuracy,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    user_data: BTreeMap<String, String>,
+}
+
+impl From<&Link> for JsonLink {
+    fn from(link: &Link) -> Self {
+        Self {
+            href: link.href.clone(),
+            text: link.text.clone(),
+            mime_type: link.mime_type.clone(),
+        }
+    }
+}
+
+impl From<JsonLink> for Link {
+    fn from(link: JsonLink) -> Self {
+        Self {
+            href: link.href,
+            text: link.text,
+            mime_type: link.mime_type,
+        }
+    }
+}
+
+impl From<&Metadata> for JsonMetadata {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            name: metadata.name.clone(),
+            description: metadata.description.clone(),
+            author: metadata.author.as_ref().map(|person| JsonPerson {
+                name: person.name.clone(),
+                email: person.email.clone(),
+                link: person.link.as_ref().map(JsonLink::from),
+            }),
+            copyright: metadata.copyright.as_ref().map(|copyright| JsonCopyright {
+                author: copyright.author.clone(),
+                year: copyright.year,
+                license: copyright.license.clone(),
+            }),
+            links: metadata.links.iter().map(JsonLink::from).collect(),
+            time: metadata.time.clone(),
+            keywords: metadata.keywords.clone(),
+            bounds: metadata.bounds,
+        }
+    }
+}
+
+impl From<JsonMetadata> for Metadata {
+    fn from(metadata: JsonMetadata) -> Self {
+        Self {
+            name: metadata.name,
+            description: metadata.description,
+            author: metadata.author.map(|person| Person {
+                name: person.name,
+                email: person.email,
+                link: person.link.map(Link::from),
+            }),
+            copyright: metadata.copyright.map(|copyright| Copyright {
+                author: copyright.author,
+                year: copyright.year,
+                license: copyright.license,
+            }),
+            links: metadata.links.into_iter().map(Link::from).collect(),
+            time: metadata.time,
+            keywords: metadata.keywords,
+            bounds: metadata.bounds,
+            unknown: UnknownXml::default(),
+        }
+    }
+}
+
+impl From<&Point> for JsonPoint {
+    fn from(point: &Point) -> Self {
+        Self {
+            lat: point.lat,
+            lon: point.lon,
+            elevation: point.elevation,
+            time: point.time,
+            heart_rate: point.extensions.heart_rate,
+            cadence: point.extensions.cadence,
+            power: point.extensions.power,
+            accuracy: JsonAccuracy {
+                fix: point.fix.clone(),
+                satellites: point.satellites,
+                hdop: point.hdop,
+                vdop: point.vdop,
+                pdop: point.pdop,
+                dgps_age: point.dgps_age,
+                dgps_id: point.dgps_id,
+            },
+            user_data: user_data_map(&point.user_data),
+        }
+    }
+}
+
+impl From<JsonPoint> for Point {
+    fn from(point: JsonPoint) -> Self {
+        Self {
+            elevation: point.elevation,
+            time: point.time,
+            fix: point.accuracy.fix,
+            satellites: point.accuracy.satellites,
+            hdop: point.accuracy.hdop,
+            vdop: point.accuracy.vdop,
+            pdop: point.accuracy.pdop,
+            dgps_age: point.accuracy.dgps_age,
+            dgps_id: point.accuracy.dgps_id,
+            extensions: PointExtensions {
+                heart_rate: point.heart_rate,
+                cadence: point.cadence,
+                power: point.power,
+                unknown: UnknownXml::default(),
+            },
+            user_data: point.user_data.into_iter().collect(),
+            ..Point::new(point.lat, point.lon)
+        }
+    }
+}
+
+impl From<&Waypoint> for JsonWaypoint {
+    fn from(waypoint: &Waypoint) -> Self {
+        Self {
+            lat: waypoint.lat,
+            lon: waypoint.lon,
+            name: waypoint.name.clone(),
+            comment: waypoint.comment.clone(),
+            description: waypoint.description.clone(),
+            source: waypoint.source.clone(),
+            links: waypoint.links.iter().map(JsonLink::from).collect(),
+            elevation: waypoint.elevation,
+            time: waypoint.time,
+            symbol: waypoint.symbol.clone(),
+            kind: waypoint.kind.clone(),
+            accuracy: JsonAccuracy {
+                fix: waypoint.fix.clone(),
+                satellites: waypoint.satellites,
+                hdop: waypoint.hdop,
+                vdop: waypoint.vdop,
+                pdop: waypoint.pdop,
+                dgps_age: waypoint.dgps_age,
+                dgps_id: waypoint.dgps_id,
+            },
+            user_data: user_data_map(&waypoint.user_data),
+        }
+    }
+}
+
+impl From<JsonWaypoint> for Waypoint {
+    fn from(waypoint: JsonWaypoint) -> Self {
+        Self {
+            name: waypoint.name,
+            comment: waypoint.comment,
+            description: waypoint.description,
+            source: waypoint.source,
+            links: waypoint.links.into_iter().map(Link::from).collect(),
+            elevation: waypoint.elevation,
+            time: waypoint.time,
+            symbol: waypoint.symbol,
+            kind: waypoint.kind,
+            fix: waypoint.accuracy.fix,
+            satellites: waypoint.accuracy.satellites,
+            hdop: waypoint.accuracy.hdop,
+            vdop: waypoint.accuracy.vdop,
+            pdop: waypoint.accuracy.pdop,
+            dgps_age: waypoint.accuracy.dgps_age,
+            dgps_id: waypoint.accuracy.dgps_id,
+            user_data: waypoint.user_data.into_iter().collect(),
+            ..Waypoint::new(waypoint.lat, waypoint.lon)
+        }
+    }
+}
+
+impl From<&Gpx> for JsonGpx {
+    fn from(gpx: &Gpx) -> Self {
+        Self {
+            creator: gpx.creator.clone(),
+            metadata: gpx.metadata.as_ref().map(JsonMetadata::from),
+            tracks: gpx
+                .tracks
+                .iter()
+                .map(|track| JsonTrack {
+                    name: track.name.clone(),
+                    comment: track.comment.clone(),
+                    description: track.description.clone(),
+                    source: track.source.clone(),
+                    links: track.links.iter().map(JsonLink::from).collect(),
+                    kind: track.kind.clone(),
+                    color: track.extensions.color.map(Color::to_hex),
+                    user_data: user_data_map(&track.user_data),
+                    segments: track
+                        .segments
+                        .iter()
+                        .map(|segment| segment.points.iter().map(JsonPoint::from).collect())
+                        .collect(),
+                })
+                .collect(),
+            waypoints: gpx.waypoints.iter().map(JsonWaypoint::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<JsonGpx> for Gpx {
+    type Error = String;
+
+    fn try_from(json: JsonGpx) -> Result<Self, Self::Error> {
+        let mut tracks = Vec::with_capacity(json.tracks.len());
+        for track in json.tracks {
+            let color = match track.color {
+                Some(hex) => Some(
+                    Color::from_hex(&hex).ok_or_else(|| format!("invalid track color {hex:?}"))?,
+                ),
+                None => None,
+            };
+            tracks.push(Track {
+                name: track.name,
+                comment: track.comment,
+                description: track.description,
+                source: track.source,
+                links: track.links.into_iter().map(Link::from).collect(),
+                kind: track.kind,
+                unknown: UnknownXml::default(),
+                extensions: TrackExtensions {
+                    color,
+                    unknown: UnknownXml::default(),
+                },
+                user_data: track.user_data.into_iter().collect(),
+                segments: track
+                    .segments
+                    .into_iter()
+                    .map(|points| {
+                        TrackSegment::with_points(points.into_iter().map(Point::from).collect())
+                    })
+                    .collect(),
+            });
+        }
+        Ok(Self {
+            tracks,
+            waypoints: json.waypoints.into_iter().map(Waypoint::from).collect(),
+            metadata: json.metadata.map(Metadata::from),
+            creator: json.creator,
+            ..Self::new()
+        })
+    }
+}
+
+/// JSON-friendly serialization with plain field names, unrelated to the GPX XML layout
+///
+/// Tracks hold their segments as arrays of points, and sensor data and the
+/// track color are flattened into `heart_rate`, `cadence`, `power` and a
+/// `RRGGBB` `color`. Missing values are left out.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::Gpx;
+///
+/// let gpx = Gpx::try_from_str(r#"<gpx><trk><name>Ride</name><trkseg>
+///     <trkpt lat="40.0" lon="-3.0"><ele>650</ele></trkpt>
+/// </trkseg></trk></gpx>"#).unwrap();
+///
+/// let json = serde_json::to_string(&gpx).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"tracks":[{"name":"Ride","segments":[[{"lat":40.0,"lon":-3.0,"elevation":650.0}]]}],"waypoints":[]}"#
+/// );
+/// let back: Gpx = serde_json::from_str(&json).unwrap();
+/// assert_eq!(back.total_points(), 1);
+/// ```
+impl Serialize for Gpx {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        JsonGpx::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Gpx {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Gpx::try_from(JsonGpx::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::parser::GpxStatistics;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_json_round_trip_keeps_the_whole_model() {
+        let mut point = Point::with_time(
+            40.0,
+            -3.0,
+            Some(650.0),
+            Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap(),
+        );
+        point.extensions.heart_rate = Some(140);
+        let mut track = Track::new();
+        track.name = Some("Ride".to_string());
+        track.extensions.color = Some(Color::rgb(255, 0, 0));
+        track.add_segment(TrackSegment::with_points(vec![
+            point,
+            Point::new(40.1, -3.0),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx.add_waypoint(Waypoint::with_name(40.05, -3.0, "Col".to_string()));
+        gpx.set_author(Person::new("Ana").with_link(Link::new("https://example.com")));
+        gpx.update_metadata_bounds();
+        gpx.creator = Some("test".to_string());
+
+        let json = serde_json::to_value(&gpx).unwrap();
+        assert_eq!(json["tracks"][0]["color"], "FF0000");
+        assert_eq!(json["tracks"][0]["segments"][0][0]["heart_rate"], 140);
+        assert_eq!(
+            json["tracks"][0]["segments"][0][0]["time"],
+            "2024-07-01T08:00:00Z"
+        );
+        assert_eq!(json["metadata"]["bounds"]["max_lat"], 40.1);
+
+        let back: Gpx = serde_json::from_value(json).unwrap();
+        assert_eq!(back.to_xml(), gpx.to_xml());
+
+        let statistics = serde_json::to_value(gpx.statistics()).unwrap();
+        assert_eq!(statistics["total_points"], 2);
+        let _: GpxStatistics = serde_json::from_value(statistics).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_color_is_rejected() {
+        let error = serde_json::from_str::<Gpx>(r#"{"tracks":[{"color":"red"}]}"#).unwrap_err();
+        assert!(error.to_string().contains("invalid track color"));
+        assert!(serde_json::from_str::<Gpx>("{}").unwrap().is_empty());
+    }
+}
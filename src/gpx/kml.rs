@@ -0,0 +1,430 @@
+use crate::gpx::{
+    error::GpxError,
+    formats::FormatWriter,
+    parser::Gpx,
+    point::Point,
+    track::{Track, TrackSegment},
+    waypoint::Waypoint,
+};
+use quick_xml::se::Serializer;
+use serde::Serialize;
+
+/// Namespace of KML 2.2 documents
+pub const KML_NAMESPACE: &str = "http://www.opengis.net/kml/2.2";
+
+/// Writer for Google Earth KML documents (`.kml`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KmlFormat;
+
+impl FormatWriter for KmlFormat {
+    fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+        Ok(gpx.to_kml()?.into_bytes())
+    }
+}
+
+impl Gpx {
+    /// Converts the document to a Google Earth–compatible KML document
+    ///
+    /// Each track becomes a `Placemark` with one `LineString` per segment, using
+    /// the `absolute` altitude mode when the segment has elevations and
+    /// `clampToGround` otherwise, and a line style with the track color. Each
+    /// waypoint becomes a `Point` placemark.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the XML serialization fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::GpxBuilder;
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.name("Ridge").segment(|s| s.point_with_elevation(42.0, 1.0, 2100.0)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let kml = gpx.to_kml().unwrap();
+    /// assert!(kml.contains("<altitudeMode>absolute</altitudeMode>"));
+    /// assert!(kml.contains("<coordinates>1,42,2100</coordinates>"));
+    /// ```
+    pub fn to_kml(&self) -> Result<String, GpxError> {
+        self.to_kml_with_icons(&[])
+    }
+
+    /// KML con estilos de icono para los waypoints cuyo símbolo aparece en `icons`
+    pub(crate) fn to_kml_with_icons(&self, icons: &[(String, String)]) -> Result<String, GpxError> {
+        let mut styles: Vec<StyleRef> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, track)| {
+                let color = track.color()?;
+                Some(StyleRef {
+                    id: format!("track-{i}"),
+                    line_style: Some(LineStyleRef {
+                        // KML ordena los canales como aabbggrr
+                        color: format!("ff{:02x}{:02x}{:02x}", color.b, color.g, color.r),
+                        width: 3,
+                    }),
+                    icon_style: None,
+                })
+            })
+            .collect();
+        styles.extend(icons.iter().enumerate().map(|(i, (_, href))| StyleRef {
+            id: format!("icon-{i}"),
+            line_style: None,
+            icon_style: Some(IconStyleRef {
+                icon: IconRef { href },
+            }),
+        }));
+
+        let placemarks = self
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(i, track)| track_placemark(i, track))
+            .chain(self.waypoints.iter().map(|waypoint| {
+                let icon = waypoint
+                    .symbol
+                    .as_deref()
+                    .and_then(|symbol| icons.iter().position(|(s, _)| s == symbol));
+                waypoint_placemark(waypoint, icon)
+            }))
+            .collect();
+
+        let kml = KmlRef {
+            xmlns: KML_NAMESPACE,
+            document: DocumentRef {
+                name: self.metadata.as_ref().and_then(|m| m.name.as_deref()),
+                styles,
+                placemarks,
+            },
+        };
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        kml.serialize(Serializer::new(&mut xml))?;
+        Ok(xml)
+    }
+}
+
+fn coordinates(point: &Point) -> String {
+    match point.elevation {
+        Some(elevation) => format!("{},{},{}", point.lon, point.lat, elevation),
+        None => format!("{},{}", point.lon, point.lat),
+    }
+}
+
+fn line_string(segment: &TrackSegment) -> LineStringRef {
+    let has_elevation = segment.points.iter().any(|p| p.elevation.is_some());
+    LineStringRef {
+        altitude_mode: if has_elevation {
+            "absolute"
+        } else {
+            "clampToGround"
+        },
+        coordinates: segment
+            .points
+            .iter()
+            .map(coordinates)
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn track_placemark(index: usize, track: &Track) -> PlacemarkRef<'_> {
+    PlacemarkRef {
+        name: track.name.as_deref(),
+        style_url: track.color().map(|_| format!("#track-{index}")),
+        geometry: Some(MultiGeometryRef {
+            line_strings: track
+                .segments
+                .iter()
+                .filter(|s| !s.points.is_empty())
+                .map(line_string)
+                .collect(),
+        }),
+        point: None,
+    }
+}
+
+fn waypoint_placemark(waypoint: &Waypoint, icon: Option<usize>) -> PlacemarkRef<'_> {
+    let mut position = Point::new(waypoint.lat, waypoint.lon);
+    position.elevation = waypoint.elevation;
+    PlacemarkRef {
+        name: waypoint.name.as_deref(),
+        style_url: icon.map(|i| format!("#icon-{i}")),
+        geometry: None,
+        point: Some(PointRef {
+            coordinates: coordinates(&position),
+        }),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "kml")]
+struct KmlRef<'a> {
+    #[serde(rename = "@xmlns")]
+    xmlns: &'static str,
+    #[serde(rename = "Document")]
+    document: DocumentRef<'a>,
+}
+
+#[derive(Serialize)]
+struct DocumentRef<'a> {
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(rename = "Style")]
+    styles: Vec<StyleRef<'a>>,
+    #[serde(rename = "Placemark")]
+    placemarks: Vec<PlacemarkRef<'a>>,
+}
+
+#[derive(Serialize)]
+struct StyleRef<'a> {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "IconStyle", skip_serializing_if = "Option::is_none")]
+    icon_style: Option<IconStyleRef<'a>>,
+    #[serde(rename = "LineStyle", skip_serializing_if = "Option::is_none")]
+    line_style: Option<LineStyleRef>,
+}
+
+#[derive(Serialize)]
+struct LineStyleRef {
+    #[serde(rename = "color")]
+    color: String,
+    #[serde(rename = "width")]
+    width: u8,
+}
+
+#[derive(Serialize)]
+struct IconStyleRef<'a> {
+    #[serde(rename = "Icon")]
+    icon: IconRef<'a>,
+}
+
+#[derive(Serialize)]
+struct IconRef<'a> {
+    #[serde(rename = "href")]
+    href: &'a str,
+}
+
+#[derive(Serialize)]
+struct PlacemarkRef<'a> {
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(rename = "styleUrl", skip_serializing_if = "Option::is_none")]
+    style_url: Option<String>,
+    #[serde(rename = "MultiGeometry", skip_serializing_if = "Option::is_none")]
+    geometry: Option<MultiGeometryRef>,
+    #[serde(rename = "Point", skip_serializing_if = "Option::is_none")]
+    point: Option<PointRef>,
+}
+
+#[derive(Serialize)]
+struct MultiGeometryRef {
+    #[serde(rename = "LineString")]
+    line_strings: Vec<LineStringRef>,
+}
+
+#[derive(Serialize)]
+struct LineStringRef {
+    #[serde(rename = "altitudeMode")]
+    altitude_mode: &'static str,
+    #[serde(rename = "coordinates")]
+    coordinates: String,
+}
+
+#[derive(Serialize)]
+struct PointRef {
+    #[serde(rename = "coordinates")]
+    coordinates: String,
+}
+
+#[cfg(feature = "kmz")]
+pub use kmz::KmzWriter;
+
+#[cfg(feature = "kmz")]
+mod kmz {
+    use crate::gpx::{error::GpxError, parser::Gpx};
+    use std::io::{Seek, Write};
+    use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    /// Packs a document as KMZ: a zip archive with `doc.kml` plus optional resources
+    ///
+    /// Icons added with [`KmzWriter::icon`] are stored under `files/` and used by the
+    /// waypoints whose symbol matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{kml::KmzWriter, Gpx};
+    /// use std::io::Cursor;
+    ///
+    /// let archive = KmzWriter::new(Cursor::new(Vec::new()))
+    ///     .icon("Flag", "flag.png", vec![0x89, b'P', b'N', b'G'])
+    ///     .write(&Gpx::new())
+    ///     .unwrap()
+    ///     .into_inner();
+    /// assert!(archive.starts_with(b"PK"));
+    /// ```
+    pub struct KmzWriter<W: Write + Seek> {
+        writer: W,
+        icons: Vec<(String, String, Vec<u8>)>,
+    }
+
+    impl<W: Write + Seek> KmzWriter<W> {
+        /// Crea un escritor KMZ sin recursos adicionales
+        pub fn new(writer: W) -> Self {
+            Self {
+                writer,
+                icons: Vec::new(),
+            }
+        }
+
+        /// Adds an icon image for the waypoints whose symbol is `symbol`
+        #[must_use]
+        pub fn icon(mut self, symbol: &str, file_name: &str, bytes: Vec<u8>) -> Self {
+            self.icons
+                .push((symbol.to_string(), format!("files/{file_name}"), bytes));
+            self
+        }
+
+        /// Writes the archive and returns the underlying writer
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the KML cannot be serialized or the archive cannot
+        /// be written
+        pub fn write(self, gpx: &Gpx) -> Result<W, GpxError> {
+            let hrefs: Vec<(String, String)> = self
+                .icons
+                .iter()
+                .map(|(symbol, path, _)| (symbol.clone(), path.clone()))
+                .collect();
+            let kml = gpx.to_kml_with_icons(&hrefs)?;
+
+            let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+            let mut zip = ZipWriter::new(self.writer);
+            // Google Earth abre la primera entrada .kml del archivo
+            zip.start_file("doc.kml", options).map_err(zip_error)?;
+            zip.write_all(kml.as_bytes())?;
+            for (_, path, bytes) in &self.icons {
+                zip.start_file(path.as_str(), options).map_err(zip_error)?;
+                zip.write_all(bytes)?;
+            }
+            zip.finish().map_err(zip_error)
+        }
+    }
+
+    fn zip_error(e: zip::result::ZipError) -> GpxError {
+        match e {
+            zip::result::ZipError::Io(e) => GpxError::Io(e),
+            other => GpxError::Schema(format!("cannot write KMZ archive: {other}")),
+        }
+    }
+
+    impl Gpx {
+        /// Writes the document as a KMZ archive (zipped KML), see [`KmzWriter`]
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the KML cannot be serialized or the archive cannot
+        /// be written
+        pub fn to_kmz<W: Write + Seek>(&self, writer: W) -> Result<W, GpxError> {
+            KmzWriter::new(writer).write(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::extensions::Color;
+    use crate::GpxBuilder;
+
+    fn sample() -> Gpx {
+        let mut flag = Waypoint::with_name(42.1, 1.1, "Summit & hut".to_string());
+        flag.symbol = Some("Flag".to_string());
+        let mut gpx = GpxBuilder::new()
+            .track(|t| {
+                t.name("Day 1")
+                    .color(Color::rgb(0xFF, 0x80, 0x00))
+                    .segment(|s| {
+                        s.point_with_elevation(42.0, 1.0, 1500.0)
+                            .point_with_elevation(42.1, 1.1, 2100.0)
+                    })
+                    .segment(|s| s.point(42.2, 1.2))
+            })
+            .waypoint(flag)
+            .build()
+            .unwrap();
+        gpx.set_name("Pyrenees");
+        gpx
+    }
+
+    #[test]
+    fn test_to_kml_tracks_styles_and_placemarks() {
+        let kml = sample().to_kml().unwrap();
+
+        assert!(kml.contains(&format!(
+            "<kml xmlns=\"{KML_NAMESPACE}\"><Document><name>Pyrenees</name>"
+        )));
+        assert!(kml.contains("<Style id=\"track-0\"><LineStyle><color>ff0080ff</color>"));
+        assert!(kml.contains("<styleUrl>#track-0</styleUrl>"));
+        assert!(kml.contains(
+            "<LineString><altitudeMode>absolute</altitudeMode><coordinates>1,42,1500 1.1,42.1,2100</coordinates></LineString>"
+        ));
+        assert!(kml.contains(
+            "<altitudeMode>clampToGround</altitudeMode><coordinates>1.2,42.2</coordinates>"
+        ));
+        assert!(
+            kml.contains("<name>Summit &amp; hut</name><Point><coordinates>1.1,42.1</coordinates>")
+        );
+        // Sin recursos no se generan estilos de icono
+        assert!(!kml.contains("IconStyle"));
+    }
+
+    #[test]
+    fn test_registry_writes_kml() {
+        let registry = crate::gpx::formats::FormatRegistry::default();
+        let bytes = registry.write_as("kml", &sample()).unwrap();
+        assert_eq!(
+            crate::gpx::formats::detect(&bytes),
+            Some(crate::gpx::formats::Format::Kml)
+        );
+    }
+
+    #[cfg(feature = "kmz")]
+    #[test]
+    fn test_kmz_archive_with_icons() {
+        use std::io::{Cursor, Read};
+
+        let archive = KmzWriter::new(Cursor::new(Vec::new()))
+            .icon("Flag", "flag.png", b"png-bytes".to_vec())
+            .write(&sample())
+            .unwrap();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive.into_inner())).unwrap();
+        assert_eq!(zip.by_index(0).unwrap().name(), "doc.kml");
+
+        let mut kml = String::new();
+        zip.by_name("doc.kml")
+            .unwrap()
+            .read_to_string(&mut kml)
+            .unwrap();
+        assert!(kml.contains("<Style id=\"icon-0\"><IconStyle><Icon><href>files/flag.png</href>"));
+        assert!(kml.contains("<styleUrl>#icon-0</styleUrl>"));
+
+        let mut icon = Vec::new();
+        zip.by_name("files/flag.png")
+            .unwrap()
+            .read_to_end(&mut icon)
+            .unwrap();
+        assert_eq!(icon, b"png-bytes");
+
+        let plain = sample().to_kmz(Cursor::new(Vec::new())).unwrap();
+        assert!(plain.into_inner().starts_with(b"PK"));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::gpx::{
+    elevation::ElevationOptions,
+    parser::Gpx,
+    point::{haversine_distance, Point},
+};
+
+/// Default radius, in meters, used by [`LapStrategy::at_waypoints`] and [`LapStrategy::start_line`]
+pub const DEFAULT_LAP_RADIUS_M: f64 = 25.0;
+
+/// How [`Gpx::laps`] decides where a lap ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LapStrategy {
+    /// A new lap every given number of kilometers
+    Distance(f64),
+    /// A lap ends at the track point nearest to each waypoint, if it is within `radius_m`
+    Waypoints {
+        /// Farthest a waypoint can be from the track to end a lap, in meters
+        radius_m: f64,
+    },
+    /// A lap ends each time the track passes through a start/finish coordinate
+    StartLine {
+        /// Latitude of the start/finish line
+        lat: f64,
+        /// Longitude of the start/finish line
+        lon: f64,
+        /// Distance, in meters, within which the track counts as passing through it
+        radius_m: f64,
+    },
+}
+
+impl LapStrategy {
+    /// Una vuelta cada `km` kilómetros
+    pub fn every_km(km: f64) -> Self {
+        Self::Distance(km)
+    }
+
+    /// Una vuelta en cada waypoint a menos de [`DEFAULT_LAP_RADIUS_M`] del track
+    pub fn at_waypoints() -> Self {
+        Self::Waypoints {
+            radius_m: DEFAULT_LAP_RADIUS_M,
+        }
+    }
+
+    /// Una vuelta en cada paso a menos de [`DEFAULT_LAP_RADIUS_M`] de la línea de salida
+    pub fn start_line(lat: f64, lon: f64) -> Self {
+        Self::StartLine {
+            lat,
+            lon,
+            radius_m: DEFAULT_LAP_RADIUS_M,
+        }
+    }
+}
+
+/// One lap found by [`Gpx::laps`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lap {
+    /// Lap number, starting at 1
+    pub number: usize,
+    /// Index of the first point in [`Gpx::get_all_points`] order
+    pub start_index: usize,
+    /// Index of the last point in [`Gpx::get_all_points`] order; it also starts the next lap
+    pub end_index: usize,
+    /// Distance of the lap, in kilometers
+    pub distance_km: f64,
+    /// Time of the lap, in seconds; `None` without timestamps
+    pub duration_seconds: Option<i64>,
+    /// Average speed of the lap, in km/h; `None` without timestamps
+    pub avg_speed_kmh: Option<f64>,
+    /// Elevation gained during the lap, in meters
+    pub elevation_gain_m: Option<f64>,
+    /// Elevation lost during the lap, in meters
+    pub elevation_loss_m: Option<f64>,
+}
+
+/// Índices de los puntos donde termina cada vuelta, sin contar el primero ni el último
+fn boundaries(
+    gpx: &Gpx,
+    points: &[&Point],
+    distances: &[f64],
+    strategy: LapStrategy,
+) -> Vec<usize> {
+    let last = points.len() - 1;
+    let mut cuts = match strategy {
+        LapStrategy::Distance(km) if km > 0.0 => {
+            let mut cuts = Vec::new();
+            let mut next = km;
+            for (i, &distance) in distances.iter().enumerate() {
+                if distance >= next {
+                    cuts.push(i);
+                    // Un salto largo entre dos puntos puede cubrir varias vueltas
+                    while distance >= next {
+                        next += km;
+                    }
+                }
+            }
+            cuts
+        }
+        LapStrategy::Distance(_) => Vec::new(),
+        LapStrategy::Waypoints { radius_m } => gpx
+            .waypoints
+            .iter()
+            .filter_map(|waypoint| {
+                let target = Point::new(waypoint.lat, waypoint.lon);
+                points
+                    .iter()
+                    .map(|p| haversine_distance(p, &target) * 1000.0)
+                    .enumerate()
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .filter(|&(_, meters)| meters <= radius_m)
+                    .map(|(i, _)| i)
+            })
+            .collect(),
+        LapStrategy::StartLine { lat, lon, radius_m } => {
+            let line = Point::new(lat, lon);
+            let mut cuts = Vec::new();
+            // Cada paso es el punto más cercano de una racha dentro del radio
+            let mut pass: Option<(usize, f64)> = None;
+            for (i, point) in points.iter().enumerate() {
+                let meters = haversine_distance(point, &line) * 1000.0;
+                if meters <= radius_m {
+                    if pass.map_or(true, |(_, best)| meters < best) {
+                        pass = Some((i, meters));
+                    }
+                } else if let Some((closest, _)) = pass.take() {
+                    cuts.push(closest);
+                }
+            }
+            cuts.extend(pass.map(|(closest, _)| closest));
+            cuts
+        }
+    };
+    cuts.sort_unstable();
+    cuts.dedup();
+    cuts.retain(|&i| i > 0 && i < last);
+    cuts
+}
+
+impl Gpx {
+    /// Splits the recording into laps and computes time, distance, speed and
+    /// elevation for each one
+    ///
+    /// The point where one lap ends also starts the next. Points of all tracks
+    /// and segments are taken in order; gaps between segments do not add
+    /// distance. When the track starts or ends on the start/finish line, passing
+    /// through it there does not make an empty lap. Returns no laps if the
+    /// document has fewer than two points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, LapStrategy};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.000" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.005" lon="-3.0"><ele>610</ele><time>2024-07-01T08:02:00Z</time></trkpt>
+    ///     <trkpt lat="40.010" lon="-3.0"><ele>630</ele><time>2024-07-01T08:04:00Z</time></trkpt>
+    ///     <trkpt lat="40.015" lon="-3.0"><ele>620</ele><time>2024-07-01T08:07:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let laps = gpx.laps(LapStrategy::every_km(1.0));
+    /// assert_eq!(laps.len(), 2);
+    /// assert_eq!(laps[0].end_index, 2);
+    /// assert_eq!(laps[0].duration_seconds, Some(240));
+    /// assert_eq!(laps[0].elevation_gain_m, Some(30.0));
+    /// assert_eq!(laps[1].elevation_loss_m, Some(10.0));
+    /// ```
+    pub fn laps(&self, strategy: LapStrategy) -> Vec<Lap> {
+        let points = self.get_all_points();
+        if points.is_empty() {
+            return Vec::new();
+        }
+        let distances: Vec<f64> = self.point_metrics().iter().map(|m| m.distance_km).collect();
+
+        let mut edges = vec![0];
+        edges.extend(boundaries(self, &points, &distances, strategy));
+        edges.push(points.len() - 1);
+        edges.dedup();
+
+        edges
+            .windows(2)
+            .enumerate()
+            .map(|(n, pair)| {
+                let (start, end) = (pair[0], pair[1]);
+                let lap = &points[start..=end];
+                let distance_km = distances[end] - distances[start];
+                let duration_seconds = lap
+                    .iter()
+                    .find_map(|p| p.time)
+                    .zip(lap.iter().rev().find_map(|p| p.time))
+                    .map(|(first, last)| (last - first).num_seconds());
+                #[allow(clippy::cast_precision_loss)]
+                let avg_speed_kmh = duration_seconds
+                    .filter(|&seconds| seconds > 0)
+                    .map(|seconds| distance_km / (seconds as f64 / 3600.0));
+                let elevations: Vec<Option<f64>> = lap.iter().map(|p| p.elevation).collect();
+                let gain_loss = ElevationOptions::raw().gain_loss(&elevations);
+                Lap {
+                    number: n + 1,
+                    start_index: start,
+                    end_index: end,
+                    distance_km,
+                    duration_seconds,
+                    avg_speed_kmh,
+                    elevation_gain_m: gain_loss.map(|(gain, _)| gain),
+                    elevation_loss_m: gain_loss.map(|(_, loss)| loss),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{
+        track::{Track, TrackSegment},
+        waypoint::Waypoint,
+    };
+
+    /// Tres vueltas a un circuito cuadrado de ~400 m que empieza en (40.0, -3.0)
+    fn circuit() -> Gpx {
+        let square = [
+            (40.0, -3.0),
+            (40.0009, -3.0),
+            (40.0009, -2.9988),
+            (40.0, -2.9988),
+        ];
+        let mut coords = Vec::new();
+        for _ in 0..3 {
+            coords.extend_from_slice(&square);
+        }
+        coords.push((40.0, -3.0));
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::from_latlon_pairs(&coords));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_start_line_laps() {
+        let gpx = circuit();
+        let laps = gpx.laps(LapStrategy::start_line(40.0, -3.0));
+        assert_eq!(laps.len(), 3);
+        assert_eq!(
+            laps.iter()
+                .map(|l| (l.start_index, l.end_index))
+                .collect::<Vec<_>>(),
+            vec![(0, 4), (4, 8), (8, 12)]
+        );
+        assert!((laps[1].distance_km - laps[0].distance_km).abs() < 1e-9);
+        assert_eq!(laps[2].number, 3);
+        assert_eq!(laps[0].duration_seconds, None);
+        assert_eq!(laps[0].elevation_gain_m, None);
+    }
+
+    #[test]
+    fn test_waypoint_laps_ignore_far_waypoints() {
+        let mut gpx = circuit();
+        gpx.add_waypoint(Waypoint::new(40.0009, -2.99881));
+        gpx.add_waypoint(Waypoint::new(41.0, -3.0));
+        let laps = gpx.laps(LapStrategy::at_waypoints());
+        // El waypoint cercano cae en la primera pasada por la esquina
+        assert_eq!(laps.len(), 2);
+        assert_eq!(laps[0].end_index, 2);
+
+        assert_eq!(gpx.laps(LapStrategy::every_km(0.0)).len(), 1);
+        assert!(Gpx::new().laps(LapStrategy::every_km(1.0)).is_empty());
+    }
+}
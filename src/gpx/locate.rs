@@ -0,0 +1,117 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, interpolate, Point},
+    track::Track,
+};
+
+/// Margen, en kilómetros, para aceptar la distancia total pese al redondeo
+const DISTANCE_EPSILON_KM: f64 = 1e-9;
+
+impl Gpx {
+    /// First point of the first track that has any, skipping empty tracks and segments
+    pub fn start_point(&self) -> Option<&Point> {
+        self.tracks.iter().flat_map(Track::points).next()
+    }
+
+    /// Last point of the last track that has any, skipping empty tracks and segments
+    pub fn end_point(&self) -> Option<&Point> {
+        self.tracks.iter().flat_map(Track::points).next_back()
+    }
+}
+
+impl Track {
+    /// Position `km` kilometers along the track, interpolated between the recorded points
+    ///
+    /// Distances add up segment after segment as in [`Track::total_distance_km`],
+    /// so the gap between two segments is not travelled: a distance falling
+    /// exactly on a segment boundary returns the last point of the earlier
+    /// segment. Elevation and time are interpolated as well. Returns `None` for
+    /// negative distances, distances past the end and tracks without points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[(0.0, 0.0), (0.0, 0.1)]));
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[(0.0, 0.5), (0.0, 0.6)]));
+    ///
+    /// // Cada segmento mide unos 11,1 km
+    /// let point = track.point_at_distance(13.0).unwrap();
+    /// assert!(point.lon > 0.5 && point.lon < 0.6);
+    /// assert!(track.point_at_distance(30.0).is_none());
+    ///
+    /// // La mitad cae en el final del primer segmento
+    /// let middle = track.midpoint().unwrap();
+    /// assert!((middle.lon - 0.1).abs() < 1e-9);
+    /// ```
+    pub fn point_at_distance(&self, km: f64) -> Option<Point> {
+        if km < 0.0 {
+            return None;
+        }
+        let mut travelled = 0.0;
+        let mut last = None;
+        for segment in &self.segments {
+            let Some(first) = segment.points.first() else {
+                continue;
+            };
+            if km <= travelled {
+                return Some(first.clone());
+            }
+            for pair in segment.points.windows(2) {
+                let step = haversine_distance(&pair[0], &pair[1]);
+                if km <= travelled + step {
+                    return Some(interpolate(&pair[0], &pair[1], (km - travelled) / step));
+                }
+                travelled += step;
+            }
+            last = segment.points.last();
+        }
+        last.filter(|_| km - travelled <= DISTANCE_EPSILON_KM)
+            .cloned()
+    }
+
+    /// Position halfway along the track (see [`Track::point_at_distance`])
+    pub fn midpoint(&self) -> Option<Point> {
+        self.point_at_distance(self.total_distance_km() / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::TrackSegment;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_point_at_distance_interpolates_and_skips_empty_segments() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::new());
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(0.0, 0.0, Some(100.0), start),
+            Point::with_time(0.0, 0.1, Some(200.0), start + Duration::minutes(40)),
+        ]));
+        track.add_segment(TrackSegment::with_points(vec![Point::new(5.0, 5.0)]));
+
+        let total = track.total_distance_km();
+        let quarter = track.point_at_distance(total / 4.0).unwrap();
+        assert!((quarter.lon - 0.025).abs() < 1e-9);
+        assert_eq!(quarter.elevation, Some(125.0));
+        assert_eq!(quarter.time, Some(start + Duration::minutes(10)));
+
+        // El final del primer segmento con puntos, no el punto suelto del siguiente
+        assert_eq!(track.point_at_distance(total).unwrap().lon, 0.1);
+        assert_eq!(track.point_at_distance(0.0).unwrap().lon, 0.0);
+        assert!(track.point_at_distance(-1.0).is_none());
+        assert!(Track::new().midpoint().is_none());
+
+        let mut gpx = Gpx::new();
+        gpx.add_track(Track::new());
+        gpx.add_track(track);
+        assert_eq!(gpx.start_point().map(|p| p.lon), Some(0.0));
+        assert_eq!(gpx.end_point().map(|p| p.lat), Some(5.0));
+        assert!(Gpx::new().start_point().is_none());
+    }
+}
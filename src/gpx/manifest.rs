@@ -0,0 +1,438 @@
+use crate::gpx::{bounds::Bounds, collection::GpxCollection, error::GpxError, parser::start_time};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const CSV_HEADER: &str = "file,sha256,start_time,distance_km,min_lat,min_lon,max_lat,max_lon";
+
+/// One file recorded in a [`Manifest`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    /// Path of the file, relative to the collection directory
+    pub file: PathBuf,
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+    /// Timestamp of the first timed track point
+    pub start_time: Option<DateTime<Utc>>,
+    /// Total track distance in kilometers
+    pub distance_km: f64,
+    /// Bounding box of the document
+    pub bounds: Option<Bounds>,
+}
+
+/// Integrity manifest of an archive of GPX files
+///
+/// Created with [`GpxCollection::manifest`] and checked later with
+/// [`GpxCollection::verify_manifest`] to detect files that changed, disappeared
+/// or were added since. Manifests can be stored as JSON or CSV.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    /// Recorded files, in collection order
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// Representación plana de una entrada, compartida por JSON y CSV
+#[derive(Serialize, Deserialize)]
+struct ManifestRecord {
+    file: String,
+    sha256: String,
+    start_time: Option<DateTime<Utc>>,
+    distance_km: f64,
+    min_lat: Option<f64>,
+    min_lon: Option<f64>,
+    max_lat: Option<f64>,
+    max_lon: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestDocument {
+    files: Vec<ManifestRecord>,
+}
+
+impl From<&ManifestEntry> for ManifestRecord {
+    fn from(entry: &ManifestEntry) -> Self {
+        Self {
+            file: entry.file.to_string_lossy().into_owned(),
+            sha256: entry.sha256.clone(),
+            start_time: entry.start_time,
+            distance_km: entry.distance_km,
+            min_lat: entry.bounds.map(|b| b.min_lat),
+            min_lon: entry.bounds.map(|b| b.min_lon),
+            max_lat: entry.bounds.map(|b| b.max_lat),
+            max_lon: entry.bounds.map(|b| b.max_lon),
+        }
+    }
+}
+
+impl From<ManifestRecord> for ManifestEntry {
+    fn from(record: ManifestRecord) -> Self {
+        let bounds = match (
+            record.min_lat,
+            record.min_lon,
+            record.max_lat,
+            record.max_lon,
+        ) {
+            (Some(min_lat), Some(min_lon), Some(max_lat), Some(max_lon)) => Some(Bounds {
+                min_lat,
+                min_lon,
+                max_lat,
+                max_lon,
+            }),
+            _ => None,
+        };
+
+        Self {
+            file: PathBuf::from(record.file),
+            sha256: record.sha256,
+            start_time: record.start_time,
+            distance_km: record.distance_km,
+            bounds,
+        }
+    }
+}
+
+impl Manifest {
+    /// Serializes the manifest as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        let document = ManifestDocument {
+            files: self.entries.iter().map(ManifestRecord::from).collect(),
+        };
+        // Solo contiene strings, números y fechas: no puede fallar
+        serde_json::to_string_pretty(&document).unwrap_or_default()
+    }
+
+    /// Parses a manifest written by [`Manifest::to_json`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidManifest`] if the JSON is malformed
+    pub fn from_json(json: &str) -> Result<Self, GpxError> {
+        let document: ManifestDocument =
+            serde_json::from_str(json).map_err(|e| GpxError::InvalidManifest(e.to_string()))?;
+        Ok(Self {
+            entries: document
+                .files
+                .into_iter()
+                .map(ManifestEntry::from)
+                .collect(),
+        })
+    }
+
+    /// Serializes the manifest as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        for entry in &self.entries {
+            let record = ManifestRecord::from(entry);
+            let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+            let fields = [
+                csv_quote(&record.file),
+                record.sha256,
+                record
+                    .start_time
+                    .map(|time| time.to_rfc3339())
+                    .unwrap_or_default(),
+                record.distance_km.to_string(),
+                optional(record.min_lat),
+                optional(record.min_lon),
+                optional(record.max_lat),
+                optional(record.max_lon),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Parses a manifest written by [`Manifest::to_csv`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidManifest`] if the header or a row is malformed
+    pub fn from_csv(csv: &str) -> Result<Self, GpxError> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some(CSV_HEADER) {
+            return Err(GpxError::InvalidManifest(
+                "missing or unexpected CSV header".to_string(),
+            ));
+        }
+
+        let entries = lines
+            .enumerate()
+            .map(|(i, line)| {
+                parse_csv_row(line)
+                    .map(ManifestEntry::from)
+                    .map_err(|msg| GpxError::InvalidManifest(format!("row {}: {msg}", i + 1)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { entries })
+    }
+}
+
+fn csv_quote(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn parse_csv_row(line: &str) -> Result<ManifestRecord, String> {
+    let fields = split_csv_line(line)?;
+    let [file, sha256, start_time, distance_km, min_lat, min_lon, max_lat, max_lon] =
+        <[String; 8]>::try_from(fields)
+            .map_err(|fields| format!("expected 8 fields, got {}", fields.len()))?;
+
+    let optional = |value: &str| -> Result<Option<f64>, String> {
+        if value.is_empty() {
+            Ok(None)
+        } else {
+            value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid number '{value}'"))
+        }
+    };
+
+    Ok(ManifestRecord {
+        file,
+        sha256,
+        start_time: if start_time.is_empty() {
+            None
+        } else {
+            Some(
+                DateTime::parse_from_rfc3339(&start_time)
+                    .map_err(|e| format!("invalid start_time: {e}"))?
+                    .with_timezone(&Utc),
+            )
+        },
+        distance_km: distance_km
+            .parse()
+            .map_err(|_| format!("invalid distance '{distance_km}'"))?,
+        min_lat: optional(&min_lat)?,
+        min_lon: optional(&min_lon)?,
+        max_lat: optional(&max_lat)?,
+        max_lon: optional(&max_lon)?,
+    })
+}
+
+/// Result of [`GpxCollection::verify_manifest`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestCheck {
+    /// Files whose contents match the manifest
+    pub unchanged: Vec<PathBuf>,
+    /// Files whose contents differ from the manifest
+    pub changed: Vec<PathBuf>,
+    /// Files listed in the manifest that no longer exist
+    pub missing: Vec<PathBuf>,
+    /// Files in the collection that the manifest does not list
+    pub untracked: Vec<PathBuf>,
+}
+
+impl ManifestCheck {
+    /// Verifica que no haya archivos modificados, desaparecidos ni nuevos
+    pub fn is_clean(&self) -> bool {
+        self.changed.is_empty() && self.missing.is_empty() && self.untracked.is_empty()
+    }
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+impl GpxCollection {
+    /// Path relative to the directory the collection was loaded from
+    fn relative_path<'p>(&self, path: &'p Path) -> &'p Path {
+        self.root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+    }
+
+    fn resolve_path(&self, file: &Path) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(file),
+            None => file.to_path_buf(),
+        }
+    }
+
+    /// Builds an integrity manifest of the loaded files
+    ///
+    /// Files are re-read from disk to hash their exact contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file can no longer be read
+    pub fn manifest(&self) -> Result<Manifest, GpxError> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let bytes = std::fs::read(&entry.path)?;
+                Ok(ManifestEntry {
+                    file: self.relative_path(&entry.path).to_path_buf(),
+                    sha256: sha256_hex(&bytes),
+                    start_time: start_time(&entry.gpx),
+                    distance_km: entry.gpx.total_distance_km(),
+                    bounds: entry.gpx.bounds(),
+                })
+            })
+            .collect::<Result<_, GpxError>>()?;
+        Ok(Manifest { entries })
+    }
+
+    /// Compares the files on disk against a previously generated manifest
+    ///
+    /// Manifest paths are resolved against the collection directory. Files of
+    /// this collection not listed in the manifest, including those that failed
+    /// to load, are reported as untracked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a listed file exists but cannot be read
+    pub fn verify_manifest(&self, manifest: &Manifest) -> Result<ManifestCheck, GpxError> {
+        let mut check = ManifestCheck::default();
+
+        for entry in &manifest.entries {
+            match std::fs::read(self.resolve_path(&entry.file)) {
+                Ok(bytes) if sha256_hex(&bytes) == entry.sha256 => {
+                    check.unchanged.push(entry.file.clone());
+                }
+                Ok(_) => check.changed.push(entry.file.clone()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    check.missing.push(entry.file.clone());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let listed: HashSet<&Path> = manifest.entries.iter().map(|e| e.file.as_path()).collect();
+        check.untracked = self
+            .entries
+            .iter()
+            .map(|entry| entry.path.as_path())
+            .chain(self.failures.iter().map(|(path, _)| path.as_path()))
+            .map(|path| self.relative_path(path))
+            .filter(|path| !listed.contains(path))
+            .map(Path::to_path_buf)
+            .collect();
+
+        Ok(check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACK: &str = r#"<gpx version="1.1" creator="test"><trk><name>A</name><trkseg>
+        <trkpt lat="40.0" lon="-3.0"><time>2024-07-11T10:00:00Z</time></trkpt>
+        <trkpt lat="40.1" lon="-3.1"><time>2024-07-11T10:30:00Z</time></trkpt>
+        </trkseg></trk></gpx>"#;
+
+    fn archive() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.gpx"), TRACK).unwrap();
+        std::fs::write(dir.path().join("b, old.gpx"), "<gpx></gpx>").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_manifest_contents() {
+        let dir = archive();
+        let manifest = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .manifest()
+            .unwrap();
+
+        assert_eq!(manifest.entries.len(), 2);
+        let entry = &manifest.entries[0];
+        assert_eq!(entry.file, PathBuf::from("a.gpx"));
+        assert_eq!(entry.sha256, sha256_hex(TRACK.as_bytes()));
+        assert_eq!(entry.sha256.len(), 64);
+        assert_eq!(
+            entry.start_time.unwrap().to_rfc3339(),
+            "2024-07-11T10:00:00+00:00"
+        );
+        assert!(entry.distance_km > 13.0);
+        assert_eq!(entry.bounds.unwrap().max_lat, 40.1);
+        assert!(manifest.entries[1].bounds.is_none());
+    }
+
+    #[test]
+    fn test_manifest_json_and_csv_roundtrip() {
+        let dir = archive();
+        let manifest = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .manifest()
+            .unwrap();
+
+        assert_eq!(Manifest::from_json(&manifest.to_json()).unwrap(), manifest);
+
+        let csv = manifest.to_csv();
+        assert!(csv.contains("\"b, old.gpx\""));
+        assert_eq!(Manifest::from_csv(&csv).unwrap(), manifest);
+
+        assert!(matches!(
+            Manifest::from_json("{"),
+            Err(GpxError::InvalidManifest(_))
+        ));
+        assert!(Manifest::from_csv("file,sha256\n").is_err());
+        assert!(Manifest::from_csv(&format!("{CSV_HEADER}\na.gpx,abc\n")).is_err());
+    }
+
+    #[test]
+    fn test_verify_manifest_detects_changes() {
+        let dir = archive();
+        let manifest = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .manifest()
+            .unwrap();
+
+        let clean = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .verify_manifest(&manifest)
+            .unwrap();
+        assert!(clean.is_clean());
+        assert_eq!(clean.unchanged.len(), 2);
+
+        std::fs::write(dir.path().join("a.gpx"), TRACK.replace("40.1", "40.2")).unwrap();
+        std::fs::remove_file(dir.path().join("b, old.gpx")).unwrap();
+        std::fs::write(dir.path().join("c.gpx"), "<gpx></gpx>").unwrap();
+
+        let check = GpxCollection::from_dir(dir.path())
+            .unwrap()
+            .verify_manifest(&manifest)
+            .unwrap();
+        assert!(!check.is_clean());
+        assert_eq!(check.changed, vec![PathBuf::from("a.gpx")]);
+        assert_eq!(check.missing, vec![PathBuf::from("b, old.gpx")]);
+        assert_eq!(check.untracked, vec![PathBuf::from("c.gpx")]);
+    }
+}
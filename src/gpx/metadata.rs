@@ -0,0 +1,416 @@
+use crate::gpx::{bounds::Bounds, parser::Gpx, unknown::UnknownXml};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A link to an external resource (`<link>`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Link {
+    /// URL of the resource
+    #[serde(rename = "@href")]
+    pub href: String,
+    /// Text shown for the link
+    #[serde(rename = "text", skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// MIME type of the content (e.g. `"image/jpeg"`)
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl Link {
+    /// Crea un enlace sin texto ni tipo
+    pub fn new(href: impl Into<String>) -> Self {
+        Self {
+            href: href.into(),
+            text: None,
+            mime_type: None,
+        }
+    }
+
+    /// Crea un enlace con texto descriptivo
+    pub fn with_text(href: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            text: Some(text.into()),
+            ..Self::new(href)
+        }
+    }
+}
+
+/// A person or organization (`<author>`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "RawPerson", into = "RawPerson")]
+pub struct Person {
+    /// Name of the person or organization
+    pub name: Option<String>,
+    /// Email address, as `id@domain`
+    pub email: Option<String>,
+    /// Link to a web site or other information about the person
+    pub link: Option<Link>,
+}
+
+impl Person {
+    /// Crea una persona solo con nombre
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the email address
+    #[must_use]
+    pub fn with_email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Sets the link
+    #[must_use]
+    pub fn with_link(mut self, link: Link) -> Self {
+        self.link = Some(link);
+        self
+    }
+}
+
+impl From<&str> for Person {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Person {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+// GPX 1.1 guarda el email partido en atributos: <email id="juanjo" domain="example.com"/>
+#[derive(Deserialize, Serialize)]
+struct RawEmail {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "@domain")]
+    domain: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct RawPerson {
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(rename = "email", skip_serializing_if = "Option::is_none")]
+    email: Option<RawEmail>,
+    #[serde(rename = "link", skip_serializing_if = "Option::is_none")]
+    link: Option<Link>,
+}
+
+impl From<RawPerson> for Person {
+    fn from(raw: RawPerson) -> Self {
+        Self {
+            name: raw.name,
+            email: raw
+                .email
+                .map(|email| format!("{}@{}", email.id, email.domain)),
+            link: raw.link,
+        }
+    }
+}
+
+impl From<Person> for RawPerson {
+    fn from(person: Person) -> Self {
+        Self {
+            name: person.name,
+            email: person.email.map(|email| match email.split_once('@') {
+                Some((id, domain)) => RawEmail {
+                    id: id.to_string(),
+                    domain: domain.to_string(),
+                },
+                None => RawEmail {
+                    id: email,
+                    domain: String::new(),
+                },
+            }),
+            link: person.link,
+        }
+    }
+}
+
+/// Copyright and license information (`<copyright>`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Copyright {
+    /// Copyright holder
+    #[serde(rename = "@author")]
+    pub author: String,
+    /// Year of the copyright
+    #[serde(rename = "year", skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    /// URL of the license
+    #[serde(rename = "license", skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+impl Copyright {
+    /// Crea un copyright solo con el titular
+    pub fn new(author: impl Into<String>) -> Self {
+        Self {
+            author: author.into(),
+            year: None,
+            license: None,
+        }
+    }
+}
+
+/// GPX metadata (`<metadata>`): name, description, author, links, time, keywords and bounds
+///
+/// Fields are declared in the order required by the GPX 1.1 schema.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Metadata {
+    /// Name of the document
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Description of the document
+    #[serde(rename = "desc", skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Person or organization who created the document
+    #[serde(rename = "author", skip_serializing_if = "Option::is_none")]
+    pub author: Option<Person>,
+    /// Copyright and license information
+    #[serde(rename = "copyright", skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<Copyright>,
+    /// Links to external resources
+    #[serde(rename = "link", default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+    /// Timestamp of when the GPX file was created
+    #[serde(rename = "time", skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Comma-separated keywords, as stored in the file
+    #[serde(rename = "keywords", skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+    /// Bounding box of the document
+    #[serde(
+        rename = "bounds",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_bounds",
+        deserialize_with = "deserialize_bounds"
+    )]
+    pub bounds: Option<Bounds>,
+    /// Child elements not modeled by this crate (`<extensions>`), written back as read
+    #[serde(
+        rename = "gpx_extractor_raw",
+        skip_deserializing,
+        skip_serializing_if = "UnknownXml::is_empty"
+    )]
+    pub unknown: UnknownXml,
+}
+
+impl Metadata {
+    /// Individual keywords, trimmed and without empty entries
+    pub fn keyword_list(&self) -> Vec<&str> {
+        self.keywords
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+            .collect()
+    }
+
+    /// Replaces the keywords with the given list
+    pub fn set_keywords<I, S>(&mut self, keywords: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let joined = keywords
+            .into_iter()
+            .map(|keyword| keyword.as_ref().trim().to_string())
+            .filter(|keyword| !keyword.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.keywords = (!joined.is_empty()).then_some(joined);
+    }
+
+    /// Agrega una palabra clave si no estaba ya presente
+    pub fn add_keyword(&mut self, keyword: &str) {
+        if !self.keyword_list().contains(&keyword.trim()) {
+            let mut keywords: Vec<String> =
+                self.keyword_list().into_iter().map(String::from).collect();
+            keywords.push(keyword.to_string());
+            self.set_keywords(keywords);
+        }
+    }
+}
+
+/// `<bounds minlat=".." minlon=".." maxlat=".." maxlon=".."/>`
+#[derive(Deserialize, Serialize)]
+pub(crate) struct RawBounds {
+    #[serde(rename = "@minlat")]
+    min_lat: f64,
+    #[serde(rename = "@minlon")]
+    min_lon: f64,
+    #[serde(rename = "@maxlat")]
+    max_lat: f64,
+    #[serde(rename = "@maxlon")]
+    max_lon: f64,
+}
+
+impl From<Bounds> for RawBounds {
+    fn from(bounds: Bounds) -> Self {
+        Self {
+            min_lat: bounds.min_lat,
+            min_lon: bounds.min_lon,
+            max_lat: bounds.max_lat,
+            max_lon: bounds.max_lon,
+        }
+    }
+}
+
+impl From<RawBounds> for Bounds {
+    fn from(raw: RawBounds) -> Self {
+        Self {
+            min_lat: raw.min_lat,
+            min_lon: raw.min_lon,
+            max_lat: raw.max_lat,
+            max_lon: raw.max_lon,
+        }
+    }
+}
+
+// Firma impuesta por `serialize_with`
+#[allow(clippy::ref_option, clippy::trivially_copy_pass_by_ref)]
+pub(crate) fn serialize_bounds<S: Serializer>(
+    bounds: &Option<Bounds>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    bounds.map(RawBounds::from).serialize(serializer)
+}
+
+pub(crate) fn deserialize_bounds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Bounds>, D::Error> {
+    Ok(Option::<RawBounds>::deserialize(deserializer)?.map(Bounds::from))
+}
+
+impl Gpx {
+    /// Metadata of the document, created empty if missing
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        self.metadata.get_or_insert_with(Metadata::default)
+    }
+
+    /// Sets the document name
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.metadata_mut().name = Some(name.into());
+    }
+
+    /// Sets the document description
+    pub fn set_description(&mut self, description: impl Into<String>) {
+        self.metadata_mut().description = Some(description.into());
+    }
+
+    /// Sets the author, either a full [`Person`] or just a name
+    pub fn set_author(&mut self, author: impl Into<Person>) {
+        self.metadata_mut().author = Some(author.into());
+    }
+
+    /// Sets the copyright information
+    pub fn set_copyright(&mut self, copyright: Copyright) {
+        self.metadata_mut().copyright = Some(copyright);
+    }
+
+    /// Adds a link to the metadata
+    pub fn add_link(&mut self, link: Link) {
+        self.metadata_mut().links.push(link);
+    }
+
+    /// Replaces the metadata keywords
+    pub fn set_keywords<I, S>(&mut self, keywords: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.metadata_mut().set_keywords(keywords);
+    }
+
+    /// Stores the current bounding box of the document in the metadata
+    pub fn update_metadata_bounds(&mut self) {
+        let bounds = self.bounds();
+        self.metadata_mut().bounds = bounds;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_METADATA: &str = r#"<gpx version="1.1" creator="test">
+      <metadata>
+        <name>Alps 2024</name>
+        <desc>Summer traverse</desc>
+        <author>
+          <name>Juanjo</name>
+          <email id="juanjo" domain="example.com"/>
+          <link href="https://example.com"><text>Blog</text></link>
+        </author>
+        <copyright author="Juanjo"><year>2024</year><license>https://creativecommons.org/licenses/by/4.0/</license></copyright>
+        <link href="https://example.com/alps"><text>Trip report</text><type>text/html</type></link>
+        <link href="https://example.com/photo.jpg"/>
+        <time>2024-07-11T09:00:00Z</time>
+        <keywords>alps, hiking,  summer</keywords>
+        <bounds minlat="45.9" minlon="6.8" maxlat="46.1" maxlon="7.2"/>
+      </metadata>
+    </gpx>"#;
+
+    #[test]
+    fn test_parse_full_metadata() {
+        let gpx = Gpx::try_from_str(FULL_METADATA).unwrap();
+        let metadata = gpx.metadata.as_ref().unwrap();
+
+        assert_eq!(metadata.name.as_deref(), Some("Alps 2024"));
+        assert_eq!(metadata.description.as_deref(), Some("Summer traverse"));
+        let author = metadata.author.as_ref().unwrap();
+        assert_eq!(author.name.as_deref(), Some("Juanjo"));
+        assert_eq!(author.email.as_deref(), Some("juanjo@example.com"));
+        assert_eq!(author.link.as_ref().unwrap().text.as_deref(), Some("Blog"));
+        let copyright = metadata.copyright.as_ref().unwrap();
+        assert_eq!(copyright.author, "Juanjo");
+        assert_eq!(copyright.year, Some(2024));
+        assert_eq!(metadata.links.len(), 2);
+        assert_eq!(metadata.links[0].mime_type.as_deref(), Some("text/html"));
+        assert_eq!(gpx.date(), Some("2024-07-11T09:00:00Z"));
+        assert_eq!(metadata.keyword_list(), vec!["alps", "hiking", "summer"]);
+        assert_eq!(metadata.bounds.unwrap().max_lon, 7.2);
+    }
+
+    #[test]
+    fn test_full_metadata_roundtrip() {
+        let gpx = Gpx::try_from_str(FULL_METADATA).unwrap();
+        let xml = gpx.to_xml();
+        assert!(xml.contains(r#"<email id="juanjo" domain="example.com"/>"#));
+        assert!(xml.contains(r#"<bounds minlat="45.9" minlon="6.8" maxlat="46.1" maxlon="7.2"/>"#));
+
+        let reparsed = Gpx::try_from_str(&xml).unwrap();
+        assert_eq!(reparsed.metadata, gpx.metadata);
+    }
+
+    #[test]
+    fn test_setters() {
+        let mut gpx = crate::GpxBuilder::new()
+            .track(|t| t.segment(|s| s.point(40.0, -3.0).point(40.5, -3.5)))
+            .build()
+            .unwrap();
+        gpx.set_name("Ride");
+        gpx.set_description("Evening loop");
+        gpx.set_author(Person::new("Ana").with_email("ana@example.org"));
+        gpx.set_copyright(Copyright::new("Ana"));
+        gpx.add_link(Link::with_text("https://example.org", "Home"));
+        gpx.set_keywords(["bike", " road ", ""]);
+        gpx.metadata_mut().add_keyword("bike");
+        gpx.metadata_mut().add_keyword("2024");
+        gpx.update_metadata_bounds();
+
+        let metadata = gpx.metadata.as_ref().unwrap();
+        assert_eq!(metadata.keywords.as_deref(), Some("bike, road, 2024"));
+        assert_eq!(metadata.bounds.unwrap().min_lon, -3.5);
+
+        let reparsed = Gpx::try_from_str(&gpx.to_xml()).unwrap();
+        assert_eq!(reparsed.metadata, gpx.metadata);
+    }
+}
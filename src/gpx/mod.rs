@@ -1,5 +1,80 @@
 // Módulos del paquete GPX
+pub mod aggregate;
+pub mod analysis;
+#[cfg(feature = "async")]
+mod async_io;
+pub mod bounds;
+pub mod builder;
+pub mod climbs;
+pub mod cluster;
+pub mod collection;
+pub mod compare;
+pub mod crop;
+pub mod csv;
+pub mod cues;
+pub mod dem;
+pub mod distance;
+pub mod elevation;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+pub mod error;
+pub mod eta;
+pub mod extensions;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fingerprint;
+#[cfg(feature = "fit")]
+pub mod fit;
+pub mod fitness;
+pub mod formats;
+pub mod geocode;
+pub mod geofence;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+pub mod geotag;
+pub mod goals;
+pub mod heal;
+#[cfg(feature = "manifest")]
+pub mod index;
+#[cfg(feature = "serde")]
+mod json;
+pub mod kml;
+pub mod laps;
+pub mod locate;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+pub mod metadata;
+pub mod outliers;
+pub mod pace;
 pub mod parser;
 pub mod point;
+pub mod polyline;
+pub mod privacy;
+pub mod quality;
+pub mod report;
+pub mod resample;
+pub mod route;
+#[cfg(feature = "s3")]
+pub mod s3;
+pub mod series;
+#[cfg(feature = "signature")]
+pub mod signature;
+pub mod simplify;
+pub mod snapshot;
+pub mod split;
+pub mod sport;
+pub mod statistics;
+pub mod stops;
+pub mod store;
+pub mod stream;
+pub mod stream_writer;
+pub mod tcx;
+pub mod template;
+pub mod tiles;
 pub mod track;
+pub mod units;
+pub mod unknown;
+pub mod user_data;
+pub mod validate;
 pub mod waypoint;
+pub mod write;
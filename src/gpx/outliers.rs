@@ -0,0 +1,546 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, interpolate, Point},
+    template::{NameTemplate, TemplateValues},
+    track::TrackSegment,
+    waypoint::Waypoint,
+};
+use std::fmt;
+
+/// Valor de `<type>` de los waypoints añadidos por [`Gpx::flag_anomalies`]
+pub const ANOMALY_WAYPOINT_TYPE: &str = "GPS anomaly";
+
+/// What [`Gpx::remove_outliers`] does with the points it flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutlierAction {
+    /// Drop the points from their segment
+    #[default]
+    Remove,
+    /// Move the points onto the line between their valid neighbours, keeping
+    /// timestamps and sensor data; points without a valid neighbour on both sides
+    /// are removed
+    Interpolate,
+}
+
+/// Thresholds for [`Gpx::remove_outliers`]
+///
+/// Speed and acceleration checks need timestamps; points without them are only
+/// checked for elevation spikes. Set a threshold to `f64::INFINITY` to disable it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlierOptions {
+    /// Highest plausible speed between two points, in km/h
+    pub max_speed_kmh: f64,
+    /// Highest plausible change of speed, in m/s²
+    pub max_acceleration_mps2: f64,
+    /// Largest jump, in meters, a single point may show above or below both neighbours
+    pub max_elevation_spike_m: f64,
+    /// What to do with the flagged points
+    pub action: OutlierAction,
+}
+
+impl Default for OutlierOptions {
+    fn default() -> Self {
+        Self {
+            max_speed_kmh: 150.0,
+            max_acceleration_mps2: 10.0,
+            max_elevation_spike_m: 50.0,
+            action: OutlierAction::Remove,
+        }
+    }
+}
+
+/// Why a point was flagged by [`Gpx::remove_outliers`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutlierReason {
+    /// Reaching it would need a speed above `max_speed_kmh`
+    Speed,
+    /// Reaching it would need an acceleration above `max_acceleration_mps2`
+    Acceleration,
+    /// Its elevation jumps away from both neighbours by more than `max_elevation_spike_m`
+    ElevationSpike,
+}
+
+/// A point flagged by [`Gpx::remove_outliers`]
+#[derive(Debug, Clone)]
+pub struct Outlier {
+    /// Index of the point in the original [`Gpx::get_all_points`] order
+    pub index: usize,
+    /// Reason it was flagged
+    pub reason: OutlierReason,
+    /// Whether it was interpolated (`true`) or removed (`false`)
+    pub interpolated: bool,
+    /// The point as it was before the correction
+    pub original: Point,
+}
+
+/// Points flagged by [`Gpx::remove_outliers`], in document order
+#[derive(Debug, Clone, Default)]
+pub struct OutlierReport {
+    /// Flagged points
+    pub outliers: Vec<Outlier>,
+}
+
+impl OutlierReport {
+    /// Número de puntos marcados
+    pub fn len(&self) -> usize {
+        self.outliers.len()
+    }
+
+    /// Verifica si no se marcó ningún punto
+    pub fn is_empty(&self) -> bool {
+        self.outliers.is_empty()
+    }
+
+    /// Número de puntos marcados por un motivo concreto
+    pub fn count(&self, reason: OutlierReason) -> usize {
+        self.outliers.iter().filter(|o| o.reason == reason).count()
+    }
+}
+
+/// Segundos entre dos puntos, si ambos tienen tiempo y el segundo es posterior
+fn seconds_between(a: &Point, b: &Point) -> Option<f64> {
+    let (from, to) = (a.time?, b.time?);
+    #[allow(clippy::cast_precision_loss)]
+    let seconds = (to - from).num_milliseconds() as f64 / 1000.0;
+    (seconds > 0.0).then_some(seconds)
+}
+
+/// Marca los puntos anómalos de un segmento
+fn classify(points: &[Point], options: OutlierOptions) -> Vec<Option<OutlierReason>> {
+    let mut flags = vec![None; points.len()];
+
+    // Picos de elevación: un punto aislado muy por encima o por debajo de sus vecinos
+    for i in 1..points.len().saturating_sub(1) {
+        if let (Some(prev), Some(ele), Some(next)) = (
+            points[i - 1].elevation,
+            points[i].elevation,
+            points[i + 1].elevation,
+        ) {
+            let (up, down) = (ele - prev, ele - next);
+            if up.signum() == down.signum()
+                && up.abs().min(down.abs()) > options.max_elevation_spike_m
+            {
+                flags[i] = Some(OutlierReason::ElevationSpike);
+            }
+        }
+    }
+
+    // Velocidad y aceleración respecto al último punto válido; el primero se da por bueno
+    let mut anchor: Option<(usize, Option<f64>)> = None;
+    for i in 0..points.len() {
+        if flags[i].is_some() {
+            continue;
+        }
+        if let Some((last, last_speed)) = anchor {
+            if let Some(seconds) = seconds_between(&points[last], &points[i]) {
+                let speed_mps = haversine_distance(&points[last], &points[i]) * 1000.0 / seconds;
+                if speed_mps * 3.6 > options.max_speed_kmh {
+                    flags[i] = Some(OutlierReason::Speed);
+                    continue;
+                }
+                if let Some(last_speed) = last_speed {
+                    if (speed_mps - last_speed).abs() / seconds > options.max_acceleration_mps2 {
+                        flags[i] = Some(OutlierReason::Acceleration);
+                        continue;
+                    }
+                }
+                anchor = Some((i, Some(speed_mps)));
+                continue;
+            }
+        }
+        anchor = Some((i, None));
+    }
+    flags
+}
+
+/// Aplica la acción a un segmento y añade los puntos marcados al informe
+fn clean_segment(
+    segment: &mut TrackSegment,
+    offset: usize,
+    options: OutlierOptions,
+    report: &mut OutlierReport,
+) {
+    let flags = classify(&segment.points, options);
+    if flags.iter().all(Option::is_none) {
+        return;
+    }
+
+    let mut points = Vec::with_capacity(segment.points.len());
+    for (i, point) in segment.points.iter().enumerate() {
+        let Some(reason) = flags[i] else {
+            points.push(point.clone());
+            continue;
+        };
+
+        let neighbours = match options.action {
+            OutlierAction::Remove => None,
+            OutlierAction::Interpolate => {
+                let before = (0..i).rev().find(|&j| flags[j].is_none());
+                let after = (i + 1..flags.len()).find(|&j| flags[j].is_none());
+                before.zip(after)
+            }
+        };
+        if let Some((before, after)) = neighbours {
+            let (a, b) = (&segment.points[before], &segment.points[after]);
+            // Fracción por tiempo si es posible, si no por posición en el segmento
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = match (seconds_between(a, point), seconds_between(a, b)) {
+                (Some(elapsed), Some(total)) => elapsed / total,
+                _ => (i - before) as f64 / (after - before) as f64,
+            };
+            let estimate = interpolate(a, b, fraction);
+            let mut fixed = point.clone();
+            if reason != OutlierReason::ElevationSpike {
+                fixed.lat = estimate.lat;
+                fixed.lon = estimate.lon;
+            }
+            fixed.elevation = estimate.elevation.or(point.elevation);
+            points.push(fixed);
+        }
+
+        report.outliers.push(Outlier {
+            index: offset + i,
+            reason,
+            interpolated: neighbours.is_some(),
+            original: point.clone(),
+        });
+    }
+    segment.points = points;
+}
+
+/// Kind of device misbehaviour found by [`Gpx::anomalies`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnomalyKind {
+    /// The position jumps at an impossible speed or acceleration
+    GpsJump,
+    /// The elevation jumps away from both neighbours
+    ElevationSpike,
+    /// The timestamp is earlier than the one of the previous point
+    TimeReversal,
+}
+
+impl fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::GpsJump => "GPS jump",
+            Self::ElevationSpike => "Elevation spike",
+            Self::TimeReversal => "Time reversal",
+        })
+    }
+}
+
+/// A suspicious track point found by [`Gpx::anomalies`]
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    /// Index of the point in [`Gpx::get_all_points`] order
+    pub index: usize,
+    /// What is wrong with it
+    pub kind: AnomalyKind,
+    /// The point itself
+    pub point: Point,
+}
+
+impl Anomaly {
+    /// Waypoint de diagnóstico situado en el punto anómalo, con el tipo de anomalía como nombre
+    pub fn to_waypoint(&self) -> Waypoint {
+        self.waypoint(self.kind.to_string())
+    }
+
+    /// Diagnostic waypoint named with `template` (see [`Anomaly::template_values`])
+    pub fn to_waypoint_named(&self, template: &NameTemplate) -> Waypoint {
+        self.waypoint(template.render(&self.template_values()))
+    }
+
+    /// Template values describing the anomaly
+    ///
+    /// Provides `kind` (e.g. `GPS jump`), `index` (track point index) and,
+    /// when the point has them, `ele_m` and `time`.
+    pub fn template_values(&self) -> TemplateValues {
+        let mut values = TemplateValues::new()
+            .with("kind", self.kind.to_string())
+            .with("index", self.index);
+        if let Some(elevation) = self.point.elevation {
+            values.set("ele_m", elevation);
+        }
+        if let Some(time) = self.point.time {
+            values.set("time", time.to_rfc3339());
+        }
+        values
+    }
+
+    fn waypoint(&self, name: String) -> Waypoint {
+        let mut waypoint = Waypoint::with_details(
+            self.point.lat,
+            self.point.lon,
+            Some(name),
+            self.point.elevation,
+            self.point.time,
+        );
+        waypoint.comment = Some(format!("Track point {}", self.index));
+        waypoint.symbol = Some("Flag, Red".to_string());
+        waypoint.kind = Some(ANOMALY_WAYPOINT_TYPE.to_string());
+        waypoint
+    }
+}
+
+impl Gpx {
+    /// Finds GPS jumps, elevation spikes and time reversals without changing anything
+    ///
+    /// Jumps and spikes use the same checks as [`Gpx::remove_outliers`]; a time
+    /// reversal is a point timestamped earlier than the previous timed point of its
+    /// segment. Results are in document order.
+    pub fn anomalies(&self, options: OutlierOptions) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        let mut offset = 0;
+        for segment in self.tracks.iter().flat_map(|track| &track.segments) {
+            let flags = classify(&segment.points, options);
+            let mut latest = None;
+            for (i, point) in segment.points.iter().enumerate() {
+                let reversed =
+                    matches!((latest, point.time), (Some(latest), Some(time)) if time < latest);
+                let kind = match flags[i] {
+                    _ if reversed => Some(AnomalyKind::TimeReversal),
+                    Some(OutlierReason::ElevationSpike) => Some(AnomalyKind::ElevationSpike),
+                    Some(_) => Some(AnomalyKind::GpsJump),
+                    None => None,
+                };
+                if let Some(kind) = kind {
+                    anomalies.push(Anomaly {
+                        index: offset + i,
+                        kind,
+                        point: point.clone(),
+                    });
+                }
+                latest = point.time.or(latest);
+            }
+            offset += segment.points.len();
+        }
+        anomalies
+    }
+
+    /// Adds a diagnostic waypoint at every anomaly found by [`Gpx::anomalies`]
+    ///
+    /// The waypoints are named after the [`AnomalyKind`], point to the track point
+    /// index in their comment and have the type [`ANOMALY_WAYPOINT_TYPE`], so they
+    /// can be spotted in any map viewer and told apart from the real waypoints.
+    /// Returns the anomalies found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{AnomalyKind, Gpx, OutlierOptions};
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0000" lon="-3.0"><time>2024-07-01T08:00:10Z</time></trkpt>
+    ///     <trkpt lat="40.0001" lon="-3.0"><time>2024-07-01T08:00:05Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let anomalies = gpx.flag_anomalies(OutlierOptions::default());
+    /// assert_eq!(anomalies[0].kind, AnomalyKind::TimeReversal);
+    /// assert_eq!(gpx.waypoints[0].name.as_deref(), Some("Time reversal"));
+    /// assert_eq!(gpx.waypoints[0].comment.as_deref(), Some("Track point 1"));
+    /// ```
+    pub fn flag_anomalies(&mut self, options: OutlierOptions) -> Vec<Anomaly> {
+        let anomalies = self.anomalies(options);
+        self.waypoints
+            .extend(anomalies.iter().map(Anomaly::to_waypoint));
+        anomalies
+    }
+
+    /// Like [`Gpx::flag_anomalies`], naming the waypoints with `template`
+    /// (see [`Anomaly::template_values`])
+    pub fn flag_anomalies_named(
+        &mut self,
+        options: OutlierOptions,
+        template: &NameTemplate,
+    ) -> Vec<Anomaly> {
+        let anomalies = self.anomalies(options);
+        self.waypoints.extend(
+            anomalies
+                .iter()
+                .map(|anomaly| anomaly.to_waypoint_named(template)),
+        );
+        anomalies
+    }
+
+    /// Removes or interpolates GPS glitches in every track segment
+    ///
+    /// A point is flagged when reaching it from the last valid point would need an
+    /// impossible speed or acceleration, or when its elevation jumps away from
+    /// both neighbours (a spike). The first point of each segment is trusted.
+    /// Waypoints are left untouched. Returns the flagged points so the
+    /// correction can be reviewed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, OutlierOptions, OutlierReason};
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0000" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.0001" lon="-3.0"><time>2024-07-01T08:00:05Z</time></trkpt>
+    ///     <trkpt lat="40.0500" lon="-3.0"><time>2024-07-01T08:00:10Z</time></trkpt>
+    ///     <trkpt lat="40.0002" lon="-3.0"><time>2024-07-01T08:00:15Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let report = gpx.remove_outliers(OutlierOptions::default());
+    /// assert_eq!(report.len(), 1);
+    /// assert_eq!(report.outliers[0].index, 2);
+    /// assert_eq!(report.outliers[0].reason, OutlierReason::Speed);
+    /// assert_eq!(gpx.total_points(), 3);
+    /// ```
+    pub fn remove_outliers(&mut self, options: OutlierOptions) -> OutlierReport {
+        let mut report = OutlierReport::default();
+        let mut offset = 0;
+        for segment in self
+            .tracks
+            .iter_mut()
+            .flat_map(|track| track.segments.iter_mut())
+        {
+            let len = segment.points.len();
+            clean_segment(segment, offset, options, &mut report);
+            offset += len;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::Track;
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Paseo hacia el norte a ~4 km/h, un punto cada 10 s, con un pico y un salto
+    fn walk() -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let points = (0..8)
+            .map(|i| {
+                let mut lat = 40.0 + f64::from(i) * 0.0001;
+                let mut ele = 600.0 + f64::from(i);
+                if i == 3 {
+                    ele += 120.0;
+                }
+                if i == 5 {
+                    lat += 0.01;
+                }
+                Point::with_time(
+                    lat,
+                    -3.0,
+                    Some(ele),
+                    start + Duration::seconds(i64::from(i) * 10),
+                )
+            })
+            .collect();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(points));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_remove_outliers_drops_spikes_and_jumps() {
+        let mut gpx = walk();
+        let report = gpx.remove_outliers(OutlierOptions::default());
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report.count(OutlierReason::ElevationSpike), 1);
+        assert_eq!(report.count(OutlierReason::Speed), 1);
+        assert_eq!(report.outliers[0].index, 3);
+        assert_eq!(report.outliers[1].index, 5);
+        assert!(!report.outliers[1].interpolated);
+        assert_eq!(report.outliers[0].original.elevation, Some(723.0));
+        assert_eq!(gpx.total_points(), 6);
+        assert!(gpx.total_distance_km() < 0.1);
+
+        // Una segunda pasada no encuentra nada más
+        assert!(gpx.remove_outliers(OutlierOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_interpolate_outliers_keeps_time_and_fixes_values() {
+        let mut gpx = walk();
+        let options = OutlierOptions {
+            action: OutlierAction::Interpolate,
+            ..OutlierOptions::default()
+        };
+        let report = gpx.remove_outliers(options);
+        assert_eq!(report.len(), 2);
+        assert!(report.outliers.iter().all(|o| o.interpolated));
+
+        let points = &gpx.tracks[0].segments[0].points;
+        assert_eq!(points.len(), 8);
+        assert!((points[3].elevation.unwrap() - 603.0).abs() < 1e-9);
+        assert!((points[5].lat - 40.0005).abs() < 1e-9);
+        assert_eq!(points[5].time, walk().get_all_points()[5].time);
+    }
+
+    #[test]
+    fn test_acceleration_and_untimed_points() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |seconds: i64| start + Duration::seconds(seconds);
+        // Parado y de repente 25 m en un segundo (90 km/h): posible en velocidad, no en aceleración
+        let mut segment = TrackSegment::with_points(vec![
+            Point::with_time(40.0, -3.0, None, at(0)),
+            Point::with_time(40.0, -3.0, None, at(1)),
+            Point::with_time(40.000_225, -3.0, None, at(2)),
+            Point::with_time(40.0, -3.0, None, at(3)),
+        ]);
+        let mut report = OutlierReport::default();
+        clean_segment(&mut segment, 0, OutlierOptions::default(), &mut report);
+        assert_eq!(report.count(OutlierReason::Acceleration), 1);
+        assert_eq!(segment.points.len(), 3);
+
+        // Sin tiempos solo se revisan los picos de elevación
+        let mut untimed = TrackSegment::with_points(vec![
+            Point::new(40.0, -3.0),
+            Point::new(41.0, -3.0),
+            Point::new(40.0, -3.0),
+        ]);
+        let mut report = OutlierReport::default();
+        clean_segment(&mut untimed, 0, OutlierOptions::default(), &mut report);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_flag_anomalies_adds_diagnostic_waypoints() {
+        let mut gpx = walk();
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        gpx.tracks[0].segments[0].points[7].time = Some(start);
+
+        let anomalies = gpx.flag_anomalies(OutlierOptions::default());
+        let kinds: Vec<_> = anomalies.iter().map(|a| (a.index, a.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (3, AnomalyKind::ElevationSpike),
+                (5, AnomalyKind::GpsJump),
+                (7, AnomalyKind::TimeReversal),
+            ]
+        );
+        // Los puntos del track no se tocan
+        assert_eq!(gpx.total_points(), 8);
+        assert_eq!(gpx.waypoints.len(), 3);
+        assert_eq!(gpx.waypoints[1].name.as_deref(), Some("GPS jump"));
+        assert_eq!(gpx.waypoints[1].lat, anomalies[1].point.lat);
+
+        assert!(gpx
+            .waypoints
+            .iter()
+            .all(|w| w.kind.as_deref() == Some(ANOMALY_WAYPOINT_TYPE)));
+    }
+    #[test]
+    fn test_flag_anomalies_named() {
+        let mut gpx = walk();
+        let template = NameTemplate::parse("{kind} at point {index} ({ele_m:.0} m)").unwrap();
+        gpx.flag_anomalies_named(OutlierOptions::default(), &template);
+        let names: Vec<_> = gpx
+            .waypoints
+            .iter()
+            .filter_map(|w| w.name.as_deref())
+            .collect();
+        assert_eq!(names[0], "Elevation spike at point 3 (723 m)");
+        assert!(names[1].starts_with("GPS jump at point 5"));
+    }
+}
@@ -0,0 +1,275 @@
+use crate::gpx::{
+    parser::{Gpx, GpxStatistics},
+    point::{haversine_distance, Point},
+};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// Kilómetros en una milla terrestre
+pub(crate) const KM_PER_MILE: f64 = 1.609_344;
+
+/// Distance unit used for paces and splits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SplitUnit {
+    /// Kilometers
+    #[default]
+    Kilometer,
+    /// Statute miles
+    Mile,
+}
+
+impl SplitUnit {
+    /// Longitud de la unidad en kilómetros
+    pub fn km(self) -> f64 {
+        match self {
+            Self::Kilometer => 1.0,
+            Self::Mile => KM_PER_MILE,
+        }
+    }
+
+    /// Abreviatura de la unidad (`"km"` o `"mi"`)
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Kilometer => "km",
+            Self::Mile => "mi",
+        }
+    }
+}
+
+/// Time needed to cover a unit of distance, the usual way runners express speed
+///
+/// Displays as minutes per kilometer (`"5:32 /km"`); use [`Pace::format`] for miles.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Pace {
+    seconds_per_km: f64,
+}
+
+impl Pace {
+    /// Ritmo a partir de segundos por kilómetro; `None` si no es positivo y finito
+    pub fn from_seconds_per_km(seconds: f64) -> Option<Self> {
+        (seconds.is_finite() && seconds > 0.0).then_some(Self {
+            seconds_per_km: seconds,
+        })
+    }
+
+    /// Ritmo equivalente a una velocidad en km/h; `None` si la velocidad no es positiva
+    pub fn from_speed_kmh(speed_kmh: f64) -> Option<Self> {
+        Self::from_seconds_per_km(3600.0 / speed_kmh)
+    }
+
+    /// Segundos por kilómetro
+    pub fn seconds_per_km(self) -> f64 {
+        self.seconds_per_km
+    }
+
+    /// Segundos por unidad de distancia
+    pub fn seconds_per(self, unit: SplitUnit) -> f64 {
+        self.seconds_per_km * unit.km()
+    }
+
+    /// Velocidad equivalente en km/h
+    pub fn speed_kmh(self) -> f64 {
+        3600.0 / self.seconds_per_km
+    }
+
+    /// Formats the pace per `unit`, as `"m:ss /km"` or `"h:mm:ss /mi"` when slower than an hour
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Pace, SplitUnit};
+    ///
+    /// let pace = Pace::from_speed_kmh(10.0).unwrap();
+    /// assert_eq!(pace.to_string(), "6:00 /km");
+    /// assert_eq!(pace.format(SplitUnit::Mile), "9:39 /mi");
+    /// ```
+    pub fn format(self, unit: SplitUnit) -> String {
+        // El ritmo es positivo y finito por construcción
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let total = self.seconds_per(unit).round() as u64;
+        let (hours, minutes, seconds) = (total / 3600, total % 3600 / 60, total % 60);
+        if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02} /{}", unit.label())
+        } else {
+            format!("{minutes}:{seconds:02} /{}", unit.label())
+        }
+    }
+}
+
+impl fmt::Display for Pace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(SplitUnit::Kilometer))
+    }
+}
+
+/// One split of [`Gpx::pace_splits`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaceSplit {
+    /// Split number, starting at 1
+    pub number: usize,
+    /// Distance covered in the split, in kilometers; only the last one can be shorter than the unit
+    pub distance_km: f64,
+    /// Elapsed time of the split in seconds, including pauses
+    pub duration_seconds: f64,
+    /// Average pace over the split
+    pub pace: Option<Pace>,
+}
+
+impl PaceSplit {
+    fn new(number: usize, distance_km: f64, duration_seconds: f64) -> Self {
+        Self {
+            number,
+            distance_km,
+            duration_seconds,
+            pace: Pace::from_seconds_per_km(duration_seconds / distance_km),
+        }
+    }
+}
+
+impl GpxStatistics {
+    /// Average pace over the whole recording, if distance and duration are known
+    pub fn average_pace(&self) -> Option<Pace> {
+        self.average_speed_kmh.and_then(Pace::from_speed_kmh)
+    }
+}
+
+impl Gpx {
+    /// Time taken for every kilometer or mile of the recording
+    ///
+    /// Split boundaries are interpolated between the points around them, and the
+    /// last split covers the remaining distance if it is shorter than a unit. Only
+    /// timed points are used; gaps between segments and tracks add time but no distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, SplitUnit};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.000" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.018" lon="-3.0"><time>2024-07-01T08:10:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let splits = gpx.pace_splits(SplitUnit::Kilometer);
+    /// assert_eq!(splits.len(), 3);
+    /// assert_eq!(splits[0].pace.unwrap().to_string(), "5:00 /km");
+    /// assert!(splits[2].distance_km < 0.01);
+    /// ```
+    pub fn pace_splits(&self, unit: SplitUnit) -> Vec<PaceSplit> {
+        let unit_km = unit.km();
+        let mut splits = Vec::new();
+        // Distancia y tiempo acumulados al inicio del split actual
+        let mut split_start = (0.0, 0.0);
+        let mut distance_km = 0.0;
+        let mut elapsed = 0.0;
+
+        let mut previous: Option<(&Point, DateTime<Utc>)> = None;
+        for track in &self.tracks {
+            for segment in &track.segments {
+                let mut in_segment = false;
+                let timed = segment
+                    .points
+                    .iter()
+                    .filter_map(|point| point.time.map(|time| (point, time)));
+                for (point, time) in timed {
+                    if let Some((prev, prev_time)) = previous {
+                        #[allow(clippy::cast_precision_loss)]
+                        let seconds = (time - prev_time).num_milliseconds() as f64 / 1000.0;
+                        let step_km = if in_segment {
+                            haversine_distance(prev, point)
+                        } else {
+                            0.0
+                        };
+
+                        // Cada frontera de unidad cruzada en este tramo cierra un split
+                        while step_km > 0.0 && distance_km + step_km >= split_start.0 + unit_km {
+                            let boundary = split_start.0 + unit_km;
+                            let fraction = (boundary - distance_km) / step_km;
+                            let at = elapsed + seconds * fraction;
+                            splits.push(PaceSplit::new(
+                                splits.len() + 1,
+                                unit_km,
+                                at - split_start.1,
+                            ));
+                            split_start = (boundary, at);
+                        }
+                        distance_km += step_km;
+                        elapsed += seconds;
+                    }
+                    previous = Some((point, time));
+                    in_segment = true;
+                }
+            }
+        }
+
+        let remaining = distance_km - split_start.0;
+        if remaining > 1e-9 {
+            splits.push(PaceSplit::new(
+                splits.len() + 1,
+                remaining,
+                elapsed - split_start.1,
+            ));
+        }
+        splits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_pace_formatting_and_conversions() {
+        let pace = Pace::from_seconds_per_km(332.0).unwrap();
+        assert_eq!(pace.to_string(), "5:32 /km");
+        assert!((pace.seconds_per(SplitUnit::Mile) - 534.3).abs() < 0.1);
+        assert!((pace.speed_kmh() - 10.843).abs() < 0.001);
+        assert_eq!(
+            Pace::from_seconds_per_km(3725.0).unwrap().to_string(),
+            "1:02:05 /km"
+        );
+        assert_eq!(Pace::from_speed_kmh(0.0), None);
+        assert_eq!(Pace::from_seconds_per_km(-1.0), None);
+    }
+
+    #[test]
+    fn test_average_pace_and_mile_splits_across_segments() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 7, 0, 0).unwrap();
+        // 0.01° de latitud ≈ 1.112 km, a 6 minutos cada tramo
+        let leg = |from: f64, minutes: i64| {
+            TrackSegment::with_points(vec![
+                Point::with_time(from, -3.0, None, start + Duration::minutes(minutes)),
+                Point::new(from + 0.005, -3.0),
+                Point::with_time(
+                    from + 0.01,
+                    -3.0,
+                    None,
+                    start + Duration::minutes(minutes + 6),
+                ),
+            ])
+        };
+        let mut track = Track::new();
+        track.add_segment(leg(40.0, 0));
+        // Pausa de 4 minutos entre segmentos
+        track.add_segment(leg(40.01, 10));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        let pace = gpx.statistics().average_pace().unwrap();
+        assert!((pace.seconds_per_km() - 960.0 / 2.2239).abs() < 1.0);
+
+        let splits = gpx.pace_splits(SplitUnit::Mile);
+        assert_eq!(splits.len(), 2);
+        assert_eq!(splits[0].number, 1);
+        assert!((splits[0].distance_km - KM_PER_MILE).abs() < 1e-9);
+        // La primera milla incluye la pausa
+        let first_leg = 1.11195;
+        let expected = 360.0 + 240.0 + (KM_PER_MILE - first_leg) / first_leg * 360.0;
+        assert!((splits[0].duration_seconds - expected).abs() < 1.0);
+        assert!((splits[1].distance_km - (2.0 * first_leg - KM_PER_MILE)).abs() < 0.001);
+        let total: f64 = splits.iter().map(|s| s.duration_seconds).sum();
+        assert!((total - 960.0).abs() < 1e-6);
+    }
+}
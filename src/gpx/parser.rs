@@ -1,37 +1,267 @@
-use crate::gpx::{point::Point, track::Track, waypoint::Waypoint};
-use quick_xml::{de::from_str, se::to_string};
+use crate::gpx::{
+    bounds::Bounds,
+    error::GpxError,
+    metadata::{self, Link, Metadata, Person},
+    point::Point,
+    statistics::StatisticsOptions,
+    track::{Track, TrackSegment},
+    units::Units,
+    unknown::UnknownXml,
+    waypoint::{Waypoint, WaypointFilter},
+    write::{GpxVersion, RootRef, WriteOptions},
+};
+use chrono::{DateTime, Utc};
+use quick_xml::de::from_str;
+#[cfg(feature = "regex")]
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 
-/// GPX metadata containing timestamp and other optional information
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct Metadata {
-    /// Timestamp of when the GPX file was created
-    #[serde(rename = "time")]
-    pub time: Option<String>,
-}
-
+/// Raw `<gpx>` root element, mirroring the XML document field by field
+///
+/// This is the low-level representation used for (de)serialization. Most users
+/// should work with [`Gpx`]; `GpxRoot` is exposed for cases that need exact control
+/// over the serialized attributes (version, creator, namespace) before the
+/// high-level API offers a setter for them.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Gpx, GpxRoot};
+///
+/// let mut root = GpxRoot::from(&Gpx::new());
+/// root.version = "1.1".to_string();
+/// root.creator = "my-app".to_string();
+/// root.xmlns = Some("http://www.topografix.com/GPX/1/1".to_string());
+///
+/// let xml = root.to_xml().unwrap();
+/// assert!(xml.contains("creator=\"my-app\""));
+/// ```
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename = "gpx")]
 pub struct GpxRoot {
+    /// Value of the `version` attribute (defaults to `"1.1"`)
     #[serde(rename = "@version", default = "default_version")]
     pub version: String,
+    /// Value of the `creator` attribute (defaults to `"gpx-extractor"`)
     #[serde(rename = "@creator", default = "default_creator")]
     pub creator: String,
+    /// Default XML namespace (`xmlns` attribute), if any
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+    /// Optional `<metadata>` element
     #[serde(rename = "metadata")]
     pub metadata: Option<Metadata>,
-    #[serde(rename = "trk", default)]
-    pub tracks: Vec<Track>,
+    /// GPX 1.0 `<name>`, placed directly under `<gpx>`
+    #[serde(rename = "name", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// GPX 1.0 `<desc>`
+    #[serde(rename = "desc", default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    /// GPX 1.0 `<author>` (a plain name)
+    #[serde(rename = "author", default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// GPX 1.0 `<email>` (a plain address)
+    #[serde(rename = "email", default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// GPX 1.0 `<url>`
+    #[serde(rename = "url", default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// GPX 1.0 `<urlname>`
+    #[serde(rename = "urlname", default, skip_serializing_if = "Option::is_none")]
+    pub urlname: Option<String>,
+    /// GPX 1.0 `<time>`
+    #[serde(rename = "time", default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// GPX 1.0 `<keywords>`
+    #[serde(rename = "keywords", default, skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+    /// GPX 1.0 `<bounds>`
+    #[serde(
+        rename = "bounds",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "metadata::serialize_bounds",
+        deserialize_with = "metadata::deserialize_bounds"
+    )]
+    pub bounds: Option<Bounds>,
+    /// `<wpt>` elements
     #[serde(rename = "wpt", default)]
     pub waypoints: Vec<Waypoint>,
+    /// `<trk>` elements
+    #[serde(rename = "trk", default)]
+    pub tracks: Vec<Track>,
 }
 
 fn default_version() -> String {
     "1.1".to_string()
 }
 
+/// Creador que se escribe cuando el documento no tiene uno
+const DEFAULT_CREATOR: &str = "gpx-extractor";
+
 fn default_creator() -> String {
-    "gpx-extractor".to_string()
+    DEFAULT_CREATOR.to_string()
+}
+
+impl GpxRoot {
+    /// Parses the raw root element from an XML string
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the XML string cannot be parsed into a `<gpx>` element
+    pub fn try_from_str(s: &str) -> Result<Self, GpxError> {
+        // Algunos programas de Windows escriben la marca de orden de bytes de UTF-8
+        let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+        Ok(from_str::<GpxRoot>(s)?)
+    }
+
+    /// Serializes the root element exactly as it is, prefixed by the XML declaration
+    ///
+    /// Unlike [`Gpx::to_xml`], no `xmlns` or `xsi:schemaLocation` is added: the
+    /// root carries only the attributes stored in the struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the structure cannot be serialized
+    pub fn to_xml(&self) -> Result<String, GpxError> {
+        self.to_xml_with(&WriteOptions {
+            schema_location: false,
+            ..WriteOptions::default()
+        })
+    }
+
+    /// Serializes the root element with the given [`WriteOptions`]
+    ///
+    /// The struct's own `xmlns` is written unless [`WriteOptions::xmlns`] overrides
+    /// it; when both are `None` no default namespace is declared. `xsi:schemaLocation`
+    /// is only added when [`WriteOptions::schema_location`] is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the structure cannot be serialized
+    pub fn to_xml_with(&self, options: &WriteOptions) -> Result<String, GpxError> {
+        RootRef {
+            version: &self.version,
+            creator: &self.creator,
+            xmlns: self.xmlns.as_deref(),
+            metadata: self.metadata.as_ref(),
+            name: self.name.as_deref(),
+            desc: self.desc.as_deref(),
+            author: self.author.as_deref(),
+            email: self.email.as_deref(),
+            url: self.url.as_deref(),
+            urlname: self.urlname.as_deref(),
+            time: self.time.as_deref(),
+            keywords: self.keywords.as_deref(),
+            bounds: self.bounds,
+            tracks: &self.tracks,
+            waypoints: &self.waypoints,
+            unknown: None,
+            exact: true,
+        }
+        .write(options)
+    }
+}
+
+impl Default for GpxRoot {
+    fn default() -> Self {
+        Self {
+            version: default_version(),
+            creator: default_creator(),
+            xmlns: None,
+            metadata: None,
+            name: None,
+            desc: None,
+            author: None,
+            email: None,
+            url: None,
+            urlname: None,
+            time: None,
+            keywords: None,
+            bounds: None,
+            tracks: Vec::new(),
+            waypoints: Vec::new(),
+        }
+    }
+}
+
+impl From<&Gpx> for GpxRoot {
+    fn from(gpx: &Gpx) -> Self {
+        Self {
+            version: gpx.version.as_str().to_string(),
+            creator: gpx.creator.clone().unwrap_or_else(default_creator),
+            metadata: gpx.metadata.clone(),
+            tracks: gpx.tracks.clone(),
+            waypoints: gpx.waypoints.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<Gpx> for GpxRoot {
+    fn from(gpx: Gpx) -> Self {
+        Self {
+            version: gpx.version.as_str().to_string(),
+            creator: gpx.creator.unwrap_or_else(default_creator),
+            metadata: gpx.metadata,
+            tracks: gpx.tracks,
+            waypoints: gpx.waypoints,
+            ..Self::default()
+        }
+    }
+}
+
+impl GpxRoot {
+    /// Metadata stored GPX 1.0-style, directly under `<gpx>`
+    fn legacy_metadata(&mut self) -> Option<Metadata> {
+        let author = match (self.author.take(), self.email.take()) {
+            (None, None) => None,
+            (name, email) => Some(Person {
+                name,
+                email,
+                link: None,
+            }),
+        };
+        let link = self.url.take().map(|href| Link {
+            href,
+            text: self.urlname.take(),
+            mime_type: None,
+        });
+
+        let metadata = Metadata {
+            name: self.name.take(),
+            description: self.desc.take(),
+            author,
+            links: link.into_iter().collect(),
+            time: self.time.take(),
+            keywords: self.keywords.take(),
+            bounds: self.bounds.take(),
+            ..Metadata::default()
+        };
+        (metadata != Metadata::default()).then_some(metadata)
+    }
+}
+
+impl From<GpxRoot> for Gpx {
+    fn from(mut root: GpxRoot) -> Self {
+        // En GPX 1.0 los metadatos están directamente bajo <gpx>
+        let legacy = root.legacy_metadata();
+        let metadata = root.metadata.or(legacy);
+
+        Self {
+            tracks: root.tracks,
+            waypoints: root.waypoints,
+            metadata,
+            version: GpxVersion::from_attribute(&root.version).unwrap_or_default(),
+            // El creador por defecto equivale a no tener ninguno
+            creator: (root.creator != default_creator()).then_some(root.creator),
+            unknown: UnknownXml::default(),
+        }
+    }
 }
 
 /// Main GPX structure containing tracks, waypoints, and metadata
@@ -47,7 +277,7 @@ fn default_creator() -> String {
 /// let mut gpx = Gpx::new();
 /// // Add tracks, waypoints, etc.
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Gpx {
     /// Collection of GPS tracks (recorded routes)
     pub tracks: Vec<Track>,
@@ -55,6 +285,15 @@ pub struct Gpx {
     pub waypoints: Vec<Waypoint>,
     /// Optional metadata (timestamp, etc.)
     pub metadata: Option<Metadata>,
+    /// Schema version the document was read as; [`Gpx::to_xml`] writes it back,
+    /// while [`Gpx::to_xml_with`] follows [`WriteOptions::version`]
+    pub version: GpxVersion,
+    /// Value written to the `creator` attribute (`"gpx-extractor"` when `None`),
+    /// kept from the parsed file
+    pub creator: Option<String>,
+    /// Root attributes and child elements not modeled by this crate (namespace
+    /// declarations, `<rte>`, `<extensions>`), written back as read
+    pub unknown: UnknownXml,
 }
 
 impl Gpx {
@@ -64,9 +303,57 @@ impl Gpx {
             tracks: Vec::new(),
             waypoints: Vec::new(),
             metadata: None,
+            version: GpxVersion::V1_1,
+            creator: None,
+            unknown: UnknownXml::default(),
         }
     }
 
+    /// Value of the `creator` attribute written on export
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx version="1.1" creator="Garmin Edge 530"/>"#).unwrap();
+    /// assert_eq!(gpx.creator(), "Garmin Edge 530");
+    ///
+    /// gpx.set_creator("MyApp 2.1");
+    /// assert!(gpx.to_xml().contains(r#"creator="MyApp 2.1""#));
+    /// ```
+    pub fn creator(&self) -> &str {
+        self.creator.as_deref().unwrap_or(DEFAULT_CREATOR)
+    }
+
+    /// Sets the `creator` attribute, so apps can brand the files they export
+    pub fn set_creator(&mut self, creator: impl Into<String>) {
+        self.creator = Some(creator.into());
+    }
+
+    /// Builds a document with a single unnamed track from `(lat, lon)` pairs
+    ///
+    /// Handy to convert the coordinate arrays of other libraries or of a decoded
+    /// polyline. Coordinates are not validated; use
+    /// [`GpxBuilder`](crate::GpxBuilder) to check them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::from_polyline_coords(&[(40.0, -3.0), (40.01, -3.0)]);
+    /// assert_eq!(gpx.total_points(), 2);
+    /// assert!((gpx.total_distance_km() - 1.112).abs() < 0.001);
+    /// ```
+    pub fn from_polyline_coords(coords: &[(f64, f64)]) -> Self {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::from_latlon_pairs(coords));
+        let mut gpx = Self::new();
+        gpx.add_track(track);
+        gpx
+    }
+
     /// Obtiene la fecha de la metadata si existe
     pub fn date(&self) -> Option<&str> {
         self.metadata.as_ref()?.time.as_deref()
@@ -74,10 +361,44 @@ impl Gpx {
 
     /// Obtiene todos los puntos de todos los tracks
     pub fn get_all_points(&self) -> Vec<&Point> {
-        self.tracks
-            .iter()
-            .flat_map(|track| track.get_all_points())
-            .collect()
+        self.points().collect()
+    }
+
+    /// Points of every track and segment in document order
+    ///
+    /// Unlike [`Gpx::get_all_points`], nothing is allocated, so this is the
+    /// cheaper choice for a single pass over a long recording.
+    pub fn points(&self) -> impl DoubleEndedIterator<Item = &Point> {
+        self.tracks.iter().flat_map(Track::points)
+    }
+
+    /// Points of every track and segment in document order, for in-place edits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::from_latlon_ele(&[(40.0, -3.0, 600.0), (40.1, -3.0, 620.0)]));
+    /// let mut gpx = Gpx::new();
+    /// gpx.add_track(track);
+    ///
+    /// // Corrección de la altitud barométrica
+    /// for point in gpx.points_mut() {
+    ///     point.elevation = point.elevation.map(|ele| ele - 12.0);
+    /// }
+    /// assert_eq!(gpx.points().next().unwrap().elevation, Some(588.0));
+    /// ```
+    pub fn points_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Point> {
+        self.tracks.iter_mut().flat_map(Track::points_mut)
+    }
+
+    /// Consumes the document and yields the points of every track in order
+    ///
+    /// Waypoints and metadata are dropped.
+    pub fn into_points(self) -> impl DoubleEndedIterator<Item = Point> {
+        self.tracks.into_iter().flat_map(Track::into_points)
     }
 
     /// Calcula la distancia total aproximada en kilómetros
@@ -209,6 +530,7 @@ impl Gpx {
             return None;
         }
 
+        #[allow(clippy::cast_precision_loss)]
         let duration_hours = duration_seconds as f64 / 3600.0;
         Some(distance_km / duration_hours)
     }
@@ -229,6 +551,16 @@ impl Gpx {
         }
     }
 
+    /// Statistics of every track, paired with its display name
+    ///
+    /// Unnamed tracks are reported as `"Unnamed Track"`, in document order.
+    pub fn statistics_per_track(&self) -> Vec<(String, GpxStatistics)> {
+        self.tracks
+            .iter()
+            .map(|track| (track.display_name(), track.statistics()))
+            .collect()
+    }
+
     /// Agrega un track al GPX
     pub fn add_track(&mut self, track: Track) {
         self.tracks.push(track);
@@ -239,6 +571,46 @@ impl Gpx {
         self.waypoints.push(waypoint);
     }
 
+    /// Keeps only the waypoints for which `keep` returns `true`
+    ///
+    /// Returns the number of waypoints removed.
+    pub fn filter_waypoints_by<F>(&mut self, keep: F) -> usize
+    where
+        F: FnMut(&Waypoint) -> bool,
+    {
+        let before = self.waypoints.len();
+        self.waypoints.retain(keep);
+        before - self.waypoints.len()
+    }
+
+    /// Keeps only the waypoints whose `<sym>` matches `symbol` (case-insensitive)
+    pub fn filter_waypoints_by_symbol(&mut self, symbol: &str) -> usize {
+        let filter = WaypointFilter::Symbol(symbol.to_string());
+        self.filter_waypoints_by(|w| filter.matches(w))
+    }
+
+    /// Keeps only the waypoints whose `<type>` matches `kind` (case-insensitive)
+    pub fn filter_waypoints_by_type(&mut self, kind: &str) -> usize {
+        let filter = WaypointFilter::Kind(kind.to_string());
+        self.filter_waypoints_by(|w| filter.matches(w))
+    }
+
+    /// Keeps only the waypoints whose name matches `pattern`
+    #[cfg(feature = "regex")]
+    pub fn filter_waypoints_by_name(&mut self, pattern: &Regex) -> usize {
+        self.filter_waypoints_by(|w| w.name.as_deref().is_some_and(|n| pattern.is_match(n)))
+    }
+
+    /// Keeps only the waypoints matching at least one of `filters`
+    ///
+    /// An empty filter list keeps every waypoint.
+    pub fn keep_waypoints(&mut self, filters: &[WaypointFilter]) -> usize {
+        if filters.is_empty() {
+            return 0;
+        }
+        self.filter_waypoints_by(|w| filters.iter().any(|filter| filter.matches(w)))
+    }
+
     /// Verifica si el GPX está vacío
     pub fn is_empty(&self) -> bool {
         self.tracks.is_empty() && self.waypoints.is_empty()
@@ -260,18 +632,62 @@ impl Gpx {
             .collect()
     }
 
-    /// Convierte el GPX a string XML
+    /// Convierte el GPX a string XML, en la versión con la que se leyó
     pub fn to_xml(&self) -> String {
-        let gpx_root = GpxRoot {
-            version: default_version(),
-            creator: default_creator(),
-            metadata: self.metadata.clone(),
-            tracks: self.tracks.clone(),
-            waypoints: self.waypoints.clone(),
+        self.to_xml_lossy(&WriteOptions {
+            version: self.version,
+            ..WriteOptions::default()
+        })
+    }
+
+    /// Converts the GPX to indented, human-readable XML (see [`WriteOptions::pretty`])
+    /// in the version it was read as
+    pub fn to_xml_pretty(&self) -> String {
+        self.to_xml_lossy(&WriteOptions {
+            version: self.version,
+            ..WriteOptions::pretty()
+        })
+    }
+
+    /// Converts the GPX to GPX 1.0 XML for legacy consumers (see [`WriteOptions::v1_0`])
+    pub fn to_xml_v10(&self) -> String {
+        self.to_xml_lossy(&WriteOptions::v1_0())
+    }
+
+    /// Converts the GPX to XML with the given [`WriteOptions`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Validation`] if [`WriteOptions::validate`] is set and
+    /// the document has errors, or another error if it cannot be serialized
+    pub fn to_xml_with(&self, options: &WriteOptions) -> Result<String, GpxError> {
+        if options.validate {
+            self.check_valid()?;
+        }
+        // Los límites se recalculan para que nunca queden desfasados respecto al contenido
+        let bounded_metadata = match self.bounds() {
+            Some(bounds) if options.bounds => Some(Metadata {
+                bounds: Some(bounds),
+                ..self.metadata.clone().unwrap_or_default()
+            }),
+            _ => None,
         };
+        RootRef {
+            version: "1.1",
+            creator: self.creator(),
+            xmlns: None,
+            metadata: bounded_metadata.as_ref().or(self.metadata.as_ref()),
+            tracks: &self.tracks,
+            waypoints: &self.waypoints,
+            unknown: Some(&self.unknown),
+            ..RootRef::default()
+        }
+        .write(options)
+    }
 
-        match to_string(&gpx_root) {
-            Ok(xml) => format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{xml}"),
+    fn to_xml_lossy(&self, options: &WriteOptions) -> String {
+        match self.to_xml_with(options) {
+            Ok(xml) => xml,
             Err(e) => {
                 eprintln!("Error serializing GPX to XML: {e}");
                 String::new()
@@ -284,9 +700,26 @@ impl Gpx {
     /// # Errors
     ///
     /// Returns an error if the file cannot be written
-    pub fn save_to_file(&self, path: &str) -> Result<(), std::io::Error> {
+    pub fn save_to_file(&self, path: &str) -> Result<(), GpxError> {
         use std::fs;
-        fs::write(path, self.to_xml())
+        fs::write(path, self.to_xml())?;
+        Ok(())
+    }
+
+    /// Saves the GPX to a file using the given [`WriteOptions`]
+    ///
+    /// The file starts with a UTF-8 byte order mark when [`WriteOptions::bom`] is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be serialized or the file cannot be written
+    pub fn save_to_file_with(&self, path: &str, options: &WriteOptions) -> Result<(), GpxError> {
+        let mut xml = self.to_xml_with(options)?;
+        if options.bom {
+            xml.insert(0, '\u{feff}');
+        }
+        std::fs::write(path, xml)?;
+        Ok(())
     }
 }
 
@@ -314,24 +747,167 @@ impl From<&Gpx> for String {
     }
 }
 
+/// Options that control how a GPX document is read
+///
+/// By default the elements and attributes this crate does not model are kept
+/// (see [`UnknownXml`]) so they survive a roundtrip. Keeping them needs a second
+/// pass over the whole text; [`ParseOptions::streaming`] skips it so readers are
+/// parsed incrementally, without holding the text in memory.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Gpx, ParseOptions};
+///
+/// let xml = r#"<gpx><trk><trkseg><trkpt lat="40.0" lon="-3.0"><x:v xmlns:x="urn:x">1</x:v></trkpt></trkseg></trk></gpx>"#;
+///
+/// let kept = Gpx::try_from_str_with(xml, &ParseOptions::default()).unwrap();
+/// assert!(kept.to_xml().contains("<x:v"));
+///
+/// let dropped = Gpx::try_from_str_with(xml, &ParseOptions::streaming()).unwrap();
+/// assert!(!dropped.to_xml().contains("<x:v"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Whether to keep the elements and attributes the model has no field for
+    pub preserve_unknown: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            preserve_unknown: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Single-pass parsing that drops unknown elements, for large files and streams
+    pub fn streaming() -> Self {
+        Self {
+            preserve_unknown: false,
+        }
+    }
+}
+
 impl Gpx {
     /// Attempts to create a GPX from an XML string
     ///
+    /// Unknown elements and attributes are kept; see [`ParseOptions`].
+    ///
     /// # Errors
     ///
-    /// Returns an error if the XML string cannot be parsed into a valid GPX structure
-    pub fn try_from_str(s: &str) -> Result<Self, quick_xml::DeError> {
-        let gpx_root = from_str::<GpxRoot>(s)?;
-        Ok(Gpx {
-            tracks: gpx_root.tracks,
-            waypoints: gpx_root.waypoints,
-            metadata: gpx_root.metadata,
-        })
+    /// Returns an error if the XML string cannot be parsed into a valid GPX structure,
+    /// declares an unsupported version, or contains out-of-range coordinates
+    pub fn try_from_str(s: &str) -> Result<Self, GpxError> {
+        Self::try_from_str_with(s, &ParseOptions::default())
+    }
+
+    /// Attempts to create a GPX from an XML string with the given options
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Gpx::try_from_str`]
+    pub fn try_from_str_with(s: &str, options: &ParseOptions) -> Result<Self, GpxError> {
+        let mut gpx = Self::try_from_root(GpxRoot::try_from_str(s)?)?;
+        if options.preserve_unknown {
+            gpx.capture_unknown(s)?;
+        }
+        Ok(gpx)
+    }
+
+    /// Reads and parses a GPX file
+    ///
+    /// Unknown elements and attributes are kept, so saving the document again
+    /// does not lose them; use [`Gpx::from_reader`] to parse large files in a
+    /// single pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or for the same reasons as
+    /// [`Gpx::try_from_str`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let gpx = Gpx::from_file("route.gpx")?;
+    /// println!("{:.2} km", gpx.total_distance_km());
+    /// # Ok::<(), gpx_extractor::GpxError>(())
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, GpxError> {
+        Self::from_reader_with(BufReader::new(File::open(path)?), &ParseOptions::default())
+    }
+
+    /// Parses a GPX document from a buffered reader, such as standard input or a socket
+    ///
+    /// The XML is parsed incrementally as it is read, without loading the text
+    /// in memory first; only the resulting document is kept. A UTF-8 byte order
+    /// mark is skipped. Elements this crate does not model are dropped; use
+    /// [`Gpx::from_reader_with`] to keep them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or for the same reasons as
+    /// [`Gpx::try_from_str`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let xml = br#"<gpx><trk><trkseg><trkpt lat="40.0" lon="-3.0"/></trkseg></trk></gpx>"#;
+    /// let gpx = Gpx::from_reader(&xml[..]).unwrap();
+    /// assert_eq!(gpx.total_points(), 1);
+    /// ```
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, GpxError> {
+        Self::from_reader_with(reader, &ParseOptions::streaming())
+    }
+
+    /// Parses a GPX document from a buffered reader with the given options
+    ///
+    /// Keeping unknown elements reads the whole text before parsing it.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Gpx::from_reader`]
+    pub fn from_reader_with(
+        mut reader: impl BufRead,
+        options: &ParseOptions,
+    ) -> Result<Self, GpxError> {
+        // Algunos programas de Windows escriben la marca de orden de bytes de UTF-8
+        if reader.fill_buf()?.starts_with(b"\xEF\xBB\xBF") {
+            reader.consume(3);
+        }
+        if options.preserve_unknown {
+            let mut xml = String::new();
+            reader.read_to_string(&mut xml)?;
+            return Self::try_from_str_with(&xml, options);
+        }
+        Self::try_from_root(quick_xml::de::from_reader(reader)?)
+    }
+
+    /// Valida la versión y las coordenadas del elemento raíz ya leído
+    fn try_from_root(root: GpxRoot) -> Result<Self, GpxError> {
+        if GpxVersion::from_attribute(&root.version).is_none() {
+            return Err(GpxError::UnsupportedVersion(root.version));
+        }
+
+        let gpx = Gpx::from(root);
+        for point in gpx.get_all_points() {
+            Point::check_coordinates(point.lat, point.lon)?;
+        }
+        for waypoint in &gpx.waypoints {
+            Point::check_coordinates(waypoint.lat, waypoint.lon)?;
+        }
+
+        Ok(gpx)
     }
 }
 
 impl TryFrom<&str> for Gpx {
-    type Error = quick_xml::DeError;
+    type Error = GpxError;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         Self::try_from_str(s)
@@ -342,6 +918,7 @@ impl TryFrom<&str> for Gpx {
 ///
 /// Contains computed metrics including distances, elevations, and counts.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GpxStatistics {
     /// Total number of tracks in the GPX
     pub total_tracks: usize,
@@ -366,6 +943,17 @@ pub struct GpxStatistics {
 }
 
 impl GpxStatistics {
+    /// Estadísticas de un conjunto de segmentos, sin contar tracks ni waypoints
+    ///
+    /// Gain and loss are accumulated within each segment, and the duration spans
+    /// from the earliest to the latest timestamp, as in [`Gpx::statistics`].
+    pub(crate) fn from_segments<'a, I>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TrackSegment>,
+    {
+        Self::from_segments_with(segments, StatisticsOptions::default())
+    }
+
     /// Calcula la ganancia de elevación (diferencia min-max)
     pub fn elevation_difference(&self) -> Option<f64> {
         self.elevation_range.map(|(min, max)| max - min)
@@ -383,56 +971,23 @@ impl GpxStatistics {
 
     /// Obtiene una descripción legible de las estadísticas
     pub fn summary(&self) -> String {
-        let mut summary = format!(
-            "GPX Statistics:\n\
-             - Tracks: {}\n\
-             - Waypoints: {}\n\
-             - Segments: {}\n\
-             - Points: {}\n\
-             - Distance: {:.2} km",
-            self.total_tracks,
-            self.total_waypoints,
-            self.total_segments,
-            self.total_points,
-            self.total_distance_km
-        );
-
-        if let Some(duration) = self.duration_formatted() {
-            summary.push_str(&format!("\n- Duration: {}", duration));
-        }
-
-        if let Some(speed) = self.average_speed_kmh {
-            summary.push_str(&format!("\n- Average speed: {:.2} km/h", speed));
-        }
-
-        if let Some((min_ele, max_ele)) = self.elevation_range {
-            use std::fmt::Write;
-            let _ = write!(
-                &mut summary,
-                "\n- Elevation range: {min_ele:.1}m - {max_ele:.1}m"
-            );
-        }
-
-        if let Some(gain) = self.elevation_gain {
-            use std::fmt::Write;
-            let _ = write!(&mut summary, "\n- Elevation gain: {gain:.1}m");
-        }
-
-        if let Some(loss) = self.elevation_loss {
-            use std::fmt::Write;
-            let _ = write!(&mut summary, "\n- Elevation loss: {loss:.1}m");
-        }
-
-        summary
+        self.display(Units::Metric).to_string()
     }
 }
 
+/// Fecha del primer punto con tiempo
+pub(crate) fn start_time(gpx: &Gpx) -> Option<DateTime<Utc>> {
+    gpx.get_all_points().into_iter().find_map(|p| p.time)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::gpx::{
+        extensions::Color,
         point::Point,
         track::{Track, TrackSegment},
+        write::GPX_11_NAMESPACE,
     };
     use chrono::TimeZone;
 
@@ -885,4 +1440,272 @@ mod tests {
 
         assert_eq!(gpx.average_speed_kmh(), None); // Should handle zero duration
     }
+
+    #[test]
+    fn test_gpx_root_preserves_raw_attributes() {
+        let xml = r#"
+        <gpx version="1.0" creator="Garmin" xmlns="http://www.topografix.com/GPX/1/0">
+            <trk><name>Raw</name></trk>
+        </gpx>"#;
+
+        let root = GpxRoot::try_from_str(xml).unwrap();
+        assert_eq!(root.version, "1.0");
+        assert_eq!(root.creator, "Garmin");
+        assert_eq!(
+            root.xmlns.as_deref(),
+            Some("http://www.topografix.com/GPX/1/0")
+        );
+
+        let output = root.to_xml().unwrap();
+        assert!(output.contains("version=\"1.0\""));
+        assert!(output.contains("creator=\"Garmin\""));
+        assert!(output.contains("xmlns=\"http://www.topografix.com/GPX/1/0\""));
+    }
+
+    #[test]
+    fn test_gpx_root_conversions() {
+        let mut gpx = Gpx::new();
+        gpx.add_track(Track::with_name("Converted".to_string()));
+
+        let root = GpxRoot::from(&gpx);
+        assert_eq!(root.version, "1.1");
+        assert_eq!(root.creator, "gpx-extractor");
+        assert!(root.xmlns.is_none());
+        assert_eq!(root.tracks.len(), 1);
+
+        let back = Gpx::from(root);
+        assert_eq!(back.track_names(), vec!["Converted".to_string()]);
+    }
+
+    #[test]
+    fn test_gpx_root_without_namespace_writes_none() {
+        let root = GpxRoot::from(&Gpx::new());
+        let output = root.to_xml().unwrap();
+        assert!(!output.contains("xmlns"));
+        assert!(!output.contains("schemaLocation"));
+
+        // Las opciones explícitas sí se aplican
+        let options = WriteOptions {
+            xmlns: Some(GPX_11_NAMESPACE.to_string()),
+            ..WriteOptions::default()
+        };
+        let output = root.to_xml_with(&options).unwrap();
+        assert_eq!(output.matches(" xmlns=").count(), 1);
+        assert!(output.contains("xsi:schemaLocation"));
+    }
+
+    #[test]
+    fn test_gpx_unsupported_version() {
+        let xml = r#"<gpx version="2.0"><trk><name>Future</name></trk></gpx>"#;
+        let result = Gpx::try_from_str(xml);
+        assert!(matches!(result, Err(GpxError::UnsupportedVersion(v)) if v == "2.0"));
+    }
+
+    #[test]
+    fn test_gpx_invalid_coordinates_are_rejected() {
+        let xml = r#"<gpx><wpt lat="95.0" lon="10.0"><name>Nowhere</name></wpt></gpx>"#;
+        let result = Gpx::try_from_str(xml);
+        assert!(matches!(
+            result,
+            Err(GpxError::InvalidCoordinate { lat, .. }) if lat == 95.0
+        ));
+    }
+
+    #[test]
+    fn test_gpx_error_kinds() {
+        assert!(matches!(
+            Gpx::try_from_str("<gpx><trk></gpx>"),
+            Err(GpxError::Xml(_))
+        ));
+
+        let missing_lat = r#"<gpx><trk><trkseg><trkpt lon="1.0"/></trkseg></trk></gpx>"#;
+        assert!(matches!(
+            Gpx::try_from_str(missing_lat),
+            Err(GpxError::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn test_gpx_save_to_file_io_error() {
+        let gpx = Gpx::new();
+        let result = gpx.save_to_file("/nonexistent-dir/output.gpx");
+        assert!(matches!(result, Err(GpxError::Io(_))));
+    }
+
+    #[test]
+    fn test_gpx_from_file_and_reader() {
+        let mut gpx = Gpx::new();
+        let mut track = Track::with_name("Reader Test".to_string());
+        let mut point = Point::new(40.0, -3.0);
+        point.extensions.heart_rate = Some(150);
+        track.add_segment(TrackSegment::with_points(vec![point]));
+        gpx.add_track(track);
+        gpx.add_waypoint(Waypoint::with_name(40.1, -3.1, "Top".to_string()));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reader.gpx");
+        gpx.save_to_file_with(path.to_str().unwrap(), &WriteOptions::windows())
+            .unwrap();
+        let read = Gpx::from_file(&path).unwrap();
+        assert_eq!(read.to_xml(), gpx.to_xml());
+        assert_eq!(read.get_all_points()[0].heart_rate(), Some(150));
+
+        let bad = r#"<gpx version="2.0"></gpx>"#;
+        assert!(matches!(
+            Gpx::from_reader(bad.as_bytes()),
+            Err(GpxError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            Gpx::from_file(dir.path().join("missing.gpx")),
+            Err(GpxError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_gpx_from_reader_keeps_unknown_only_when_asked() {
+        let xml = "\u{feff}<gpx><wpt lat=\"40.0\" lon=\"-3.0\"><x:v xmlns:x=\"urn:x\">1</x:v></wpt></gpx>";
+
+        let streamed = Gpx::from_reader(xml.as_bytes()).unwrap();
+        assert_eq!(streamed.waypoints.len(), 1);
+        assert!(!streamed.to_xml().contains("<x:v"));
+
+        let kept = Gpx::from_reader_with(xml.as_bytes(), &ParseOptions::default()).unwrap();
+        assert!(kept.to_xml().contains(r#"<x:v xmlns:x="urn:x">1</x:v>"#));
+    }
+
+    #[test]
+    fn test_gpx_track_color_extensions() {
+        let xml = r#"
+        <gpx version="1.1" creator="BaseCamp"
+             xmlns:gpxx="http://www.garmin.com/xmlschemas/GpxExtensions/v3"
+             xmlns:gpx_style="http://www.topografix.com/GPX/gpx_style/0/2">
+            <trk>
+                <name>Styled</name>
+                <extensions>
+                    <gpx_style:line><gpx_style:color>3366FF</gpx_style:color></gpx_style:line>
+                </extensions>
+            </trk>
+            <trk>
+                <name>Garmin</name>
+                <extensions>
+                    <gpxx:TrackExtension><gpxx:DisplayColor>DarkGreen</gpxx:DisplayColor></gpxx:TrackExtension>
+                </extensions>
+            </trk>
+            <trk><name>Plain</name></trk>
+        </gpx>"#;
+
+        let gpx = Gpx::try_from_str(xml).unwrap();
+        assert_eq!(gpx.tracks[0].color(), Some(Color::rgb(0x33, 0x66, 0xFF)));
+        assert_eq!(gpx.tracks[1].color(), Some(Color::rgb(0x00, 0x64, 0x00)));
+        assert_eq!(gpx.tracks[2].color(), None);
+
+        let output = gpx.to_xml();
+        assert!(output.contains("<gpx_style:color>3366FF</gpx_style:color>"));
+        assert!(output.contains("<gpxx:DisplayColor>Blue</gpxx:DisplayColor>"));
+        assert!(output.contains("xmlns:gpxx=\"http://www.garmin.com/xmlschemas/GpxExtensions/v3\""));
+        assert_eq!(output.matches("<extensions").count(), 2);
+
+        let reparsed = Gpx::try_from_str(&output).unwrap();
+        assert_eq!(reparsed.tracks[0].color(), gpx.tracks[0].color());
+        assert_eq!(reparsed.tracks[1].color(), gpx.tracks[1].color());
+    }
+
+    #[test]
+    fn test_filter_waypoints() {
+        let xml = r#"<gpx version="1.1" creator="test">
+            <wpt lat="46.0" lon="7.0"><name>Piz Buin</name><sym>Summit</sym></wpt>
+            <wpt lat="46.1" lon="7.1"><name>Hut</name><sym>Lodge</sym><type>Shelter</type></wpt>
+            <wpt lat="46.2" lon="7.2"><name>Parking</name></wpt>
+        </gpx>"#;
+        let gpx = Gpx::try_from_str(xml).unwrap();
+        assert_eq!(gpx.waypoints[1].kind.as_deref(), Some("Shelter"));
+
+        let mut summits = gpx.clone();
+        assert_eq!(summits.filter_waypoints_by_symbol("summit"), 2);
+        assert_eq!(summits.waypoint_names(), vec!["Piz Buin"]);
+
+        let mut shelters = gpx.clone();
+        shelters.filter_waypoints_by_type("Shelter");
+        assert_eq!(shelters.waypoint_names(), vec!["Hut"]);
+
+        #[cfg(feature = "regex")]
+        {
+            let mut named = gpx.clone();
+            named.filter_waypoints_by_name(&Regex::new("^P").unwrap());
+            assert_eq!(named.waypoint_names(), vec!["Piz Buin", "Parking"]);
+
+            let mut any = gpx.clone();
+            let filters = ["sym=Lodge".parse().unwrap(), "name=king$".parse().unwrap()];
+            assert_eq!(any.keep_waypoints(&filters), 1);
+            assert_eq!(any.waypoint_names(), vec!["Hut", "Parking"]);
+        }
+
+        let mut custom = gpx;
+        custom.filter_waypoints_by(|w| w.lat > 46.05);
+        assert_eq!(custom.waypoints.len(), 2);
+
+        let output = summits.to_xml();
+        assert!(output.contains("<sym>Summit</sym>"));
+    }
+
+    #[test]
+    fn test_parse_gpx_1_0_document() {
+        let xml = r#"<?xml version="1.0"?>
+        <gpx version="1.0" creator="GPSBabel" xmlns="http://www.topografix.com/GPX/1/0">
+          <name>Legacy export</name>
+          <time>2008-05-01T07:30:00Z</time>
+          <bounds minlat="46.0" minlon="7.0" maxlat="46.1" maxlon="7.1"/>
+          <wpt lat="46.05" lon="7.05"><name>Hut</name><sym>Lodge</sym></wpt>
+          <trk>
+            <name>Day 1</name>
+            <trkseg>
+              <trkpt lat="46.0" lon="7.0"><ele>1500</ele><course>12.5</course><speed>1.2</speed></trkpt>
+              <trkpt lat="46.1" lon="7.1"><ele>1600</ele></trkpt>
+            </trkseg>
+          </trk>
+        </gpx>"#;
+
+        let gpx = Gpx::try_from_str(xml).unwrap();
+        assert_eq!(gpx.date(), Some("2008-05-01T07:30:00Z"));
+        let metadata = gpx.metadata.as_ref().unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Legacy export"));
+        assert_eq!(metadata.bounds.unwrap().max_lat, 46.1);
+        assert_eq!(gpx.track_names(), vec!["Day 1"]);
+        assert_eq!(gpx.total_points(), 2);
+        assert_eq!(gpx.waypoints[0].symbol.as_deref(), Some("Lodge"));
+
+        let v10 = gpx.to_xml_v10();
+        assert!(v10.contains("<name>Legacy export</name><time>"));
+        let reparsed = Gpx::try_from_str(&v10).unwrap();
+        assert_eq!(reparsed.metadata, gpx.metadata);
+        assert_eq!(reparsed.total_points(), 2);
+    }
+
+    #[test]
+    fn test_version_and_creator_survive_a_roundtrip() {
+        let xml = r#"<gpx version="1.0" creator="GPSBabel"><trk><name>Old</name></trk></gpx>"#;
+        let mut gpx = Gpx::try_from_str(xml).unwrap();
+        assert_eq!(gpx.version, GpxVersion::V1_0);
+        assert_eq!(gpx.creator(), "GPSBabel");
+
+        let output = gpx.to_xml();
+        assert!(output.contains(r#"<gpx version="1.0" creator="GPSBabel""#));
+        let reparsed = Gpx::try_from_str(&output).unwrap();
+        assert_eq!(
+            (reparsed.version, reparsed.creator, reparsed.tracks),
+            (gpx.version, gpx.creator.clone(), gpx.tracks.clone())
+        );
+
+        gpx.set_creator("MyApp");
+        assert!(gpx.to_xml_pretty().contains(r#"creator="MyApp""#));
+        // Las opciones explícitas mandan sobre la versión leída
+        assert!(gpx
+            .to_xml_with(&WriteOptions::default())
+            .unwrap()
+            .contains(r#"version="1.1""#));
+
+        let unbranded = Gpx::try_from_str("<gpx/>").unwrap();
+        assert_eq!(unbranded.creator, None);
+        assert_eq!(unbranded.creator(), "gpx-extractor");
+    }
 }
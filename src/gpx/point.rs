@@ -1,10 +1,14 @@
+use crate::gpx::error::GpxError;
+use crate::gpx::extensions::PointExtensions;
+use crate::gpx::unknown::UnknownXml;
+use crate::gpx::user_data::UserData;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A geographic point with latitude, longitude, and optional elevation and timestamp
 ///
 /// Represents a single point in a GPS track or a waypoint location.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Point {
     /// Latitude in decimal degrees (WGS84)
     #[serde(rename = "@lat")]
@@ -18,6 +22,52 @@ pub struct Point {
     /// Timestamp of when the point was recorded
     #[serde(rename = "time", skip_serializing_if = "Option::is_none")]
     pub time: Option<DateTime<Utc>>,
+    /// Type of GPS fix (`<fix>`): `none`, `2d`, `3d`, `dgps` or `pps`
+    #[serde(rename = "fix", default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+    /// Number of satellites used for the fix (`<sat>`)
+    #[serde(rename = "sat", default, skip_serializing_if = "Option::is_none")]
+    pub satellites: Option<u32>,
+    /// Horizontal dilution of precision (`<hdop>`); lower is more accurate
+    #[serde(rename = "hdop", default, skip_serializing_if = "Option::is_none")]
+    pub hdop: Option<f64>,
+    /// Vertical dilution of precision (`<vdop>`)
+    #[serde(rename = "vdop", default, skip_serializing_if = "Option::is_none")]
+    pub vdop: Option<f64>,
+    /// Position dilution of precision (`<pdop>`)
+    #[serde(rename = "pdop", default, skip_serializing_if = "Option::is_none")]
+    pub pdop: Option<f64>,
+    /// Seconds since the last DGPS update (`<ageofdgpsdata>`)
+    #[serde(
+        rename = "ageofdgpsdata",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dgps_age: Option<f64>,
+    /// Id of the DGPS station used (`<dgpsid>`)
+    #[serde(rename = "dgpsid", default, skip_serializing_if = "Option::is_none")]
+    pub dgps_id: Option<u16>,
+    /// Child elements not modeled by this crate (`<magvar>`, `<geoidheight>`...), written back as read
+    #[serde(
+        rename = "gpx_extractor_raw",
+        skip_deserializing,
+        skip_serializing_if = "UnknownXml::is_empty"
+    )]
+    pub unknown: UnknownXml,
+    /// Sensor data such as heart rate, cadence and power
+    #[serde(
+        rename = "extensions",
+        default,
+        skip_serializing_if = "PointExtensions::is_empty"
+    )]
+    pub extensions: PointExtensions,
+    /// Application annotations, written into a `gpxud:UserData` extension
+    #[serde(
+        rename = "gpx_extractor_user_data",
+        skip_deserializing,
+        skip_serializing_if = "UserData::is_empty"
+    )]
+    pub user_data: UserData,
 }
 
 impl Point {
@@ -28,34 +78,180 @@ impl Point {
             lon,
             elevation: None,
             time: None,
+            fix: None,
+            satellites: None,
+            hdop: None,
+            vdop: None,
+            pdop: None,
+            dgps_age: None,
+            dgps_id: None,
+            unknown: UnknownXml::default(),
+            extensions: PointExtensions::default(),
+            user_data: UserData::default(),
         }
     }
 
     /// Crea un nuevo punto con elevación
     pub fn with_elevation(lat: f64, lon: f64, elevation: f64) -> Self {
         Self {
-            lat,
-            lon,
             elevation: Some(elevation),
-            time: None,
+            ..Self::new(lat, lon)
         }
     }
 
     /// Crea un nuevo punto completo con tiempo
     pub fn with_time(lat: f64, lon: f64, elevation: Option<f64>, time: DateTime<Utc>) -> Self {
         Self {
-            lat,
-            lon,
             elevation,
             time: Some(time),
+            ..Self::new(lat, lon)
+        }
+    }
+
+    /// Checks that a coordinate pair lies within the WGS84 range
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidCoordinate`] if the latitude is outside `[-90, 90]`
+    /// or the longitude is outside `[-180, 180]` (NaN included).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Point;
+    ///
+    /// assert!(Point::check_coordinates(40.4168, -3.7038).is_ok());
+    /// assert!(Point::check_coordinates(91.0, 0.0).is_err());
+    /// ```
+    pub fn check_coordinates(lat: f64, lon: f64) -> Result<(), GpxError> {
+        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+            Ok(())
+        } else {
+            Err(GpxError::InvalidCoordinate { lat, lon })
+        }
+    }
+
+    /// Frecuencia cardiaca en pulsaciones por minuto, si se registró
+    pub fn heart_rate(&self) -> Option<u8> {
+        self.extensions.heart_rate
+    }
+
+    /// Cadencia por minuto, si se registró
+    pub fn cadence(&self) -> Option<u8> {
+        self.extensions.cadence
+    }
+
+    /// Potencia en vatios, si se registró
+    pub fn power(&self) -> Option<u16> {
+        self.extensions.power
+    }
+
+    /// Initial great-circle bearing towards `other`, in degrees clockwise from north (0 to 360)
+    ///
+    /// This is the compass course to follow when leaving this point; on long
+    /// legs the course changes along the way. Returns 0 for identical positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Point;
+    ///
+    /// let origin = Point::new(0.0, 0.0);
+    /// assert!((origin.bearing_to(&Point::new(0.0, 1.0)) - 90.0).abs() < 1e-9);
+    /// assert!((origin.bearing_to(&Point::new(-1.0, 0.0)) - 180.0).abs() < 1e-9);
+    /// assert!((origin.bearing_to(&Point::new(1.0, -1.0)) - 315.0).abs() < 0.01);
+    /// ```
+    pub fn bearing_to(&self, other: &Point) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let delta_lon = (other.lon - self.lon).to_radians();
+        let y = delta_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// Geohash of the position with `precision` characters, capped at [`MAX_GEOHASH_PRECISION`]
+    ///
+    /// Each extra character makes the cell about 32 times smaller: 5 characters
+    /// cover roughly 5 × 5 km and 7 characters about 150 × 150 m. Points sharing a
+    /// prefix are close to each other, which makes geohashes handy as keys for
+    /// grouping or indexing positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Point;
+    ///
+    /// let sol = Point::new(40.416_8, -3.703_8);
+    /// assert_eq!(sol.geohash(7), "ezjmgtw");
+    /// assert_eq!(sol.geohash(20).len(), 12);
+    /// ```
+    pub fn geohash(&self, precision: usize) -> String {
+        let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+        let precision = precision.min(MAX_GEOHASH_PRECISION);
+        let mut hash = String::with_capacity(precision);
+        // Los bits alternan longitud y latitud, empezando por la longitud
+        let mut even = true;
+        let (mut bits, mut index) = (0, 0);
+        while hash.len() < precision {
+            let (range, value): (&mut (f64, f64), f64) = if even {
+                (&mut lon_range, self.lon)
+            } else {
+                (&mut lat_range, self.lat)
+            };
+            let middle = (range.0 + range.1) / 2.0;
+            index <<= 1;
+            if value >= middle {
+                index |= 1;
+                range.0 = middle;
+            } else {
+                range.1 = middle;
+            }
+            even = !even;
+            bits += 1;
+            if bits == 5 {
+                hash.push(char::from(GEOHASH_ALPHABET[index]));
+                (bits, index) = (0, 0);
+            }
         }
+        hash
+    }
+}
+
+/// Alfabeto base 32 de los geohash
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest geohash returned by [`Point::geohash`], about 4 cm across
+pub const MAX_GEOHASH_PRECISION: usize = 12;
+
+/// Punto intermedio entre `a` (`fraction` 0) y `b` (`fraction` 1)
+///
+/// Interpola linealmente posición, elevación y tiempo cuando ambos extremos los tienen;
+/// los datos de sensores no se copian.
+pub(crate) fn interpolate(a: &Point, b: &Point, fraction: f64) -> Point {
+    let lerp = |from: f64, to: f64| from + (to - from) * fraction;
+    let mut point = Point::new(lerp(a.lat, b.lat), lerp(a.lon, b.lon));
+    point.elevation = a
+        .elevation
+        .zip(b.elevation)
+        .map(|(from, to)| lerp(from, to));
+    if let (Some(from), Some(to)) = (a.time, b.time) {
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        let offset = ((to - from).num_milliseconds() as f64 * fraction).round() as i64;
+        point.time = Some(from + chrono::Duration::milliseconds(offset));
     }
+    point
 }
 
+/// Mean radius of the Earth, in kilometers
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
 /// Calcula la distancia Haversine entre dos puntos en kilómetros
 pub fn haversine_distance(p1: &Point, p2: &Point) -> f64 {
-    const R: f64 = 6371.0; // Radio de la Tierra en km
+    haversine_distance_with_radius(p1, p2, EARTH_RADIUS_KM)
+}
 
+/// Calcula la distancia Haversine sobre una esfera de radio `radius_km`, en kilómetros
+pub fn haversine_distance_with_radius(p1: &Point, p2: &Point, radius_km: f64) -> f64 {
     let lat1_rad = p1.lat.to_radians();
     let lat2_rad = p2.lat.to_radians();
     let delta_lat = (p2.lat - p1.lat).to_radians();
@@ -66,13 +262,41 @@ pub fn haversine_distance(p1: &Point, p2: &Point) -> f64 {
 
     let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
 
-    R * c
+    radius_km * c
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_check_coordinates() {
+        assert!(Point::check_coordinates(40.4168, -3.7038).is_ok());
+        assert!(Point::check_coordinates(90.0, 180.0).is_ok());
+        assert!(matches!(
+            Point::check_coordinates(91.0, 0.0),
+            Err(GpxError::InvalidCoordinate { .. })
+        ));
+        assert!(Point::check_coordinates(0.0, -180.5).is_err());
+        assert!(Point::check_coordinates(f64::NAN, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_bearing_to() {
+        let madrid = Point::new(40.4168, -3.7038);
+        let paris = Point::new(48.8566, 2.3522);
+        assert!((madrid.bearing_to(&paris) - 24.96).abs() < 0.01);
+        assert!((paris.bearing_to(&madrid) - 209.23).abs() < 0.01);
+        assert_eq!(madrid.bearing_to(&madrid), 0.0);
+    }
+
+    #[test]
+    fn test_geohash() {
+        assert_eq!(Point::new(57.649_11, 10.407_44).geohash(11), "u4pruydqqvj");
+        assert_eq!(Point::new(-25.382_708, -49.265_506).geohash(6), "6gkzwg");
+        assert_eq!(Point::new(0.0, 0.0).geohash(0), "");
+    }
+
     #[test]
     fn test_point_new() {
         let point = Point::new(40.7128, -74.0060);
@@ -120,4 +344,24 @@ mod tests {
         assert!(distance > 5000.0); // Más de 5000 km
         assert!(distance < 7000.0); // Menos de 7000 km
     }
+
+    #[test]
+    fn test_accuracy_fields_round_trip() {
+        let xml = r#"<gpx><trk><trkseg><trkpt lat="40.0" lon="-3.0"><ele>600</ele>
+            <fix>3d</fix><sat>9</sat><hdop>0.9</hdop><vdop>1.4</vdop><pdop>1.7</pdop>
+            <ageofdgpsdata>4</ageofdgpsdata><dgpsid>17</dgpsid></trkpt></trkseg></trk></gpx>"#;
+        let gpx = crate::Gpx::try_from_str(xml).unwrap();
+        let point = &gpx.tracks[0].segments[0].points[0];
+        assert_eq!(point.fix.as_deref(), Some("3d"));
+        assert_eq!(point.satellites, Some(9));
+        assert_eq!(
+            (point.hdop, point.vdop, point.pdop),
+            (Some(0.9), Some(1.4), Some(1.7))
+        );
+        assert_eq!((point.dgps_age, point.dgps_id), (Some(4.0), Some(17)));
+        assert!(point.unknown.is_empty());
+        assert!(gpx.to_xml().contains(
+            "<ele>600</ele><fix>3d</fix><sat>9</sat><hdop>0.9</hdop><vdop>1.4</vdop><pdop>1.7</pdop><ageofdgpsdata>4</ageofdgpsdata><dgpsid>17</dgpsid></trkpt>"
+        ));
+    }
 }
@@ -0,0 +1,225 @@
+use crate::gpx::{
+    error::GpxError,
+    point::Point,
+    track::{Track, TrackSegment},
+};
+
+/// Precision of Google's encoded polylines (five decimal places)
+pub const DEFAULT_POLYLINE_PRECISION: u32 = 5;
+
+/// Highest supported precision; beyond ten decimal places the scaled
+/// coordinates no longer fit the encoder's integers
+pub const MAX_POLYLINE_PRECISION: u32 = 10;
+
+/// Añade un valor con signo al resultado en trozos de 5 bits
+// El valor en zigzag nunca es negativo y cada trozo cabe en un byte
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn encode_value(value: i64, out: &mut String) {
+    let mut value = (value << 1) ^ (value >> 63);
+    while value >= 0x20 {
+        out.push(char::from((0x20 | (value & 0x1f)) as u8 + 63));
+        value >>= 5;
+    }
+    out.push(char::from(value as u8 + 63));
+}
+
+/// Factor de escala para `precision` decimales, ya acotada
+fn polyline_factor(precision: u32) -> f64 {
+    10f64.powi(i32::try_from(precision).unwrap_or(i32::MAX))
+}
+
+/// Lee el siguiente valor con signo, avanzando el iterador
+fn decode_value(bytes: &mut impl Iterator<Item = u8>) -> Result<i64, GpxError> {
+    let mut value: i64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes
+            .next()
+            .ok_or_else(|| GpxError::Schema("invalid polyline: truncated value".to_string()))?;
+        if !(63..=126).contains(&byte) || shift > 60 {
+            return Err(GpxError::Schema(format!(
+                "invalid polyline: unexpected character {:?}",
+                char::from(byte)
+            )));
+        }
+        let chunk = i64::from(byte - 63);
+        value |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk < 0x20 {
+            break;
+        }
+    }
+    Ok((value >> 1) ^ -(value & 1))
+}
+
+impl Track {
+    /// Encodes every point of the track with the encoded polyline algorithm
+    ///
+    /// Segments are concatenated and only latitude and longitude are kept.
+    /// `precision` is the number of decimal places: 5 for Google Maps
+    /// ([`DEFAULT_POLYLINE_PRECISION`]), 6 for OSRM or Valhalla. Values above
+    /// [`MAX_POLYLINE_PRECISION`] are clamped to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Point, Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::with_points(vec![
+    ///     Point::new(38.5, -120.2),
+    ///     Point::new(40.7, -120.95),
+    ///     Point::new(43.252, -126.453),
+    /// ]));
+    /// assert_eq!(track.to_polyline(5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    /// ```
+    pub fn to_polyline(&self, precision: u32) -> String {
+        let factor = polyline_factor(precision.min(MAX_POLYLINE_PRECISION));
+        #[allow(clippy::cast_possible_truncation)]
+        let scale = |coordinate: f64| (coordinate * factor).round() as i64;
+
+        let mut out = String::new();
+        let (mut last_lat, mut last_lon) = (0, 0);
+        for point in self.segments.iter().flat_map(|segment| &segment.points) {
+            let (lat, lon) = (scale(point.lat), scale(point.lon));
+            encode_value(lat - last_lat, &mut out);
+            encode_value(lon - last_lon, &mut out);
+            (last_lat, last_lon) = (lat, lon);
+        }
+        out
+    }
+
+    /// Decodes a Google encoded polyline (precision 5) into a single-segment track
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Schema`] if the string is not a valid polyline, and
+    /// [`GpxError::InvalidCoordinate`] if it decodes to an out-of-range point
+    pub fn from_polyline(encoded: &str) -> Result<Track, GpxError> {
+        Self::from_polyline_with_precision(encoded, DEFAULT_POLYLINE_PRECISION)
+    }
+
+    /// Decodes a polyline encoded with `precision` decimal places
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Schema`] if the string is not a valid polyline or
+    /// `precision` exceeds [`MAX_POLYLINE_PRECISION`], and
+    /// [`GpxError::InvalidCoordinate`] if it decodes to an out-of-range point
+    pub fn from_polyline_with_precision(encoded: &str, precision: u32) -> Result<Track, GpxError> {
+        if precision > MAX_POLYLINE_PRECISION {
+            return Err(GpxError::Schema(format!(
+                "invalid polyline: precision {precision} is above {MAX_POLYLINE_PRECISION}"
+            )));
+        }
+        let factor = polyline_factor(precision);
+        let mut bytes = encoded.trim().bytes().peekable();
+        let mut segment = TrackSegment::new();
+        let (mut lat, mut lon) = (0i64, 0i64);
+        while bytes.peek().is_some() {
+            lat = lat.saturating_add(decode_value(&mut bytes)?);
+            lon = lon.saturating_add(decode_value(&mut bytes)?);
+            #[allow(clippy::cast_precision_loss)]
+            let (lat, lon) = (lat as f64 / factor, lon as f64 / factor);
+            Point::check_coordinates(lat, lon)?;
+            segment.add_point(Point::new(lat, lon));
+        }
+
+        let mut track = Track::new();
+        track.add_segment(segment);
+        Ok(track)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Track {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::new(38.5, -120.2),
+            Point::new(40.7, -120.95),
+        ]));
+        track.add_segment(TrackSegment::with_points(vec![Point::new(
+            43.252, -126.453,
+        )]));
+        track
+    }
+
+    #[test]
+    fn test_polyline_reference_example() {
+        let encoded = sample().to_polyline(DEFAULT_POLYLINE_PRECISION);
+        assert_eq!(encoded, "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+
+        let decoded = Track::from_polyline(&encoded).unwrap();
+        assert_eq!(decoded.segments.len(), 1);
+        let coordinates: Vec<(f64, f64)> = decoded.segments[0]
+            .points
+            .iter()
+            .map(|p| (p.lat, p.lon))
+            .collect();
+        assert_eq!(
+            coordinates,
+            vec![(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)]
+        );
+    }
+
+    #[test]
+    fn test_polyline_precision_6_roundtrip() {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::new(40.416_775, -3.703_790),
+            Point::new(-33.868_820, 151.209_296),
+            Point::new(0.0, 0.0),
+        ]));
+        let encoded = track.to_polyline(6);
+        let decoded = Track::from_polyline_with_precision(&encoded, 6).unwrap();
+
+        for (a, b) in track.segments[0]
+            .points
+            .iter()
+            .zip(&decoded.segments[0].points)
+        {
+            assert!((a.lat - b.lat).abs() < 1e-9);
+            assert!((a.lon - b.lon).abs() < 1e-9);
+        }
+        assert_eq!(Track::new().to_polyline(5), "");
+    }
+
+    #[test]
+    fn test_invalid_polyline() {
+        assert!(matches!(
+            Track::from_polyline("_p~iF~ps|"),
+            Err(GpxError::Schema(msg)) if msg.contains("truncated")
+        ));
+        assert!(matches!(
+            Track::from_polyline("_p~iF ps|U"),
+            Err(GpxError::Schema(msg)) if msg.contains("unexpected")
+        ));
+    }
+
+    #[test]
+    fn test_polyline_precision_limit_and_range() {
+        let track = sample();
+        assert_eq!(
+            track.to_polyline(18),
+            track.to_polyline(MAX_POLYLINE_PRECISION)
+        );
+        let decoded =
+            Track::from_polyline_with_precision(&track.to_polyline(18), MAX_POLYLINE_PRECISION)
+                .unwrap();
+        assert_eq!(decoded.segments[0].points[2].lon, -126.453);
+        assert!(matches!(
+            Track::from_polyline_with_precision("??", 11),
+            Err(GpxError::Schema(msg)) if msg.contains("precision")
+        ));
+
+        let mut outside = Track::new();
+        outside.add_segment(TrackSegment::with_points(vec![Point::new(95.0, 0.0)]));
+        assert!(matches!(
+            Track::from_polyline(&outside.to_polyline(5)),
+            Err(GpxError::InvalidCoordinate { .. })
+        ));
+    }
+}
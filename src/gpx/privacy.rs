@@ -0,0 +1,146 @@
+use crate::gpx::parser::Gpx;
+use chrono::{DateTime, Duration, DurationRound, SecondsFormat, Utc};
+
+/// Redondea al múltiplo más cercano de `resolution`; deja el tiempo intacto si falla
+fn round(time: DateTime<Utc>, resolution: Duration) -> DateTime<Utc> {
+    time.duration_round(resolution).unwrap_or(time)
+}
+
+impl Gpx {
+    /// Rounds every timestamp to the nearest multiple of `resolution`
+    ///
+    /// Track points, waypoints and the metadata time are all rounded, so a shared
+    /// track does not reveal the exact schedule. Rounding never reorders points,
+    /// though consecutive points may end up with the same time. A zero or
+    /// negative resolution leaves the document unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:03:10Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// gpx.coarsen_times(Duration::minutes(5));
+    /// let time = gpx.tracks[0].segments[0].points[0].time.unwrap();
+    /// assert_eq!(time.to_rfc3339(), "2024-07-01T08:05:00+00:00");
+    /// ```
+    pub fn coarsen_times(&mut self, resolution: Duration) {
+        if resolution <= Duration::zero() {
+            return;
+        }
+        self.map_times(|time| round(time, resolution));
+    }
+
+    /// Shifts every timestamp by `offset`, e.g. to fix a device clock set to the
+    /// wrong time zone
+    ///
+    /// Track points, waypoints and the metadata time move together, so
+    /// durations and speeds do not change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:03:10Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// gpx.shift_times(Duration::hours(-2));
+    /// let time = gpx.tracks[0].segments[0].points[0].time.unwrap();
+    /// assert_eq!(time.to_rfc3339(), "2024-07-01T06:03:10+00:00");
+    /// ```
+    pub fn shift_times(&mut self, offset: Duration) {
+        self.map_times(|time| time + offset);
+    }
+
+    /// Aplica `change` a los tiempos de puntos, waypoints y metadata
+    fn map_times(&mut self, change: impl Fn(DateTime<Utc>) -> DateTime<Utc>) {
+        let points = self
+            .tracks
+            .iter_mut()
+            .flat_map(|track| &mut track.segments)
+            .flat_map(|segment| &mut segment.points);
+        for point in points {
+            point.time = point.time.map(&change);
+        }
+        for waypoint in &mut self.waypoints {
+            waypoint.time = waypoint.time.map(&change);
+        }
+
+        if let Some(metadata) = self.metadata.as_mut() {
+            let parsed = metadata
+                .time
+                .as_deref()
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok());
+            if let Some(time) = parsed {
+                let changed = change(time.with_timezone(&Utc));
+                metadata.time = Some(changed.to_rfc3339_opts(SecondsFormat::Secs, true));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{point::Point, track::Track, track::TrackSegment, waypoint::Waypoint};
+    use chrono::TimeZone;
+
+    fn at(minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 7, 1, 8, minute, second).unwrap()
+    }
+
+    #[test]
+    fn test_coarsen_times_rounds_everything_and_keeps_order() {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(40.0, -3.0, None, at(1, 0)),
+            Point::with_time(40.1, -3.0, None, at(3, 59)),
+            Point::with_time(40.2, -3.0, None, at(7, 30)),
+            Point::new(40.3, -3.0),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        let mut waypoint = Waypoint::new(40.0, -3.0);
+        waypoint.time = Some(at(12, 31));
+        gpx.add_waypoint(waypoint);
+        gpx.metadata_mut().time = Some("2024-07-01T08:01:00+02:00".to_string());
+
+        gpx.coarsen_times(Duration::minutes(5));
+
+        let times: Vec<_> = gpx.tracks[0].segments[0]
+            .points
+            .iter()
+            .map(|p| p.time)
+            .collect();
+        assert_eq!(
+            times,
+            vec![Some(at(0, 0)), Some(at(5, 0)), Some(at(10, 0)), None]
+        );
+        assert!(times.windows(2).all(|w| w[1].is_none() || w[0] <= w[1]));
+        assert_eq!(gpx.waypoints[0].time, Some(at(15, 0)));
+        assert_eq!(gpx.date(), Some("2024-07-01T06:00:00Z"));
+    }
+
+    #[test]
+    fn test_coarsen_times_ignores_non_positive_resolution() {
+        let mut gpx = Gpx::new();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![Point::with_time(
+            40.0,
+            -3.0,
+            None,
+            at(1, 7),
+        )]));
+        gpx.add_track(track);
+
+        gpx.coarsen_times(Duration::zero());
+        assert_eq!(gpx.tracks[0].segments[0].points[0].time, Some(at(1, 7)));
+    }
+}
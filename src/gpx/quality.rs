@@ -0,0 +1,368 @@
+use crate::gpx::{outliers::OutlierOptions, parser::Gpx, point::Point};
+use std::fmt;
+
+/// Dilution of precision up to which a fix counts as good
+const GOOD_DOP: f64 = 2.0;
+
+/// Aspect of a recording rated by [`Gpx::quality_report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QualityCriterion {
+    /// How evenly spaced in time the points are
+    SamplingRegularity,
+    /// Positional accuracy reported by the receiver
+    Accuracy,
+    /// Share of points that look like GPS glitches (see [`Gpx::remove_outliers`])
+    OutlierRate,
+    /// Share of points with an elevation
+    ElevationCompleteness,
+    /// Whether timestamps always move forward
+    TimestampMonotonicity,
+}
+
+impl fmt::Display for QualityCriterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SamplingRegularity => "Sampling regularity",
+            Self::Accuracy => "Accuracy",
+            Self::OutlierRate => "Outlier rate",
+            Self::ElevationCompleteness => "Elevation completeness",
+            Self::TimestampMonotonicity => "Timestamp monotonicity",
+        })
+    }
+}
+
+/// Rating of one [`QualityCriterion`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityScore {
+    /// What was rated
+    pub criterion: QualityCriterion,
+    /// Score from 0 (worst) to 100 (best); `None` if the recording has no data to judge it
+    pub score: Option<f64>,
+    /// Human-readable explanation of the score
+    pub finding: String,
+}
+
+/// Outcome of [`Gpx::quality_report`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QualityReport {
+    /// One score per criterion
+    pub scores: Vec<QualityScore>,
+}
+
+impl QualityReport {
+    /// Media de las puntuaciones disponibles, de 0 a 100
+    pub fn overall(&self) -> Option<f64> {
+        let known: Vec<f64> = self.scores.iter().filter_map(|s| s.score).collect();
+        #[allow(clippy::cast_precision_loss)]
+        let mean = (!known.is_empty()).then(|| known.iter().sum::<f64>() / known.len() as f64);
+        mean
+    }
+
+    /// Puntuación de un criterio concreto
+    pub fn score(&self, criterion: QualityCriterion) -> Option<f64> {
+        self.scores
+            .iter()
+            .find(|s| s.criterion == criterion)
+            .and_then(|s| s.score)
+    }
+
+    /// Obtiene una descripción legible del informe
+    pub fn summary(&self) -> String {
+        let mut summary = match self.overall() {
+            Some(overall) => format!("Quality: {overall:.0}/100"),
+            None => "Quality: unknown".to_string(),
+        };
+        for score in &self.scores {
+            let value = score
+                .score
+                .map_or_else(|| "n/a".to_string(), |value| format!("{value:.0}"));
+            summary.push_str(&format!(
+                "\n- {}: {value} ({})",
+                score.criterion, score.finding
+            ));
+        }
+        summary
+    }
+}
+
+/// Porcentaje de `part` sobre `total`
+#[allow(clippy::cast_precision_loss)]
+fn percent(part: usize, total: usize) -> f64 {
+    part as f64 / total as f64 * 100.0
+}
+
+/// Intervalos en segundos entre puntos consecutivos con tiempo, dentro de cada segmento
+fn intervals(gpx: &Gpx) -> Vec<f64> {
+    let mut intervals = Vec::new();
+    for segment in gpx.tracks.iter().flat_map(|track| &track.segments) {
+        let times: Vec<_> = segment.points.iter().filter_map(|p| p.time).collect();
+        #[allow(clippy::cast_precision_loss)]
+        intervals.extend(
+            times
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).num_milliseconds() as f64 / 1000.0),
+        );
+    }
+    intervals
+}
+
+fn sampling_regularity(intervals: &[f64]) -> QualityScore {
+    let mut forward: Vec<f64> = intervals.iter().copied().filter(|&s| s > 0.0).collect();
+    let (score, finding) = if forward.len() < 2 {
+        (None, "Not enough timestamps to judge sampling".to_string())
+    } else {
+        forward.sort_by(f64::total_cmp);
+        let median = forward[forward.len() / 2];
+        // Se toleran intervalos entre la mitad y el doble de la mediana
+        let regular = forward
+            .iter()
+            .filter(|&&s| s >= median / 2.0 && s <= median * 2.0)
+            .count();
+        let share = percent(regular, forward.len());
+        (
+            Some(share),
+            format!("Median interval {median:.1} s; {share:.0}% of intervals are regular"),
+        )
+    };
+    QualityScore {
+        criterion: QualityCriterion::SamplingRegularity,
+        score,
+        finding,
+    }
+}
+
+fn timestamp_monotonicity(intervals: &[f64]) -> QualityScore {
+    let backwards = intervals.iter().filter(|&&s| s <= 0.0).count();
+    let (score, finding) = match (intervals.len(), backwards) {
+        (0, _) => (
+            None,
+            "Not enough timestamps to check their order".to_string(),
+        ),
+        (_, 0) => (Some(100.0), "All timestamps move forward".to_string()),
+        (total, bad) => (
+            Some(100.0 - percent(bad, total)),
+            format!("{bad} of {total} timestamps repeat or go backwards"),
+        ),
+    };
+    QualityScore {
+        criterion: QualityCriterion::TimestampMonotonicity,
+        score,
+        finding,
+    }
+}
+
+fn accuracy(points: &[&Point]) -> QualityScore {
+    // HDOP describe la posición en el plano; PDOP sirve si el receptor no da HDOP
+    let mut dops: Vec<f64> = points
+        .iter()
+        .filter_map(|p| p.hdop.or(p.pdop))
+        .filter(|dop| dop.is_finite())
+        .collect();
+    let (score, finding) = if dops.is_empty() {
+        (
+            None,
+            "No accuracy fields (HDOP, VDOP, PDOP) recorded".to_string(),
+        )
+    } else {
+        dops.sort_by(f64::total_cmp);
+        let median = dops[dops.len() / 2];
+        let good = dops.iter().filter(|&&dop| dop <= GOOD_DOP).count();
+        let share = percent(good, dops.len());
+        (
+            Some(share),
+            format!(
+                "Median dilution of precision {median:.1}; {share:.0}% of {} rated points at or below {GOOD_DOP}",
+                dops.len()
+            ),
+        )
+    };
+    QualityScore {
+        criterion: QualityCriterion::Accuracy,
+        score,
+        finding,
+    }
+}
+
+fn outlier_rate(gpx: &Gpx, total: usize) -> QualityScore {
+    let outliers = gpx.clone().remove_outliers(OutlierOptions::default()).len();
+    let (score, finding) = if total == 0 {
+        (None, "No track points".to_string())
+    } else {
+        let rate = percent(outliers, total);
+        // Un 1 % de puntos anómalos ya resta 10 puntos
+        (
+            Some((100.0 - rate * 10.0).max(0.0)),
+            format!("{outliers} of {total} points look like GPS glitches ({rate:.1}%)"),
+        )
+    };
+    QualityScore {
+        criterion: QualityCriterion::OutlierRate,
+        score,
+        finding,
+    }
+}
+
+fn elevation_completeness(points: &[&Point]) -> QualityScore {
+    let with_elevation = points.iter().filter(|p| p.elevation.is_some()).count();
+    let (score, finding) = if points.is_empty() {
+        (None, "No track points".to_string())
+    } else {
+        let share = percent(with_elevation, points.len());
+        (
+            Some(share),
+            format!("{share:.0}% of points have an elevation"),
+        )
+    };
+    QualityScore {
+        criterion: QualityCriterion::ElevationCompleteness,
+        score,
+        finding,
+    }
+}
+
+impl Gpx {
+    /// Rates the integrity of the recording, criterion by criterion
+    ///
+    /// Every score goes from 0 to 100 and comes with a short finding; criteria
+    /// without data to judge (such as sampling on an untimed route) have no score
+    /// and are left out of [`QualityReport::overall`]. Comparing the overall
+    /// score of two recordings of the same activity tells which one to keep.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, QualityCriterion};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0000" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.0001" lon="-3.0"><ele>601</ele><time>2024-07-01T08:00:05Z</time></trkpt>
+    ///     <trkpt lat="40.0002" lon="-3.0"><time>2024-07-01T08:00:04Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let report = gpx.quality_report();
+    /// assert_eq!(report.score(QualityCriterion::TimestampMonotonicity), Some(50.0));
+    /// assert!(report.overall().unwrap() < 100.0);
+    /// assert!(report.summary().contains("1 of 2 timestamps repeat or go backwards"));
+    /// ```
+    pub fn quality_report(&self) -> QualityReport {
+        let points = self.get_all_points();
+        let intervals = intervals(self);
+        QualityReport {
+            scores: vec![
+                sampling_regularity(&intervals),
+                accuracy(&points),
+                outlier_rate(self, points.len()),
+                elevation_completeness(&points),
+                timestamp_monotonicity(&intervals),
+            ],
+        }
+    }
+
+    /// Removes the track points whose HDOP is above `max_hdop`
+    ///
+    /// Points without an HDOP are kept, since there is nothing to judge them by.
+    /// Returns how many points were removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let mut gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.0000" lon="-3.0"><hdop>0.9</hdop></trkpt>
+    ///     <trkpt lat="40.0001" lon="-3.0"><hdop>8.5</hdop></trkpt>
+    ///     <trkpt lat="40.0002" lon="-3.0"/>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// assert_eq!(gpx.filter_by_accuracy(2.0), 1);
+    /// assert_eq!(gpx.total_points(), 2);
+    /// ```
+    pub fn filter_by_accuracy(&mut self, max_hdop: f64) -> usize {
+        let before = self.total_points();
+        for segment in self.tracks.iter_mut().flat_map(|track| &mut track.segments) {
+            segment
+                .points
+                .retain(|point| point.hdop.map_or(true, |hdop| hdop <= max_hdop));
+        }
+        before - self.total_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn recording(seconds: &[i64], glitch: Option<usize>) -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let points = seconds
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| {
+                #[allow(clippy::cast_precision_loss)]
+                let mut lat = 40.0 + i as f64 * 0.0001;
+                if glitch == Some(i) {
+                    lat += 0.05;
+                }
+                Point::with_time(lat, -3.0, Some(600.0), start + Duration::seconds(s))
+            })
+            .collect();
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(points));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_clean_recording_scores_high() {
+        let gpx = recording(&[0, 10, 20, 30, 40, 50], None);
+        let report = gpx.quality_report();
+        assert_eq!(report.scores.len(), 5);
+        assert_eq!(
+            report.score(QualityCriterion::SamplingRegularity),
+            Some(100.0)
+        );
+        assert_eq!(report.score(QualityCriterion::Accuracy), None);
+        assert_eq!(report.score(QualityCriterion::OutlierRate), Some(100.0));
+        assert_eq!(report.overall(), Some(100.0));
+        assert!(report.summary().starts_with("Quality: 100/100"));
+    }
+
+    #[test]
+    fn test_accuracy_uses_hdop_then_pdop() {
+        let mut gpx = recording(&[0, 10, 20, 30], None);
+        let points = &mut gpx.tracks[0].segments[0].points;
+        points[0].hdop = Some(0.8);
+        points[1].hdop = Some(1.5);
+        points[2].pdop = Some(6.0);
+
+        let report = gpx.quality_report();
+        let accuracy = &report.scores[1];
+        assert_eq!(accuracy.criterion, QualityCriterion::Accuracy);
+        assert!((accuracy.score.unwrap() - 200.0 / 3.0).abs() < 1e-9);
+        assert!(accuracy
+            .finding
+            .starts_with("Median dilution of precision 1.5"));
+
+        assert_eq!(gpx.filter_by_accuracy(1.0), 1);
+        assert_eq!(gpx.total_points(), 3);
+    }
+
+    #[test]
+    fn test_glitchy_recording_scores_lower() {
+        let clean = recording(&[0, 10, 20, 30, 40, 50], None).quality_report();
+        let glitchy = recording(&[0, 10, 20, 60, 70, 80], Some(2)).quality_report();
+
+        assert!(glitchy.score(QualityCriterion::SamplingRegularity).unwrap() < 100.0);
+        let outliers = &glitchy.scores[2];
+        assert_eq!(outliers.criterion, QualityCriterion::OutlierRate);
+        assert!(outliers.finding.starts_with("1 of 6 points"));
+        assert!(glitchy.overall() < clean.overall());
+
+        let empty = Gpx::new().quality_report();
+        assert_eq!(empty.overall(), None);
+        assert!(empty.summary().starts_with("Quality: unknown"));
+    }
+}
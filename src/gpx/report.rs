@@ -0,0 +1,161 @@
+use crate::gpx::parser::{Gpx, GpxStatistics};
+use std::fmt::Write;
+
+/// Añade una línea `etiqueta: valor`, con `unknown` si falta el valor
+fn line(report: &mut String, label: &str, value: Option<String>) {
+    if !report.is_empty() {
+        report.push('\n');
+    }
+    let value = value.unwrap_or_else(|| "unknown".to_string());
+    let _ = write!(report, "{label}: {value}");
+}
+
+/// Formats statistics as plain `label: value` lines, one per metric
+///
+/// Meant for screen readers and other accessibility-conscious frontends: no
+/// emoji, box drawing or color, units spelled after the value, and metrics
+/// that cannot be computed reported as `unknown` instead of being left out.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::report::plain_statistics;
+/// use gpx_extractor::Gpx;
+///
+/// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+///     <trkpt lat="40.000" lon="-3.0"><ele>600</ele></trkpt>
+///     <trkpt lat="40.009" lon="-3.0"><ele>650</ele></trkpt>
+/// </trkseg></trk></gpx>"#).unwrap();
+///
+/// let report = plain_statistics(&gpx.statistics());
+/// assert!(report.contains("\nDistance: 1.00 km\n"));
+/// assert!(report.contains("\nDuration: unknown\n"));
+/// assert!(report.ends_with("Elevation loss: 0.0 m"));
+/// ```
+pub fn plain_statistics(stats: &GpxStatistics) -> String {
+    let mut report = String::new();
+    line(&mut report, "Tracks", Some(stats.total_tracks.to_string()));
+    line(
+        &mut report,
+        "Waypoints",
+        Some(stats.total_waypoints.to_string()),
+    );
+    line(
+        &mut report,
+        "Segments",
+        Some(stats.total_segments.to_string()),
+    );
+    line(&mut report, "Points", Some(stats.total_points.to_string()));
+    line(
+        &mut report,
+        "Distance",
+        Some(format!("{:.2} km", stats.total_distance_km)),
+    );
+    line(&mut report, "Duration", stats.duration_formatted());
+    line(
+        &mut report,
+        "Average speed",
+        stats
+            .average_speed_kmh
+            .map(|speed| format!("{speed:.2} km/h")),
+    );
+    line(
+        &mut report,
+        "Elevation range",
+        stats
+            .elevation_range
+            .map(|(min, max)| format!("{min:.1} m to {max:.1} m")),
+    );
+    line(
+        &mut report,
+        "Elevation gain",
+        stats.elevation_gain.map(|gain| format!("{gain:.1} m")),
+    );
+    line(
+        &mut report,
+        "Elevation loss",
+        stats.elevation_loss.map(|loss| format!("{loss:.1} m")),
+    );
+    report
+}
+
+/// Plain `label: value` summary of a document, as printed by `gpx-cli --plain`
+///
+/// The short form has the counts and the distance; the verbose one adds the
+/// date, every metric of [`plain_statistics`] and one line per track.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::report::plain_summary;
+/// use gpx_extractor::Gpx;
+///
+/// let gpx = Gpx::from_polyline_coords(&[(40.0, -3.0), (40.009, -3.0)]);
+/// assert_eq!(
+///     plain_summary(&gpx, false),
+///     "Tracks: 1\nWaypoints: 0\nPoints: 2\nDistance: 1.00 km"
+/// );
+/// assert!(plain_summary(&gpx, true).ends_with("Track 1: Unnamed Track, 1 segments, 2 points"));
+/// ```
+pub fn plain_summary(gpx: &Gpx, verbose: bool) -> String {
+    if !verbose {
+        let mut report = String::new();
+        line(&mut report, "Tracks", Some(gpx.tracks.len().to_string()));
+        line(
+            &mut report,
+            "Waypoints",
+            Some(gpx.waypoints.len().to_string()),
+        );
+        line(&mut report, "Points", Some(gpx.total_points().to_string()));
+        line(
+            &mut report,
+            "Distance",
+            Some(format!("{:.2} km", gpx.total_distance_km())),
+        );
+        return report;
+    }
+
+    let mut report = String::new();
+    line(&mut report, "Date", gpx.date().map(str::to_string));
+    report.push('\n');
+    report.push_str(&plain_statistics(&gpx.statistics()));
+    for (i, track) in gpx.tracks.iter().enumerate() {
+        line(
+            &mut report,
+            &format!("Track {}", i + 1),
+            Some(format!(
+                "{}, {} segments, {} points",
+                track.display_name(),
+                track.segments.len(),
+                track.total_points()
+            )),
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_report_has_no_decoration() {
+        let gpx = Gpx::try_from_str(
+            r#"<gpx><metadata><time>2024-07-01T08:00:00Z</time></metadata><trk><name>Ride</name><trkseg>
+                <trkpt lat="40.000" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+                <trkpt lat="40.009" lon="-3.0"><time>2024-07-01T08:05:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let report = plain_summary(&gpx, true);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Date: 2024-07-01T08:00:00Z");
+        assert!(lines.contains(&"Duration: 00:05:00"));
+        assert!(lines.contains(&"Average speed: 12.01 km/h"));
+        assert!(lines.contains(&"Elevation range: unknown"));
+        assert_eq!(lines.last(), Some(&"Track 1: Ride, 1 segments, 2 points"));
+        assert!(lines
+            .iter()
+            .all(|line| line.is_ascii() && line.contains(": ")));
+    }
+}
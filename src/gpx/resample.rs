@@ -0,0 +1,206 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, interpolate, Point},
+    track::{Track, TrackSegment},
+};
+
+/// Sampling step for [`TrackSegment::resample`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interval {
+    /// One point every given number of seconds; needs timestamps
+    Seconds(u32),
+    /// One point every given number of meters along the track
+    Meters(u32),
+}
+
+/// Muestrea `points` cada `step` unidades de `keys` (valores acumulados no decrecientes)
+fn sample(points: &[&Point], keys: &[f64], step: f64) -> Vec<Point> {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return Vec::new();
+    };
+    let total = keys.last().copied().unwrap_or(0.0);
+    if points.len() < 2 || step <= 0.0 || total <= 0.0 {
+        return points.iter().map(|&p| p.clone()).collect();
+    }
+
+    let mut samples = vec![(*first).clone()];
+    let mut j = 0;
+    for k in 1_usize.. {
+        #[allow(clippy::cast_precision_loss)]
+        let target = step * k as f64;
+        // Se evita un punto casi duplicado justo antes del final
+        if target >= total - step * 1e-6 {
+            break;
+        }
+        while keys[j + 1] < target {
+            j += 1;
+        }
+        let span = keys[j + 1] - keys[j];
+        let fraction = if span > 0.0 {
+            (target - keys[j]) / span
+        } else {
+            0.0
+        };
+        samples.push(interpolate(points[j], points[j + 1], fraction));
+    }
+    samples.push((*last).clone());
+    samples
+}
+
+impl TrackSegment {
+    /// Resamples the segment at a fixed time or distance interval
+    ///
+    /// Position, elevation and time are interpolated linearly between the
+    /// original points; sensor data is only kept on the first and last point,
+    /// which are always preserved. With [`Interval::Seconds`], points without a
+    /// timestamp are ignored and timestamps going backwards are treated as a
+    /// pause. A zero interval, or a time interval on a segment without
+    /// timestamps, returns the segment unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Interval, Point, TrackSegment};
+    ///
+    /// let segment = TrackSegment::with_points(vec![
+    ///     Point::new(40.0, -3.0),
+    ///     Point::new(40.001, -3.0),
+    /// ]);
+    ///
+    /// // ~111 m cada 10 m: 11 tramos completos más el punto final
+    /// let resampled = segment.resample(Interval::Meters(10));
+    /// assert_eq!(resampled.points.len(), 13);
+    /// assert_eq!(resampled.points[12].lat, 40.001);
+    /// ```
+    #[must_use]
+    pub fn resample(&self, interval: Interval) -> TrackSegment {
+        let points = match interval {
+            Interval::Seconds(0) | Interval::Meters(0) => self.points.clone(),
+            Interval::Meters(meters) => {
+                let points: Vec<&Point> = self.points.iter().collect();
+                let mut keys = Vec::with_capacity(points.len());
+                let mut total = 0.0;
+                for (i, point) in points.iter().enumerate() {
+                    if i > 0 {
+                        total += haversine_distance(points[i - 1], point) * 1000.0;
+                    }
+                    keys.push(total);
+                }
+                sample(&points, &keys, f64::from(meters))
+            }
+            Interval::Seconds(seconds) => {
+                let timed: Vec<(&Point, f64)> = match self.points.iter().find_map(|p| p.time) {
+                    Some(start) => self
+                        .points
+                        .iter()
+                        .filter_map(|p| {
+                            #[allow(clippy::cast_precision_loss)]
+                            p.time
+                                .map(|time| (p, (time - start).num_milliseconds() as f64 / 1000.0))
+                        })
+                        .collect(),
+                    None => return self.clone(),
+                };
+                let points: Vec<&Point> = timed.iter().map(|(p, _)| *p).collect();
+                // El tiempo acumulado nunca retrocede
+                let keys: Vec<f64> = timed
+                    .iter()
+                    .scan(f64::NEG_INFINITY, |max, (_, key)| {
+                        *max = max.max(*key);
+                        Some(*max)
+                    })
+                    .collect();
+                sample(&points, &keys, f64::from(seconds))
+            }
+        };
+        TrackSegment::with_points(points)
+    }
+}
+
+impl Track {
+    /// Resamples every segment of the track (see [`TrackSegment::resample`])
+    #[must_use]
+    pub fn resample(&self, interval: Interval) -> Track {
+        let mut track = self.clone();
+        track.segments = self
+            .segments
+            .iter()
+            .map(|segment| segment.resample(interval))
+            .collect();
+        track
+    }
+}
+
+impl Gpx {
+    /// Resamples every track at a fixed interval (see [`TrackSegment::resample`])
+    ///
+    /// Waypoints and metadata are kept as they are.
+    #[must_use]
+    pub fn resample(&self, interval: Interval) -> Gpx {
+        let mut gpx = self.clone();
+        gpx.tracks = self
+            .tracks
+            .iter()
+            .map(|track| track.resample(interval))
+            .collect();
+        gpx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_resample_seconds_interpolates_and_keeps_endpoints() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let mut first = Point::with_time(40.0, -3.0, Some(600.0), start);
+        first.extensions.heart_rate = Some(120);
+        let segment = TrackSegment::with_points(vec![
+            first,
+            Point::new(40.0005, -3.0),
+            Point::with_time(40.001, -3.0, Some(610.0), start + Duration::seconds(5)),
+            Point::with_time(
+                40.002,
+                -3.0,
+                Some(600.0),
+                start + Duration::milliseconds(7500),
+            ),
+        ]);
+
+        let resampled = segment.resample(Interval::Seconds(2));
+        let points = &resampled.points;
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0].heart_rate(), Some(120));
+        assert_eq!(points[1].time, Some(start + Duration::seconds(2)));
+        assert!((points[1].lat - 40.0004).abs() < 1e-9);
+        assert!((points[1].elevation.unwrap() - 604.0).abs() < 1e-9);
+        assert_eq!(points[3].time, Some(start + Duration::seconds(6)));
+        assert!((points[3].elevation.unwrap() - 606.0).abs() < 1e-9);
+        assert_eq!(points[4].time, Some(start + Duration::milliseconds(7500)));
+    }
+
+    #[test]
+    fn test_resample_meters_and_degenerate_cases() {
+        let segment = TrackSegment::with_points(vec![
+            Point::with_elevation(40.0, -3.0, 100.0),
+            Point::with_elevation(40.0, -3.0, 100.0),
+            Point::with_elevation(40.009, -3.0, 200.0),
+        ]);
+        let resampled = segment.resample(Interval::Meters(250));
+        assert_eq!(resampled.points.len(), 6);
+        let metrics = resampled.point_metrics();
+        assert!((metrics[1].distance_km - 0.25).abs() < 1e-6);
+        assert!((metrics[3].distance_km - 0.75).abs() < 1e-6);
+        assert!((resampled.points[2].elevation.unwrap() - 150.0).abs() < 0.1);
+
+        // Sin tiempos el muestreo por segundos no cambia nada
+        assert_eq!(segment.resample(Interval::Seconds(1)).points.len(), 3);
+        assert_eq!(segment.resample(Interval::Meters(0)).points.len(), 3);
+        assert!(TrackSegment::new()
+            .resample(Interval::Meters(10))
+            .points
+            .is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, Point},
+};
+use chrono::{DateTime, Utc};
+
+/// How [`Gpx::close_loop`] joins the end of a route back to its start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LoopClosure {
+    /// Append a copy of the start point, adding a straight closing stretch
+    #[default]
+    Straight,
+    /// Move the last point onto the start, so no point is added
+    Snap,
+}
+
+/// Primer y último instante de los puntos del documento
+fn time_span(gpx: &Gpx) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut times = gpx.points().filter_map(|point| point.time);
+    let first = times.next()?;
+    Some(times.fold((first, first), |(min, max), t| (min.min(t), max.max(t))))
+}
+
+impl Gpx {
+    /// Reverses the direction of travel of every track
+    ///
+    /// Tracks, segments and points are put in reverse order, so the route starts
+    /// where it used to end. Timestamps are mirrored within the recorded time
+    /// span, so they still go forward and keep the original gaps between points.
+    /// Waypoints keep their positions, but their times are mirrored the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[(40.0, -3.0), (40.1, -3.0)]));
+    /// let mut gpx = Gpx::new();
+    /// gpx.add_track(track);
+    ///
+    /// gpx.reverse();
+    /// assert_eq!(gpx.tracks[0].segments[0].points[0].lat, 40.1);
+    /// ```
+    pub fn reverse(&mut self) {
+        let span = time_span(self);
+        self.tracks.reverse();
+        for track in &mut self.tracks {
+            track.segments.reverse();
+            for segment in &mut track.segments {
+                segment.points.reverse();
+            }
+        }
+
+        let Some((first, last)) = span else {
+            return;
+        };
+        // Refleja cada instante dentro del intervalo para que el tiempo siga avanzando
+        let mirror = |time: DateTime<Utc>| first + (last - time);
+        for point in self.points_mut() {
+            point.time = point.time.map(mirror);
+        }
+        for waypoint in &mut self.waypoints {
+            waypoint.time = waypoint.time.map(mirror);
+        }
+    }
+
+    /// Distance in meters from the last track point back to the first one
+    ///
+    /// Returns `None` when the document has no track points.
+    pub fn loop_gap_m(&self) -> Option<f64> {
+        let first = self.points().next()?;
+        let last = self.points().next_back()?;
+        Some(haversine_distance(last, first) * 1000.0)
+    }
+
+    /// Closes the route into a loop when its end lies within `max_gap_m` meters
+    /// of its start
+    ///
+    /// With [`LoopClosure::Straight`] a copy of the start point (without a
+    /// timestamp) is appended to the last segment; with [`LoopClosure::Snap`]
+    /// the last point is moved onto the start. Returns the gap that was closed,
+    /// in meters, or `None` when there was nothing to do: no track points, a
+    /// route that already ends at its start, or a gap wider than `max_gap_m`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, LoopClosure, Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[
+    ///     (40.0, -3.0),
+    ///     (40.01, -3.0),
+    ///     (40.001, -3.0),
+    /// ]));
+    /// let mut gpx = Gpx::new();
+    /// gpx.add_track(track);
+    ///
+    /// assert_eq!(gpx.close_loop(50.0, LoopClosure::Straight), None);
+    /// let gap = gpx.close_loop(200.0, LoopClosure::Straight).unwrap();
+    /// assert!((gap - 111.2).abs() < 0.5);
+    /// assert_eq!(gpx.tracks[0].segments[0].points.len(), 4);
+    /// assert_eq!(gpx.loop_gap_m(), Some(0.0));
+    /// ```
+    pub fn close_loop(&mut self, max_gap_m: f64, closure: LoopClosure) -> Option<f64> {
+        let gap = self.loop_gap_m()?;
+        if gap <= 0.0 || gap > max_gap_m {
+            return None;
+        }
+        let start = self.points().next()?;
+        let (lat, lon, elevation) = (start.lat, start.lon, start.elevation);
+
+        let segment = self
+            .tracks
+            .iter_mut()
+            .flat_map(|track| &mut track.segments)
+            .filter(|segment| !segment.points.is_empty())
+            .last()?;
+        match closure {
+            LoopClosure::Straight => {
+                let mut point = Point::new(lat, lon);
+                point.elevation = elevation;
+                segment.points.push(point);
+            }
+            LoopClosure::Snap => {
+                let end = segment.points.last_mut()?;
+                end.lat = lat;
+                end.lon = lon;
+                end.elevation = elevation;
+            }
+        }
+        Some(gap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_mirrors_times() {
+        let mut gpx = Gpx::try_from_str(
+            r#"<gpx><wpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:30Z</time></wpt>
+            <trk><trkseg>
+                <trkpt lat="40.0" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+                <trkpt lat="40.1" lon="-3.0"><time>2024-07-01T08:01:00Z</time></trkpt>
+            </trkseg><trkseg>
+                <trkpt lat="40.2" lon="-3.0"><time>2024-07-01T08:05:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        gpx.reverse();
+
+        let reversed: Vec<(f64, String)> = gpx
+            .points()
+            .map(|p| (p.lat, p.time.unwrap().format("%H:%M:%S").to_string()))
+            .collect();
+        assert_eq!(
+            reversed,
+            vec![
+                (40.2, "08:00:00".to_string()),
+                (40.1, "08:04:00".to_string()),
+                (40.0, "08:05:00".to_string()),
+            ]
+        );
+        let waypoint_time = gpx.waypoints[0].time.unwrap();
+        assert_eq!(waypoint_time.format("%H:%M:%S").to_string(), "08:04:30");
+    }
+
+    #[test]
+    fn test_close_loop_snap() {
+        let mut gpx = Gpx::new();
+        assert_eq!(gpx.close_loop(200.0, LoopClosure::Snap), None);
+
+        gpx = Gpx::try_from_str(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="40.0" lon="-3.0"><ele>600</ele></trkpt>
+                <trkpt lat="40.01" lon="-3.0"><ele>650</ele></trkpt>
+                <trkpt lat="40.001" lon="-3.0"><ele>610</ele></trkpt>
+            </trkseg><trkseg/></trk></gpx>"#,
+        )
+        .unwrap();
+        assert!(gpx.close_loop(200.0, LoopClosure::Snap).is_some());
+        let points = &gpx.tracks[0].segments[0].points;
+        assert_eq!(points.len(), 3);
+        assert_eq!((points[2].lat, points[2].elevation), (40.0, Some(600.0)));
+        assert_eq!(gpx.close_loop(200.0, LoopClosure::Snap), None);
+    }
+}
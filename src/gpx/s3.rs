@@ -0,0 +1,140 @@
+use crate::gpx::{error::GpxError, store::GpxStore};
+use futures::TryStreamExt;
+use object_store::{aws::AmazonS3Builder, path::Path as ObjectPath, ObjectStore, PutPayload};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// [`GpxStore`] backed by S3 or any other [`ObjectStore`] (requires the `s3` feature)
+///
+/// Documents are read straight into memory, so [`crate::GpxCollection::from_store`]
+/// can load a cloud archive without temporary downloads. Calls block on an
+/// internal runtime and must not be made from inside another async runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gpx_extractor::{GpxCollection, S3Store};
+///
+/// // Credenciales y región desde AWS_ACCESS_KEY_ID, AWS_REGION, AWS_ENDPOINT...
+/// let store = S3Store::new("my-activities").unwrap();
+/// let rides = GpxCollection::from_store(&store, "2024/").unwrap();
+/// println!("{:.1} km", rides.total_distance_km());
+/// ```
+#[derive(Debug)]
+pub struct S3Store {
+    store: Arc<dyn ObjectStore>,
+    runtime: Runtime,
+}
+
+impl S3Store {
+    /// Connects to an S3 bucket configured from the standard `AWS_*` environment variables
+    ///
+    /// `AWS_ENDPOINT` allows S3-compatible services such as `MinIO` or R2.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Store`] if the configuration is incomplete
+    pub fn new(bucket: &str) -> Result<Self, GpxError> {
+        let s3 = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()?;
+        Self::from_object_store(Arc::new(s3))
+    }
+
+    /// Wraps an already configured [`ObjectStore`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the internal runtime cannot be started
+    pub fn from_object_store(store: Arc<dyn ObjectStore>) -> Result<Self, GpxError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self { store, runtime })
+    }
+}
+
+impl GpxStore for S3Store {
+    fn list(&self, prefix: &str) -> Result<Vec<String>, GpxError> {
+        // El listado de object_store va por segmentos; se filtra el resto del prefijo
+        let directory = prefix
+            .rsplit_once('/')
+            .map(|(dir, _)| ObjectPath::from(dir));
+        let objects: Vec<_> = self
+            .runtime
+            .block_on(self.store.list(directory.as_ref()).try_collect())?;
+
+        let mut keys: Vec<String> = objects
+            .into_iter()
+            .map(|meta| meta.location.to_string())
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn read(&self, key: &str) -> Result<Vec<u8>, GpxError> {
+        let path = ObjectPath::from(key);
+        let bytes = self
+            .runtime
+            .block_on(async { self.store.get(&path).await?.bytes().await })?;
+        Ok(bytes.to_vec())
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> Result<(), GpxError> {
+        let path = ObjectPath::from(key);
+        let payload = PutPayload::from(bytes.to_vec());
+        self.runtime.block_on(self.store.put(&path, payload))?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), GpxError> {
+        let path = ObjectPath::from(key);
+        // S3 no informa de claves inexistentes al borrar; se comprueba antes
+        self.runtime.block_on(async {
+            self.store.head(&path).await?;
+            self.store.delete(&path).await
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gpx, GpxCollection};
+    use object_store::memory::InMemory;
+
+    #[test]
+    fn test_object_store_backend() {
+        let store = S3Store::from_object_store(Arc::new(InMemory::new())).unwrap();
+        let gpx = Gpx::builder()
+            .track(|t| {
+                t.name("Ride")
+                    .segment(|s| s.point(40.0, -3.0).point(40.1, -3.0))
+            })
+            .build()
+            .unwrap();
+
+        store.write_gpx("2024/05/ride.gpx", &gpx).unwrap();
+        store.write_gpx("2024/06/run.gpx", &gpx).unwrap();
+        store.write("2023/old.gpx", b"<gpx><trk>").unwrap();
+
+        assert_eq!(
+            store.list("2024/0").unwrap(),
+            vec!["2024/05/ride.gpx", "2024/06/run.gpx"]
+        );
+        assert_eq!(store.list("").unwrap().len(), 3);
+
+        let collection = GpxCollection::from_store(&store, "2024/").unwrap();
+        assert_eq!(collection.len(), 2);
+        assert!(collection.failures.is_empty());
+
+        store.delete("2024/06/run.gpx").unwrap();
+        assert!(matches!(
+            store.read("2024/06/run.gpx"),
+            Err(GpxError::Store(_))
+        ));
+        assert!(store.delete("2024/06/run.gpx").is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::gpx::parser::Gpx;
+
+/// Value plotted by [`Gpx::series`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeriesKind {
+    /// Speed from the previous point, in km/h
+    Speed,
+    /// Elevation, in meters
+    Elevation,
+    /// Heart rate, in beats per minute
+    HeartRate,
+    /// Slope from the previous point, in percent
+    Grade,
+}
+
+/// Horizontal axis of [`Gpx::series`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XAxis {
+    /// Distance from the start, in kilometers
+    Distance,
+    /// Seconds since the first timed point
+    Time,
+}
+
+/// Valor de `samples` puntos equiespaciados en x, interpolando linealmente
+fn resample(raw: &[(f64, f64)], samples: usize) -> Vec<(f64, f64)> {
+    let (Some(&(first, _)), Some(&end)) = (raw.first(), raw.last()) else {
+        return Vec::new();
+    };
+    if samples == 0 {
+        return Vec::new();
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let step = if samples > 1 {
+        (end.0 - first) / (samples - 1) as f64
+    } else {
+        0.0
+    };
+    (0..samples)
+        .map(|i| {
+            #[allow(clippy::cast_precision_loss)]
+            let x = first + step * i as f64;
+            let j = raw.partition_point(|&(at, _)| at < x);
+            // Por redondeo la última muestra puede quedar justo detrás del último punto
+            let (x1, y1) = raw.get(j).copied().unwrap_or(end);
+            match j.checked_sub(1).map(|k| raw[k]) {
+                Some((x0, y0)) if x1 > x0 => (x, y0 + (y1 - y0) * (x - x0) / (x1 - x0)),
+                _ => (x, y1),
+            }
+        })
+        .collect()
+}
+
+impl Gpx {
+    /// Chart-ready `(x, y)` pairs of a metric, resampled to `samples` evenly spaced values of x
+    ///
+    /// The metric is read from [`Gpx::point_metrics`] and the points, leaving out
+    /// points where it or the x value is unknown, and linearly interpolated at each
+    /// sample. The samples run from the first to the last known x. The result is
+    /// empty when no point has both values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{analysis::{SeriesKind, XAxis}, Gpx};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.000" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.009" lon="-3.0"><ele>700</ele><time>2024-07-01T08:10:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let profile = gpx.series(SeriesKind::Elevation, XAxis::Time, 3);
+    /// assert_eq!(profile, vec![(0.0, 600.0), (300.0, 650.0), (600.0, 700.0)]);
+    /// ```
+    pub fn series(&self, kind: SeriesKind, axis: XAxis, samples: usize) -> Vec<(f64, f64)> {
+        let start = self.get_all_points().iter().find_map(|point| point.time);
+        let raw: Vec<(f64, f64)> = self
+            .get_all_points()
+            .into_iter()
+            .zip(self.point_metrics())
+            .filter_map(|(point, metrics)| {
+                let x = match axis {
+                    XAxis::Distance => Some(metrics.distance_km),
+                    #[allow(clippy::cast_precision_loss)]
+                    XAxis::Time => point
+                        .time
+                        .zip(start)
+                        .map(|(time, start)| (time - start).num_milliseconds() as f64 / 1000.0),
+                }?;
+                let y = match kind {
+                    SeriesKind::Speed => metrics.speed_kmh,
+                    SeriesKind::Elevation => point.elevation,
+                    SeriesKind::HeartRate => point.extensions.heart_rate.map(f64::from),
+                    SeriesKind::Grade => metrics.grade_percent,
+                }?;
+                Some((x, y))
+            })
+            .collect();
+        resample(&raw, samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{
+        point::Point,
+        track::{Track, TrackSegment},
+    };
+
+    #[test]
+    fn test_series_skips_unknown_values_and_resamples_by_distance() {
+        let with_hr = |lat: f64, hr: Option<u8>| {
+            let mut point = Point::new(lat, -3.0);
+            point.extensions.heart_rate = hr;
+            point
+        };
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            with_hr(40.0, Some(100)),
+            with_hr(40.01, None),
+            with_hr(40.02, Some(160)),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        let hr = gpx.series(SeriesKind::HeartRate, XAxis::Distance, 5);
+        assert_eq!(hr.len(), 5);
+        let total = gpx.total_distance_km();
+        assert!((hr[4].0 - total).abs() < 1e-9);
+        assert!((hr[2].1 - 130.0).abs() < 1e-6);
+        assert_eq!(hr[0], (0.0, 100.0));
+
+        // Sin tiempos no hay eje temporal, ni velocidad
+        assert!(gpx.series(SeriesKind::HeartRate, XAxis::Time, 5).is_empty());
+        assert!(gpx.series(SeriesKind::Speed, XAxis::Distance, 5).is_empty());
+        assert!(gpx
+            .series(SeriesKind::HeartRate, XAxis::Distance, 0)
+            .is_empty());
+        assert_eq!(gpx.series(SeriesKind::Grade, XAxis::Distance, 1).len(), 0);
+    }
+}
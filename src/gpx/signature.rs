@@ -0,0 +1,141 @@
+use crate::gpx::{error::GpxError, parser::Gpx, write::WriteOptions};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Inicio del comentario que lleva la firma, al final del documento
+const SIGNATURE_PREFIX: &str = "\n<!-- gpx-signature hmac-sha256 ";
+const SIGNATURE_SUFFIX: &str = " -->\n";
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(key: &[u8], body: &str) -> HmacSha256 {
+    // HMAC acepta claves de cualquier longitud, así que `new_from_slice` no falla
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key).unwrap_or_else(|_| unreachable!());
+    mac.update(body.as_bytes());
+    mac
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl Gpx {
+    /// Serializes the document and appends an HMAC-SHA256 signature comment
+    ///
+    /// The signature covers every byte before the trailing
+    /// `<!-- gpx-signature hmac-sha256 ... -->` comment, so any later edit (even
+    /// whitespace) is detected by [`Gpx::verify_signed_xml`]. The comment is ignored
+    /// by XML parsers, so signed files still open in any GPX tool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be serialized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, GpxBuilder};
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.name("Race").segment(|s| s.point(40.0, -3.0)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let signed = gpx.to_signed_xml(b"event-2024").unwrap();
+    /// assert!(Gpx::verify_signed_xml(&signed, b"event-2024").is_ok());
+    ///
+    /// let edited = signed.replace("Race", "Rice");
+    /// assert!(Gpx::verify_signed_xml(&edited, b"event-2024").is_err());
+    /// ```
+    pub fn to_signed_xml(&self, key: &[u8]) -> Result<String, GpxError> {
+        let mut xml = self.to_xml_with(&WriteOptions::default())?;
+        let signature = mac(key, &xml).finalize().into_bytes();
+        xml.push_str(SIGNATURE_PREFIX);
+        xml.push_str(&format!("{signature:x}"));
+        xml.push_str(SIGNATURE_SUFFIX);
+        Ok(xml)
+    }
+
+    /// Checks the signature written by [`Gpx::to_signed_xml`] and parses the document
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidSignature`] if the document has no signature or
+    /// was modified after signing (or signed with another key), or a parse error if
+    /// the signed content is not valid GPX
+    pub fn verify_signed_xml(xml: &str, key: &[u8]) -> Result<Self, GpxError> {
+        let missing = || GpxError::InvalidSignature("document is not signed".to_string());
+        let start = xml.rfind(SIGNATURE_PREFIX).ok_or_else(missing)?;
+        let (body, comment) = xml.split_at(start);
+        let signature = comment
+            .strip_prefix(SIGNATURE_PREFIX)
+            .and_then(|rest| rest.strip_suffix(SIGNATURE_SUFFIX))
+            .and_then(decode_hex)
+            .ok_or_else(missing)?;
+
+        mac(key, body).verify_slice(&signature).map_err(|_| {
+            GpxError::InvalidSignature("signature does not match the content".to_string())
+        })?;
+        Self::try_from_str(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GpxBuilder;
+
+    fn sample() -> Gpx {
+        GpxBuilder::new()
+            .track(|t| {
+                t.name("Marathon")
+                    .segment(|s| s.point(40.0, -3.0).point(40.1, -3.0))
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signed_xml_roundtrip() {
+        let signed = sample().to_signed_xml(b"secret").unwrap();
+        assert!(signed.ends_with(" -->\n"));
+
+        let gpx = Gpx::verify_signed_xml(&signed, b"secret").unwrap();
+        assert_eq!(gpx.track_names(), vec!["Marathon"]);
+        // Cualquier lector GPX ignora el comentario de la firma
+        assert_eq!(Gpx::try_from_str(&signed).unwrap().total_points(), 2);
+    }
+
+    #[test]
+    fn test_signature_rejects_edits_and_wrong_keys() {
+        let signed = sample().to_signed_xml(b"secret").unwrap();
+
+        let wrong_key = Gpx::verify_signed_xml(&signed, b"other");
+        assert!(matches!(wrong_key, Err(GpxError::InvalidSignature(_))));
+
+        let moved = signed.replace("lat=\"40.1\"", "lat=\"40.2\"");
+        assert!(matches!(
+            Gpx::verify_signed_xml(&moved, b"secret"),
+            Err(GpxError::InvalidSignature(msg)) if msg.contains("does not match")
+        ));
+
+        let unsigned = sample().to_xml();
+        assert!(matches!(
+            Gpx::verify_signed_xml(&unsigned, b"secret"),
+            Err(GpxError::InvalidSignature(msg)) if msg.contains("not signed")
+        ));
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("00ff10"), Some(vec![0x00, 0xFF, 0x10]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, Point, EARTH_RADIUS_KM},
+    track::{Track, TrackSegment},
+};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+const EARTH_RADIUS_M: f64 = EARTH_RADIUS_KM * 1000.0;
+
+/// Line simplification algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimplifyAlgorithm {
+    /// Ramer–Douglas–Peucker: keeps every point farther than the tolerance from the
+    /// simplified line. Good at preserving the overall shape.
+    #[default]
+    RamerDouglasPeucker,
+    /// Visvalingam–Whyatt: repeatedly drops the point whose triangle with its
+    /// neighbours has the smallest area, while that area is below `tolerance²`.
+    /// Produces smoother results that look more natural on maps.
+    VisvalingamWhyatt,
+}
+
+/// Simplifies a sequence of points, always keeping the first and last one
+///
+/// Kept points are cloned untouched, so timestamps, elevation and extensions
+/// survive. Distances are computed on a local equirectangular projection, which is
+/// accurate for the short spans between consecutive GPS fixes.
+pub(crate) fn simplify_points(
+    points: &[Point],
+    tolerance_m: f64,
+    algorithm: SimplifyAlgorithm,
+) -> Vec<Point> {
+    if points.len() < 3 || tolerance_m <= 0.0 {
+        return points.to_vec();
+    }
+
+    let projected = project(points);
+    let keep = match algorithm {
+        SimplifyAlgorithm::RamerDouglasPeucker => ramer_douglas_peucker(&projected, tolerance_m),
+        SimplifyAlgorithm::VisvalingamWhyatt => {
+            visvalingam_whyatt(&projected, tolerance_m * tolerance_m)
+        }
+    };
+
+    points
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(point, _)| point.clone())
+        .collect()
+}
+
+impl TrackSegment {
+    /// Simplifies the segment with Ramer–Douglas–Peucker and a tolerance in meters
+    #[must_use]
+    pub fn simplify(&self, tolerance_m: f64) -> TrackSegment {
+        self.simplify_with(tolerance_m, SimplifyAlgorithm::RamerDouglasPeucker)
+    }
+
+    /// Simplifies the segment with the given algorithm and a tolerance in meters
+    #[must_use]
+    pub fn simplify_with(&self, tolerance_m: f64, algorithm: SimplifyAlgorithm) -> TrackSegment {
+        TrackSegment::with_points(simplify_points(&self.points, tolerance_m, algorithm))
+    }
+}
+
+impl Track {
+    /// Simplifies every segment of the track (see [`TrackSegment::simplify_with`])
+    #[must_use]
+    pub fn simplify_with(&self, tolerance_m: f64, algorithm: SimplifyAlgorithm) -> Track {
+        let mut track = self.clone();
+        track.segments = self
+            .segments
+            .iter()
+            .map(|segment| segment.simplify_with(tolerance_m, algorithm))
+            .collect();
+        track
+    }
+}
+
+impl Gpx {
+    /// Simplifies every track with Ramer–Douglas–Peucker and a tolerance in meters
+    ///
+    /// Waypoints and metadata are kept as they are.
+    #[must_use]
+    pub fn simplify(&self, tolerance_m: f64) -> Gpx {
+        self.simplify_with(tolerance_m, SimplifyAlgorithm::RamerDouglasPeucker)
+    }
+
+    /// Simplifies every track with the given algorithm and a tolerance in meters
+    #[must_use]
+    pub fn simplify_with(&self, tolerance_m: f64, algorithm: SimplifyAlgorithm) -> Gpx {
+        let mut gpx = self.clone();
+        gpx.tracks = self
+            .tracks
+            .iter()
+            .map(|track| track.simplify_with(tolerance_m, algorithm))
+            .collect();
+        gpx
+    }
+}
+
+impl Gpx {
+    /// Simplifies the tracks with Ramer–Douglas–Peucker so that at most `max_points`
+    /// track points remain, using the smallest tolerance that achieves it
+    ///
+    /// The tolerance is found by binary search, so the shape error is kept as low
+    /// as the limit allows. Every segment keeps its first and last point, so the
+    /// result can exceed `max_points` when the limit is below twice the number of
+    /// segments. Waypoints and metadata are kept as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::GpxBuilder;
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| {
+    ///         t.segment(|s| {
+    ///             s.point(40.0, -3.0)
+    ///                 .point(40.001, -3.0001)
+    ///                 .point(40.002, -3.0)
+    ///                 .point(40.003, -3.01)
+    ///                 .point(40.004, -3.0)
+    ///         })
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let reduced = gpx.reduce_to_max_points(3);
+    /// assert_eq!(reduced.total_points(), 3);
+    /// assert_eq!(reduced.tracks[0].segments[0].points[1].lon, -3.01);
+    /// ```
+    #[must_use]
+    pub fn reduce_to_max_points(&self, max_points: usize) -> Gpx {
+        if self.total_points() <= max_points {
+            return self.clone();
+        }
+        let Some(bounds) = self.bounds() else {
+            return self.clone();
+        };
+
+        // Con una tolerancia mayor que la diagonal solo quedan los extremos
+        let diagonal_m = haversine_distance(
+            &Point::new(bounds.min_lat, bounds.min_lon),
+            &Point::new(bounds.max_lat, bounds.max_lon),
+        ) * 1000.0;
+        let (mut low, mut high) = (0.0, diagonal_m + 1.0);
+        let mut best = self.simplify(high);
+        while high - low > 0.01 {
+            let tolerance = (low + high) / 2.0;
+            let candidate = self.simplify(tolerance);
+            if candidate.total_points() <= max_points {
+                high = tolerance;
+                best = candidate;
+            } else {
+                low = tolerance;
+            }
+        }
+        best
+    }
+}
+
+/// Proyecta los puntos a un plano local (metros) centrado en la latitud media
+fn project(points: &[Point]) -> Vec<(f64, f64)> {
+    #[allow(clippy::cast_precision_loss)]
+    let mean_lat = points.iter().map(|p| p.lat).sum::<f64>() / points.len() as f64;
+    let cos_lat = mean_lat.to_radians().cos();
+
+    points
+        .iter()
+        .map(|p| {
+            (
+                p.lon.to_radians() * cos_lat * EARTH_RADIUS_M,
+                p.lat.to_radians() * EARTH_RADIUS_M,
+            )
+        })
+        .collect()
+}
+
+fn perpendicular_distance(p: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_sq = dx * dx + dy * dy;
+
+    if length_sq == 0.0 {
+        return ((p.0 - start.0).powi(2) + (p.1 - start.1).powi(2)).sqrt();
+    }
+
+    ((dy * p.0 - dx * p.1 + end.0 * start.1 - end.1 * start.0) / length_sq.sqrt()).abs()
+}
+
+fn ramer_douglas_peucker(points: &[(f64, f64)], tolerance: f64) -> Vec<bool> {
+    let mut keep = vec![false; points.len()];
+    let last = points.len() - 1;
+    keep[0] = true;
+    keep[last] = true;
+
+    // Pila explícita para no desbordar la recursión en tracks de miles de puntos
+    let mut stack = vec![(0, last)];
+    while let Some((start, end)) = stack.pop() {
+        if end <= start + 1 {
+            continue;
+        }
+
+        let (index, distance) = (start + 1..end)
+            .map(|i| {
+                (
+                    i,
+                    perpendicular_distance(points[i], points[start], points[end]),
+                )
+            })
+            .fold((start, 0.0), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        if distance > tolerance {
+            keep[index] = true;
+            stack.push((start, index));
+            stack.push((index, end));
+        }
+    }
+
+    keep
+}
+
+fn triangle_area(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0
+}
+
+/// Entrada del montículo de Visvalingam (orden inverso: menor área primero)
+#[derive(PartialEq)]
+struct Candidate {
+    area: f64,
+    index: usize,
+    version: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+fn visvalingam_whyatt(points: &[(f64, f64)], min_area: f64) -> Vec<bool> {
+    let n = points.len();
+    let mut keep = vec![true; n];
+    let mut prev: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(|i| (i + 1).min(n - 1)).collect();
+    let mut version = vec![0usize; n];
+
+    let mut heap: BinaryHeap<Candidate> = (1..n - 1)
+        .map(|i| Candidate {
+            area: triangle_area(points[i - 1], points[i], points[i + 1]),
+            index: i,
+            version: 0,
+        })
+        .collect();
+
+    let mut last_area = 0.0_f64;
+    while let Some(candidate) = heap.pop() {
+        let i = candidate.index;
+        if !keep[i] || candidate.version != version[i] {
+            continue;
+        }
+
+        // El área efectiva nunca decrece, para que eliminar un punto no haga
+        // "más importantes" a sus vecinos que puntos ya descartados
+        let area = candidate.area.max(last_area);
+        if area >= min_area {
+            break;
+        }
+        last_area = area;
+        keep[i] = false;
+
+        let (before, after) = (prev[i], next[i]);
+        next[before] = after;
+        prev[after] = before;
+
+        for neighbour in [before, after] {
+            if neighbour == 0 || neighbour == n - 1 {
+                continue;
+            }
+            version[neighbour] += 1;
+            heap.push(Candidate {
+                area: triangle_area(
+                    points[prev[neighbour]],
+                    points[neighbour],
+                    points[next[neighbour]],
+                ),
+                index: neighbour,
+                version: version[neighbour],
+            });
+        }
+    }
+
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Línea recta hacia el norte con un pico lateral de ~110 m en el centro
+    fn zigzag_segment() -> TrackSegment {
+        let start = chrono::Utc.with_ymd_and_hms(2024, 7, 11, 10, 0, 0).unwrap();
+        let points = (0..=20)
+            .map(|i| {
+                let lon = if i == 10 { 0.001 } else { 0.0 };
+                Point::with_time(
+                    40.0 + f64::from(i) * 0.0001,
+                    lon,
+                    Some(100.0),
+                    start + chrono::Duration::seconds(i64::from(i)),
+                )
+            })
+            .collect();
+        TrackSegment::with_points(points)
+    }
+
+    #[test]
+    fn test_rdp_keeps_endpoints_and_spike() {
+        let segment = zigzag_segment();
+        let simplified = segment.simplify(10.0);
+
+        assert_eq!(simplified.point_count(), 5);
+        assert_eq!(simplified.points[0].lat, segment.points[0].lat);
+        assert_eq!(simplified.points[2].lon, 0.001);
+        assert_eq!(simplified.points[4].lat, segment.points[20].lat);
+        assert_eq!(simplified.points[2].time, segment.points[10].time);
+    }
+
+    #[test]
+    fn test_rdp_large_tolerance_keeps_only_endpoints() {
+        let simplified = zigzag_segment().simplify(1000.0);
+        assert_eq!(simplified.point_count(), 2);
+    }
+
+    #[test]
+    fn test_visvalingam_removes_collinear_points() {
+        let segment = zigzag_segment();
+        let simplified = segment.simplify_with(10.0, SimplifyAlgorithm::VisvalingamWhyatt);
+
+        assert!(simplified.point_count() < segment.point_count());
+        assert!(simplified.points.iter().any(|p| p.lon == 0.001));
+        assert_eq!(
+            simplified.points.first().unwrap().time,
+            segment.points[0].time
+        );
+        assert_eq!(
+            simplified.points.last().unwrap().time,
+            segment.points[20].time
+        );
+    }
+
+    #[test]
+    fn test_simplify_short_or_zero_tolerance_is_noop() {
+        let short = TrackSegment::with_points(vec![Point::new(1.0, 1.0), Point::new(1.0, 1.1)]);
+        assert_eq!(short.simplify(100.0).point_count(), 2);
+        assert_eq!(zigzag_segment().simplify(0.0).point_count(), 21);
+    }
+
+    #[test]
+    fn test_gpx_simplify_keeps_tracks_and_waypoints() {
+        let mut gpx = Gpx::new();
+        let mut track = Track::with_name("Dense".to_string());
+        track.add_segment(zigzag_segment());
+        track.add_segment(zigzag_segment());
+        gpx.add_track(track);
+        gpx.add_waypoint(crate::Waypoint::new(40.0, 0.0));
+
+        let simplified = gpx.simplify(10.0);
+        assert_eq!(simplified.tracks[0].name.as_deref(), Some("Dense"));
+        assert_eq!(simplified.total_segments(), 2);
+        assert_eq!(simplified.total_points(), 10);
+        assert_eq!(simplified.waypoints.len(), 1);
+    }
+
+    #[test]
+    fn test_reduce_to_max_points() {
+        let mut gpx = Gpx::new();
+        let mut track = Track::new();
+        track.add_segment(zigzag_segment());
+        gpx.add_track(track);
+
+        // Con 3 puntos se conserva el pico lateral
+        let reduced = gpx.reduce_to_max_points(3);
+        let points = &reduced.tracks[0].segments[0].points;
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[1].lon, 0.001);
+
+        assert_eq!(gpx.reduce_to_max_points(100).total_points(), 21);
+        assert!(gpx.reduce_to_max_points(8).total_points() <= 8);
+        // Los extremos del segmento se conservan siempre
+        assert_eq!(gpx.reduce_to_max_points(1).total_points(), 2);
+        assert!(Gpx::new().reduce_to_max_points(0).is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+use crate::gpx::{
+    metadata::Metadata, parser::Gpx, track::Track, unknown::UnknownXml, waypoint::Waypoint,
+    write::GpxVersion,
+};
+use std::sync::Arc;
+
+/// Saved state of a [`Gpx`], for undo and redo in interactive editors
+///
+/// Tracks, the waypoint list and the metadata are kept behind [`Arc`]s, so a
+/// snapshot is cheap to clone and store. Taken with [`Gpx::snapshot_after`],
+/// a snapshot shares every part that did not change with the previous one,
+/// and [`Gpx::restore`] only copies back the parts that differ from the
+/// document, so an edit to one track of a long document does not copy the others.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    tracks: Vec<Arc<Track>>,
+    waypoints: Arc<Vec<Waypoint>>,
+    metadata: Arc<Option<Metadata>>,
+    version: GpxVersion,
+    creator: Option<String>,
+    unknown: Arc<UnknownXml>,
+}
+
+/// Reutiliza la copia de `previous` si `current` no ha cambiado
+fn share<T: Clone + PartialEq>(current: &T, previous: Option<&Arc<T>>) -> Arc<T> {
+    match previous {
+        Some(previous) if **previous == *current => Arc::clone(previous),
+        _ => Arc::new(current.clone()),
+    }
+}
+
+/// Posición del primer elemento igual a `wanted` que no se haya usado ya,
+/// mirando antes en `hint`, donde suele estar si no se han reordenado
+fn find_unused(used: &[bool], hint: usize, same: impl Fn(usize) -> bool) -> Option<usize> {
+    // La comparación de `Vec` mira primero la longitud, así que descartar
+    // un track distinto casi nunca recorre sus puntos
+    std::iter::once(hint)
+        .chain(0..used.len())
+        .find(|&i| i < used.len() && !used[i] && same(i))
+}
+
+impl Gpx {
+    /// Takes a full snapshot of the document
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let mut gpx = Gpx::new();
+    /// let before = gpx.snapshot();
+    /// gpx.set_name("Edited");
+    /// gpx.restore(&before);
+    /// assert_eq!(gpx, Gpx::new());
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            tracks: self.tracks.iter().cloned().map(Arc::new).collect(),
+            waypoints: Arc::new(self.waypoints.clone()),
+            metadata: Arc::new(self.metadata.clone()),
+            version: self.version,
+            creator: self.creator.clone(),
+            unknown: Arc::new(self.unknown.clone()),
+        }
+    }
+
+    /// Takes a snapshot that shares unchanged tracks, waypoints and metadata with `previous`
+    ///
+    /// Each track is compared first with the track at the same position in
+    /// `previous` and then with the others, so reordering or deleting tracks
+    /// copies nothing. Only parts that differ are cloned.
+    pub fn snapshot_after(&self, previous: &Snapshot) -> Snapshot {
+        let mut used = vec![false; previous.tracks.len()];
+        let tracks = self
+            .tracks
+            .iter()
+            .enumerate()
+            .map(
+                |(i, track)| match find_unused(&used, i, |j| *previous.tracks[j] == *track) {
+                    Some(old) => {
+                        used[old] = true;
+                        Arc::clone(&previous.tracks[old])
+                    }
+                    None => Arc::new(track.clone()),
+                },
+            )
+            .collect();
+        Snapshot {
+            tracks,
+            waypoints: share(&self.waypoints, Some(&previous.waypoints)),
+            metadata: share(&self.metadata, Some(&previous.metadata)),
+            version: self.version,
+            creator: self.creator.clone(),
+            unknown: share(&self.unknown, Some(&previous.unknown)),
+        }
+    }
+
+    /// Puts the document back in the state saved in `snapshot`
+    ///
+    /// Tracks, waypoints and metadata that already match the snapshot are kept
+    /// as they are; only the ones that differ are copied from it.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let mut current: Vec<Option<Track>> = std::mem::take(&mut self.tracks)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut used = vec![false; current.len()];
+        for (i, saved) in snapshot.tracks.iter().enumerate() {
+            let same = |j: usize| current[j].as_ref() == Some(&**saved);
+            let track = match find_unused(&used, i, same) {
+                Some(kept) => {
+                    used[kept] = true;
+                    current[kept].take()
+                }
+                None => None,
+            };
+            self.tracks
+                .push(track.unwrap_or_else(|| Track::clone(saved)));
+        }
+        if self.waypoints != *snapshot.waypoints {
+            self.waypoints.clone_from(&snapshot.waypoints);
+        }
+        if self.metadata != *snapshot.metadata {
+            self.metadata.clone_from(&snapshot.metadata);
+        }
+        self.version = snapshot.version;
+        self.creator.clone_from(&snapshot.creator);
+        if self.unknown != *snapshot.unknown {
+            self.unknown.clone_from(&snapshot.unknown);
+        }
+    }
+}
+
+/// Undo and redo stacks of [`Snapshot`]s
+///
+/// Call [`History::record`] before each edit; [`History::undo`] and
+/// [`History::redo`] then move the document between the recorded states.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Gpx, History};
+///
+/// let mut gpx = Gpx::new();
+/// let mut history = History::with_limit(50);
+///
+/// history.record(&gpx);
+/// gpx.set_name("Morning ride");
+///
+/// assert!(history.undo(&mut gpx));
+/// assert!(gpx.metadata.is_none());
+/// assert!(history.redo(&mut gpx));
+/// assert_eq!(gpx.metadata.unwrap().name.as_deref(), Some("Morning ride"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    limit: Option<usize>,
+}
+
+impl History {
+    /// Crea un historial sin límite de pasos
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crea un historial que solo recuerda los últimos `limit` pasos
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    /// Saves the current state before an edit and forgets the redo steps
+    pub fn record(&mut self, gpx: &Gpx) {
+        let snapshot = self.take(gpx);
+        self.undo.push(snapshot);
+        if let Some(limit) = self.limit {
+            let excess = self.undo.len().saturating_sub(limit);
+            self.undo.drain(..excess);
+        }
+        self.redo.clear();
+    }
+
+    /// Goes back to the last recorded state; returns `false` if there is none
+    pub fn undo(&mut self, gpx: &mut Gpx) -> bool {
+        let Some(snapshot) = self.undo.pop() else {
+            return false;
+        };
+        let current = self.take(gpx);
+        self.redo.push(current);
+        gpx.restore(&snapshot);
+        true
+    }
+
+    /// Reapplies the last undone edit; returns `false` if there is none
+    pub fn redo(&mut self, gpx: &mut Gpx) -> bool {
+        let Some(snapshot) = self.redo.pop() else {
+            return false;
+        };
+        let current = self.take(gpx);
+        self.undo.push(current);
+        gpx.restore(&snapshot);
+        true
+    }
+
+    /// Verifica si hay algún paso que deshacer
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Verifica si hay algún paso que rehacer
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Instantánea que comparte lo posible con la más reciente del historial
+    fn take(&self, gpx: &Gpx) -> Snapshot {
+        let latest = self.undo.last().or(self.redo.last());
+        latest.map_or_else(|| gpx.snapshot(), |latest| gpx.snapshot_after(latest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::TrackSegment;
+
+    fn sample() -> Gpx {
+        let mut gpx = Gpx::new();
+        for name in ["A", "B", "C"] {
+            let mut track = Track::with_name(name.to_string());
+            track.add_segment(TrackSegment::from_latlon_pairs(&[
+                (40.0, -3.0),
+                (40.1, -3.1),
+            ]));
+            gpx.add_track(track);
+        }
+        gpx
+    }
+
+    #[test]
+    fn test_snapshot_after_shares_unchanged_parts() {
+        let mut gpx = sample();
+        let first = gpx.snapshot();
+        gpx.tracks[1].name = Some("Edited".to_string());
+        gpx.tracks.remove(0);
+        let second = gpx.snapshot_after(&first);
+
+        assert!(Arc::ptr_eq(&second.tracks[1], &first.tracks[2]));
+        assert!(!Arc::ptr_eq(&second.tracks[0], &first.tracks[1]));
+        assert!(Arc::ptr_eq(&second.waypoints, &first.waypoints));
+        assert!(Arc::ptr_eq(&second.metadata, &first.metadata));
+
+        gpx.restore(&first);
+        assert_eq!(gpx, sample());
+    }
+
+    #[test]
+    fn test_unchanged_tracks_are_shared_and_kept_on_restore() {
+        let mut gpx = sample();
+        let first = gpx.snapshot();
+        gpx.tracks[1].name = Some("Edited".to_string());
+        let second = gpx.snapshot_after(&first);
+        assert!(Arc::ptr_eq(&second.tracks[0], &first.tracks[0]));
+        assert!(Arc::ptr_eq(&second.tracks[2], &first.tracks[2]));
+        assert!(!Arc::ptr_eq(&second.tracks[1], &first.tracks[1]));
+
+        // Dos tracks iguales no pueden compartir la misma copia anterior
+        gpx.tracks[1] = gpx.tracks[0].clone();
+        let third = gpx.snapshot_after(&first);
+        assert!(Arc::ptr_eq(&third.tracks[0], &first.tracks[0]));
+        assert!(!Arc::ptr_eq(&third.tracks[1], &first.tracks[0]));
+
+        // Restaurar deja en su sitio los puntos de los tracks que no cambiaron
+        let untouched = gpx.tracks[2].segments[0].points.as_ptr();
+        gpx.restore(&second);
+        assert_eq!(gpx.tracks[2].segments[0].points.as_ptr(), untouched);
+        assert_eq!(gpx.tracks[1].name.as_deref(), Some("Edited"));
+    }
+
+    #[test]
+    fn test_history_limit_and_redo_reset() {
+        let mut gpx = sample();
+        let mut history = History::with_limit(2);
+        for name in ["x", "y", "z"] {
+            history.record(&gpx);
+            gpx.tracks[0].name = Some(name.to_string());
+        }
+        assert!(history.undo(&mut gpx));
+        assert!(history.undo(&mut gpx));
+        assert!(!history.undo(&mut gpx));
+        assert_eq!(gpx.tracks[0].name.as_deref(), Some("x"));
+
+        assert!(history.redo(&mut gpx));
+        assert!(history.can_redo());
+        history.record(&gpx);
+        assert!(!history.can_redo());
+        assert!(history.can_undo());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::gpx::{
+    parser::{start_time, Gpx},
+    point::{haversine_distance, Point},
+    template::NameTemplate,
+    track::{Track, TrackSegment},
+};
+use chrono::{DateTime, Duration, FixedOffset, Offset, SecondsFormat, Utc};
+
+/// Where [`Gpx::split`] cuts a recording into separate documents
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitStrategy {
+    /// Cut wherever two consecutive timed points are further apart than this
+    TimeGap(Duration),
+    /// Cut every given number of kilometers; the cut point ends one piece and starts the next
+    Distance(f64),
+    /// Cut at midnight of the given time zone offset
+    Day(FixedOffset),
+}
+
+impl SplitStrategy {
+    /// Corta en pausas de más de `minutes` minutos
+    pub fn gap_minutes(minutes: i64) -> Self {
+        Self::TimeGap(Duration::minutes(minutes))
+    }
+
+    /// Corta cada `km` kilómetros
+    pub fn every_km(km: f64) -> Self {
+        Self::Distance(km)
+    }
+
+    /// Corta por día natural en UTC
+    pub fn per_day() -> Self {
+        Self::Day(Utc.fix())
+    }
+}
+
+/// Estado del documento que se está construyendo
+pub(crate) struct Piece {
+    pub(crate) gpx: Gpx,
+    /// Track y segmento de origen del último punto añadido
+    open: Option<(usize, usize)>,
+    distance_km: f64,
+}
+
+impl Piece {
+    pub(crate) fn new(source: &Gpx) -> Self {
+        let mut gpx = Gpx::new();
+        gpx.version = source.version;
+        gpx.creator.clone_from(&source.creator);
+        gpx.metadata.clone_from(&source.metadata);
+        Self {
+            gpx,
+            open: None,
+            distance_km: 0.0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, key: (usize, usize), track: &Track, point: Point) {
+        if self.open.map(|(t, _)| t) != Some(key.0) {
+            self.gpx.tracks.push(track.header());
+        }
+        let Some(track) = self.gpx.tracks.last_mut() else {
+            return;
+        };
+        if self.open != Some(key) {
+            track.segments.push(TrackSegment::new());
+        }
+        if let Some(segment) = track.segments.last_mut() {
+            segment.add_point(point);
+        }
+        self.open = Some(key);
+    }
+}
+
+impl Gpx {
+    /// Splits the recording into separate documents, e.g. one per day of a multi-day hike
+    ///
+    /// Track and segment structure is preserved inside each piece and the metadata
+    /// is copied, with its time set to the start of the piece. Waypoints go to every
+    /// piece whose bounding box contains them. Untimed points never trigger a time
+    /// or day cut. A document without track points yields no pieces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, SplitStrategy};
+    ///
+    /// let xml = r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="42.0" lon="1.0"><time>2024-07-01T16:00:00Z</time></trkpt>
+    ///     <trkpt lat="42.1" lon="1.0"><time>2024-07-01T18:00:00Z</time></trkpt>
+    ///     <trkpt lat="42.1" lon="1.0"><time>2024-07-02T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="42.2" lon="1.0"><time>2024-07-02T12:00:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#;
+    ///
+    /// let days = Gpx::try_from_str(xml).unwrap().split(SplitStrategy::per_day());
+    /// assert_eq!(days.len(), 2);
+    /// assert_eq!(days[1].date(), Some("2024-07-02T08:00:00Z"));
+    /// ```
+    pub fn split(&self, strategy: SplitStrategy) -> Vec<Gpx> {
+        let mut pieces = Vec::new();
+        let mut current: Option<Piece> = None;
+        let mut last_time: Option<DateTime<Utc>> = None;
+
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (s, segment) in track.segments.iter().enumerate() {
+                for (i, point) in segment.points.iter().enumerate() {
+                    let key = (t, s);
+                    let previous = i.checked_sub(1).map(|i| &segment.points[i]);
+                    let cut = match (&current, strategy) {
+                        (None, _) => true,
+                        (Some(piece), SplitStrategy::Distance(km)) => piece.distance_km >= km,
+                        (Some(_), SplitStrategy::TimeGap(gap)) => {
+                            matches!((last_time, point.time), (Some(a), Some(b)) if b - a > gap)
+                        }
+                        (Some(_), SplitStrategy::Day(offset)) => {
+                            let day =
+                                |time: DateTime<Utc>| time.with_timezone(&offset).date_naive();
+                            matches!((last_time, point.time), (Some(a), Some(b)) if day(a) != day(b))
+                        }
+                    };
+
+                    if cut {
+                        let mut piece = Piece::new(self);
+                        // Al cortar por distancia el punto de corte abre también la pieza siguiente
+                        if let (SplitStrategy::Distance(_), Some(previous), Some(_)) =
+                            (strategy, previous, &current)
+                        {
+                            piece.push(key, track, previous.clone());
+                        }
+                        if let Some(done) = current.replace(piece) {
+                            pieces.push(self.finish_piece(done.gpx));
+                        }
+                    }
+
+                    let Some(piece) = current.as_mut() else {
+                        continue;
+                    };
+                    if let (Some(previous), Some(open)) = (previous, piece.open) {
+                        if open == key {
+                            piece.distance_km += haversine_distance(previous, point);
+                        }
+                    }
+                    piece.push(key, track, point.clone());
+                    last_time = point.time.or(last_time);
+                }
+            }
+        }
+
+        if let Some(done) = current {
+            pieces.push(self.finish_piece(done.gpx));
+        }
+        pieces
+    }
+
+    /// Splits like [`Gpx::split`] and names every piece with `template`
+    ///
+    /// The name goes to the metadata of each piece. The template gets the
+    /// [`Gpx::template_values`] of the piece plus `n` (1-based piece number) and
+    /// `pieces` (number of pieces).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, NameTemplate, SplitStrategy};
+    ///
+    /// let xml = r#"<gpx><trk><name>Hike</name><trkseg>
+    ///     <trkpt lat="42.0" lon="1.0"><time>2024-07-01T16:00:00Z</time></trkpt>
+    ///     <trkpt lat="42.1" lon="1.0"><time>2024-07-02T08:00:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#;
+    ///
+    /// let template: NameTemplate = "{name}, day {n} of {pieces}".parse().unwrap();
+    /// let days = Gpx::try_from_str(xml)
+    ///     .unwrap()
+    ///     .split_named(SplitStrategy::per_day(), &template);
+    /// assert_eq!(days[1].metadata.as_ref().unwrap().name.as_deref(), Some("Hike, day 2 of 2"));
+    /// ```
+    pub fn split_named(&self, strategy: SplitStrategy, template: &NameTemplate) -> Vec<Gpx> {
+        let mut pieces = self.split(strategy);
+        let total = pieces.len();
+        for (i, piece) in pieces.iter_mut().enumerate() {
+            let values = piece
+                .template_values()
+                .with("n", i + 1)
+                .with("pieces", total);
+            piece.set_name(template.render(&values));
+        }
+        pieces
+    }
+
+    /// Completa una pieza con los waypoints que caen en ella y su fecha de inicio
+    pub(crate) fn finish_piece(&self, mut piece: Gpx) -> Gpx {
+        if let Some(bounds) = piece.bounds() {
+            piece.waypoints = self
+                .waypoints
+                .iter()
+                .filter(|w| bounds.contains(w.lat, w.lon))
+                .cloned()
+                .collect();
+        }
+        if let Some(start) = start_time(&piece) {
+            piece.metadata_mut().time = Some(start.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        if piece.metadata.as_ref().is_some_and(|m| m.bounds.is_some()) {
+            piece.update_metadata_bounds();
+        }
+        piece
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::waypoint::Waypoint;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 7, day, hour, 0, 0).unwrap()
+    }
+
+    fn hike() -> Gpx {
+        let mut track = Track::with_name("Hike".to_string());
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(42.00, 1.0, None, at(1, 9)),
+            Point::with_time(42.01, 1.0, None, at(1, 10)),
+            Point::with_time(42.02, 1.0, None, at(1, 22)),
+        ]));
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(42.03, 1.0, None, at(2, 8)),
+            Point::new(42.04, 1.0),
+            Point::with_time(42.05, 1.0, None, at(2, 9)),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx.add_waypoint(Waypoint::with_name(42.035, 1.0, "Hut".to_string()));
+        gpx
+    }
+
+    #[test]
+    fn test_split_keeps_the_track_header() {
+        let mut gpx = hike();
+        gpx.tracks[0].kind = Some("hiking".to_string());
+        gpx.tracks[0].user_data.insert("guide", "Ana");
+
+        for piece in gpx.split(SplitStrategy::per_day()) {
+            assert_eq!(piece.tracks[0].kind.as_deref(), Some("hiking"));
+            assert_eq!(piece.tracks[0].user_data.get("guide"), Some("Ana"));
+        }
+    }
+
+    #[test]
+    fn test_split_by_time_gap() {
+        let pieces = hike().split(SplitStrategy::gap_minutes(90));
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[0].total_points(), 2);
+        assert_eq!(pieces[1].total_points(), 1);
+        // El punto sin tiempo se queda con el anterior
+        assert_eq!(pieces[2].total_points(), 3);
+        assert_eq!(pieces[2].track_names(), vec!["Hike"]);
+        assert_eq!(pieces[2].date(), Some("2024-07-02T08:00:00Z"));
+        assert_eq!(pieces[2].waypoint_names(), vec!["Hut"]);
+        assert!(pieces[0].waypoints.is_empty());
+    }
+
+    #[test]
+    fn test_split_per_day_with_offset() {
+        assert_eq!(hike().split(SplitStrategy::per_day()).len(), 2);
+
+        // A las 22:00 UTC ya es día 2 en UTC+3
+        let offset = FixedOffset::east_opt(3 * 3600).unwrap();
+        let pieces = hike().split(SplitStrategy::Day(offset));
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0].total_points(), 2);
+        assert_eq!(pieces[1].total_points(), 4);
+        assert_eq!(pieces[1].total_segments(), 2);
+    }
+
+    #[test]
+    fn test_split_every_km_shares_cut_points() {
+        let gpx = hike();
+        let pieces = gpx.split(SplitStrategy::every_km(1.0));
+        assert_eq!(pieces.len(), 4);
+
+        let total: f64 = pieces.iter().map(Gpx::total_distance_km).sum();
+        assert!((total - gpx.total_distance_km()).abs() < 1e-9);
+        assert!(pieces[0].total_distance_km() >= 1.0);
+        assert_eq!(
+            pieces[0].tracks[0].segments[0].points.last().unwrap().lat,
+            pieces[1].tracks[0].segments[0].points[0].lat
+        );
+
+        assert!(Gpx::new().split(SplitStrategy::every_km(1.0)).is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::gpx::{
+    parser::{Gpx, GpxStatistics},
+    point::Point,
+    split::Piece,
+    track::Track,
+};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// Sport of a leg found by [`Gpx::segment_by_sport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sport {
+    /// Swimming, the slowest leg
+    Swim,
+    /// Cycling
+    Bike,
+    /// Running
+    Run,
+}
+
+impl fmt::Display for Sport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Swim => "swim",
+            Self::Bike => "bike",
+            Self::Run => "run",
+        })
+    }
+}
+
+/// Thresholds used by [`Gpx::segment_by_sport`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SportOptions {
+    /// Below this speed, in km/h, a stretch is considered swimming
+    pub swim_max_kmh: f64,
+    /// Above this speed, in km/h, a stretch is considered cycling
+    pub run_max_kmh: f64,
+    /// Cadence, in steps per minute, from which a stretch is running whatever its speed
+    pub run_min_cadence: u8,
+    /// Width, in seconds, of the window the speed is averaged over
+    pub window_seconds: i64,
+    /// Legs shorter than this, in seconds, are merged into the previous one
+    pub min_leg_seconds: i64,
+}
+
+impl Default for SportOptions {
+    fn default() -> Self {
+        Self {
+            swim_max_kmh: 5.0,
+            run_max_kmh: 18.0,
+            run_min_cadence: 150,
+            window_seconds: 60,
+            min_leg_seconds: 180,
+        }
+    }
+}
+
+impl SportOptions {
+    /// Deporte de un tramo según su velocidad media y la cadencia del punto
+    fn classify(self, speed_kmh: f64, cadence: Option<u8>) -> Sport {
+        if speed_kmh < self.swim_max_kmh {
+            Sport::Swim
+        } else if cadence.is_some_and(|c| c >= self.run_min_cadence)
+            || speed_kmh <= self.run_max_kmh
+        {
+            Sport::Run
+        } else {
+            Sport::Bike
+        }
+    }
+}
+
+/// One leg of a multi-sport activity
+#[derive(Debug, Clone)]
+pub struct SportLeg {
+    /// Detected sport
+    pub sport: Sport,
+    /// Index of the first point in [`Gpx::get_all_points`] order
+    pub start_index: usize,
+    /// Index of the last point in [`Gpx::get_all_points`] order
+    pub end_index: usize,
+    /// The points of the leg, keeping the track and segment structure
+    pub gpx: Gpx,
+    /// Statistics of the leg
+    pub statistics: GpxStatistics,
+}
+
+/// Tramo provisional: deporte, primer índice y último índice
+type Run = (Sport, usize, usize);
+
+/// Duración en segundos de un tramo, según los puntos con tiempo que contiene
+fn span_seconds(times: &[Option<DateTime<Utc>>], (_, start, end): Run) -> i64 {
+    let first = times[start..=end].iter().flatten().next();
+    let last = times[start..=end].iter().flatten().next_back();
+    first.zip(last).map_or(0, |(a, b)| (*b - *a).num_seconds())
+}
+
+impl Gpx {
+    /// Splits a multi-sport recording (such as a triathlon) into swim, bike and run legs
+    ///
+    /// Each point is classified by the average speed over a window around it:
+    /// slower than `swim_max_kmh` is swimming, faster than `run_max_kmh` is
+    /// cycling, and in between, or with a cadence of at least `run_min_cadence`,
+    /// running. Legs shorter than `min_leg_seconds` (transitions, traffic lights)
+    /// are merged into the previous leg, or into the next one at the start. Points
+    /// without a timestamp join the leg they are in. Returns no legs if the
+    /// document has fewer than two timed points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, Sport, SportOptions};
+    ///
+    /// // 10 minutos a 2.2 km/h y otros 10 a 26.7 km/h
+    /// let mut xml = String::from("<gpx><trk><trkseg>");
+    /// for minute in 0..=20 {
+    ///     let lat = if minute <= 10 { minute as f64 * 0.0003 } else { 0.003 + (minute - 10) as f64 * 0.004 };
+    ///     xml.push_str(&format!(
+    ///         r#"<trkpt lat="{}" lon="0"><time>2024-07-01T08:{minute:02}:00Z</time></trkpt>"#,
+    ///         40.0 + lat
+    ///     ));
+    /// }
+    /// xml.push_str("</trkseg></trk></gpx>");
+    ///
+    /// let legs = Gpx::try_from_str(&xml).unwrap().segment_by_sport(SportOptions::default());
+    /// assert_eq!(legs.len(), 2);
+    /// assert_eq!(legs[0].sport, Sport::Swim);
+    /// assert_eq!(legs[1].sport, Sport::Bike);
+    /// assert!(legs[1].statistics.total_distance_km > 4.0);
+    /// ```
+    pub fn segment_by_sport(&self, options: SportOptions) -> Vec<SportLeg> {
+        let points: Vec<(usize, usize, &Track, &Point)> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .flat_map(|(t, track)| {
+                track
+                    .segments
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(s, segment)| {
+                        segment.points.iter().map(move |point| (t, s, track, point))
+                    })
+            })
+            .collect();
+        let distances: Vec<f64> = self.point_metrics().iter().map(|m| m.distance_km).collect();
+        let times: Vec<Option<DateTime<Utc>>> = points.iter().map(|(.., p)| p.time).collect();
+        let with_time: Vec<usize> = (0..points.len()).filter(|&i| times[i].is_some()).collect();
+        if with_time.len() < 2 {
+            return Vec::new();
+        }
+
+        // Velocidad media en una ventana centrada en cada punto con tiempo
+        let half = chrono::Duration::seconds(options.window_seconds / 2);
+        let (mut from, mut to) = (0, 0);
+        let mut sports: Vec<Option<Sport>> = vec![None; points.len()];
+        for (k, &i) in with_time.iter().enumerate() {
+            let Some(time) = times[i] else { continue };
+            while times[with_time[from]].is_some_and(|t| t < time - half) {
+                from += 1;
+            }
+            to = to.max(k);
+            while to + 1 < with_time.len()
+                && times[with_time[to + 1]].is_some_and(|t| t <= time + half)
+            {
+                to += 1;
+            }
+            let (a, b) = (with_time[from.min(k)], with_time[to]);
+            let (a, b) = if a == b {
+                (
+                    with_time[k.saturating_sub(1)],
+                    with_time[(k + 1).min(with_time.len() - 1)],
+                )
+            } else {
+                (a, b)
+            };
+            if let (Some(start), Some(end)) = (times[a], times[b]) {
+                #[allow(clippy::cast_precision_loss)]
+                let hours = (end - start).num_milliseconds() as f64 / 3_600_000.0;
+                if hours > 0.0 {
+                    let speed = (distances[b] - distances[a]) / hours;
+                    sports[i] = Some(options.classify(speed, points[i].3.cadence()));
+                }
+            }
+        }
+
+        // Tramos consecutivos del mismo deporte; los puntos sin clasificar siguen al anterior
+        let mut runs: Vec<Run> = Vec::new();
+        let first_sport = sports
+            .iter()
+            .flatten()
+            .next()
+            .copied()
+            .unwrap_or(Sport::Run);
+        for (i, sport) in sports.iter().enumerate() {
+            let sport = sport.unwrap_or_else(|| runs.last().map_or(first_sport, |r| r.0));
+            match runs.last_mut() {
+                Some(run) if run.0 == sport => run.2 = i,
+                _ => runs.push((sport, i, i)),
+            }
+        }
+
+        // Los tramos cortos se absorben en el anterior (o en el siguiente, si es el primero)
+        let mut merged: Vec<Run> = Vec::new();
+        for run in runs {
+            let short = span_seconds(&times, run) < options.min_leg_seconds;
+            match merged.last_mut() {
+                Some(last) if short || last.0 == run.0 => last.2 = run.2,
+                Some(last) if span_seconds(&times, *last) < options.min_leg_seconds => {
+                    *last = (run.0, last.1, run.2);
+                }
+                _ => merged.push(run),
+            }
+        }
+
+        merged
+            .into_iter()
+            .map(|(sport, start, end)| {
+                let mut piece = Piece::new(self);
+                for &(t, s, track, point) in &points[start..=end] {
+                    piece.push((t, s), track, point.clone());
+                }
+                let gpx = piece.gpx;
+                SportLeg {
+                    sport,
+                    start_index: start,
+                    end_index: end,
+                    statistics: gpx.statistics(),
+                    gpx,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::TrackSegment;
+    use chrono::{Duration, TimeZone};
+
+    /// Tramos de (minutos, km/h, cadencia) hacia el norte, con un punto cada 30 s
+    fn multisport(legs: &[(i64, f64, Option<u8>)]) -> Gpx {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 7, 0, 0).unwrap();
+        let mut points = vec![Point::with_time(40.0, -3.0, None, start)];
+        let mut lat = 40.0;
+        let mut elapsed = 0;
+        for &(minutes, speed_kmh, cadence) in legs {
+            for _ in 0..minutes * 2 {
+                // 1° de latitud ≈ 111.195 km
+                lat += speed_kmh / 120.0 / 111.195;
+                elapsed += 30;
+                let mut point =
+                    Point::with_time(lat, -3.0, None, start + Duration::seconds(elapsed));
+                point.extensions.cadence = cadence;
+                points.push(point);
+            }
+        }
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(points));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx
+    }
+
+    #[test]
+    fn test_triathlon_legs_with_short_transitions() {
+        // Natación, T1 andando, bici, parada en un semáforo, T2 y carrera
+        let gpx = multisport(&[
+            (30, 3.0, None),
+            (2, 6.0, None),
+            (60, 32.0, Some(90)),
+            (1, 0.0, None),
+            (60, 30.0, Some(85)),
+            (2, 6.0, None),
+            (40, 12.0, Some(170)),
+        ]);
+        let legs = gpx.segment_by_sport(SportOptions::default());
+        let sports: Vec<Sport> = legs.iter().map(|leg| leg.sport).collect();
+        assert_eq!(sports, vec![Sport::Swim, Sport::Bike, Sport::Run]);
+
+        let bike = &legs[1];
+        assert!((bike.statistics.total_distance_km - 62.0).abs() < 1.5);
+        assert_eq!(legs[0].start_index, 0);
+        assert_eq!(legs[1].start_index, legs[0].end_index + 1);
+        assert_eq!(legs[2].end_index, gpx.total_points() - 1);
+        assert_eq!(
+            legs.iter().map(|leg| leg.gpx.total_points()).sum::<usize>(),
+            gpx.total_points()
+        );
+        assert_eq!(Sport::Bike.to_string(), "bike");
+    }
+
+    #[test]
+    fn test_high_cadence_means_running_and_untimed_input() {
+        let options = SportOptions::default();
+        assert_eq!(options.classify(20.0, Some(180)), Sport::Run);
+        assert_eq!(options.classify(20.0, Some(90)), Sport::Bike);
+        assert_eq!(options.classify(2.0, Some(180)), Sport::Swim);
+
+        let untimed = Gpx::from_polyline_coords(&[(40.0, -3.0), (40.1, -3.0)]);
+        assert!(untimed.segment_by_sport(options).is_empty());
+    }
+}
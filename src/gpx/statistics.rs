@@ -0,0 +1,320 @@
+use crate::gpx::{
+    distance::{DistanceModel, Planet},
+    elevation::ElevationOptions,
+    parser::{Gpx, GpxStatistics},
+    point::Point,
+    track::{Track, TrackSegment},
+    units::Units,
+};
+use chrono::{DateTime, Utc};
+
+/// How [`Gpx::statistics_with`] computes its figures
+///
+/// Keeping one set of options lets an application compute every figure the
+/// same way instead of combining separate methods. The default options give
+/// the same result as [`Gpx::statistics`].
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{DistanceModel, ElevationOptions, Gpx, StatisticsOptions};
+///
+/// let gpx = Gpx::try_from_str(
+///     r#"<gpx><trk><trkseg>
+///         <trkpt lat="0.0" lon="0.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+///         <trkpt lat="0.0" lon="0.1"><time>2024-07-01T08:30:00Z</time></trkpt>
+///         <trkpt lat="0.0" lon="0.1"><time>2024-07-01T09:00:00Z</time></trkpt>
+///     </trkseg></trk></gpx>"#,
+/// )
+/// .unwrap();
+///
+/// let options = StatisticsOptions {
+///     distance_model: DistanceModel::Vincenty,
+///     elevation: Some(ElevationOptions::default()),
+///     moving_threshold_kmh: Some(1.0),
+///     ..StatisticsOptions::default()
+/// };
+/// let stats = gpx.statistics_with(options);
+/// assert!((stats.total_distance_km - 11.132).abs() < 0.001);
+/// // La media hora parado no cuenta
+/// assert_eq!(stats.duration_seconds, Some(1800));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StatisticsOptions {
+    /// Distance between consecutive points
+    pub distance_model: DistanceModel,
+    /// Body the track was recorded on, whose radius scales every distance
+    pub planet: Planet,
+    /// Count the elevation change in the distance, as in [`Gpx::total_distance_3d_km`]
+    pub include_elevation: bool,
+    /// Smoothing for the elevation gain and loss; `None` sums the raw differences
+    pub elevation: Option<ElevationOptions>,
+    /// Minimum speed counted as moving; when set, `duration_seconds` and
+    /// `average_speed_kmh` only cover the time between points recorded at this
+    /// speed or faster, leaving out stops
+    pub moving_threshold_kmh: Option<f64>,
+    /// Unit system the figures are meant to be shown in, for
+    /// [`GpxStatistics::display`]; the statistics themselves are always metric
+    pub units: Units,
+}
+
+impl StatisticsOptions {
+    /// Distancia entre dos puntos según el modelo, el planeta y el desnivel
+    fn step_distance_km(self, p1: &Point, p2: &Point) -> f64 {
+        if self.include_elevation {
+            self.distance_model.distance_3d_km_on(self.planet, p1, p2)
+        } else {
+            self.distance_model.distance_km_on(self.planet, p1, p2)
+        }
+    }
+
+    /// Distancia de un segmento según las opciones
+    pub(crate) fn segment_distance_km(self, segment: &TrackSegment) -> f64 {
+        segment
+            .points
+            .windows(2)
+            .map(|window| self.step_distance_km(&window[0], &window[1]))
+            .sum()
+    }
+
+    /// Segundos en movimiento de un segmento y la distancia recorrida en ellos
+    ///
+    /// Solo cuentan los pares de puntos con tiempo cuya velocidad llega al umbral.
+    fn moving(self, segment: &TrackSegment, threshold_kmh: f64) -> Option<(i64, f64)> {
+        let mut moving: Option<(i64, f64)> = None;
+        for window in segment.points.windows(2) {
+            let (Some(t1), Some(t2)) = (window[0].time, window[1].time) else {
+                continue;
+            };
+            let (seconds, km) = moving.get_or_insert((0, 0.0));
+            let step_seconds = (t2 - t1).num_seconds();
+            let step_km = self.step_distance_km(&window[0], &window[1]);
+            #[allow(clippy::cast_precision_loss)]
+            let hours = step_seconds as f64 / 3600.0;
+            if step_seconds > 0 && step_km / hours >= threshold_kmh {
+                *seconds += step_seconds;
+                *km += step_km;
+            }
+        }
+        moving
+    }
+}
+
+impl GpxStatistics {
+    /// Como [`GpxStatistics::from_segments`], con las opciones indicadas
+    pub(crate) fn from_segments_with<'a, I>(segments: I, options: StatisticsOptions) -> Self
+    where
+        I: IntoIterator<Item = &'a TrackSegment>,
+    {
+        let mut stats = Self {
+            total_tracks: 0,
+            total_waypoints: 0,
+            total_segments: 0,
+            total_points: 0,
+            total_distance_km: 0.0,
+            elevation_range: None,
+            elevation_gain: None,
+            elevation_loss: None,
+            duration_seconds: None,
+            average_speed_kmh: None,
+        };
+        let mut time_range: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+        let mut smoothed: Option<(f64, f64)> = None;
+        let mut moving: Option<(i64, f64)> = None;
+
+        for segment in segments {
+            stats.total_segments += 1;
+            stats.total_points += segment.point_count();
+            stats.total_distance_km += options.segment_distance_km(segment);
+
+            for point in &segment.points {
+                if let Some(ele) = point.elevation {
+                    stats.elevation_range = Some(match stats.elevation_range {
+                        Some((min, max)) => (min.min(ele), max.max(ele)),
+                        None => (ele, ele),
+                    });
+                }
+                if let Some(time) = point.time {
+                    time_range = Some(match time_range {
+                        Some((first, last)) => (first.min(time), last.max(time)),
+                        None => (time, time),
+                    });
+                }
+            }
+
+            if let Some(elevation) = options.elevation {
+                if let Some((gain, loss)) = segment.elevation_gain_loss_smoothed(elevation) {
+                    let total = smoothed.get_or_insert((0.0, 0.0));
+                    total.0 += gain;
+                    total.1 += loss;
+                }
+            } else {
+                for window in segment.points.windows(2) {
+                    if let (Some(ele1), Some(ele2)) = (window[0].elevation, window[1].elevation) {
+                        let diff = ele2 - ele1;
+                        *stats.elevation_gain.get_or_insert(0.0) += diff.max(0.0);
+                        *stats.elevation_loss.get_or_insert(0.0) += (-diff).max(0.0);
+                    }
+                }
+            }
+
+            if let Some(threshold) = options.moving_threshold_kmh {
+                if let Some((seconds, km)) = options.moving(segment, threshold) {
+                    let total = moving.get_or_insert((0, 0.0));
+                    total.0 += seconds;
+                    total.1 += km;
+                }
+            }
+        }
+
+        if let Some((gain, loss)) = smoothed {
+            stats.elevation_gain = Some(gain);
+            stats.elevation_loss = Some(loss);
+        }
+
+        // En movimiento, la velocidad media es la de los tramos que cuentan
+        let (duration_seconds, moving_km) = match moving {
+            Some((seconds, km)) => (Some(seconds), km),
+            None if options.moving_threshold_kmh.is_some() => (None, 0.0),
+            None => (
+                time_range.map(|(first, last)| (last - first).num_seconds()),
+                stats.total_distance_km,
+            ),
+        };
+        stats.duration_seconds = duration_seconds;
+        if let Some(seconds) = duration_seconds.filter(|&s| s > 0) {
+            #[allow(clippy::cast_precision_loss)]
+            let hours = seconds as f64 / 3600.0;
+            stats.average_speed_kmh = Some(moving_km / hours);
+        }
+        stats
+    }
+}
+
+impl Gpx {
+    /// Statistics of the whole document, computed with `options`
+    pub fn statistics_with(&self, options: StatisticsOptions) -> GpxStatistics {
+        GpxStatistics {
+            total_tracks: self.tracks.len(),
+            total_waypoints: self.waypoints.len(),
+            ..GpxStatistics::from_segments_with(
+                self.tracks.iter().flat_map(|track| &track.segments),
+                options,
+            )
+        }
+    }
+}
+
+impl Track {
+    /// Statistics of this track alone, computed with `options`
+    pub fn statistics_with(&self, options: StatisticsOptions) -> GpxStatistics {
+        GpxStatistics {
+            total_tracks: 1,
+            ..GpxStatistics::from_segments_with(&self.segments, options)
+        }
+    }
+}
+
+impl TrackSegment {
+    /// Statistics of this segment alone, computed with `options`
+    pub fn statistics_with(&self, options: StatisticsOptions) -> GpxStatistics {
+        GpxStatistics::from_segments_with(std::iter::once(self), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hike() -> Gpx {
+        Gpx::try_from_str(
+            r#"<gpx><wpt lat="40.0" lon="-3.0"/><trk><trkseg>
+                <trkpt lat="40.0" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+                <trkpt lat="40.1" lon="-3.1"><ele>650</ele><time>2024-07-01T09:00:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_default_options_match_statistics() {
+        let gpx = hike();
+        let plain = gpx.statistics();
+        let default = gpx.statistics_with(StatisticsOptions::default());
+        assert_eq!(default.total_waypoints, 1);
+        assert_eq!(default.total_distance_km, plain.total_distance_km);
+        assert_eq!(default.average_speed_kmh, plain.average_speed_kmh);
+        assert_eq!(default.elevation_gain, plain.elevation_gain);
+
+        let options = StatisticsOptions {
+            distance_model: DistanceModel::Vincenty,
+            ..StatisticsOptions::default()
+        };
+        let vincenty = gpx.statistics_with(options);
+        let track = gpx.tracks[0].statistics_with(options);
+        assert_eq!(track.total_distance_km, vincenty.total_distance_km);
+        // El elipsoide y la esfera difieren en unas décimas por ciento
+        let ratio = vincenty.total_distance_km / plain.total_distance_km;
+        assert!(ratio != 1.0 && (ratio - 1.0).abs() < 0.005);
+
+        let options = StatisticsOptions {
+            include_elevation: true,
+            ..StatisticsOptions::default()
+        };
+        let steep = gpx.statistics_with(options);
+        assert_eq!(steep.total_distance_km, gpx.total_distance_3d_km());
+        assert!(steep.average_speed_kmh > plain.average_speed_kmh);
+    }
+
+    #[test]
+    fn test_planet_scales_distances() {
+        let gpx = hike();
+        let earth = gpx.statistics();
+        let options = StatisticsOptions {
+            planet: Planet::Mars,
+            ..StatisticsOptions::default()
+        };
+        let mars = gpx.statistics_with(options);
+
+        let ratio = Planet::Mars.radius_km() / Planet::Earth.radius_km();
+        assert!((mars.total_distance_km / earth.total_distance_km - ratio).abs() < 1e-12);
+        let speed_ratio = mars.average_speed_kmh.unwrap() / earth.average_speed_kmh.unwrap();
+        assert!((speed_ratio - ratio).abs() < 1e-12);
+        assert_eq!(mars.duration_seconds, earth.duration_seconds);
+    }
+
+    #[test]
+    fn test_smoothing_and_moving_time() {
+        let mut gpx = hike();
+        // Una parada de media hora al final y ruido de altitud
+        let stop = Gpx::try_from_str(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="40.1" lon="-3.1"><ele>651</ele><time>2024-07-01T09:10:00Z</time></trkpt>
+                <trkpt lat="40.1" lon="-3.1"><ele>650</ele><time>2024-07-01T09:30:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        gpx.tracks[0].segments[0].points.extend(stop.into_points());
+
+        let plain = gpx.statistics();
+        assert_eq!(plain.duration_seconds, Some(5400));
+        assert_eq!(plain.elevation_gain, Some(51.0));
+
+        let options = StatisticsOptions {
+            elevation: Some(ElevationOptions::threshold(5.0)),
+            moving_threshold_kmh: Some(1.0),
+            ..StatisticsOptions::default()
+        };
+        let stats = gpx.statistics_with(options);
+        assert_eq!(stats.elevation_gain, Some(50.0));
+        assert_eq!(stats.total_distance_km, plain.total_distance_km);
+        assert_eq!(stats.duration_seconds, Some(3600));
+        let moving_speed = stats.average_speed_kmh.unwrap();
+        assert!((moving_speed - plain.total_distance_km).abs() < 1e-9);
+
+        // Sin ningún par de puntos con tiempo no hay duración en movimiento
+        let mut untimed = gpx.clone();
+        untimed.points_mut().for_each(|point| point.time = None);
+        assert_eq!(untimed.statistics_with(options).duration_seconds, None);
+    }
+}
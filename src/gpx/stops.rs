@@ -0,0 +1,185 @@
+use crate::gpx::{
+    parser::Gpx,
+    point::{haversine_distance, Point},
+};
+use chrono::{DateTime, Utc};
+
+/// Options for [`Gpx::detect_stops`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StopOptions {
+    /// Speed, in km/h, below which the recording is considered stopped
+    pub max_speed_kmh: f64,
+    /// Shortest pause reported, in seconds
+    pub min_duration_seconds: i64,
+}
+
+impl Default for StopOptions {
+    fn default() -> Self {
+        Self {
+            max_speed_kmh: 1.5,
+            min_duration_seconds: 120,
+        }
+    }
+}
+
+/// A pause found by [`Gpx::detect_stops`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stop {
+    /// Mean position of the points recorded during the stop
+    pub location: Point,
+    /// Time of the first stopped point
+    pub start: DateTime<Utc>,
+    /// Time of the last stopped point
+    pub end: DateTime<Utc>,
+    /// Length of the stop, in seconds
+    pub duration_seconds: i64,
+    /// Index of the first point, in [`Gpx::get_all_points`] order
+    pub start_index: usize,
+    /// Index of the last point, in [`Gpx::get_all_points`] order
+    pub end_index: usize,
+}
+
+/// Punto con hora: índice global, punto e instante
+type TimedPoint<'a> = (usize, &'a Point, DateTime<Utc>);
+
+/// Guarda la parada si dura lo suficiente
+fn emit(run: &[TimedPoint<'_>], options: StopOptions, out: &mut Vec<Stop>) {
+    let (Some(&(start_index, _, start)), Some(&(end_index, _, end))) = (run.first(), run.last())
+    else {
+        return;
+    };
+    let duration_seconds = (end - start).num_seconds();
+    if duration_seconds < options.min_duration_seconds {
+        return;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let count = run.len() as f64;
+    let lat = run.iter().map(|(_, point, _)| point.lat).sum::<f64>() / count;
+    let lon = run.iter().map(|(_, point, _)| point.lon).sum::<f64>() / count;
+    out.push(Stop {
+        location: Point::new(lat, lon),
+        start,
+        end,
+        duration_seconds,
+        start_index,
+        end_index,
+    });
+}
+
+impl Gpx {
+    /// Finds the pauses of the recording, e.g. to plan rest points or audit café stops
+    ///
+    /// A stop is a run of consecutive timed points moving slower than
+    /// `max_speed_kmh` for at least `min_duration_seconds`. Stops never span two
+    /// segments; untimed points are ignored. Results are in recording order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, StopOptions};
+    ///
+    /// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+    ///     <trkpt lat="40.00" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+    ///     <trkpt lat="40.05" lon="-3.0"><time>2024-07-01T08:15:00Z</time></trkpt>
+    ///     <trkpt lat="40.05" lon="-3.0"><time>2024-07-01T08:35:00Z</time></trkpt>
+    ///     <trkpt lat="40.10" lon="-3.0"><time>2024-07-01T08:50:00Z</time></trkpt>
+    /// </trkseg></trk></gpx>"#).unwrap();
+    ///
+    /// let stops = gpx.detect_stops(StopOptions::default());
+    /// assert_eq!(stops.len(), 1);
+    /// assert_eq!(stops[0].duration_seconds, 20 * 60);
+    /// assert_eq!((stops[0].start_index, stops[0].end_index), (1, 2));
+    /// assert_eq!(gpx.stopped_seconds(StopOptions::default()), 20 * 60);
+    /// ```
+    pub fn detect_stops(&self, options: StopOptions) -> Vec<Stop> {
+        let mut stops = Vec::new();
+        let mut offset = 0;
+        for segment in self.tracks.iter().flat_map(|track| &track.segments) {
+            let timed: Vec<TimedPoint<'_>> = segment
+                .points
+                .iter()
+                .enumerate()
+                .filter_map(|(i, point)| point.time.map(|time| (offset + i, point, time)))
+                .collect();
+            let mut run_start: Option<usize> = None;
+            for k in 1..timed.len() {
+                let ((_, before, from), (_, after, to)) = (timed[k - 1], timed[k]);
+                #[allow(clippy::cast_precision_loss)]
+                let hours = (to - from).num_milliseconds() as f64 / 3_600_000.0;
+                if hours <= 0.0 {
+                    // Misma hora repetida: ni alarga ni corta la parada
+                    continue;
+                }
+                if haversine_distance(before, after) / hours < options.max_speed_kmh {
+                    run_start.get_or_insert(k - 1);
+                } else if let Some(start) = run_start.take() {
+                    emit(&timed[start..k], options, &mut stops);
+                }
+            }
+            if let Some(start) = run_start {
+                emit(&timed[start..], options, &mut stops);
+            }
+            offset += segment.points.len();
+        }
+        stops
+    }
+
+    /// Total time spent in the stops found by [`Gpx::detect_stops`], in seconds
+    pub fn stopped_seconds(&self, options: StopOptions) -> i64 {
+        self.detect_stops(options)
+            .iter()
+            .map(|stop| stop.duration_seconds)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::track::{Track, TrackSegment};
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_detect_stops_with_jitter_and_segments() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |lat: f64, minutes: i64| {
+            Point::with_time(lat, -3.0, None, start + Duration::minutes(minutes))
+        };
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::with_points(vec![
+            at(40.0, 0),
+            at(40.02, 10),
+            // Café: once minutos con unos metros de deriva del GPS
+            at(40.02, 11),
+            at(40.020_05, 16),
+            Point::new(40.5, -3.0),
+            at(40.02, 21),
+            at(40.04, 31),
+            // Un semáforo, demasiado corto
+            at(40.04, 32),
+            at(40.06, 42),
+        ]));
+        // Las paradas no cruzan de un segmento al siguiente
+        track.add_segment(TrackSegment::with_points(vec![
+            at(40.06, 50),
+            at(40.06, 53),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        let stops = gpx.detect_stops(StopOptions::default());
+        assert_eq!(stops.len(), 2);
+        assert_eq!((stops[0].start_index, stops[0].end_index), (1, 5));
+        assert_eq!(stops[0].start, start + Duration::minutes(10));
+        assert_eq!(stops[0].duration_seconds, 11 * 60);
+        assert!((stops[0].location.lat - 40.020_012_5).abs() < 1e-9);
+        assert_eq!((stops[1].start_index, stops[1].end_index), (9, 10));
+
+        assert_eq!(gpx.stopped_seconds(StopOptions::default()), 14 * 60);
+        let strict = StopOptions {
+            min_duration_seconds: 15 * 60,
+            ..StopOptions::default()
+        };
+        assert!(gpx.detect_stops(strict).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::gpx::{
+    collection::GpxCollection, error::GpxError, formats::FormatRegistry, parser::Gpx,
+    write::WriteOptions,
+};
+use std::path::{Path, PathBuf};
+
+/// Storage backend holding documents under `/`-separated keys
+///
+/// Keys look like relative paths (`2024/05/ride.gpx`) regardless of the backend,
+/// and listing is recursive by prefix, as in object stores. [`FsStore`] maps keys
+/// to files below a directory; other backends can be plugged into
+/// [`GpxCollection::from_store`] by implementing the four operations.
+pub trait GpxStore: Send + Sync {
+    /// Keys starting with `prefix`, sorted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend cannot be listed
+    fn list(&self, prefix: &str) -> Result<Vec<String>, GpxError>;
+
+    /// Raw contents stored under `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist or cannot be read
+    fn read(&self, key: &str) -> Result<Vec<u8>, GpxError>;
+
+    /// Stores `bytes` under `key`, replacing any previous contents
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is invalid or the write fails
+    fn write(&self, key: &str, bytes: &[u8]) -> Result<(), GpxError>;
+
+    /// Removes `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist or cannot be removed
+    fn delete(&self, key: &str) -> Result<(), GpxError>;
+
+    /// Reads and parses the GPX document stored under `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be read or is not a valid GPX document
+    fn read_gpx(&self, key: &str) -> Result<Gpx, GpxError> {
+        let bytes = self.read(key)?;
+        let xml = std::str::from_utf8(&bytes)
+            .map_err(|e| GpxError::Schema(format!("document is not valid UTF-8: {e}")))?;
+        Gpx::try_from_str(xml)
+    }
+
+    /// Serializes `gpx` as GPX XML and stores it under `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be serialized or written
+    fn write_gpx(&self, key: &str, gpx: &Gpx) -> Result<(), GpxError> {
+        let xml = gpx.to_xml_with(&WriteOptions::default())?;
+        self.write(key, xml.as_bytes())
+    }
+}
+
+/// [`GpxStore`] backed by a local directory
+#[derive(Debug, Clone)]
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    /// Crea un almacén sobre el directorio indicado
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Directorio raíz del almacén
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resuelve una clave a una ruta dentro de la raíz, rechazando `..` y rutas absolutas
+    fn path(&self, key: &str) -> Result<PathBuf, GpxError> {
+        let mut path = self.root.clone();
+        for component in key.split('/') {
+            if component.is_empty() || component == "." || component == ".." {
+                return Err(GpxError::Store(format!("invalid key '{key}'")));
+            }
+            path.push(component);
+        }
+        Ok(path)
+    }
+}
+
+impl GpxStore for FsStore {
+    fn list(&self, prefix: &str) -> Result<Vec<String>, GpxError> {
+        let mut keys = Vec::new();
+        let mut pending = vec![(self.root.clone(), String::new())];
+
+        while let Some((dir, dir_key)) = pending.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let key = format!("{dir_key}{name}");
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    // Solo se desciende en directorios que pueden contener el prefijo
+                    let dir_prefix = format!("{key}/");
+                    if dir_prefix.starts_with(prefix) || prefix.starts_with(&dir_prefix) {
+                        pending.push((entry.path(), dir_prefix));
+                    }
+                } else if file_type.is_file() && key.starts_with(prefix) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn read(&self, key: &str) -> Result<Vec<u8>, GpxError> {
+        Ok(std::fs::read(self.path(key)?)?)
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> Result<(), GpxError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(std::fs::write(path, bytes)?)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), GpxError> {
+        Ok(std::fs::remove_file(self.path(key)?)?)
+    }
+}
+
+impl GpxCollection {
+    /// Loads every supported document stored under `prefix`
+    ///
+    /// Entry paths are the store keys. As with [`GpxCollection::from_dir`], keys in
+    /// an unrecognized format are skipped and broken documents are reported in
+    /// [`GpxCollection::failures`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be listed
+    pub fn from_store(store: &dyn GpxStore, prefix: &str) -> Result<Self, GpxError> {
+        Self::from_store_with(store, prefix, &FormatRegistry::default())
+    }
+
+    /// Like [`GpxCollection::from_store`], reading documents with `registry`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be listed
+    pub fn from_store_with(
+        store: &dyn GpxStore,
+        prefix: &str,
+        registry: &FormatRegistry,
+    ) -> Result<Self, GpxError> {
+        let mut collection = Self::new();
+        for key in store.list(prefix)? {
+            let path = PathBuf::from(&key);
+            match store.read(&key) {
+                Ok(bytes) => collection.load(path, &bytes, registry),
+                Err(e) => collection.failures.push((path, e)),
+            }
+        }
+        Ok(collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fs_store_round_trip_and_listing() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FsStore::new(dir.path());
+        let gpx = Gpx::builder()
+            .track(|t| {
+                t.name("Ride")
+                    .segment(|s| s.point(40.0, -3.0).point(40.1, -3.0))
+            })
+            .build()
+            .unwrap();
+
+        store.write_gpx("2024/05/ride.gpx", &gpx).unwrap();
+        store.write_gpx("2024/06/run.gpx", &gpx).unwrap();
+        store.write("2024/notes.txt", b"not a track").unwrap();
+        store.write("2023/old.gpx", b"<gpx><trk>").unwrap();
+
+        assert_eq!(
+            store.list("2024/").unwrap(),
+            vec!["2024/05/ride.gpx", "2024/06/run.gpx", "2024/notes.txt"]
+        );
+        assert_eq!(store.list("2024/05").unwrap(), vec!["2024/05/ride.gpx"]);
+        assert_eq!(
+            store.read_gpx("2024/05/ride.gpx").unwrap().track_names(),
+            vec!["Ride"]
+        );
+
+        let collection = GpxCollection::from_store(&store, "").unwrap();
+        assert_eq!(collection.len(), 2);
+        assert_eq!(
+            collection.entries[0].path,
+            PathBuf::from("2024/05/ride.gpx")
+        );
+        assert_eq!(collection.failures.len(), 1);
+
+        store.delete("2024/06/run.gpx").unwrap();
+        assert_eq!(store.list("2024/06").unwrap(), Vec::<String>::new());
+        assert!(store.delete("2024/06/run.gpx").is_err());
+    }
+
+    #[test]
+    fn test_fs_store_rejects_escaping_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FsStore::new(dir.path());
+        for key in ["../outside.gpx", "/abs.gpx", "a//b.gpx", ""] {
+            assert!(
+                matches!(store.write(key, b""), Err(GpxError::Store(_))),
+                "{key}"
+            );
+        }
+    }
+}
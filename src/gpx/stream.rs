@@ -0,0 +1,547 @@
+use crate::gpx::{
+    error::GpxError,
+    parser::GpxStatistics,
+    point::{haversine_distance, Point},
+    waypoint::Waypoint,
+};
+use chrono::{DateTime, Utc};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// An event produced while streaming through a GPX document
+///
+/// Events are emitted in document order. Every `TrackStart` is eventually
+/// followed by a matching `TrackEnd`, and every `SegmentStart` by a `SegmentEnd`:
+/// if the input ends inside a track, as in a truncated recording, the missing
+/// closing events are emitted at the end of the stream and a point cut off
+/// halfway is dropped.
+#[derive(Debug, Clone)]
+pub enum TrackPointEvent {
+    /// A `<trk>` element was opened
+    TrackStart {
+        /// Name of the track, if it appears before its first segment
+        name: Option<String>,
+    },
+    /// A `<trkseg>` element was opened
+    SegmentStart,
+    /// A complete `<trkpt>` was read
+    Point(Point),
+    /// The current `<trkseg>` was closed
+    SegmentEnd,
+    /// The current `<trk>` was closed
+    TrackEnd,
+    /// A complete `<wpt>` was read
+    Waypoint(Waypoint),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PointKind {
+    TrackPoint,
+    Waypoint,
+}
+
+/// Estado del `<trk>` actual: el `TrackStart` se emite al abrir el primer segmento
+#[derive(Debug)]
+enum TrackState {
+    Outside,
+    Pending(Option<String>),
+    Announced,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Name,
+    Elevation,
+    Time,
+}
+
+#[derive(Debug)]
+struct PendingPoint {
+    kind: PointKind,
+    lat: f64,
+    lon: f64,
+    name: Option<String>,
+    elevation: Option<f64>,
+    time: Option<DateTime<Utc>>,
+}
+
+impl PendingPoint {
+    fn into_event(self) -> TrackPointEvent {
+        match self.kind {
+            PointKind::TrackPoint => TrackPointEvent::Point(Point {
+                elevation: self.elevation,
+                time: self.time,
+                ..Point::new(self.lat, self.lon)
+            }),
+            PointKind::Waypoint => TrackPointEvent::Waypoint(Waypoint::with_details(
+                self.lat,
+                self.lon,
+                self.name,
+                self.elevation,
+                self.time,
+            )),
+        }
+    }
+}
+
+/// Lazy, event-based GPX parser over any `BufRead` source
+///
+/// Unlike [`Gpx::try_from_str`](crate::Gpx::try_from_str), the reader never holds
+/// more than one point in memory, which makes it suitable for very large recordings.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::stream::{GpxReader, TrackPointEvent};
+///
+/// let xml = r#"<gpx><trk><trkseg>
+///     <trkpt lat="40.0" lon="-3.0"><ele>600</ele></trkpt>
+///     <trkpt lat="40.1" lon="-3.0"><ele>650</ele></trkpt>
+/// </trkseg></trk></gpx>"#;
+///
+/// let points = GpxReader::new(xml.as_bytes())
+///     .filter_map(Result::ok)
+///     .filter(|event| matches!(event, TrackPointEvent::Point(_)))
+///     .count();
+/// assert_eq!(points, 2);
+/// ```
+pub struct GpxReader<R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    queue: VecDeque<TrackPointEvent>,
+    track: TrackState,
+    in_segment: bool,
+    current: Option<PendingPoint>,
+    text_field: Option<TextField>,
+    finished: bool,
+}
+
+impl GpxReader<BufReader<File>> {
+    /// Opens a GPX file for streaming
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> GpxReader<R> {
+    /// Crea un lector de eventos sobre cualquier fuente `BufRead`
+    pub fn new(source: R) -> Self {
+        let mut reader = Reader::from_reader(source);
+        reader.trim_text(true);
+
+        Self {
+            reader,
+            buf: Vec::new(),
+            queue: VecDeque::new(),
+            track: TrackState::Outside,
+            in_segment: false,
+            current: None,
+            text_field: None,
+            finished: false,
+        }
+    }
+
+    /// Consumes the whole stream computing statistics in constant memory
+    ///
+    /// # Errors
+    ///
+    /// Returns the first parse error found in the stream
+    pub fn statistics(self) -> Result<GpxStatistics, GpxError> {
+        let mut acc = StreamAccumulator::default();
+        for event in self {
+            acc.push(&event?);
+        }
+        Ok(acc.finish())
+    }
+
+    fn announce_track(&mut self) {
+        if let TrackState::Pending(name) = std::mem::replace(&mut self.track, TrackState::Announced)
+        {
+            self.queue.push_back(TrackPointEvent::TrackStart { name });
+        }
+    }
+
+    fn handle_start(&mut self, element: &BytesStart<'_>, is_empty: bool) -> Result<(), GpxError> {
+        match element.local_name().as_ref() {
+            b"trk" => {
+                self.track = TrackState::Pending(None);
+                if is_empty {
+                    self.handle_end(b"trk");
+                }
+            }
+            b"trkseg" if !matches!(self.track, TrackState::Outside) => {
+                self.announce_track();
+                self.in_segment = true;
+                self.queue.push_back(TrackPointEvent::SegmentStart);
+                if is_empty {
+                    self.handle_end(b"trkseg");
+                }
+            }
+            b"trkpt" if self.in_segment => {
+                self.current = Some(read_coordinates(element, PointKind::TrackPoint)?);
+                if is_empty {
+                    self.handle_end(b"trkpt");
+                }
+            }
+            b"wpt" => {
+                self.current = Some(read_coordinates(element, PointKind::Waypoint)?);
+                if is_empty {
+                    self.handle_end(b"wpt");
+                }
+            }
+            b"name" if !is_empty => self.text_field = Some(TextField::Name),
+            b"ele" if !is_empty => self.text_field = Some(TextField::Elevation),
+            b"time" if !is_empty => self.text_field = Some(TextField::Time),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_end(&mut self, local_name: &[u8]) {
+        match local_name {
+            b"trk" if !matches!(self.track, TrackState::Outside) => {
+                self.announce_track();
+                self.track = TrackState::Outside;
+                self.queue.push_back(TrackPointEvent::TrackEnd);
+            }
+            b"trkseg" if self.in_segment => {
+                self.in_segment = false;
+                self.queue.push_back(TrackPointEvent::SegmentEnd);
+            }
+            b"trkpt" | b"wpt" => {
+                if let Some(point) = self.current.take() {
+                    self.queue.push_back(point.into_event());
+                }
+            }
+            b"name" | b"ele" | b"time" => self.text_field = None,
+            _ => {}
+        }
+    }
+
+    fn handle_text(&mut self, text: &str) -> Result<(), GpxError> {
+        let Some(field) = self.text_field else {
+            return Ok(());
+        };
+
+        match (&mut self.current, field) {
+            (None, TextField::Name) => {
+                if let TrackState::Pending(name) = &mut self.track {
+                    *name = Some(text.to_string());
+                }
+            }
+            (Some(point), TextField::Name) => point.name = Some(text.to_string()),
+            (Some(point), TextField::Elevation) => point.elevation = Some(parse_number(text)?),
+            (Some(point), TextField::Time) => point.time = Some(parse_time(text)?),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn read_next(&mut self) -> Result<(), GpxError> {
+        while self.queue.is_empty() {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(element) => {
+                    let element = element.into_owned();
+                    self.handle_start(&element, false)?;
+                }
+                Event::Empty(element) => {
+                    let element = element.into_owned();
+                    self.handle_start(&element, true)?;
+                }
+                Event::End(element) => {
+                    let local_name = element.local_name().as_ref().to_vec();
+                    self.handle_end(&local_name);
+                }
+                Event::Text(text) => {
+                    let text = text.unescape()?.into_owned();
+                    self.handle_text(&text)?;
+                }
+                Event::CData(data) => {
+                    let text = String::from_utf8(data.into_inner().into_owned())
+                        .map_err(|e| GpxError::Schema(e.to_string()))?;
+                    self.handle_text(&text)?;
+                }
+                Event::Eof => {
+                    // Un fichero cortado deja abiertos el segmento y el track
+                    self.current = None;
+                    self.handle_end(b"trkseg");
+                    self.handle_end(b"trk");
+                    self.finished = true;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for GpxReader<R> {
+    type Item = Result<TrackPointEvent, GpxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.queue.pop_front() {
+            return Some(Ok(event));
+        }
+        if self.finished {
+            return None;
+        }
+
+        match self.read_next() {
+            Ok(()) => self.queue.pop_front().map(Ok),
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+fn read_coordinates(element: &BytesStart<'_>, kind: PointKind) -> Result<PendingPoint, GpxError> {
+    let mut lat = None;
+    let mut lon = None;
+
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        match attribute.key.local_name().as_ref() {
+            b"lat" => lat = Some(parse_number(&attribute.unescape_value()?)?),
+            b"lon" => lon = Some(parse_number(&attribute.unescape_value()?)?),
+            _ => {}
+        }
+    }
+
+    match (lat, lon) {
+        (Some(lat), Some(lon)) => {
+            Point::check_coordinates(lat, lon)?;
+            Ok(PendingPoint {
+                kind,
+                lat,
+                lon,
+                name: None,
+                elevation: None,
+                time: None,
+            })
+        }
+        _ => Err(GpxError::Schema(
+            "point is missing the `lat` or `lon` attribute".to_string(),
+        )),
+    }
+}
+
+fn parse_number(text: &str) -> Result<f64, GpxError> {
+    text.trim()
+        .parse::<f64>()
+        .map_err(|e| GpxError::Schema(format!("invalid number `{text}`: {e}")))
+}
+
+fn parse_time(text: &str) -> Result<DateTime<Utc>, GpxError> {
+    text.trim()
+        .parse::<DateTime<Utc>>()
+        .map_err(|e| GpxError::Schema(format!("invalid timestamp `{text}`: {e}")))
+}
+
+/// Acumulador de estadísticas que solo recuerda el punto anterior
+#[derive(Default)]
+struct StreamAccumulator {
+    tracks: usize,
+    waypoints: usize,
+    segments: usize,
+    points: usize,
+    distance_km: f64,
+    elevation_range: Option<(f64, f64)>,
+    gain: f64,
+    loss: f64,
+    has_elevation_pair: bool,
+    time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    previous: Option<Point>,
+}
+
+impl StreamAccumulator {
+    fn push(&mut self, event: &TrackPointEvent) {
+        match event {
+            TrackPointEvent::TrackStart { .. } => self.tracks += 1,
+            TrackPointEvent::SegmentStart => {
+                self.segments += 1;
+                self.previous = None;
+            }
+            TrackPointEvent::Point(point) => self.push_point(point),
+            TrackPointEvent::Waypoint(_) => self.waypoints += 1,
+            TrackPointEvent::SegmentEnd | TrackPointEvent::TrackEnd => {}
+        }
+    }
+
+    fn push_point(&mut self, point: &Point) {
+        self.points += 1;
+
+        if let Some(ele) = point.elevation {
+            self.elevation_range = Some(match self.elevation_range {
+                Some((min, max)) => (min.min(ele), max.max(ele)),
+                None => (ele, ele),
+            });
+        }
+
+        if let Some(time) = point.time {
+            self.time_range = Some(match self.time_range {
+                Some((first, last)) => (first.min(time), last.max(time)),
+                None => (time, time),
+            });
+        }
+
+        if let Some(previous) = &self.previous {
+            self.distance_km += haversine_distance(previous, point);
+
+            if let (Some(ele1), Some(ele2)) = (previous.elevation, point.elevation) {
+                self.has_elevation_pair = true;
+                let diff = ele2 - ele1;
+                if diff > 0.0 {
+                    self.gain += diff;
+                } else {
+                    self.loss += diff.abs();
+                }
+            }
+        }
+
+        self.previous = Some(point.clone());
+    }
+
+    fn finish(self) -> GpxStatistics {
+        let duration_seconds = self
+            .time_range
+            .map(|(first, last)| (last - first).num_seconds());
+
+        #[allow(clippy::cast_precision_loss)]
+        let average_speed_kmh = duration_seconds
+            .filter(|&seconds| seconds > 0)
+            .map(|seconds| self.distance_km / (seconds as f64 / 3600.0));
+
+        GpxStatistics {
+            total_tracks: self.tracks,
+            total_waypoints: self.waypoints,
+            total_segments: self.segments,
+            total_points: self.points,
+            total_distance_km: self.distance_km,
+            elevation_range: self.elevation_range,
+            elevation_gain: self.has_elevation_pair.then_some(self.gain),
+            elevation_loss: self.has_elevation_pair.then_some(self.loss),
+            duration_seconds,
+            average_speed_kmh,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Gpx;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <gpx version="1.1" creator="test">
+            <metadata><name>Metadata name</name><time>2024-07-11T09:00:00Z</time></metadata>
+            <wpt lat="40.7589" lon="-73.9851">
+                <name>Central Park</name>
+                <ele>15.0</ele>
+            </wpt>
+            <trk>
+                <name>Morning Run</name>
+                <trkseg>
+                    <trkpt lat="40.7128" lon="-74.0060">
+                        <ele>10.0</ele>
+                        <time>2024-07-11T10:00:00Z</time>
+                    </trkpt>
+                    <trkpt lat="40.7589" lon="-73.9851">
+                        <ele>25.0</ele>
+                        <time>2024-07-11T10:30:00Z</time>
+                    </trkpt>
+                </trkseg>
+                <trkseg>
+                    <trkpt lat="40.7600" lon="-73.9800"><ele>20.0</ele></trkpt>
+                    <trkpt lat="40.7700" lon="-73.9750"/>
+                </trkseg>
+            </trk>
+        </gpx>"#;
+
+    #[test]
+    fn test_stream_events_in_document_order() {
+        let events: Vec<TrackPointEvent> = GpxReader::new(SAMPLE.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert!(
+            matches!(&events[0], TrackPointEvent::Waypoint(w) if w.name.as_deref() == Some("Central Park"))
+        );
+        assert!(
+            matches!(&events[1], TrackPointEvent::TrackStart { name } if name.as_deref() == Some("Morning Run"))
+        );
+        assert!(matches!(events[2], TrackPointEvent::SegmentStart));
+        assert!(
+            matches!(&events[3], TrackPointEvent::Point(p) if p.elevation == Some(10.0) && p.time.is_some())
+        );
+        assert!(matches!(events[5], TrackPointEvent::SegmentEnd));
+        assert!(matches!(&events[8], TrackPointEvent::Point(p) if p.elevation.is_none()));
+        assert!(matches!(events[10], TrackPointEvent::TrackEnd));
+        assert_eq!(events.len(), 11);
+    }
+
+    #[test]
+    fn test_stream_statistics_match_in_memory_statistics() {
+        let streamed = GpxReader::new(SAMPLE.as_bytes()).statistics().unwrap();
+        let in_memory = Gpx::try_from_str(SAMPLE).unwrap().statistics();
+
+        assert_eq!(streamed.total_tracks, in_memory.total_tracks);
+        assert_eq!(streamed.total_waypoints, in_memory.total_waypoints);
+        assert_eq!(streamed.total_segments, in_memory.total_segments);
+        assert_eq!(streamed.total_points, in_memory.total_points);
+        assert!((streamed.total_distance_km - in_memory.total_distance_km).abs() < 1e-9);
+        assert_eq!(streamed.elevation_range, in_memory.elevation_range);
+        assert_eq!(streamed.elevation_gain, in_memory.elevation_gain);
+        assert_eq!(streamed.elevation_loss, in_memory.elevation_loss);
+        assert_eq!(streamed.duration_seconds, Some(1800));
+    }
+
+    #[test]
+    fn test_stream_missing_coordinates_is_an_error() {
+        let xml = r#"<gpx><trk><trkseg><trkpt lat="1.0"/></trkseg></trk></gpx>"#;
+        let result: Result<Vec<_>, _> = GpxReader::new(xml.as_bytes()).collect();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stream_empty_track() {
+        let xml = "<gpx><trk/></gpx>";
+        let events: Vec<_> = GpxReader::new(xml.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            TrackPointEvent::TrackStart { name: None }
+        ));
+        assert!(matches!(events[1], TrackPointEvent::TrackEnd));
+    }
+
+    #[test]
+    fn test_stream_truncated_input_closes_open_elements() {
+        let xml =
+            r#"<gpx><trk><trkseg><trkpt lat="1.0" lon="2.0"/><trkpt lat="1.1" lon="2.0"><ele>6"#;
+        let events: Vec<_> = GpxReader::new(xml.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(events.len(), 5);
+        assert!(matches!(events[2], TrackPointEvent::Point(_)));
+        assert!(matches!(events[3], TrackPointEvent::SegmentEnd));
+        assert!(matches!(events[4], TrackPointEvent::TrackEnd));
+    }
+}
@@ -0,0 +1,769 @@
+use crate::gpx::{
+    error::GpxError,
+    metadata::Metadata,
+    point::Point,
+    track::Track,
+    unknown, user_data,
+    waypoint::Waypoint,
+    write::{GpxVersion, GPX_11_NAMESPACE, XSI_NAMESPACE},
+};
+use quick_xml::{escape::escape, se::Serializer};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Posición del escritor dentro del documento
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Aún no se ha escrito la cabecera
+    Start,
+    /// Cabecera escrita, sin tracks todavía (se admiten waypoints)
+    Root,
+    /// Dentro de un `<trk>`, fuera de segmentos
+    Track,
+    /// Dentro de un `<trkseg>`
+    Segment,
+    /// Documento cerrado
+    Closed,
+}
+
+/// Writes a GPX document incrementally to any [`Write`] sink
+///
+/// The counterpart of [`GpxReader`](crate::stream::GpxReader): points are
+/// serialized as soon as they are received, so recorders and converters can
+/// produce arbitrarily large files in constant memory. Tracks and segments are
+/// opened implicitly when needed and every open element is closed by
+/// [`GpxWriter::finish`], or on drop as a best effort.
+///
+/// Waypoints must be written before the first track, as required by the schema.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{stream::GpxWriter, Gpx, Point};
+///
+/// let mut writer = GpxWriter::new(Vec::new());
+/// writer.begin_track(Some("Morning run")).unwrap();
+/// for i in 0..3 {
+///     writer.write_point(&Point::new(40.0 + f64::from(i) * 0.01, -3.0)).unwrap();
+/// }
+/// let bytes = writer.finish().unwrap();
+///
+/// let gpx = Gpx::try_from_str(std::str::from_utf8(&bytes).unwrap()).unwrap();
+/// assert_eq!(gpx.track_names(), vec!["Morning run"]);
+/// assert_eq!(gpx.total_points(), 3);
+/// ```
+pub struct GpxWriter<W: Write> {
+    sink: Option<W>,
+    state: State,
+    creator: String,
+    metadata: Option<Metadata>,
+    has_tracks: bool,
+    points_written: usize,
+    bytes_written: u64,
+}
+
+impl GpxWriter<BufWriter<File>> {
+    /// Creates (or truncates) a file and writes the document into it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> GpxWriter<W> {
+    /// Crea un escritor sobre cualquier destino `Write`; la cabecera se escribe con el primer elemento
+    pub fn new(sink: W) -> Self {
+        Self {
+            sink: Some(sink),
+            state: State::Start,
+            creator: "gpx-extractor".to_string(),
+            metadata: None,
+            has_tracks: false,
+            points_written: 0,
+            bytes_written: 0,
+        }
+    }
+
+    /// Sets the `creator` attribute; only effective before anything is written
+    #[must_use]
+    pub fn creator(mut self, creator: &str) -> Self {
+        self.creator = creator.to_string();
+        self
+    }
+
+    /// Sets the `<metadata>` element; only effective before anything is written
+    #[must_use]
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Número de puntos escritos hasta ahora
+    pub fn points_written(&self) -> usize {
+        self.points_written
+    }
+
+    /// Número de bytes enviados al destino hasta ahora
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Writes a waypoint
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Schema`] if a track was already started or the writer
+    /// was finished, or an I/O error if writing fails
+    pub fn write_waypoint(&mut self, waypoint: &Waypoint) -> Result<(), GpxError> {
+        self.write_waypoint_xml(&serialize(waypoint, "wpt")?)
+    }
+
+    fn write_waypoint_xml(&mut self, xml: &str) -> Result<(), GpxError> {
+        self.open_root()?;
+        if self.has_tracks {
+            return Err(GpxError::Schema(
+                "waypoints must be written before the first track".to_string(),
+            ));
+        }
+        self.emit(xml)
+    }
+
+    /// Starts a new track, closing the current one if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer was finished or writing fails
+    pub fn begin_track(&mut self, name: Option<&str>) -> Result<(), GpxError> {
+        self.end_track()?;
+        self.open_root()?;
+        self.emit("<trk>")?;
+        self.has_tracks = true;
+        if let Some(name) = name {
+            self.emit(&format!("<name>{}</name>", escape(name)))?;
+        }
+        self.state = State::Track;
+        Ok(())
+    }
+
+    /// Starts a new segment in the current track, opening an unnamed track if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer was finished or writing fails
+    pub fn begin_segment(&mut self) -> Result<(), GpxError> {
+        self.end_segment()?;
+        if self.state != State::Track {
+            self.begin_track(None)?;
+        }
+        self.emit("<trkseg>")?;
+        self.state = State::Segment;
+        Ok(())
+    }
+
+    /// Writes a track point into the current segment, opening one if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer was finished or writing fails
+    pub fn write_point(&mut self, point: &Point) -> Result<(), GpxError> {
+        self.write_point_xml(&serialize(point, "trkpt")?)
+    }
+
+    fn write_point_xml(&mut self, xml: &str) -> Result<(), GpxError> {
+        if self.state != State::Segment {
+            self.begin_segment()?;
+        }
+        self.emit(xml)?;
+        self.points_written += 1;
+        Ok(())
+    }
+
+    /// Writes a whole in-memory track, including its name and extensions
+    ///
+    /// The track is closed right away; later points start a new track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer was finished or writing fails
+    pub fn write_track(&mut self, track: &Track) -> Result<(), GpxError> {
+        self.end_track()?;
+        self.open_root()?;
+        let xml = serialize(track, "trk")?;
+        self.emit(&xml)?;
+        self.has_tracks = true;
+        self.points_written += track.total_points();
+        Ok(())
+    }
+
+    /// Closes the current segment, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails
+    pub fn end_segment(&mut self) -> Result<(), GpxError> {
+        if self.state == State::Segment {
+            self.emit("</trkseg>")?;
+            self.state = State::Track;
+        }
+        Ok(())
+    }
+
+    /// Closes the current track and its open segment, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails
+    pub fn end_track(&mut self) -> Result<(), GpxError> {
+        self.end_segment()?;
+        if self.state == State::Track {
+            self.emit("</trk>")?;
+            self.state = State::Root;
+        }
+        Ok(())
+    }
+
+    /// Flushes the points written so far to the underlying sink
+    ///
+    /// The document stays open. A logger that flushes periodically lets other
+    /// processes follow the file, and loses less if it is killed; a file cut
+    /// short this way can still be read with [`GpxReader`](crate::stream::GpxReader)
+    /// up to the last complete point.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if flushing fails
+    pub fn flush(&mut self) -> Result<(), GpxError> {
+        if let Some(sink) = self.sink.as_mut() {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Closes every open element, flushes and returns the underlying sink
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing or flushing fails
+    pub fn finish(mut self) -> Result<W, GpxError> {
+        self.close()?;
+        self.sink
+            .take()
+            .ok_or_else(|| GpxError::Schema("the GPX writer is already finished".to_string()))
+    }
+
+    fn close(&mut self) -> Result<(), GpxError> {
+        if self.state == State::Closed {
+            return Ok(());
+        }
+        self.end_track()?;
+        self.open_root()?;
+        self.emit("</gpx>\n")?;
+        self.state = State::Closed;
+        if let Some(sink) = self.sink.as_mut() {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Escribe la declaración, `<gpx>` y los metadatos la primera vez
+    fn open_root(&mut self) -> Result<(), GpxError> {
+        match self.state {
+            State::Start => {
+                let mut header = format!(
+                    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gpx version=\"1.1\" creator=\"{}\" xmlns=\"{GPX_11_NAMESPACE}\" xmlns:xsi=\"{XSI_NAMESPACE}\" xsi:schemaLocation=\"{}\">",
+                    escape(&self.creator),
+                    GpxVersion::V1_1.schema_location()
+                );
+                if let Some(metadata) = &self.metadata {
+                    header.push_str(&serialize(metadata, "metadata")?);
+                }
+                self.emit(&header)?;
+                self.state = State::Root;
+                Ok(())
+            }
+            State::Closed => Err(GpxError::Schema(
+                "the GPX writer is already finished".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn emit(&mut self, xml: &str) -> Result<(), GpxError> {
+        if let Some(sink) = self.sink.as_mut() {
+            sink.write_all(xml.as_bytes())?;
+            self.bytes_written += xml.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for GpxWriter<W> {
+    fn drop(&mut self) {
+        // Cierre de último recurso: los errores solo se ven usando `finish`
+        let _ = self.close();
+    }
+}
+
+/// Bytes que cierran una parte con un segmento abierto
+const CLOSING_TAGS: &str = "</trkseg></trk></gpx>\n";
+
+/// Crea el destino de la parte con el número indicado (empezando en 1)
+type PartFactory<W> = Box<dyn FnMut(usize) -> std::io::Result<W>>;
+
+/// Splits a streamed GPX document across several outputs
+///
+/// Wraps [`GpxWriter`] and starts a new output whenever the current one reaches
+/// `max_points` track points or the next element would push it over `max_bytes`.
+/// Every part is a complete GPX document; an open track is continued in the next
+/// part with the same name, so recorders can rotate logs and uploads can respect
+/// device or service limits. A part always receives at least one element, even if
+/// that element alone exceeds `max_bytes`.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{stream::RolloverWriter, Gpx, Point};
+/// # let dir = tempfile::tempdir().unwrap();
+///
+/// let mut writer = RolloverWriter::to_files(dir.path(), "part").max_points(2);
+/// writer.begin_track(Some("Commute")).unwrap();
+/// for i in 0..5 {
+///     writer.write_point(&Point::new(40.0 + f64::from(i) * 0.01, -3.0)).unwrap();
+/// }
+/// assert_eq!(writer.finish().unwrap(), 3);
+///
+/// let last = std::fs::read_to_string(dir.path().join("part3.gpx")).unwrap();
+/// let gpx = Gpx::try_from_str(&last).unwrap();
+/// assert_eq!(gpx.track_names(), vec!["Commute"]);
+/// assert_eq!(gpx.total_points(), 1);
+/// ```
+pub struct RolloverWriter<W: Write> {
+    factory: PartFactory<W>,
+    current: Option<GpxWriter<W>>,
+    parts: usize,
+    items: usize,
+    max_bytes: Option<u64>,
+    max_points: Option<usize>,
+    creator: Option<String>,
+    metadata: Option<Metadata>,
+    // Track y segmento abiertos, para continuarlos en la parte siguiente
+    in_track: bool,
+    track_name: Option<String>,
+    in_segment: bool,
+}
+
+impl RolloverWriter<BufWriter<File>> {
+    /// Writes the parts as `{stem}1.gpx`, `{stem}2.gpx`… inside `dir`
+    pub fn to_files<P: AsRef<Path>>(dir: P, stem: &str) -> Self {
+        let dir = dir.as_ref().to_path_buf();
+        let stem = stem.to_string();
+        Self::new(move |part| {
+            let path = dir.join(format!("{stem}{part}.gpx"));
+            Ok(BufWriter::new(File::create(path)?))
+        })
+    }
+}
+
+impl<W: Write> RolloverWriter<W> {
+    /// Crea un escritor que pide a `factory` el destino de cada parte (numeradas desde 1)
+    pub fn new(factory: impl FnMut(usize) -> std::io::Result<W> + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+            current: None,
+            parts: 0,
+            items: 0,
+            max_bytes: None,
+            max_points: None,
+            creator: None,
+            metadata: None,
+            in_track: false,
+            track_name: None,
+            in_segment: false,
+        }
+    }
+
+    /// Maximum size of each part in bytes
+    #[must_use]
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Maximum number of track points in each part
+    #[must_use]
+    pub fn max_points(mut self, max_points: usize) -> Self {
+        self.max_points = Some(max_points);
+        self
+    }
+
+    /// Sets the `creator` attribute of every part
+    #[must_use]
+    pub fn creator(mut self, creator: &str) -> Self {
+        self.creator = Some(creator.to_string());
+        self
+    }
+
+    /// Sets the `<metadata>` element repeated in every part
+    #[must_use]
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Número de partes abiertas hasta ahora
+    pub fn parts(&self) -> usize {
+        self.parts
+    }
+
+    /// Writes a waypoint into the current part
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::Schema`] if a track was already started in the current
+    /// part, or an error if the part cannot be created or written
+    pub fn write_waypoint(&mut self, waypoint: &Waypoint) -> Result<(), GpxError> {
+        let xml = serialize(waypoint, "wpt")?;
+        let writer = self.writer_for(xml.len(), false)?;
+        writer.write_waypoint_xml(&xml)?;
+        self.items += 1;
+        Ok(())
+    }
+
+    /// Starts a new track, closing the current one if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the part cannot be created or written
+    pub fn begin_track(&mut self, name: Option<&str>) -> Result<(), GpxError> {
+        self.writer_for(0, false)?.begin_track(name)?;
+        self.in_track = true;
+        self.track_name = name.map(str::to_string);
+        self.in_segment = false;
+        Ok(())
+    }
+
+    /// Starts a new segment in the current track
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the part cannot be created or written
+    pub fn begin_segment(&mut self) -> Result<(), GpxError> {
+        self.writer_for(0, false)?.begin_segment()?;
+        self.in_track = true;
+        self.in_segment = true;
+        Ok(())
+    }
+
+    /// Writes a track point, moving to a new part first if a limit would be exceeded
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the part cannot be created or written
+    pub fn write_point(&mut self, point: &Point) -> Result<(), GpxError> {
+        let xml = serialize(point, "trkpt")?;
+        let writer = self.writer_for(xml.len(), true)?;
+        writer.write_point_xml(&xml)?;
+        self.in_track = true;
+        self.in_segment = true;
+        self.items += 1;
+        Ok(())
+    }
+
+    /// Closes the current segment, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails
+    pub fn end_segment(&mut self) -> Result<(), GpxError> {
+        if let Some(writer) = self.current.as_mut() {
+            writer.end_segment()?;
+        }
+        self.in_segment = false;
+        Ok(())
+    }
+
+    /// Closes the current track, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails
+    pub fn end_track(&mut self) -> Result<(), GpxError> {
+        if let Some(writer) = self.current.as_mut() {
+            writer.end_track()?;
+        }
+        self.in_track = false;
+        self.track_name = None;
+        self.in_segment = false;
+        Ok(())
+    }
+
+    /// Closes the last part and returns the number of parts written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing or flushing fails
+    pub fn finish(mut self) -> Result<usize, GpxError> {
+        if let Some(writer) = self.current.take() {
+            writer.finish()?;
+        }
+        Ok(self.parts)
+    }
+
+    /// Devuelve la parte actual, abriendo otra si el siguiente elemento no cabe
+    fn writer_for(&mut self, len: usize, is_point: bool) -> Result<&mut GpxWriter<W>, GpxError> {
+        // Un punto fuera de segmento abre antes `<trk>` y `<trkseg>`
+        let mut len = len;
+        if is_point && !self.in_segment {
+            len += "<trkseg>".len();
+            if !self.in_track {
+                len += "<trk>".len();
+            }
+        }
+        let full = self.current.as_ref().is_some_and(|writer| {
+            if self.items == 0 {
+                return false;
+            }
+            let points_full = is_point
+                && self
+                    .max_points
+                    .is_some_and(|max| writer.points_written() >= max);
+            let bytes_full = len > 0
+                && self.max_bytes.is_some_and(|max| {
+                    writer.bytes_written() + (len + CLOSING_TAGS.len()) as u64 > max
+                });
+            points_full || bytes_full
+        });
+
+        if full {
+            if let Some(writer) = self.current.take() {
+                writer.finish()?;
+            }
+        }
+        if self.current.is_none() {
+            self.open_part()?;
+        }
+        self.current
+            .as_mut()
+            .ok_or_else(|| GpxError::Schema("cannot open GPX part".to_string()))
+    }
+
+    /// Abre la siguiente parte y continúa el track y el segmento abiertos
+    fn open_part(&mut self) -> Result<(), GpxError> {
+        self.parts += 1;
+        self.items = 0;
+        let mut writer = GpxWriter::new((self.factory)(self.parts)?);
+        if let Some(creator) = &self.creator {
+            writer = writer.creator(creator);
+        }
+        if let Some(metadata) = &self.metadata {
+            writer = writer.metadata(metadata.clone());
+        }
+        if self.in_track {
+            writer.begin_track(self.track_name.as_deref())?;
+            if self.in_segment {
+                writer.begin_segment()?;
+            }
+        }
+        self.current = Some(writer);
+        Ok(())
+    }
+}
+
+/// Serializa un elemento suelto con la etiqueta indicada
+fn serialize<T: Serialize>(value: &T, tag: &str) -> Result<String, GpxError> {
+    let mut xml = String::new();
+    value.serialize(Serializer::with_root(&mut xml, Some(tag))?)?;
+    Ok(user_data::place_user_data(
+        unknown::expand_markers(xml),
+        true,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::parser::Gpx;
+
+    fn parse(bytes: &[u8]) -> Gpx {
+        Gpx::try_from_str(std::str::from_utf8(bytes).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_incremental_document_roundtrips() {
+        let metadata = Metadata {
+            name: Some("Recorder".to_string()),
+            ..Metadata::default()
+        };
+
+        let mut writer = GpxWriter::new(Vec::new())
+            .creator("logger <v2>")
+            .metadata(metadata);
+        writer.write_waypoint(&Waypoint::new(40.0, -3.0)).unwrap();
+        writer.begin_track(Some("A & B")).unwrap();
+        let mut point = Point::with_elevation(40.0, -3.0, 650.0);
+        point.extensions.heart_rate = Some(140);
+        point
+            .unknown
+            .push_element(None, "<geoidheight>52</geoidheight>");
+        writer.write_point(&point).unwrap();
+        writer.begin_segment().unwrap();
+        writer.write_point(&Point::new(40.1, -3.0)).unwrap();
+        writer.begin_track(None).unwrap();
+        writer.write_point(&Point::new(40.2, -3.0)).unwrap();
+        assert_eq!(writer.points_written(), 3);
+
+        let bytes = writer.finish().unwrap();
+        assert!(std::str::from_utf8(&bytes)
+            .unwrap()
+            .contains("creator=\"logger &lt;v2&gt;\""));
+        let gpx = parse(&bytes);
+        assert_eq!(gpx.metadata.unwrap().name.as_deref(), Some("Recorder"));
+        assert_eq!(gpx.waypoints.len(), 1);
+        assert_eq!(gpx.tracks.len(), 2);
+        assert_eq!(gpx.tracks[0].name.as_deref(), Some("A & B"));
+        assert_eq!(gpx.tracks[0].segments.len(), 2);
+        let first = &gpx.tracks[0].segments[0].points[0];
+        assert_eq!(first.elevation, Some(650.0));
+        assert_eq!(first.heart_rate(), Some(140));
+        assert_eq!(
+            first.unknown.elements().collect::<Vec<_>>(),
+            vec!["<geoidheight>52</geoidheight>"]
+        );
+        assert_eq!(gpx.tracks[1].total_points(), 1);
+    }
+
+    #[test]
+    fn test_flush_keeps_document_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.gpx");
+        let mut writer = GpxWriter::create(&path).unwrap();
+        writer.write_point(&Point::new(40.0, -3.0)).unwrap();
+        writer.flush().unwrap();
+
+        let partial = std::fs::read_to_string(&path).unwrap();
+        assert!(partial.ends_with("<trkpt lat=\"40\" lon=\"-3\"/>"));
+        writer.write_point(&Point::new(40.1, -3.0)).unwrap();
+        writer.finish().unwrap();
+        let gpx = Gpx::from_file(&path).unwrap();
+        assert_eq!(gpx.total_points(), 2);
+    }
+
+    #[test]
+    fn test_drop_closes_document() {
+        let mut bytes = Vec::new();
+        {
+            let mut writer = GpxWriter::new(&mut bytes);
+            writer.write_point(&Point::new(40.0, -3.0)).unwrap();
+        }
+        assert!(bytes.ends_with(b"</trkseg></trk></gpx>\n"));
+        assert_eq!(parse(&bytes).total_points(), 1);
+
+        // Un documento vacío también es válido
+        let empty = GpxWriter::new(Vec::new()).finish().unwrap();
+        assert!(parse(&empty).tracks.is_empty());
+    }
+
+    #[test]
+    fn test_write_track_and_ordering_errors() {
+        let mut track = Track::with_name("Stored".to_string());
+        track.add_segment(crate::gpx::track::TrackSegment::with_points(vec![
+            Point::new(40.0, -3.0),
+            Point::new(40.1, -3.0),
+        ]));
+
+        let mut writer = GpxWriter::new(Vec::new());
+        writer.write_track(&track).unwrap();
+        writer.write_point(&Point::new(40.2, -3.0)).unwrap();
+        assert!(matches!(
+            writer.write_waypoint(&Waypoint::new(40.0, -3.0)),
+            Err(GpxError::Schema(_))
+        ));
+        assert_eq!(writer.points_written(), 3);
+        let written = writer.bytes_written();
+
+        let bytes = writer.finish().unwrap();
+        assert!(bytes.len() as u64 > written);
+        let gpx = parse(&bytes);
+        assert_eq!(gpx.tracks.len(), 2);
+        assert_eq!(gpx.tracks[0].name.as_deref(), Some("Stored"));
+        assert_eq!(gpx.tracks[0].total_points(), 2);
+        assert_eq!(gpx.tracks[1].total_points(), 1);
+    }
+
+    fn read_part(dir: &Path, part: usize) -> Gpx {
+        let xml = std::fs::read_to_string(dir.join(format!("part{part}.gpx"))).unwrap();
+        Gpx::try_from_str(&xml).unwrap()
+    }
+
+    #[test]
+    fn test_rollover_by_points_continues_track() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RolloverWriter::to_files(dir.path(), "part")
+            .creator("recorder")
+            .max_points(3);
+        writer.write_waypoint(&Waypoint::new(40.0, -3.0)).unwrap();
+        writer.begin_track(Some("Long day")).unwrap();
+        for i in 0..4 {
+            writer
+                .write_point(&Point::new(40.0 + f64::from(i) * 0.001, -3.0))
+                .unwrap();
+        }
+        writer.begin_segment().unwrap();
+        for i in 0..3 {
+            writer
+                .write_point(&Point::new(41.0 + f64::from(i) * 0.001, -3.0))
+                .unwrap();
+        }
+        writer.begin_track(None).unwrap();
+        writer.write_point(&Point::new(42.0, -3.0)).unwrap();
+        assert_eq!(writer.finish().unwrap(), 3);
+
+        let first = read_part(dir.path(), 1);
+        assert_eq!(first.waypoints.len(), 1);
+        assert_eq!(first.total_points(), 3);
+
+        let second = read_part(dir.path(), 2);
+        assert!(second.waypoints.is_empty());
+        assert_eq!(second.track_names(), vec!["Long day"]);
+        assert_eq!(second.tracks[0].segments.len(), 2);
+        assert_eq!(second.total_points(), 3);
+
+        let third = read_part(dir.path(), 3);
+        assert_eq!(third.tracks.len(), 2);
+        assert_eq!(third.tracks[0].segments[0].points[0].lat, 41.002);
+        assert_eq!(third.tracks[1].total_points(), 1);
+        assert!(!dir.path().join("part4.gpx").exists());
+    }
+
+    #[test]
+    fn test_rollover_by_bytes_keeps_parts_under_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let max_bytes = 400;
+        let mut writer = RolloverWriter::to_files(dir.path(), "part").max_bytes(max_bytes);
+        writer.begin_track(Some("Ride")).unwrap();
+        for i in 0..20 {
+            writer
+                .write_point(&Point::with_elevation(
+                    40.0 + f64::from(i) * 0.001,
+                    -3.0,
+                    650.0,
+                ))
+                .unwrap();
+        }
+        let parts = writer.finish().unwrap();
+        assert!(parts > 1);
+
+        let mut total = 0;
+        for part in 1..=parts {
+            let path = dir.path().join(format!("part{part}.gpx"));
+            assert!(std::fs::metadata(&path).unwrap().len() <= max_bytes);
+            total += read_part(dir.path(), part).total_points();
+        }
+        assert_eq!(total, 20);
+    }
+}
@@ -0,0 +1,504 @@
+use crate::gpx::{
+    error::GpxError,
+    extensions::PointExtensions,
+    formats::{FormatReader, FormatWriter},
+    parser::Gpx,
+    point::{haversine_distance, Point},
+    track::{Track, TrackSegment},
+    unknown::UnknownXml,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use quick_xml::{de::from_str, se::Serializer};
+use serde::{Deserialize, Serialize};
+
+/// Namespace of Training Center XML v2 documents
+pub const TCX_NAMESPACE: &str = "http://www.garmin.com/xmlschemas/TrainingCenterDatabase/v2";
+
+/// Reader and writer for Garmin Training Center XML (`.tcx`) activities
+///
+/// Each `<Activity>` becomes a track named after its notes (or its sport), and
+/// each `<Lap>` a segment. Trackpoints keep their time, altitude, heart rate and
+/// cadence; trackpoints without a position (e.g. indoor sensor samples) are skipped.
+/// On export, waypoints and metadata other than the time are not written, since
+/// TCX activities have no place for them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TcxFormat;
+
+impl FormatReader for TcxFormat {
+    fn read(&self, bytes: &[u8]) -> Result<Gpx, GpxError> {
+        let xml = std::str::from_utf8(bytes)
+            .map_err(|e| GpxError::Schema(format!("document is not valid UTF-8: {e}")))?;
+        Gpx::from_tcx(xml)
+    }
+}
+
+impl FormatWriter for TcxFormat {
+    fn write(&self, gpx: &Gpx) -> Result<Vec<u8>, GpxError> {
+        Ok(gpx.to_tcx()?.into_bytes())
+    }
+}
+
+impl Gpx {
+    /// Parses a Training Center XML document
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not well-formed or its values do not
+    /// match the TCX schema
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::Gpx;
+    ///
+    /// let tcx = r#"<TrainingCenterDatabase><Activities><Activity Sport="Running">
+    ///     <Id>2024-07-01T08:00:00Z</Id>
+    ///     <Lap StartTime="2024-07-01T08:00:00Z"><Track><Trackpoint>
+    ///         <Time>2024-07-01T08:00:00Z</Time>
+    ///         <Position><LatitudeDegrees>40.0</LatitudeDegrees><LongitudeDegrees>-3.0</LongitudeDegrees></Position>
+    ///         <HeartRateBpm><Value>142</Value></HeartRateBpm>
+    ///     </Trackpoint></Track></Lap>
+    /// </Activity></Activities></TrainingCenterDatabase>"#;
+    ///
+    /// let gpx = Gpx::from_tcx(tcx).unwrap();
+    /// assert_eq!(gpx.track_names(), vec!["Running"]);
+    /// assert_eq!(gpx.tracks[0].segments[0].points[0].heart_rate(), Some(142));
+    /// ```
+    pub fn from_tcx(xml: &str) -> Result<Self, GpxError> {
+        let root: RawDatabase = from_str(xml)?;
+        let activities = root.activities.map(|a| a.activity).unwrap_or_default();
+
+        let mut gpx = Gpx::new();
+        if let Some(id) = activities.first().and_then(|a| a.id.as_deref()) {
+            gpx.metadata_mut().time = Some(id.trim().to_string());
+        }
+        for activity in activities {
+            let name = activity.notes.or(activity.sport);
+            let mut track = Track::new();
+            track.name = name.filter(|n| !n.trim().is_empty());
+            for lap in activity.laps {
+                let points = lap
+                    .tracks
+                    .into_iter()
+                    .flat_map(|t| t.trackpoints)
+                    .filter_map(RawTrackpoint::into_point)
+                    .collect();
+                track.add_segment(TrackSegment::with_points(points));
+            }
+            gpx.add_track(track);
+        }
+        Ok(gpx)
+    }
+
+    /// Serializes the document as a Training Center XML activity file
+    ///
+    /// Every track becomes an `<Activity>` and every segment a `<Lap>` with its
+    /// total time and distance. The sport is `Running` or `Biking` when the track
+    /// name says so and `Other` otherwise; the name itself goes to `<Notes>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the XML serialization fails
+    pub fn to_tcx(&self) -> Result<String, GpxError> {
+        let fallback_id = self
+            .metadata
+            .as_ref()
+            .and_then(|m| m.time.as_deref())
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map_or(DateTime::<Utc>::UNIX_EPOCH, |time| time.with_timezone(&Utc));
+
+        let database = DatabaseRef {
+            xmlns: TCX_NAMESPACE,
+            activities: ActivitiesRef {
+                activity: self
+                    .tracks
+                    .iter()
+                    .map(|track| activity(track, fallback_id))
+                    .collect(),
+            },
+        };
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        database.serialize(Serializer::new(&mut xml))?;
+        Ok(xml)
+    }
+}
+
+fn activity(track: &Track, fallback_id: DateTime<Utc>) -> ActivityRef<'_> {
+    let id = first_time(track.segments.iter().flat_map(|s| &s.points)).unwrap_or(fallback_id);
+    let sport = match track.name.as_deref().map(str::to_ascii_lowercase) {
+        Some(name) if name.contains("run") => "Running",
+        Some(name) if name.contains("bik") || name.contains("cycl") => "Biking",
+        _ => "Other",
+    };
+
+    // La distancia de cada trackpoint es acumulada desde el inicio de la actividad
+    let mut distance_m = 0.0;
+    let laps = track
+        .segments
+        .iter()
+        .map(|segment| {
+            let start = first_time(&segment.points).unwrap_or(id);
+            let end = segment.points.iter().rev().find_map(|p| p.time);
+            let mut previous: Option<&Point> = None;
+            let trackpoints = segment
+                .points
+                .iter()
+                .map(|point| {
+                    if let Some(previous) = previous {
+                        distance_m += haversine_distance(previous, point) * 1000.0;
+                    }
+                    previous = Some(point);
+                    TrackpointRef::new(point, distance_m)
+                })
+                .collect();
+
+            LapRef {
+                start_time: timestamp(start),
+                total_time_seconds: end.map_or(0, |end| (end - start).num_seconds().max(0)),
+                distance_meters: segment.distance_km() * 1000.0,
+                calories: 0,
+                intensity: "Active",
+                trigger_method: "Manual",
+                track: TrackRef { trackpoints },
+            }
+        })
+        .collect();
+
+    ActivityRef {
+        sport,
+        id: timestamp(id),
+        laps,
+        notes: track.name.as_deref(),
+    }
+}
+
+fn first_time<'a>(points: impl IntoIterator<Item = &'a Point>) -> Option<DateTime<Utc>> {
+    points.into_iter().find_map(|p| p.time)
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+// Estructuras de lectura: quick-xml compara por nombre local, así que el
+// prefijo de los elementos de extensión (`ns3:`, `ax:`...) no importa
+#[derive(Deserialize)]
+struct RawDatabase {
+    #[serde(rename = "Activities")]
+    activities: Option<RawActivities>,
+}
+
+#[derive(Deserialize)]
+struct RawActivities {
+    #[serde(rename = "Activity", default)]
+    activity: Vec<RawActivity>,
+}
+
+#[derive(Deserialize)]
+struct RawActivity {
+    #[serde(rename = "@Sport")]
+    sport: Option<String>,
+    #[serde(rename = "Id")]
+    id: Option<String>,
+    #[serde(rename = "Lap", default)]
+    laps: Vec<RawLap>,
+    #[serde(rename = "Notes")]
+    notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawLap {
+    #[serde(rename = "Track", default)]
+    tracks: Vec<RawTrack>,
+}
+
+#[derive(Deserialize)]
+struct RawTrack {
+    #[serde(rename = "Trackpoint", default)]
+    trackpoints: Vec<RawTrackpoint>,
+}
+
+#[derive(Deserialize)]
+struct RawTrackpoint {
+    #[serde(rename = "Time")]
+    time: Option<DateTime<Utc>>,
+    #[serde(rename = "Position")]
+    position: Option<RawPosition>,
+    #[serde(rename = "AltitudeMeters")]
+    altitude: Option<f64>,
+    #[serde(rename = "HeartRateBpm")]
+    heart_rate: Option<RawValue>,
+    #[serde(rename = "Cadence")]
+    cadence: Option<u8>,
+    #[serde(rename = "Extensions")]
+    extensions: Option<RawTrackpointExtensions>,
+}
+
+#[derive(Deserialize)]
+struct RawPosition {
+    #[serde(rename = "LatitudeDegrees")]
+    lat: f64,
+    #[serde(rename = "LongitudeDegrees")]
+    lon: f64,
+}
+
+#[derive(Deserialize)]
+struct RawValue {
+    #[serde(rename = "Value")]
+    value: u8,
+}
+
+#[derive(Deserialize)]
+struct RawTrackpointExtensions {
+    #[serde(rename = "TPX")]
+    tpx: Option<RawTpx>,
+}
+
+/// `ActivityExtension/v2`: los relojes de carrera guardan aquí la cadencia, y los
+/// potenciómetros la potencia
+#[derive(Deserialize)]
+struct RawTpx {
+    #[serde(rename = "RunCadence")]
+    run_cadence: Option<u8>,
+    #[serde(rename = "Watts")]
+    watts: Option<u16>,
+}
+
+impl RawTrackpoint {
+    fn into_point(self) -> Option<Point> {
+        let position = self.position?;
+        let tpx = self.extensions.and_then(|e| e.tpx);
+        let (run_cadence, watts) = tpx.map_or((None, None), |t| (t.run_cadence, t.watts));
+        Some(Point {
+            elevation: self.altitude,
+            time: self.time,
+            extensions: PointExtensions {
+                heart_rate: self.heart_rate.map(|hr| hr.value),
+                cadence: self.cadence.or(run_cadence),
+                power: watts,
+                unknown: UnknownXml::default(),
+            },
+            ..Point::new(position.lat, position.lon)
+        })
+    }
+}
+
+// Estructuras de escritura, en el orden que exige el esquema TCX v2
+#[derive(Serialize)]
+#[serde(rename = "TrainingCenterDatabase")]
+struct DatabaseRef<'a> {
+    #[serde(rename = "@xmlns")]
+    xmlns: &'static str,
+    #[serde(rename = "Activities")]
+    activities: ActivitiesRef<'a>,
+}
+
+#[derive(Serialize)]
+struct ActivitiesRef<'a> {
+    #[serde(rename = "Activity")]
+    activity: Vec<ActivityRef<'a>>,
+}
+
+#[derive(Serialize)]
+struct ActivityRef<'a> {
+    #[serde(rename = "@Sport")]
+    sport: &'static str,
+    #[serde(rename = "Id")]
+    id: String,
+    #[serde(rename = "Lap")]
+    laps: Vec<LapRef>,
+    #[serde(rename = "Notes", skip_serializing_if = "Option::is_none")]
+    notes: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct LapRef {
+    #[serde(rename = "@StartTime")]
+    start_time: String,
+    #[serde(rename = "TotalTimeSeconds")]
+    total_time_seconds: i64,
+    #[serde(rename = "DistanceMeters")]
+    distance_meters: f64,
+    #[serde(rename = "Calories")]
+    calories: u16,
+    #[serde(rename = "Intensity")]
+    intensity: &'static str,
+    #[serde(rename = "TriggerMethod")]
+    trigger_method: &'static str,
+    #[serde(rename = "Track")]
+    track: TrackRef,
+}
+
+#[derive(Serialize)]
+struct TrackRef {
+    #[serde(rename = "Trackpoint")]
+    trackpoints: Vec<TrackpointRef>,
+}
+
+#[derive(Serialize)]
+struct TrackpointRef {
+    #[serde(rename = "Time", skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(rename = "Position")]
+    position: PositionRef,
+    #[serde(rename = "AltitudeMeters", skip_serializing_if = "Option::is_none")]
+    altitude: Option<f64>,
+    #[serde(rename = "DistanceMeters")]
+    distance_meters: f64,
+    #[serde(rename = "HeartRateBpm", skip_serializing_if = "Option::is_none")]
+    heart_rate: Option<ValueRef>,
+    #[serde(rename = "Cadence", skip_serializing_if = "Option::is_none")]
+    cadence: Option<u8>,
+}
+
+impl TrackpointRef {
+    fn new(point: &Point, distance_meters: f64) -> Self {
+        Self {
+            time: point.time.map(timestamp),
+            position: PositionRef {
+                lat: point.lat,
+                lon: point.lon,
+            },
+            altitude: point.elevation,
+            distance_meters,
+            heart_rate: point.heart_rate().map(|value| ValueRef { value }),
+            cadence: point.cadence(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PositionRef {
+    #[serde(rename = "LatitudeDegrees")]
+    lat: f64,
+    #[serde(rename = "LongitudeDegrees")]
+    lon: f64,
+}
+
+#[derive(Serialize)]
+struct ValueRef {
+    #[serde(rename = "Value")]
+    value: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::formats::FormatRegistry;
+
+    const ACTIVITY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<TrainingCenterDatabase xmlns="http://www.garmin.com/xmlschemas/TrainingCenterDatabase/v2"
+    xmlns:ns3="http://www.garmin.com/xmlschemas/ActivityExtension/v2">
+  <Activities>
+    <Activity Sport="Running">
+      <Id>2024-07-01T08:00:00.000Z</Id>
+      <Lap StartTime="2024-07-01T08:00:00.000Z">
+        <TotalTimeSeconds>120.0</TotalTimeSeconds>
+        <DistanceMeters>500.0</DistanceMeters>
+        <Calories>40</Calories>
+        <Intensity>Active</Intensity>
+        <TriggerMethod>Manual</TriggerMethod>
+        <Track>
+          <Trackpoint>
+            <Time>2024-07-01T08:00:00.000Z</Time>
+            <Position><LatitudeDegrees>40.0</LatitudeDegrees><LongitudeDegrees>-3.0</LongitudeDegrees></Position>
+            <AltitudeMeters>650.5</AltitudeMeters>
+            <DistanceMeters>0.0</DistanceMeters>
+            <HeartRateBpm><Value>120</Value></HeartRateBpm>
+            <Extensions><ns3:TPX><ns3:Speed>3.1</ns3:Speed><ns3:RunCadence>84</ns3:RunCadence></ns3:TPX></Extensions>
+          </Trackpoint>
+          <Trackpoint>
+            <Time>2024-07-01T08:01:00.000Z</Time>
+            <HeartRateBpm><Value>130</Value></HeartRateBpm>
+          </Trackpoint>
+          <Trackpoint>
+            <Time>2024-07-01T08:02:00.000Z</Time>
+            <Position><LatitudeDegrees>40.004</LatitudeDegrees><LongitudeDegrees>-3.0</LongitudeDegrees></Position>
+            <Cadence>86</Cadence>
+            <Extensions><ns3:TPX><ns3:Watts>210</ns3:Watts></ns3:TPX></Extensions>
+          </Trackpoint>
+        </Track>
+      </Lap>
+      <Lap StartTime="2024-07-01T08:02:00.000Z">
+        <TotalTimeSeconds>60.0</TotalTimeSeconds>
+        <DistanceMeters>200.0</DistanceMeters>
+        <Calories>15</Calories>
+        <Intensity>Active</Intensity>
+        <TriggerMethod>Distance</TriggerMethod>
+        <Track>
+          <Trackpoint>
+            <Time>2024-07-01T08:03:00.000Z</Time>
+            <Position><LatitudeDegrees>40.006</LatitudeDegrees><LongitudeDegrees>-3.0</LongitudeDegrees></Position>
+          </Trackpoint>
+        </Track>
+      </Lap>
+      <Notes>Morning run</Notes>
+    </Activity>
+  </Activities>
+</TrainingCenterDatabase>"#;
+
+    #[test]
+    fn test_from_tcx_laps_and_sensor_data() {
+        let gpx = Gpx::from_tcx(ACTIVITY).unwrap();
+
+        assert_eq!(gpx.track_names(), vec!["Morning run"]);
+        assert_eq!(gpx.date(), Some("2024-07-01T08:00:00.000Z"));
+        let track = &gpx.tracks[0];
+        assert_eq!(track.segments.len(), 2);
+        // El trackpoint sin posición se descarta
+        assert_eq!(track.segments[0].points.len(), 2);
+
+        let first = &track.segments[0].points[0];
+        assert_eq!(first.elevation, Some(650.5));
+        assert_eq!(first.heart_rate(), Some(120));
+        assert_eq!(first.cadence(), Some(84));
+        assert_eq!(track.segments[0].points[1].cadence(), Some(86));
+        assert_eq!(track.segments[0].points[1].power(), Some(210));
+    }
+
+    #[test]
+    fn test_tcx_roundtrip() {
+        let gpx = Gpx::from_tcx(ACTIVITY).unwrap();
+        let tcx = gpx.to_tcx().unwrap();
+
+        assert!(tcx.contains(&format!("xmlns=\"{TCX_NAMESPACE}\"")));
+        assert!(tcx.contains("<Activity Sport=\"Running\"><Id>2024-07-01T08:00:00Z</Id>"));
+        assert!(tcx.contains("<TotalTimeSeconds>120</TotalTimeSeconds>"));
+        assert!(tcx.contains("<HeartRateBpm><Value>120</Value></HeartRateBpm>"));
+
+        let reparsed = Gpx::from_tcx(&tcx).unwrap();
+        assert_eq!(reparsed.track_names(), gpx.track_names());
+        assert_eq!(reparsed.total_points(), 3);
+        assert_eq!(reparsed.total_segments(), 2);
+        assert_eq!(reparsed.tracks[0].segments[0].points[0].cadence(), Some(84));
+        assert!((reparsed.total_distance_km() - gpx.total_distance_km()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tcx_through_gpx_keeps_heart_rate() {
+        let gpx = Gpx::from_tcx(ACTIVITY).unwrap();
+        let xml = gpx.to_xml();
+        assert!(xml.contains("<gpxtpx:hr>120</gpxtpx:hr><gpxtpx:cad>84</gpxtpx:cad>"));
+
+        let reparsed = Gpx::try_from_str(&xml).unwrap();
+        let point = &reparsed.tracks[0].segments[0].points[0];
+        assert_eq!(point.heart_rate(), Some(120));
+        assert_eq!(point.cadence(), Some(84));
+        assert!(!gpx.to_xml_v10().contains("gpxtpx"));
+    }
+
+    #[test]
+    fn test_default_registry_detects_tcx() {
+        let registry = FormatRegistry::default();
+        let gpx = registry.read_detected(ACTIVITY.as_bytes()).unwrap();
+        assert_eq!(gpx.total_points(), 3);
+
+        let bytes = registry.write_as("tcx", &gpx).unwrap();
+        assert!(bytes.starts_with(b"<?xml"));
+        assert!(Gpx::from_tcx("<TrainingCenterDatabase/>")
+            .unwrap()
+            .tracks
+            .is_empty());
+    }
+}
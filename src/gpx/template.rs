@@ -0,0 +1,346 @@
+use crate::gpx::{error::GpxError, parser::Gpx};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+/// A value that can be substituted into a [`NameTemplate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    /// Free text, inserted as is
+    Text(String),
+    /// Whole number (counters, indices)
+    Integer(i64),
+    /// Decimal number; honours the `:.N` precision of the placeholder
+    Number(f64),
+}
+
+impl TemplateValue {
+    fn write_to(&self, out: &mut String, precision: Option<usize>) {
+        // `write!` sobre un String no puede fallar
+        let _ = match (self, precision) {
+            (Self::Text(text), _) => write!(out, "{text}"),
+            (Self::Integer(n), _) => write!(out, "{n}"),
+            (Self::Number(x), Some(precision)) => write!(out, "{x:.precision$}"),
+            (Self::Number(x), None) => write!(out, "{x}"),
+        };
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<i64> for TemplateValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<usize> for TemplateValue {
+    fn from(value: usize) -> Self {
+        Self::Integer(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<f64> for TemplateValue {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+/// Named values available to a [`NameTemplate`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateValues {
+    values: BTreeMap<String, TemplateValue>,
+}
+
+impl TemplateValues {
+    /// Crea un conjunto de valores vacío
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a value, replacing any previous one with the same key
+    #[must_use]
+    pub fn with(mut self, key: &str, value: impl Into<TemplateValue>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Sets a value, replacing any previous one with the same key
+    pub fn set(&mut self, key: &str, value: impl Into<TemplateValue>) {
+        self.values.insert(key.to_string(), value.into());
+    }
+
+    /// Obtiene el valor asociado a una clave
+    pub fn get(&self, key: &str) -> Option<&TemplateValue> {
+        self.values.get(key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Placeholder {
+        key: String,
+        precision: Option<usize>,
+    },
+}
+
+/// A small naming template such as `"Climb {n}: {gain_m:.0} m @ {grade:.1}%"`
+///
+/// Placeholders are `{key}` or `{key:.N}`, where `N` is the number of decimals used
+/// for numeric values. Literal braces are written as `{{` and `}}`. Placeholders
+/// without a value are kept verbatim in the output, so a typo is visible rather
+/// than silently producing an empty label.
+///
+/// Templates are shared by every feature that generates names, so labels stay
+/// consistent across artifacts: split pieces ([`Gpx::split_named`]), merged tracks
+/// ([`MergeOptions::track_name`](crate::MergeOptions::track_name)), anomaly and cue
+/// waypoints ([`Gpx::flag_anomalies_named`], [`Gpx::add_cue_waypoints_named`]) and
+/// the files written by `gpx-cli split --name`.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{NameTemplate, TemplateValues};
+///
+/// let template: NameTemplate = "{distance_km:.0} km marker".parse().unwrap();
+/// let values = TemplateValues::new().with("distance_km", 4.96);
+/// assert_eq!(template.render(&values), "5 km marker");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameTemplate {
+    parts: Vec<Part>,
+}
+
+impl NameTemplate {
+    /// Parses a template string
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GpxError::InvalidTemplate`] for unbalanced braces, empty
+    /// placeholders or a malformed precision
+    pub fn parse(template: &str) -> Result<Self, GpxError> {
+        let invalid = |msg: &str| GpxError::InvalidTemplate(format!("{msg} in '{template}'"));
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid("unclosed placeholder")),
+                            Some(c) => spec.push(c),
+                        }
+                    }
+
+                    let (key, format) = match spec.split_once(':') {
+                        Some((key, format)) => (key.trim(), Some(format)),
+                        None => (spec.trim(), None),
+                    };
+                    if key.is_empty() {
+                        return Err(invalid("empty placeholder"));
+                    }
+                    let precision = format
+                        .map(|format| {
+                            format
+                                .strip_prefix('.')
+                                .and_then(|digits| digits.parse::<usize>().ok())
+                                .ok_or_else(|| invalid("expected precision like ':.2'"))
+                        })
+                        .transpose()?;
+
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Placeholder {
+                        key: key.to_string(),
+                        precision,
+                    });
+                }
+                '}' => return Err(invalid("unmatched '}'")),
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Renders the template with the given values
+    pub fn render(&self, values: &TemplateValues) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Placeholder { key, precision } => match values.get(key) {
+                    Some(value) => value.write_to(&mut out, *precision),
+                    None => {
+                        let _ = match precision {
+                            Some(precision) => write!(out, "{{{key}:.{precision}}}"),
+                            None => write!(out, "{{{key}}}"),
+                        };
+                    }
+                },
+            }
+        }
+        out
+    }
+
+    /// Names of the placeholders used by the template, in order of appearance
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Placeholder { key, .. } => Some(key.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+}
+
+impl FromStr for NameTemplate {
+    type Err = GpxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for NameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => f.write_str(&text.replace('{', "{{").replace('}', "}}"))?,
+                Part::Placeholder {
+                    key,
+                    precision: Some(precision),
+                } => write!(f, "{{{key}:.{precision}}}")?,
+                Part::Placeholder {
+                    key,
+                    precision: None,
+                } => write!(f, "{{{key}}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Gpx {
+    /// Standard template values describing the whole document
+    ///
+    /// Provides `name` (first track name), `date`, `distance_km`, `gain_m`,
+    /// `loss_m`, `duration`, `tracks`, `waypoints` and `points`; keys without
+    /// data (e.g. no timestamps) are left unset.
+    pub fn template_values(&self) -> TemplateValues {
+        let stats = self.statistics();
+        let mut values = TemplateValues::new()
+            .with("distance_km", stats.total_distance_km)
+            .with("tracks", stats.total_tracks)
+            .with("waypoints", stats.total_waypoints)
+            .with("points", stats.total_points);
+
+        if let Some(name) = self.tracks.iter().find_map(|track| track.name.clone()) {
+            values.set("name", name);
+        }
+        if let Some(date) = self.date() {
+            values.set("date", date);
+        }
+        if let Some(gain) = stats.elevation_gain {
+            values.set("gain_m", gain);
+        }
+        if let Some(loss) = stats.elevation_loss {
+            values.set("loss_m", loss);
+        }
+        if let Some(duration) = stats.duration_formatted() {
+            values.set("duration", duration);
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GpxBuilder;
+
+    #[test]
+    fn test_render_with_precision() {
+        let template = NameTemplate::parse("Climb {n}: {gain_m} m @ {grade:.1}%").unwrap();
+        let values = TemplateValues::new()
+            .with("n", 3_usize)
+            .with("gain_m", 412.0)
+            .with("grade", 7.25);
+        assert_eq!(template.render(&values), "Climb 3: 412 m @ 7.2%");
+        assert_eq!(
+            template.keys().collect::<Vec<_>>(),
+            vec!["n", "gain_m", "grade"]
+        );
+    }
+
+    #[test]
+    fn test_missing_values_and_escapes() {
+        let template = NameTemplate::parse("{{{name}}} {km:.1}").unwrap();
+        assert_eq!(
+            template.render(&TemplateValues::new().with("name", "Loop")),
+            "{Loop} {km:.1}"
+        );
+        assert_eq!(template.to_string(), "{{{name}}} {km:.1}");
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for template in ["{name", "name}", "{}", "{km:2}", "{km:.x}", "{a{b}}"] {
+            assert!(
+                matches!(
+                    NameTemplate::parse(template),
+                    Err(GpxError::InvalidTemplate(_))
+                ),
+                "{template} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gpx_template_values() {
+        let gpx = GpxBuilder::new()
+            .time("2024-07-11T10:00:00Z")
+            .track(|t| {
+                t.name("Morning Ride").segment(|s| {
+                    s.point_with_elevation(40.0, -3.0, 600.0)
+                        .point_with_elevation(40.01, -3.0, 650.0)
+                })
+            })
+            .build()
+            .unwrap();
+
+        let template: NameTemplate = "{date} {name} {distance_km:.1} km +{gain_m:.0} m {duration}"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            template.render(&gpx.template_values()),
+            "2024-07-11T10:00:00Z Morning Ride 1.1 km +50 m {duration}"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use crate::gpx::{
+    bounds::Bounds,
+    parser::{start_time, Gpx},
+    point::{interpolate, Point},
+    split::Piece,
+    track::Track,
+};
+use chrono::SecondsFormat;
+use std::collections::{BTreeSet, HashMap};
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Highest zoom level accepted by [`Tile::containing`]
+pub const MAX_TILE_ZOOM: u8 = 30;
+
+/// Latitud máxima representable en Web Mercator
+const MAX_MERCATOR_LAT: f64 = 85.051_128_779_806_6;
+
+/// A slippy-map tile (Web Mercator, as used by OpenStreetMap and most web maps)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    /// Zoom level, from 0 (the whole world) to [`MAX_TILE_ZOOM`]
+    pub zoom: u8,
+    /// Column, growing eastwards from the antimeridian
+    pub x: u32,
+    /// Row, growing southwards from the north edge
+    pub y: u32,
+}
+
+impl Tile {
+    /// Tile containing the given position; zoom levels above [`MAX_TILE_ZOOM`] are clamped
+    pub fn containing(lat: f64, lon: f64, zoom: u8) -> Self {
+        let zoom = zoom.min(MAX_TILE_ZOOM);
+        let (x, y) = to_tile_space(lat, lon, zoom);
+        let max_index = (1u32 << zoom) - 1;
+        // Coordenadas dentro del rango, ya recortadas
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let index = |value: f64| (value.floor().max(0.0) as u32).min(max_index);
+        Self {
+            zoom,
+            x: index(x),
+            y: index(y),
+        }
+    }
+
+    /// Geographic area covered by the tile
+    pub fn bounds(self) -> Bounds {
+        let (north, west) = from_tile_space(f64::from(self.x), f64::from(self.y), self.zoom);
+        let (south, east) =
+            from_tile_space(f64::from(self.x + 1), f64::from(self.y + 1), self.zoom);
+        Bounds {
+            min_lat: south,
+            min_lon: west,
+            max_lat: north,
+            max_lon: east,
+        }
+    }
+}
+
+impl Point {
+    /// Slippy-map tile containing the point (see [`Tile::containing`])
+    pub fn to_tile(&self, zoom: u8) -> Tile {
+        Tile::containing(self.lat, self.lon, zoom)
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.zoom, self.x, self.y)
+    }
+}
+
+/// Posición en coordenadas de tile fraccionarias (columna, fila)
+fn to_tile_space(lat: f64, lon: f64, zoom: u8) -> (f64, f64) {
+    let n = f64::from(1u32 << zoom);
+    let lat = lat.clamp(-MAX_MERCATOR_LAT, MAX_MERCATOR_LAT).to_radians();
+    let x = (lon + 180.0) / 360.0 * n;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n;
+    (x, y)
+}
+
+/// Inversa de [`to_tile_space`]: devuelve (latitud, longitud)
+fn from_tile_space(x: f64, y: f64, zoom: u8) -> (f64, f64) {
+    let n = f64::from(1u32 << zoom);
+    let lon = x / n * 360.0 - 180.0;
+    let lat = (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees();
+    (lat, lon)
+}
+
+/// Añade los tiles que atraviesa el tramo recto (en el plano de Mercator) de `from` a `to`
+fn add_crossed_tiles(from: &Point, to: &Point, zoom: u8, tiles: &mut BTreeSet<Tile>) {
+    let (start, end) = (from.to_tile(zoom), to.to_tile(zoom));
+    tiles.insert(start);
+    let (ax, ay) = to_tile_space(from.lat, from.lon, start.zoom);
+    let (bx, by) = to_tile_space(to.lat, to.lon, start.zoom);
+    // Recorrido de la rejilla de Amanatides y Woo: se avanza siempre por el borde más cercano
+    let axis = |a: f64, b: f64, cell: u32| {
+        let delta = (b - a).abs();
+        if delta < f64::EPSILON {
+            return (0i64, f64::INFINITY, f64::INFINITY);
+        }
+        let step = if b > a { 1 } else { -1 };
+        let edge = if b > a { cell + 1 } else { cell };
+        (step, (f64::from(edge) - a).abs() / delta, 1.0 / delta)
+    };
+    let (step_x, mut next_x, delta_x) = axis(ax, bx, start.x);
+    let (step_y, mut next_y, delta_y) = axis(ay, by, start.y);
+    let (mut x, mut y) = (i64::from(start.x), i64::from(start.y));
+    let steps = (i64::from(end.x) - x).abs() + (i64::from(end.y) - y).abs();
+    for _ in 0..steps {
+        if next_x < next_y {
+            x += step_x;
+            next_x += delta_x;
+        } else {
+            y += step_y;
+            next_y += delta_y;
+        }
+        // Fuera de la rejilla solo por redondeo en los bordes del mapa
+        let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+            break;
+        };
+        tiles.insert(Tile {
+            zoom: start.zoom,
+            x,
+            y,
+        });
+    }
+    tiles.insert(end);
+}
+
+/// Punto donde el tramo `from` → `to` cruza el borde de `tile`, que contiene a `from`
+fn border_point(tile: Tile, from: &Point, to: &Point) -> Point {
+    let (ax, ay) = to_tile_space(from.lat, from.lon, tile.zoom);
+    let (bx, by) = to_tile_space(to.lat, to.lon, tile.zoom);
+    let crossing = |a: f64, b: f64, start: u32| {
+        let edge = if b > a { start + 1 } else { start };
+        if (b - a).abs() < f64::EPSILON {
+            f64::INFINITY
+        } else {
+            (f64::from(edge) - a) / (b - a)
+        }
+    };
+    let fraction = crossing(ax, bx, tile.x)
+        .min(crossing(ay, by, tile.y))
+        .clamp(0.0, 1.0);
+
+    let mut point = interpolate(from, to, fraction);
+    let (lat, lon) = from_tile_space(
+        ax + (bx - ax) * fraction,
+        ay + (by - ay) * fraction,
+        tile.zoom,
+    );
+    point.lat = lat;
+    point.lon = lon;
+    point
+}
+
+impl Track {
+    /// Every slippy-map tile the track passes through at the given zoom level, sorted
+    ///
+    /// Besides the tiles holding points, the tiles crossed between two
+    /// consecutive points are included, so the result is the list of tiles to
+    /// prefetch to draw the whole line. Segments are not joined to each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Point, Track, TrackSegment};
+    ///
+    /// let mut track = Track::new();
+    /// track.add_segment(TrackSegment::from_latlon_pairs(&[(40.40, -3.75), (40.40, -3.55)]));
+    ///
+    /// let tiles = track.covered_tiles(12);
+    /// assert_eq!(tiles.len(), 3);
+    /// assert_eq!(tiles[0], Point::new(40.40, -3.75).to_tile(12));
+    /// assert_eq!(tiles[1].to_string(), "12/2006/1544");
+    /// ```
+    pub fn covered_tiles(&self, zoom: u8) -> Vec<Tile> {
+        let mut tiles = BTreeSet::new();
+        for segment in &self.segments {
+            if let [point] = segment.points.as_slice() {
+                tiles.insert(point.to_tile(zoom));
+            }
+            for pair in segment.points.windows(2) {
+                add_crossed_tiles(&pair[0], &pair[1], zoom, &mut tiles);
+            }
+        }
+        tiles.into_iter().collect()
+    }
+}
+
+impl Gpx {
+    /// Cuts the tracks into one document per slippy-map tile at the given zoom level
+    ///
+    /// Every time a track leaves a tile, a point is interpolated on the tile border
+    /// and added to both sides, so the chunks join up seamlessly when rendered
+    /// together. Each visit to a tile becomes a separate segment of the track.
+    /// Chunks are returned in the order the tiles are first visited; each keeps
+    /// the metadata (with the time of its first point), the track names and the
+    /// waypoints that fall inside the tile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{tiles::Tile, GpxBuilder};
+    ///
+    /// let gpx = GpxBuilder::new()
+    ///     .track(|t| t.segment(|s| s.point(40.40, -3.75).point(40.40, -3.65)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let chunks = gpx.tile_split(12);
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[0].0, Tile::containing(40.40, -3.75, 12));
+    /// assert_eq!(chunks[0].0.to_string(), "12/2005/1544");
+    /// ```
+    pub fn tile_split(&self, zoom: u8) -> Vec<(Tile, Gpx)> {
+        let mut chunks: Vec<(Tile, Piece)> = Vec::new();
+        let mut index: HashMap<Tile, usize> = HashMap::new();
+        // Cada visita a un tile abre un segmento nuevo en su documento
+        let mut visit = 0;
+        let mut push = |tile: Tile, key: (usize, usize), track: &Track, point: Point| {
+            let i = *index.entry(tile).or_insert_with(|| {
+                chunks.push((tile, Piece::new(self)));
+                chunks.len() - 1
+            });
+            chunks[i].1.push(key, track, point);
+        };
+
+        for (t, track) in self.tracks.iter().enumerate() {
+            for segment in &track.segments {
+                let mut previous: Option<(&Point, Tile)> = None;
+                for point in &segment.points {
+                    let tile = Tile::containing(point.lat, point.lon, zoom);
+                    match previous {
+                        Some((_, from_tile)) if from_tile == tile => {}
+                        Some((from, from_tile)) => {
+                            push(
+                                from_tile,
+                                (t, visit),
+                                track,
+                                border_point(from_tile, from, point),
+                            );
+                            visit += 1;
+                            push(tile, (t, visit), track, border_point(tile, point, from));
+                        }
+                        None => visit += 1,
+                    }
+                    push(tile, (t, visit), track, point.clone());
+                    previous = Some((point, tile));
+                }
+            }
+        }
+
+        chunks
+            .into_iter()
+            .map(|(tile, piece)| {
+                let mut gpx = piece.gpx;
+                gpx.waypoints = self
+                    .waypoints
+                    .iter()
+                    .filter(|w| Tile::containing(w.lat, w.lon, tile.zoom) == tile)
+                    .cloned()
+                    .collect();
+                if let Some(start) = start_time(&gpx) {
+                    gpx.metadata_mut().time =
+                        Some(start.to_rfc3339_opts(SecondsFormat::Secs, true));
+                }
+                if gpx.metadata.as_ref().is_some_and(|m| m.bounds.is_some()) {
+                    gpx.update_metadata_bounds();
+                }
+                (tile, gpx)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{track::TrackSegment, waypoint::Waypoint};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_tile_containing_and_bounds() {
+        let tile = Tile::containing(51.5074, -0.1278, 10);
+        assert_eq!((tile.x, tile.y), (511, 340));
+
+        let bounds = tile.bounds();
+        assert!(bounds.contains(51.5074, -0.1278));
+        assert!((bounds.max_lon - bounds.min_lon - 360.0 / 1024.0).abs() < 1e-9);
+
+        assert_eq!(
+            Tile::containing(89.9, 180.0, 1),
+            Tile {
+                zoom: 1,
+                x: 1,
+                y: 0
+            }
+        );
+        assert_eq!(Tile::containing(0.0, 0.0, 0).to_string(), "0/0/0");
+        assert_eq!(Tile::containing(0.0, 0.0, 40).zoom, MAX_TILE_ZOOM);
+    }
+
+    #[test]
+    fn test_covered_tiles_include_crossed_tiles() {
+        let mut track = Track::new();
+        // Una diagonal de 3 × 2 tiles a zoom 10, más un segmento de un solo punto
+        let start = Tile::containing(40.0, -3.0, 10);
+        let from = start.bounds();
+        let to = Tile {
+            x: start.x + 2,
+            y: start.y + 1,
+            ..start
+        }
+        .bounds();
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (from.max_lat - 0.01, from.min_lon + 0.01),
+            (to.min_lat + 0.01, to.max_lon - 0.01),
+        ]));
+        track.add_segment(TrackSegment::from_latlon_pairs(&[(0.0, 0.0)]));
+
+        let tiles = track.covered_tiles(10);
+        // Una diagonal que no pasa por esquinas cruza 3 + 2 - 1 tiles
+        assert_eq!(tiles.len(), 4 + 1);
+        assert!(tiles.contains(&Point::new(0.0, 0.0).to_tile(10)));
+        let mut sorted = tiles.clone();
+        sorted.sort();
+        assert_eq!(tiles, sorted);
+        assert!(Track::new().covered_tiles(10).is_empty());
+    }
+
+    #[test]
+    fn test_tile_split_adds_border_points_and_revisits() {
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+        // Ida y vuelta cruzando el meridiano 0, que es borde de tile a cualquier zoom
+        let mut track = Track::with_name("Greenwich".to_string());
+        track.add_segment(TrackSegment::with_points(vec![
+            Point::with_time(51.48, -0.02, Some(10.0), at(0)),
+            Point::with_time(51.48, 0.02, Some(30.0), at(10)),
+            Point::with_time(51.48, -0.01, Some(20.0), at(20)),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx.add_waypoint(Waypoint::new(51.47, 0.01));
+
+        let chunks = gpx.tile_split(8);
+        assert_eq!(chunks.len(), 2);
+        let (west_tile, west) = &chunks[0];
+        let (east_tile, east) = &chunks[1];
+        assert_eq!(east_tile.x, west_tile.x + 1);
+
+        // El oeste tiene dos visitas, cada una terminada o empezada en el borde
+        let segments = &west.tracks[0].segments;
+        assert_eq!(segments.len(), 2);
+        let exit = segments[0].points.last().unwrap();
+        assert!(exit.lon.abs() < 1e-9);
+        assert!((exit.lat - 51.48).abs() < 1e-6);
+        assert_eq!(exit.elevation, Some(20.0));
+        assert_eq!(exit.time, Some(at(5)));
+        assert!(segments[1].points[0].lon.abs() < 1e-9);
+
+        assert_eq!(east.track_names(), vec!["Greenwich"]);
+        assert_eq!(east.tracks[0].segments[0].points.len(), 3);
+        assert_eq!(east.waypoints.len(), 1);
+        assert!(west.waypoints.is_empty());
+        assert_eq!(east.date(), Some("2024-07-01T08:05:00Z"));
+    }
+}
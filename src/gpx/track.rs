@@ -1,26 +1,61 @@
+use crate::gpx::extensions::{Color, TrackExtensions};
+use crate::gpx::metadata::Link;
+use crate::gpx::parser::GpxStatistics;
 use crate::gpx::point::{haversine_distance, Point};
+use crate::gpx::unknown::UnknownXml;
+use crate::gpx::user_data::UserData;
 use serde::{Deserialize, Serialize};
 
 /// A continuous segment of a GPS track
 ///
 /// Tracks are divided into segments to represent continuous sections.
 /// A break in recording (e.g., GPS turned off) starts a new segment.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct TrackSegment {
     /// Sequential points that make up this segment
     #[serde(rename = "trkpt", default)]
     pub points: Vec<Point>,
+    /// Child elements not modeled by this crate, written back as read
+    #[serde(
+        rename = "gpx_extractor_raw",
+        skip_deserializing,
+        skip_serializing_if = "UnknownXml::is_empty"
+    )]
+    pub unknown: UnknownXml,
 }
 
 impl TrackSegment {
     /// Crea un nuevo segmento vacío
     pub fn new() -> Self {
-        Self { points: Vec::new() }
+        Self::with_points(Vec::new())
     }
 
     /// Crea un segmento con puntos
     pub fn with_points(points: Vec<Point>) -> Self {
-        Self { points }
+        Self {
+            points,
+            unknown: UnknownXml::default(),
+        }
+    }
+
+    /// Crea un segmento a partir de pares `(latitud, longitud)`
+    pub fn from_latlon_pairs(coords: &[(f64, f64)]) -> Self {
+        Self::with_points(
+            coords
+                .iter()
+                .map(|&(lat, lon)| Point::new(lat, lon))
+                .collect(),
+        )
+    }
+
+    /// Crea un segmento a partir de tríos `(latitud, longitud, elevación)`
+    pub fn from_latlon_ele(coords: &[(f64, f64, f64)]) -> Self {
+        Self::with_points(
+            coords
+                .iter()
+                .map(|&(lat, lon, ele)| Point::with_elevation(lat, lon, ele))
+                .collect(),
+        )
     }
 
     /// Agrega un punto al segmento
@@ -60,6 +95,28 @@ impl TrackSegment {
     pub fn point_count(&self) -> usize {
         self.points.len()
     }
+
+    /// Itera sobre los puntos del segmento
+    pub fn points(&self) -> std::slice::Iter<'_, Point> {
+        self.points.iter()
+    }
+
+    /// Itera sobre los puntos del segmento permitiendo modificarlos
+    pub fn points_mut(&mut self) -> std::slice::IterMut<'_, Point> {
+        self.points.iter_mut()
+    }
+
+    /// Consume el segmento y devuelve sus puntos
+    pub fn into_points(self) -> std::vec::IntoIter<Point> {
+        self.points.into_iter()
+    }
+
+    /// Statistics of this segment alone
+    ///
+    /// `total_segments` is 1; track and waypoint counts are 0.
+    pub fn statistics(&self) -> GpxStatistics {
+        GpxStatistics::from_segments(std::iter::once(self))
+    }
 }
 
 impl Default for TrackSegment {
@@ -72,11 +129,47 @@ impl Default for TrackSegment {
 ///
 /// A track consists of one or more segments, each containing sequential points.
 /// Tracks typically represent activities like runs, bike rides, or hikes.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Track {
     /// Optional name describing the track
     #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Free-form comment (`<cmt>`)
+    #[serde(rename = "cmt", default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Longer description (`<desc>`)
+    #[serde(rename = "desc", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Source of the data (`<src>`), e.g. the device that recorded it
+    #[serde(rename = "src", default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Links to more information, such as the activity page
+    #[serde(rename = "link", default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+    /// Activity type, such as `cycling` or `running` (`<type>` element)
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Child elements not modeled by this crate (`<number>`...), written back as read
+    #[serde(
+        rename = "gpx_extractor_raw",
+        skip_deserializing,
+        skip_serializing_if = "UnknownXml::is_empty"
+    )]
+    pub unknown: UnknownXml,
+    /// Track extensions (display color)
+    #[serde(
+        rename = "extensions",
+        default,
+        skip_serializing_if = "TrackExtensions::is_empty"
+    )]
+    pub extensions: TrackExtensions,
+    /// Application annotations, written into a `gpxud:UserData` extension
+    #[serde(
+        rename = "gpx_extractor_user_data",
+        skip_deserializing,
+        skip_serializing_if = "UserData::is_empty"
+    )]
+    pub user_data: UserData,
     /// Track segments making up this track
     #[serde(rename = "trkseg", default)]
     pub segments: Vec<TrackSegment>,
@@ -87,6 +180,14 @@ impl Track {
     pub fn new() -> Self {
         Self {
             name: None,
+            comment: None,
+            description: None,
+            source: None,
+            links: Vec::new(),
+            kind: None,
+            unknown: UnknownXml::default(),
+            extensions: TrackExtensions::default(),
+            user_data: UserData::default(),
             segments: Vec::new(),
         }
     }
@@ -95,6 +196,30 @@ impl Track {
     pub fn with_name(name: String) -> Self {
         Self {
             name: Some(name),
+            comment: None,
+            description: None,
+            source: None,
+            links: Vec::new(),
+            kind: None,
+            unknown: UnknownXml::default(),
+            extensions: TrackExtensions::default(),
+            user_data: UserData::default(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Copia del track sin sus segmentos: nombre, descripción, enlaces, extensiones y datos de usuario
+    pub(crate) fn header(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            comment: self.comment.clone(),
+            description: self.description.clone(),
+            source: self.source.clone(),
+            links: self.links.clone(),
+            kind: self.kind.clone(),
+            unknown: self.unknown.clone(),
+            extensions: self.extensions.clone(),
+            user_data: self.user_data.clone(),
             segments: Vec::new(),
         }
     }
@@ -106,10 +231,24 @@ impl Track {
 
     /// Obtiene todos los puntos de todos los segmentos
     pub fn get_all_points(&self) -> Vec<&Point> {
+        self.points().collect()
+    }
+
+    /// Points of every segment in order, without collecting them
+    pub fn points(&self) -> impl DoubleEndedIterator<Item = &Point> {
+        self.segments.iter().flat_map(TrackSegment::points)
+    }
+
+    /// Points of every segment in order, for in-place edits
+    pub fn points_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Point> {
+        self.segments.iter_mut().flat_map(TrackSegment::points_mut)
+    }
+
+    /// Consumes the track and yields the points of every segment in order
+    pub fn into_points(self) -> impl DoubleEndedIterator<Item = Point> {
         self.segments
-            .iter()
-            .flat_map(|segment| &segment.points)
-            .collect()
+            .into_iter()
+            .flat_map(TrackSegment::into_points)
     }
 
     /// Calcula la distancia total del track en kilómetros
@@ -148,6 +287,32 @@ impl Track {
             .sum()
     }
 
+    /// Color de visualización del track, si lo tiene
+    pub fn color(&self) -> Option<Color> {
+        self.extensions.color
+    }
+
+    /// Sets the display color, written as both `gpx_style` and Garmin `DisplayColor`
+    pub fn set_color(&mut self, color: Color) {
+        self.extensions.color = Some(color);
+    }
+
+    /// Elimina el color de visualización
+    pub fn clear_color(&mut self) {
+        self.extensions.color = None;
+    }
+
+    /// Statistics of this track alone
+    ///
+    /// `total_tracks` is 1 and `total_waypoints` is 0, since waypoints belong to
+    /// the document rather than to a track.
+    pub fn statistics(&self) -> GpxStatistics {
+        GpxStatistics {
+            total_tracks: 1,
+            ..GpxStatistics::from_segments(&self.segments)
+        }
+    }
+
     /// Obtiene el nombre del track o un nombre por defecto
     pub fn display_name(&self) -> String {
         self.name
@@ -166,6 +331,31 @@ impl Default for Track {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_track_point_iterators() {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (40.0, -3.0),
+            (40.1, -3.0),
+        ]));
+        track.add_segment(TrackSegment::new());
+        track.add_segment(TrackSegment::from_latlon_pairs(&[(40.2, -3.0)]));
+
+        let lats: Vec<f64> = track.points().rev().map(|p| p.lat).collect();
+        assert_eq!(lats, vec![40.2, 40.1, 40.0]);
+
+        for point in track.points_mut() {
+            point.elevation = Some(600.0);
+        }
+        assert!(track.segments[2]
+            .points()
+            .all(|p| p.elevation == Some(600.0)));
+
+        let owned: Vec<Point> = track.into_points().collect();
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned[1].lat, 40.1);
+    }
+
     #[test]
     fn test_track_segment_new() {
         let segment = TrackSegment::new();
@@ -182,6 +372,18 @@ mod tests {
         assert!(segment.distance_km() > 0.0);
     }
 
+    #[test]
+    fn test_track_segment_from_coordinate_slices() {
+        let segment = TrackSegment::from_latlon_pairs(&[(40.0, -3.0), (40.1, -3.1)]);
+        assert_eq!(segment.point_count(), 2);
+        assert_eq!(segment.points[1].lon, -3.1);
+        assert!(segment.points[0].elevation.is_none());
+
+        let segment = TrackSegment::from_latlon_ele(&[(40.0, -3.0, 600.0), (40.1, -3.1, 650.0)]);
+        assert_eq!(segment.elevation_range(), Some((600.0, 650.0)));
+        assert!(TrackSegment::from_latlon_pairs(&[]).points.is_empty());
+    }
+
     #[test]
     fn test_track_segment_add_point() {
         let mut segment = TrackSegment::new();
@@ -260,4 +462,93 @@ mod tests {
         assert_eq!(track.total_points(), 4);
         assert!(track.total_distance_km() > 0.0);
     }
+
+    #[test]
+    fn test_track_color_accessors() {
+        let mut track = Track::new();
+        assert!(track.color().is_none());
+
+        track.set_color(Color::rgb(255, 0, 0));
+        assert_eq!(track.color(), Some(Color::rgb(255, 0, 0)));
+
+        track.clear_color();
+        assert!(track.color().is_none());
+    }
+
+    #[test]
+    fn test_track_and_segment_statistics() {
+        use crate::gpx::parser::Gpx;
+        use chrono::{TimeZone, Utc};
+
+        let at = |minute| Utc.with_ymd_and_hms(2024, 6, 1, 8, minute, 0).unwrap();
+        let climb = TrackSegment::with_points(vec![
+            Point::with_time(40.00, -3.0, Some(600.0), at(0)),
+            Point::with_time(40.01, -3.0, Some(700.0), at(30)),
+        ]);
+        let descent = TrackSegment::with_points(vec![
+            Point::with_time(40.02, -3.0, Some(680.0), at(40)),
+            Point::with_time(40.03, -3.0, Some(620.0), at(50)),
+        ]);
+
+        let stats = climb.statistics();
+        assert_eq!(stats.total_tracks, 0);
+        assert_eq!(stats.total_segments, 1);
+        assert_eq!(stats.elevation_gain, Some(100.0));
+        assert_eq!(stats.elevation_loss, Some(0.0));
+        assert_eq!(stats.duration_seconds, Some(1800));
+        assert!((stats.average_speed_kmh.unwrap() - climb.distance_km() * 2.0).abs() < 1e-9);
+
+        let mut track = Track::with_name("Hill".to_string());
+        track.add_segment(climb);
+        track.add_segment(descent);
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+        gpx.add_track(Track::new());
+
+        let per_track = gpx.statistics_per_track();
+        let (name, stats) = &per_track[0];
+        let whole = gpx.statistics();
+        assert_eq!(name, "Hill");
+        assert_eq!(stats.total_tracks, 1);
+        assert_eq!(stats.total_points, 4);
+        assert_eq!(stats.elevation_range, Some((600.0, 700.0)));
+        assert_eq!(stats.elevation_gain, whole.elevation_gain);
+        assert_eq!(stats.elevation_loss, Some(60.0));
+        assert_eq!(stats.duration_seconds, Some(3000));
+        assert_eq!(stats.total_distance_km, whole.total_distance_km);
+
+        let (name, empty) = &per_track[1];
+        assert_eq!(name, "Unnamed Track");
+        assert_eq!(empty.total_points, 0);
+        assert!(empty.elevation_gain.is_none() && empty.duration_seconds.is_none());
+    }
+
+    #[test]
+    fn test_track_type_round_trip() {
+        let xml = r"<gpx><trk><name>Ride</name><type>cycling</type><trkseg/></trk></gpx>";
+        let gpx = crate::Gpx::try_from_str(xml).unwrap();
+        assert_eq!(gpx.tracks[0].kind.as_deref(), Some("cycling"));
+        assert!(gpx
+            .to_xml()
+            .contains("<name>Ride</name><type>cycling</type>"));
+        assert!(!gpx.to_xml_v10().contains("<type>"));
+    }
+
+    #[test]
+    fn test_track_descriptive_fields_round_trip() {
+        let xml = r#"<gpx><trk><name>Ride</name><cmt>Windy</cmt><desc>Lap of the lake</desc>
+            <src>Edge 530</src><link href="https://example.com/ride/1"><text>Activity</text></link>
+            <type>cycling</type><trkseg/></trk></gpx>"#;
+        let gpx = crate::Gpx::try_from_str(xml).unwrap();
+        let track = &gpx.tracks[0];
+        assert_eq!(track.comment.as_deref(), Some("Windy"));
+        assert_eq!(track.description.as_deref(), Some("Lap of the lake"));
+        assert_eq!(track.source.as_deref(), Some("Edge 530"));
+        assert_eq!(track.links[0].text.as_deref(), Some("Activity"));
+        assert!(track.unknown.is_empty());
+        assert!(gpx.to_xml().contains(
+            r#"<name>Ride</name><cmt>Windy</cmt><desc>Lap of the lake</desc><src>Edge 530</src><link href="https://example.com/ride/1"><text>Activity</text></link><type>cycling</type>"#
+        ));
+        assert!(!gpx.to_xml_v10().contains("<link"));
+    }
 }
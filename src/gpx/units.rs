@@ -0,0 +1,330 @@
+use crate::gpx::{
+    pace::{SplitUnit, KM_PER_MILE},
+    parser::GpxStatistics,
+};
+use std::fmt;
+
+/// Metros en un pie internacional
+const METERS_PER_FOOT: f64 = 0.3048;
+
+/// Unit system used to present distances, elevations and speeds
+///
+/// Every figure in the library is computed in metric units; the unit system
+/// only matters when the figures are shown to someone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Units {
+    /// Kilometers, meters and km/h
+    #[default]
+    Metric,
+    /// Miles, feet and mph
+    Imperial,
+}
+
+impl Units {
+    /// Unidad de los parciales y los ritmos en este sistema
+    pub fn split_unit(self) -> SplitUnit {
+        match self {
+            Self::Metric => SplitUnit::Kilometer,
+            Self::Imperial => SplitUnit::Mile,
+        }
+    }
+
+    /// Abreviatura de las distancias (`"km"` o `"mi"`)
+    pub fn distance_label(self) -> &'static str {
+        match self {
+            Self::Metric => "km",
+            Self::Imperial => "mi",
+        }
+    }
+
+    /// Abreviatura de las elevaciones (`"m"` o `"ft"`)
+    pub fn elevation_label(self) -> &'static str {
+        match self {
+            Self::Metric => "m",
+            Self::Imperial => "ft",
+        }
+    }
+
+    /// Abreviatura de las velocidades (`"km/h"` o `"mph"`)
+    pub fn speed_label(self) -> &'static str {
+        match self {
+            Self::Metric => "km/h",
+            Self::Imperial => "mph",
+        }
+    }
+}
+
+/// A distance, stored in kilometers
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Distance, Units};
+///
+/// let marathon = Distance::from_km(42.195);
+/// assert!((marathon.to_miles() - 26.219).abs() < 0.001);
+/// assert_eq!(marathon.in_units(Units::Metric), 42.195);
+/// assert_eq!(Distance::from_miles(1.0).km(), 1.609_344);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Distance(f64);
+
+impl Distance {
+    /// Crea una distancia a partir de kilómetros
+    pub fn from_km(km: f64) -> Self {
+        Self(km)
+    }
+
+    /// Crea una distancia a partir de millas
+    pub fn from_miles(miles: f64) -> Self {
+        Self(miles * KM_PER_MILE)
+    }
+
+    /// Distancia en kilómetros
+    pub fn km(self) -> f64 {
+        self.0
+    }
+
+    /// Distancia en metros
+    pub fn meters(self) -> f64 {
+        self.0 * 1000.0
+    }
+
+    /// Distancia en millas
+    pub fn to_miles(self) -> f64 {
+        self.0 / KM_PER_MILE
+    }
+
+    /// Distancia en la unidad del sistema indicado (km o millas)
+    pub fn in_units(self, units: Units) -> f64 {
+        match units {
+            Units::Metric => self.km(),
+            Units::Imperial => self.to_miles(),
+        }
+    }
+
+    /// Valor con dos decimales y su abreviatura, como `12.34 mi`
+    pub fn format(self, units: Units) -> String {
+        format!("{:.2} {}", self.in_units(units), units.distance_label())
+    }
+}
+
+/// An elevation or an elevation change, stored in meters
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Elevation, Units};
+///
+/// let summit = Elevation::from_meters(4808.0);
+/// assert!((summit.to_feet() - 15774.3).abs() < 0.1);
+/// assert_eq!(summit.format(Units::Imperial), "15774.3 ft");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Elevation(f64);
+
+impl Elevation {
+    /// Crea una elevación a partir de metros
+    pub fn from_meters(meters: f64) -> Self {
+        Self(meters)
+    }
+
+    /// Crea una elevación a partir de pies
+    pub fn from_feet(feet: f64) -> Self {
+        Self(feet * METERS_PER_FOOT)
+    }
+
+    /// Elevación en metros
+    pub fn meters(self) -> f64 {
+        self.0
+    }
+
+    /// Elevación en pies
+    pub fn to_feet(self) -> f64 {
+        self.0 / METERS_PER_FOOT
+    }
+
+    /// Elevación en la unidad del sistema indicado (metros o pies)
+    pub fn in_units(self, units: Units) -> f64 {
+        match units {
+            Units::Metric => self.meters(),
+            Units::Imperial => self.to_feet(),
+        }
+    }
+
+    /// Valor con un decimal y su abreviatura, como `1234.5 ft`
+    pub fn format(self, units: Units) -> String {
+        format!("{:.1} {}", self.in_units(units), units.elevation_label())
+    }
+}
+
+/// A speed, stored in kilometers per hour
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Speed(f64);
+
+impl Speed {
+    /// Crea una velocidad a partir de km/h
+    pub fn from_kmh(kmh: f64) -> Self {
+        Self(kmh)
+    }
+
+    /// Crea una velocidad a partir de millas por hora
+    pub fn from_mph(mph: f64) -> Self {
+        Self(mph * KM_PER_MILE)
+    }
+
+    /// Velocidad en km/h
+    pub fn kmh(self) -> f64 {
+        self.0
+    }
+
+    /// Velocidad en millas por hora
+    pub fn to_mph(self) -> f64 {
+        self.0 / KM_PER_MILE
+    }
+
+    /// Velocidad en la unidad del sistema indicado (km/h o mph)
+    pub fn in_units(self, units: Units) -> f64 {
+        match units {
+            Units::Metric => self.kmh(),
+            Units::Imperial => self.to_mph(),
+        }
+    }
+
+    /// Valor con dos decimales y su abreviatura, como `12.34 mph`
+    pub fn format(self, units: Units) -> String {
+        format!("{:.2} {}", self.in_units(units), units.speed_label())
+    }
+}
+
+/// Statistics summary in a given unit system, returned by [`GpxStatistics::display`]
+#[derive(Debug, Clone, Copy)]
+pub struct StatisticsDisplay<'a> {
+    stats: &'a GpxStatistics,
+    units: Units,
+}
+
+impl fmt::Display for StatisticsDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (stats, units) = (self.stats, self.units);
+        // El resumen métrico histórico pega la `m` al número; se mantiene igual
+        let elevation = |meters: f64| {
+            let value = Elevation::from_meters(meters).in_units(units);
+            match units {
+                Units::Metric => format!("{value:.1}m"),
+                Units::Imperial => format!("{value:.1}ft"),
+            }
+        };
+
+        write!(
+            f,
+            "GPX Statistics:\n\
+             - Tracks: {}\n\
+             - Waypoints: {}\n\
+             - Segments: {}\n\
+             - Points: {}\n\
+             - Distance: {}",
+            stats.total_tracks,
+            stats.total_waypoints,
+            stats.total_segments,
+            stats.total_points,
+            stats.distance().format(units)
+        )?;
+        if let Some(duration) = stats.duration_formatted() {
+            write!(f, "\n- Duration: {duration}")?;
+        }
+        if let Some(speed) = stats.average_speed() {
+            write!(f, "\n- Average speed: {}", speed.format(units))?;
+        }
+        if let Some((min, max)) = stats.elevation_range {
+            write!(
+                f,
+                "\n- Elevation range: {} - {}",
+                elevation(min),
+                elevation(max)
+            )?;
+        }
+        if let Some(gain) = stats.elevation_gain {
+            write!(f, "\n- Elevation gain: {}", elevation(gain))?;
+        }
+        if let Some(loss) = stats.elevation_loss {
+            write!(f, "\n- Elevation loss: {}", elevation(loss))?;
+        }
+        Ok(())
+    }
+}
+
+impl GpxStatistics {
+    /// Distancia total
+    pub fn distance(&self) -> Distance {
+        Distance::from_km(self.total_distance_km)
+    }
+
+    /// Velocidad media, si hay distancia y duración
+    pub fn average_speed(&self) -> Option<Speed> {
+        self.average_speed_kmh.map(Speed::from_kmh)
+    }
+
+    /// Summary of the statistics in the given unit system
+    ///
+    /// With [`Units::Metric`] the text is the same as [`GpxStatistics::summary`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, Units};
+    ///
+    /// let gpx = Gpx::try_from_str(
+    ///     r#"<gpx><trk><trkseg>
+    ///         <trkpt lat="40.0" lon="-3.0"><ele>600</ele></trkpt>
+    ///         <trkpt lat="40.0145" lon="-3.0"><ele>630</ele></trkpt>
+    ///     </trkseg></trk></gpx>"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// let summary = gpx.statistics().display(Units::Imperial).to_string();
+    /// assert!(summary.contains("- Distance: 1.00 mi"));
+    /// assert!(summary.contains("- Elevation gain: 98.4ft"));
+    /// ```
+    pub fn display(&self, units: Units) -> StatisticsDisplay<'_> {
+        StatisticsDisplay { stats: self, units }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::parser::Gpx;
+
+    #[test]
+    fn test_metric_display_matches_summary() {
+        let gpx = Gpx::try_from_str(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="40.0" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time></trkpt>
+                <trkpt lat="40.1" lon="-3.0"><ele>550</ele><time>2024-07-01T09:00:00Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let stats = gpx.statistics();
+        assert_eq!(stats.display(Units::Metric).to_string(), stats.summary());
+
+        let imperial = stats.display(Units::Imperial).to_string();
+        assert!(imperial.contains("- Distance: 6.91 mi\n"));
+        assert!(imperial.contains("- Average speed: 6.91 mph\n"));
+        assert!(imperial.contains("- Elevation range: 1804.5ft - 1968.5ft\n"));
+        assert!(imperial.ends_with("- Elevation loss: 164.0ft"));
+    }
+
+    #[test]
+    fn test_unit_round_trips() {
+        let distance = Distance::from_miles(26.2);
+        assert!((distance.to_miles() - 26.2).abs() < 1e-12);
+        assert_eq!(distance.format(Units::Imperial), "26.20 mi");
+        assert_eq!(
+            Elevation::from_feet(1000.0).format(Units::Metric),
+            "304.8 m"
+        );
+        assert!((Speed::from_mph(10.0).kmh() - 16.093_44).abs() < 1e-9);
+        assert_eq!(Units::Imperial.split_unit(), SplitUnit::Mile);
+    }
+}
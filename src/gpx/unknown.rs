@@ -0,0 +1,682 @@
+use crate::gpx::{
+    error::GpxError,
+    parser::Gpx,
+    point::Point,
+    user_data::{self, UserData},
+};
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+use std::fmt::Write;
+
+/// Elemento que marca dónde va el XML conservado; se sustituye tras serializar.
+/// Los atributos `#[serde(rename = "gpx_extractor_raw")]` repiten el nombre.
+pub(crate) const RAW_MARKER: &str = "gpx_extractor_raw";
+
+/// XML found in a parsed document that this crate does not model
+///
+/// Parsing keeps every unknown child element (such as `<magvar>`, `<rte>` or a
+/// vendor extension) verbatim in the `unknown` field of the element that
+/// contained it, and the unknown attributes of `<gpx>` (namespace declarations,
+/// `xsi:schemaLocation`) in [`Gpx::unknown`]. Writing puts them back where the
+/// GPX schema expects them (a `<magvar>` right after `<time>`, an `<rte>` before
+/// the tracks), so `parse → to_xml` neither loses data nor breaks validation.
+/// Elements the schema does not list stay after the sibling that preceded them
+/// when written. Comments and processing instructions are not kept.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::Gpx;
+///
+/// let gpx = Gpx::try_from_str(r#"<gpx><trk><trkseg>
+///     <trkpt lat="40.0" lon="-3.0"><ele>600</ele><magvar>1.2</magvar></trkpt>
+/// </trkseg></trk></gpx>"#).unwrap();
+///
+/// let point = &gpx.tracks[0].segments[0].points[0];
+/// assert_eq!(point.unknown.elements().collect::<Vec<_>>(), vec!["<magvar>1.2</magvar>"]);
+/// assert!(gpx.to_xml().contains("<ele>600</ele><magvar>1.2</magvar></trkpt>"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnknownXml(Option<Box<Captured>>);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Captured {
+    // Cada elemento va con el hijo conocido que lo contenía (`None` si es hijo directo)
+    elements: Vec<(Option<&'static str>, String)>,
+    attributes: Vec<(String, String)>,
+}
+
+impl UnknownXml {
+    /// Verifica si no se ha conservado nada
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Unknown child elements as raw XML, in document order
+    pub fn elements(&self) -> impl Iterator<Item = &str> {
+        self.0
+            .iter()
+            .flat_map(|captured| &captured.elements)
+            .map(|(_, raw)| raw.as_str())
+    }
+
+    /// Unknown attributes as `(qualified name, unescaped value)` pairs
+    pub fn attributes(&self) -> &[(String, String)] {
+        self.0.as_ref().map_or(&[], |captured| &captured.attributes)
+    }
+
+    /// Descarta todo lo conservado, para que no se vuelva a escribir
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+
+    fn captured(&mut self) -> &mut Captured {
+        self.0.get_or_insert_with(Box::default)
+    }
+
+    pub(crate) fn push_element(&mut self, within: Option<&'static str>, raw: &str) {
+        self.captured().elements.push((within, raw.to_string()));
+    }
+
+    fn push_attribute(&mut self, name: String, value: String) {
+        self.captured().attributes.push((name, value));
+    }
+
+    /// Elementos que estaban dentro del hijo conocido `within` (o directamente, con `None`)
+    pub(crate) fn within(&self, within: Option<&'static str>) -> Within<'_> {
+        Within {
+            unknown: self,
+            within,
+        }
+    }
+}
+
+/// Elementos conservados de un único nivel, serializados como marcadores
+pub(crate) struct Within<'a> {
+    unknown: &'a UnknownXml,
+    within: Option<&'static str>,
+}
+
+impl Within<'_> {
+    fn raw(&self) -> impl Iterator<Item = &str> {
+        self.unknown
+            .0
+            .iter()
+            .flat_map(|captured| &captured.elements)
+            .filter(|(within, _)| *within == self.within)
+            .map(|(_, raw)| raw.as_str())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.raw().next().is_none()
+    }
+}
+
+impl Serialize for Within<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for raw in self.raw() {
+            seq.serialize_element(raw)?;
+        }
+        seq.end()
+    }
+}
+
+impl Serialize for UnknownXml {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.within(None).serialize(serializer)
+    }
+}
+
+/// Orden de los hijos de cada elemento GPX según el esquema (1.1, con los de 1.0 intercalados)
+fn schema_children(parent: &[u8]) -> Option<&'static [&'static str]> {
+    const POINT: &[&str] = &[
+        "ele",
+        "time",
+        "course",
+        "speed",
+        "magvar",
+        "geoidheight",
+        "name",
+        "cmt",
+        "desc",
+        "src",
+        "link",
+        "url",
+        "urlname",
+        "sym",
+        "type",
+        "fix",
+        "sat",
+        "hdop",
+        "vdop",
+        "pdop",
+        "ageofdgpsdata",
+        "dgpsid",
+        "extensions",
+    ];
+    Some(match parent {
+        b"gpx" => &[
+            "name",
+            "desc",
+            "author",
+            "email",
+            "url",
+            "urlname",
+            "time",
+            "keywords",
+            "bounds",
+            "metadata",
+            "wpt",
+            "rte",
+            "trk",
+            "extensions",
+        ],
+        b"metadata" => &[
+            "name",
+            "desc",
+            "author",
+            "copyright",
+            "link",
+            "time",
+            "keywords",
+            "bounds",
+            "extensions",
+        ],
+        b"wpt" | b"trkpt" | b"rtept" => POINT,
+        b"trk" => &[
+            "name",
+            "cmt",
+            "desc",
+            "src",
+            "link",
+            "url",
+            "urlname",
+            "number",
+            "type",
+            "extensions",
+            "trkseg",
+        ],
+        b"rte" => &[
+            "name",
+            "cmt",
+            "desc",
+            "src",
+            "link",
+            "url",
+            "urlname",
+            "number",
+            "type",
+            "extensions",
+            "rtept",
+        ],
+        b"trkseg" => &["trkpt", "extensions"],
+        _ => return None,
+    })
+}
+
+/// Elemento abierto durante [`schema_order`], con sus hijos ya reordenados
+struct Frame {
+    name: Vec<u8>,
+    open: String,
+    /// Posición en el esquema y texto de cada hijo, con el espacio que lo precede
+    children: Vec<(usize, String)>,
+    /// Texto (espacios, comentarios) leído desde el último hijo
+    pending: String,
+}
+
+impl Frame {
+    /// Añade un hijo; los que el esquema no conoce van tras el anterior
+    fn push(&mut self, name: &[u8], raw: &str) {
+        let previous = self.children.last().map_or(0, |&(rank, _)| rank);
+        let rank = schema_children(&self.name)
+            .and_then(|order| order.iter().position(|known| known.as_bytes() == name))
+            .unwrap_or(previous);
+        let text = std::mem::take(&mut self.pending) + raw;
+        self.children.push((rank, text));
+    }
+
+    fn close(mut self, end: &str) -> String {
+        // Orden estable: los hijos del mismo tipo no cambian de orden entre sí
+        self.children.sort_by_key(|&(rank, _)| rank);
+        let mut xml = self.open;
+        for (_, child) in self.children {
+            xml.push_str(&child);
+        }
+        xml.push_str(&self.pending);
+        xml.push_str(end);
+        xml
+    }
+}
+
+/// Reordena los hijos de los elementos GPX como pide el esquema, sin tocar nada más
+fn schema_order(xml: String) -> String {
+    let mut reader = Reader::from_str(&xml);
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut ordered = String::with_capacity(xml.len());
+    loop {
+        let before = reader.buffer_position();
+        let event = match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(event) => event,
+            // Nunca pasa con lo que escribe el propio crate; mejor sin reordenar
+            Err(_) => return xml,
+        };
+        let raw = &xml[before..reader.buffer_position()];
+        match event {
+            Event::Start(start) => stack.push(Frame {
+                name: start.name().as_ref().to_vec(),
+                open: raw.to_string(),
+                children: Vec::new(),
+                pending: String::new(),
+            }),
+            Event::End(_) => {
+                let Some(frame) = stack.pop() else {
+                    return xml;
+                };
+                let name = frame.name.clone();
+                let element = frame.close(raw);
+                match stack.last_mut() {
+                    Some(parent) => parent.push(&name, &element),
+                    None => ordered.push_str(&element),
+                }
+            }
+            Event::Empty(start) => match stack.last_mut() {
+                Some(parent) => parent.push(start.name().as_ref(), raw),
+                None => ordered.push_str(raw),
+            },
+            _ => match stack.last_mut() {
+                Some(parent) => parent.pending.push_str(raw),
+                None => ordered.push_str(raw),
+            },
+        }
+    }
+    if !stack.is_empty() {
+        return xml;
+    }
+    ordered
+}
+
+/// Sustituye cada marcador por el XML original que lleva escapado dentro y
+/// coloca lo sustituido donde lo pide el esquema
+pub(crate) fn expand_markers(xml: String) -> String {
+    let open = format!("<{RAW_MARKER}>");
+    let close = format!("</{RAW_MARKER}>");
+    if !xml.contains(&open) {
+        return xml;
+    }
+    let mut expanded = String::with_capacity(xml.len());
+    let mut rest = xml.as_str();
+    while let Some(start) = rest.find(&open) {
+        expanded.push_str(&rest[..start]);
+        let content = &rest[start + open.len()..];
+        let end = content.find(&close).unwrap_or(content.len());
+        match unescape(&content[..end]) {
+            Ok(raw) => expanded.push_str(&raw),
+            Err(_) => expanded.push_str(&content[..end]),
+        }
+        rest = content.get(end + close.len()..).unwrap_or_default();
+    }
+    expanded.push_str(rest);
+    schema_order(expanded)
+}
+
+/// Añade atributos al final de la etiqueta de apertura de `<gpx>` ya escrita,
+/// salvo los que ya tiene (`xmlns` de las opciones, o uno repetido)
+pub(crate) fn insert_root_attributes(xml: &mut String, attributes: &[(String, String)]) {
+    let Some(start) = xml.find("<gpx") else {
+        return;
+    };
+    let mut end = xml[start..].find('>').map_or(xml.len(), |end| start + end);
+    if xml[..end].ends_with('/') {
+        // <gpx .../> sin contenido
+        end -= 1;
+    }
+    let mut missing = String::new();
+    for (name, value) in attributes {
+        let attribute = format!(" {name}=");
+        if !xml[start..end].contains(&attribute) && !missing.contains(&attribute) {
+            let _ = write!(missing, "{attribute}\"{}\"", escape(value.as_str()));
+        }
+    }
+    xml.insert_str(end, &missing);
+}
+
+/// Elemento del documento modelado en el que se está leyendo
+#[derive(Clone, Copy)]
+enum Scope {
+    Root,
+    Metadata,
+    Track(usize),
+    TrackExtensions(usize),
+    /// `gpx_style:line` o `gpxx:TrackExtension` dentro de las extensiones del track
+    TrackExtension(usize, &'static str),
+    Segment(usize, usize),
+    Point(usize, usize, usize),
+    PointExtensions(usize, usize, usize),
+    TrackPointExtension(usize, usize, usize),
+    Waypoint(usize),
+}
+
+/// Qué hacer con un hijo según el elemento que lo contiene
+enum Child {
+    /// Elemento modelado con hijos que también hay que revisar
+    Enter(Scope),
+    /// Elemento modelado por completo
+    Known,
+    /// Elemento que hay que conservar tal cual
+    Unknown,
+}
+
+/// Posición del elemento actual entre sus hermanos
+#[derive(Default)]
+struct Counters {
+    tracks: usize,
+    waypoints: usize,
+    segments: usize,
+    points: usize,
+}
+
+fn classify(scope: Scope, name: &[u8], counters: &mut Counters) -> Child {
+    use Child::{Enter, Known, Unknown};
+    match (scope, name) {
+        (Scope::Root, b"metadata") => Enter(Scope::Metadata),
+        (Scope::Root, b"trk") => {
+            counters.tracks += 1;
+            counters.segments = 0;
+            Enter(Scope::Track(counters.tracks - 1))
+        }
+        (Scope::Root, b"wpt") => {
+            counters.waypoints += 1;
+            Enter(Scope::Waypoint(counters.waypoints - 1))
+        }
+        (
+            Scope::Root,
+            b"name" | b"desc" | b"author" | b"email" | b"url" | b"urlname" | b"time" | b"keywords"
+            | b"bounds",
+        )
+        | (
+            Scope::Metadata,
+            b"name" | b"desc" | b"author" | b"copyright" | b"link" | b"time" | b"keywords"
+            | b"bounds",
+        )
+        | (Scope::Track(_), b"name" | b"cmt" | b"desc" | b"src" | b"link" | b"type")
+        | (Scope::TrackExtension(_, "line"), b"color")
+        | (Scope::TrackExtension(_, "TrackExtension"), b"DisplayColor")
+        | (
+            Scope::Point(..),
+            b"ele" | b"time" | b"fix" | b"sat" | b"hdop" | b"vdop" | b"pdop" | b"ageofdgpsdata"
+            | b"dgpsid",
+        )
+        | (Scope::PointExtensions(..), b"power")
+        | (Scope::TrackPointExtension(..), b"hr" | b"cad")
+        | (
+            Scope::Waypoint(_),
+            b"name" | b"cmt" | b"desc" | b"src" | b"link" | b"ele" | b"time" | b"sym" | b"type"
+            | b"fix" | b"sat" | b"hdop" | b"vdop" | b"pdop" | b"ageofdgpsdata" | b"dgpsid",
+        ) => Known,
+        (Scope::Track(t), b"extensions") => Enter(Scope::TrackExtensions(t)),
+        (Scope::TrackExtensions(t), b"line") => Enter(Scope::TrackExtension(t, "line")),
+        (Scope::TrackExtensions(t), b"TrackExtension") => {
+            Enter(Scope::TrackExtension(t, "TrackExtension"))
+        }
+        (Scope::Track(t), b"trkseg") => {
+            counters.segments += 1;
+            counters.points = 0;
+            Enter(Scope::Segment(t, counters.segments - 1))
+        }
+        (Scope::Segment(t, s), b"trkpt") => {
+            counters.points += 1;
+            Enter(Scope::Point(t, s, counters.points - 1))
+        }
+        (Scope::Point(t, s, p), b"extensions") => Enter(Scope::PointExtensions(t, s, p)),
+        (Scope::PointExtensions(t, s, p), b"TrackPointExtension") => {
+            Enter(Scope::TrackPointExtension(t, s, p))
+        }
+        _ => Unknown,
+    }
+}
+
+/// Dónde se guarda un elemento desconocido encontrado en `scope`
+fn target(gpx: &mut Gpx, scope: Scope) -> Option<(&mut UnknownXml, Option<&'static str>)> {
+    fn point(gpx: &mut Gpx, t: usize, s: usize, p: usize) -> Option<&mut Point> {
+        gpx.tracks
+            .get_mut(t)?
+            .segments
+            .get_mut(s)?
+            .points
+            .get_mut(p)
+    }
+    Some(match scope {
+        Scope::Root => (&mut gpx.unknown, None),
+        Scope::Metadata => (&mut gpx.metadata.as_mut()?.unknown, None),
+        Scope::Track(t) => (&mut gpx.tracks.get_mut(t)?.unknown, None),
+        Scope::TrackExtensions(t) => (&mut gpx.tracks.get_mut(t)?.extensions.unknown, None),
+        Scope::TrackExtension(t, within) => {
+            (&mut gpx.tracks.get_mut(t)?.extensions.unknown, Some(within))
+        }
+        Scope::Segment(t, s) => (
+            &mut gpx.tracks.get_mut(t)?.segments.get_mut(s)?.unknown,
+            None,
+        ),
+        Scope::Point(t, s, p) => (&mut point(gpx, t, s, p)?.unknown, None),
+        Scope::PointExtensions(t, s, p) => (&mut point(gpx, t, s, p)?.extensions.unknown, None),
+        Scope::TrackPointExtension(t, s, p) => (
+            &mut point(gpx, t, s, p)?.extensions.unknown,
+            Some("TrackPointExtension"),
+        ),
+        Scope::Waypoint(w) => (&mut gpx.waypoints.get_mut(w)?.unknown, None),
+    })
+}
+
+/// Anotaciones del elemento al que pertenece un `UserData` encontrado en `scope`
+fn user_data_target(gpx: &mut Gpx, scope: Scope) -> Option<&mut UserData> {
+    match scope {
+        Scope::TrackExtensions(t) => Some(&mut gpx.tracks.get_mut(t)?.user_data),
+        Scope::PointExtensions(t, s, p) => Some(
+            &mut gpx
+                .tracks
+                .get_mut(t)?
+                .segments
+                .get_mut(s)?
+                .points
+                .get_mut(p)?
+                .user_data,
+        ),
+        _ => None,
+    }
+}
+
+/// Atributos de `<gpx>` distintos de `version` y `creator`; `xmlns` se guarda
+/// también porque [`Gpx`] no lo modela
+fn capture_root_attributes(gpx: &mut Gpx, start: &BytesStart) -> Result<(), GpxError> {
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let name = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        if !matches!(name.as_str(), "version" | "creator") {
+            let value = attribute.unescape_value()?.into_owned();
+            gpx.unknown.push_attribute(name, value);
+        }
+    }
+    Ok(())
+}
+
+impl Gpx {
+    /// Segunda lectura del texto ya parseado que guarda en cada elemento del
+    /// modelo lo que serde ha ignorado
+    pub(crate) fn capture_unknown(&mut self, xml: &str) -> Result<(), GpxError> {
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<Scope> = Vec::new();
+        let mut counters = Counters::default();
+        loop {
+            let position = reader.buffer_position();
+            let (start, empty) = match reader.read_event()? {
+                Event::Start(start) => (start, false),
+                Event::Empty(start) => (start, true),
+                Event::End(_) => {
+                    stack.pop();
+                    continue;
+                }
+                Event::Eof => return Ok(()),
+                _ => continue,
+            };
+
+            let Some(&scope) = stack.last() else {
+                // El elemento raíz
+                capture_root_attributes(self, &start)?;
+                if !empty {
+                    stack.push(Scope::Root);
+                }
+                continue;
+            };
+            match classify(scope, start.local_name().as_ref(), &mut counters) {
+                Child::Enter(child) if !empty => stack.push(child),
+                Child::Enter(_) => {}
+                Child::Known => {
+                    if !empty {
+                        reader.read_to_end(start.name())?;
+                    }
+                }
+                Child::Unknown => {
+                    if !empty {
+                        reader.read_to_end(start.name())?;
+                    }
+                    let raw = xml[position..reader.buffer_position()].trim();
+                    let name = start.local_name();
+                    if name.as_ref() == b"UserData" {
+                        if let Some(user_data) = user_data_target(self, scope) {
+                            *user_data = UserData::from_xml(raw);
+                            continue;
+                        }
+                    }
+                    // Los waypoints no modelan <extensions>: se guarda entero sin el UserData
+                    if let (Scope::Waypoint(w), b"extensions") = (scope, name.as_ref()) {
+                        let (found, rest) = user_data::split_user_data(raw);
+                        if let Some(waypoint) = self.waypoints.get_mut(w) {
+                            waypoint.user_data = found;
+                            if let Some(rest) = rest {
+                                waypoint.unknown.push_element(None, &rest);
+                            }
+                        }
+                        continue;
+                    }
+                    if let Some((unknown, within)) = target(self, scope) {
+                        unknown.push_element(within, raw);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GARMIN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="Garmin" xmlns="http://www.topografix.com/GPX/1/1" xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v1" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.topografix.com/GPX/1/1 http://www.topografix.com/GPX/1/1/gpx.xsd">
+  <metadata><name>Ride</name><extensions><app>x</app></extensions></metadata>
+  <trk>
+    <name>Ride</name>
+    <cmt>Morning</cmt>
+    <number>3</number>
+    <trkseg>
+      <trkpt lat="40.0" lon="-3.0">
+        <ele>600</ele>
+        <geoidheight>52</geoidheight>
+        <extensions>
+          <gpxtpx:TrackPointExtension><gpxtpx:atemp>21.0</gpxtpx:atemp><gpxtpx:hr>140</gpxtpx:hr></gpxtpx:TrackPointExtension>
+          <vendor:grade xmlns:vendor="urn:vendor">4.5</vendor:grade>
+        </extensions>
+      </trkpt>
+      <trkpt lat="40.001" lon="-3.0"/>
+    </trkseg>
+  </trk>
+  <wpt lat="40.0" lon="-3.0"><name>Start</name><desc>Car park &amp; café</desc><geoidheight>49</geoidheight></wpt>
+  <rte><name>Plan</name><rtept lat="40.0" lon="-3.0"/></rte>
+</gpx>"#;
+
+    #[test]
+    fn test_unknown_xml_is_captured_where_it_was() {
+        let gpx = Gpx::try_from_str(GARMIN).unwrap();
+        let elements =
+            |unknown: &UnknownXml| unknown.elements().map(str::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            elements(&gpx.unknown),
+            vec![r#"<rte><name>Plan</name><rtept lat="40.0" lon="-3.0"/></rte>"#]
+        );
+        assert_eq!(gpx.unknown.attributes().len(), 4);
+        assert_eq!(gpx.unknown.attributes()[2].0, "xmlns:xsi");
+        assert_eq!(
+            elements(&gpx.metadata.as_ref().unwrap().unknown),
+            vec!["<extensions><app>x</app></extensions>"]
+        );
+        assert_eq!(elements(&gpx.tracks[0].unknown), vec!["<number>3</number>"]);
+
+        let point = &gpx.tracks[0].segments[0].points[0];
+        assert_eq!(
+            elements(&point.unknown),
+            vec!["<geoidheight>52</geoidheight>"]
+        );
+        assert_eq!(point.extensions.heart_rate, Some(140));
+        assert_eq!(
+            elements(&point.extensions.unknown),
+            vec![
+                "<gpxtpx:atemp>21.0</gpxtpx:atemp>",
+                r#"<vendor:grade xmlns:vendor="urn:vendor">4.5</vendor:grade>"#
+            ]
+        );
+        assert!(gpx.tracks[0].segments[0].points[1].unknown.is_empty());
+        assert_eq!(
+            elements(&gpx.waypoints[0].unknown),
+            vec!["<geoidheight>49</geoidheight>"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_xml_survives_a_roundtrip() {
+        let gpx = Gpx::try_from_str(GARMIN).unwrap();
+        let xml = gpx.to_xml();
+        assert!(xml.contains(r#"xsi:schemaLocation="http://www.topografix.com/GPX/1/1 http://www.topografix.com/GPX/1/1/gpx.xsd""#));
+        assert!(xml.contains("<name>Ride</name><cmt>Morning</cmt><number>3</number><trkseg>"));
+        assert!(xml.contains("<ele>600</ele><geoidheight>52</geoidheight><extensions"));
+        assert!(xml.contains(
+            "<gpxtpx:TrackPointExtension><gpxtpx:hr>140</gpxtpx:hr><gpxtpx:atemp>21.0</gpxtpx:atemp></gpxtpx:TrackPointExtension>"
+        ));
+        assert!(xml.contains("<desc>Car park &amp; café</desc>"));
+        assert!(!xml.contains(RAW_MARKER));
+
+        // Lo conservado se vuelve a leer igual, y la salida indentada también lo lleva
+        let reparsed = Gpx::try_from_str(&xml).unwrap();
+        assert_eq!(reparsed.to_xml(), xml);
+        assert!(gpx.to_xml_pretty().contains("\n  <rte><name>Plan</name>"));
+
+        let mut cleared = gpx.clone();
+        cleared.unknown.clear();
+        assert!(!cleared.to_xml().contains("<rte>"));
+    }
+    #[test]
+    fn test_unknown_xml_is_written_in_schema_order() {
+        let gpx = Gpx::try_from_str(
+            r#"<gpx version="1.1" creator="x"><trk><trkseg>
+<trkpt lat="40.0" lon="-3.0"><ele>600</ele><time>2024-07-01T08:00:00Z</time><magvar>1.5</magvar><sat>7</sat><vendor:x xmlns:vendor="urn:v">1</vendor:x></trkpt>
+</trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        let xml = gpx.to_xml();
+        // <magvar> vuelve tras <time>; lo que no está en el esquema sigue a lo que tenía delante
+        assert!(xml.contains(
+            r#"<ele>600</ele><time>2024-07-01T08:00:00Z</time><magvar>1.5</magvar><vendor:x xmlns:vendor="urn:v">1</vendor:x><sat>7</sat></trkpt>"#
+        ));
+
+        // Con sangría cada hijo se lleva su salto de línea
+        let pretty = gpx.to_xml_pretty();
+        assert!(pretty.contains(
+            "<time>2024-07-01T08:00:00Z</time>\n        <magvar>1.5</magvar>\n        <vendor:x"
+        ));
+        assert_eq!(Gpx::try_from_str(&pretty).unwrap().to_xml(), xml);
+    }
+}
@@ -0,0 +1,273 @@
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Namespace of the `gpxud:UserData` extension that stores [`UserData`] in GPX files
+pub const USER_DATA_NAMESPACE: &str = "https://github.com/Juanjofp/gpx-extractor/UserData/v1";
+
+/// Elemento que marca dónde va el `UserData` al serializar; se sustituye después.
+/// Los atributos `#[serde(rename = "gpx_extractor_user_data")]` repiten el nombre.
+pub(crate) const USER_DATA_MARKER: &str = "gpx_extractor_user_data";
+
+/// Application annotations attached to a point, track or waypoint
+///
+/// A sorted map of string keys to string values, such as a matched road id,
+/// a computed grade or a sensor sample index, so applications do not need
+/// arrays parallel to the points. It travels with the element through clones
+/// and transforms that keep the element (filtering, merging, splitting), and is
+/// written into a `gpxud:UserData` extension (see [`USER_DATA_NAMESPACE`]) that
+/// parsing reads back. Set [`WriteOptions::user_data`](crate::WriteOptions::user_data)
+/// to `false` to keep the annotations in memory only.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Gpx, Track, TrackSegment};
+///
+/// let mut segment = TrackSegment::from_latlon_pairs(&[(40.0, -3.0), (40.1, -3.0)]);
+/// segment.points[0].user_data.insert("road_id", "N-VI");
+/// let mut track = Track::new();
+/// track.add_segment(segment);
+/// let mut gpx = Gpx::new();
+/// gpx.add_track(track);
+///
+/// let reparsed = Gpx::try_from_str(&gpx.to_xml()).unwrap();
+/// let point = &reparsed.tracks[0].segments[0].points[0];
+/// assert_eq!(point.user_data.get("road_id"), Some("N-VI"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserData(BTreeMap<String, String>);
+
+impl UserData {
+    /// Verifica si no hay ninguna anotación
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Número de anotaciones
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Value stored under `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Stores `value` under `key`, returning the previous value
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Removes `key`, returning its value
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    /// Annotations as `(key, value)` pairs, sorted by key
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Descarta todas las anotaciones
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Elemento `gpxud:UserData` con una entrada por anotación
+    fn to_xml(&self) -> String {
+        let mut xml = format!("<gpxud:UserData xmlns:gpxud=\"{USER_DATA_NAMESPACE}\">");
+        for (key, value) in self.iter() {
+            let _ = write!(
+                xml,
+                "<gpxud:entry key=\"{}\">{}</gpxud:entry>",
+                escape(key),
+                escape(value)
+            );
+        }
+        xml.push_str("</gpxud:UserData>");
+        xml
+    }
+
+    /// Lee las entradas de un elemento `UserData` crudo; ignora lo que no entiende
+    pub(crate) fn from_xml(raw: &str) -> Self {
+        let mut user_data = Self::default();
+        let mut reader = Reader::from_str(raw);
+        loop {
+            let start = match reader.read_event() {
+                Ok(Event::Start(start)) if start.local_name().as_ref() == b"entry" => start,
+                Ok(Event::Eof) | Err(_) => return user_data,
+                Ok(_) => continue,
+            };
+            let key = start
+                .try_get_attribute("key")
+                .ok()
+                .flatten()
+                .and_then(|key| key.unescape_value().ok().map(|key| key.into_owned()));
+            let Ok(text) = reader.read_text(start.name()) else {
+                return user_data;
+            };
+            if let (Some(key), Ok(value)) = (key, unescape(&text)) {
+                user_data.insert(key, value.into_owned());
+            }
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for UserData {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut user_data = Self::default();
+        for (key, value) in iter {
+            user_data.insert(key, value);
+        }
+        user_data
+    }
+}
+
+// Se serializa como texto dentro de un marcador; `place_user_data` lo mueve
+// a `<extensions>` o lo quita según las opciones de escritura
+impl Serialize for UserData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_xml())
+    }
+}
+
+/// Sustituye cada marcador de `UserData` por la extensión, dentro del
+/// `<extensions>` que lo precede o en uno nuevo; con `write` a `false` solo los quita
+pub(crate) fn place_user_data(xml: String, write: bool) -> String {
+    let open = format!("<{USER_DATA_MARKER}>");
+    let close = format!("</{USER_DATA_MARKER}>");
+    if !xml.contains(&open) {
+        return xml;
+    }
+    let mut placed = String::with_capacity(xml.len());
+    let mut rest = xml.as_str();
+    while let Some(start) = rest.find(&open) {
+        // El espacio de indentación del marcador también sobra
+        placed.push_str(rest[..start].trim_end());
+        let content = &rest[start + open.len()..];
+        let end = content.find(&close).unwrap_or(content.len());
+        if write {
+            let extension = unescape(&content[..end])
+                .map_or_else(|_| content[..end].to_string(), std::borrow::Cow::into_owned);
+            if let Some(before_close) = placed.strip_suffix("</extensions>") {
+                // Tras el último hijo, en la misma línea si la salida va indentada
+                placed.insert_str(before_close.trim_end().len(), &extension);
+            } else {
+                placed.push_str(&rest[..start][rest[..start].trim_end().len()..]);
+                let _ = write!(placed, "<extensions>{extension}</extensions>");
+            }
+        }
+        rest = content.get(end + close.len()..).unwrap_or_default();
+    }
+    placed.push_str(rest);
+    placed
+}
+
+/// Separa el `UserData` de un `<extensions>` crudo de waypoint; devuelve las
+/// anotaciones y el resto del elemento, o `None` si ya no le queda ningún hijo
+pub(crate) fn split_user_data(raw: &str) -> (UserData, Option<String>) {
+    let mut reader = Reader::from_str(raw);
+    let mut depth = 0;
+    let mut children = 0;
+    let mut found: Option<Range<usize>> = None;
+    loop {
+        let position = reader.buffer_position();
+        match reader.read_event() {
+            Ok(Event::Start(start)) => {
+                depth += 1;
+                if depth == 2 && start.local_name().as_ref() == b"UserData" {
+                    if reader.read_to_end(start.name()).is_err() {
+                        break;
+                    }
+                    found = Some(position..reader.buffer_position());
+                    depth -= 1;
+                } else if depth == 2 {
+                    children += 1;
+                }
+            }
+            Ok(Event::Empty(_)) if depth == 1 => children += 1,
+            Ok(Event::End(_)) => depth -= 1,
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+
+    let Some(range) = found else {
+        return (UserData::default(), Some(raw.to_string()));
+    };
+    let user_data = UserData::from_xml(&raw[range.clone()]);
+    let rest = (children > 0).then(|| format!("{}{}", &raw[..range.start], &raw[range.end..]));
+    (user_data, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gpx, WriteOptions};
+
+    #[test]
+    fn test_user_data_roundtrip_on_every_element() {
+        let mut gpx = Gpx::try_from_str(
+            r#"<gpx><trk><name>Ride</name><trkseg>
+                <trkpt lat="40.0" lon="-3.0"><extensions><power>200</power></extensions></trkpt>
+                <trkpt lat="40.1" lon="-3.0"/>
+            </trkseg></trk>
+            <wpt lat="40.0" lon="-3.0"><extensions><vendor:x xmlns:vendor="urn:v">1</vendor:x></extensions></wpt>
+            </gpx>"#,
+        )
+        .unwrap();
+        gpx.tracks[0].user_data.insert("source", "strava");
+        let points = &mut gpx.tracks[0].segments[0].points;
+        points[0].user_data.insert("grade", "4.5");
+        points[1].user_data.insert("note", "a < b & \"c\"");
+        gpx.waypoints[0].user_data.insert("id", "7");
+
+        let xml = gpx.to_xml_pretty();
+        assert!(!xml.contains(USER_DATA_MARKER));
+        assert!(xml.contains(
+            "<power>200</power><gpxud:UserData xmlns:gpxud=\"https://github.com/Juanjofp/gpx-extractor/UserData/v1\"><gpxud:entry key=\"grade\">4.5</gpxud:entry></gpxud:UserData>\n        </extensions>"
+        ));
+
+        let reparsed = Gpx::try_from_str(&xml).unwrap();
+        let points = &reparsed.tracks[0].segments[0].points;
+        assert_eq!(reparsed.tracks[0].user_data.get("source"), Some("strava"));
+        assert_eq!(points[0].user_data.get("grade"), Some("4.5"));
+        assert_eq!(points[0].extensions.power, Some(200));
+        assert!(points[0].extensions.unknown.is_empty());
+        assert_eq!(points[1].user_data.get("note"), Some("a < b & \"c\""));
+        assert_eq!(reparsed.waypoints[0].user_data.get("id"), Some("7"));
+        assert_eq!(reparsed.waypoints[0].unknown.elements().count(), 1);
+        assert_eq!(reparsed.to_xml_pretty(), xml);
+    }
+
+    #[test]
+    fn test_user_data_can_stay_in_memory() {
+        let mut gpx =
+            Gpx::try_from_str(r#"<gpx><wpt lat="40.0" lon="-3.0"><name>A</name></wpt></gpx>"#)
+                .unwrap();
+        gpx.waypoints[0].user_data = [("id", "7")].into_iter().collect();
+
+        let options = WriteOptions {
+            user_data: false,
+            ..WriteOptions::default()
+        };
+        let xml = gpx.to_xml_with(&options).unwrap();
+        assert!(xml.contains("<wpt lat=\"40\" lon=\"-3\"><name>A</name></wpt>"));
+        assert!(gpx
+            .to_xml()
+            .contains("<name>A</name><extensions><gpxud:UserData"));
+
+        let (user_data, rest) = split_user_data(
+            r#"<extensions><u:UserData><u:entry key="k">v</u:entry></u:UserData></extensions>"#,
+        );
+        assert_eq!(user_data.iter().collect::<Vec<_>>(), vec![("k", "v")]);
+        assert_eq!(rest, None);
+    }
+}
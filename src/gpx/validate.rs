@@ -0,0 +1,329 @@
+use crate::gpx::{error::GpxError, parser::Gpx, point::Point};
+use std::fmt;
+
+/// How serious a [`ValidationIssue`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Harmless, but usually a sign of a sloppy recorder
+    Info,
+    /// Valid GPX that most tools will mishandle or compute wrong statistics from
+    Warning,
+    /// Invalid GPX that other tools may reject
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// Problem found by [`Gpx::validate`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IssueKind {
+    /// The latitude lies outside `[-90, 90]` or is NaN
+    InvalidLatitude(f64),
+    /// The longitude lies outside `[-180, 180]` or is NaN
+    InvalidLongitude(f64),
+    /// The elevation is NaN or infinite
+    InvalidElevation,
+    /// The timestamp is earlier than the one of the previous point in the segment
+    TimeGoesBackwards,
+    /// The segment has no points
+    EmptySegment,
+    /// The point repeats the position and time of the previous one
+    DuplicatePoint,
+}
+
+impl IssueKind {
+    /// Gravedad que corresponde a cada tipo de problema
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::InvalidLatitude(_) | Self::InvalidLongitude(_) | Self::InvalidElevation => {
+                Severity::Error
+            }
+            Self::TimeGoesBackwards | Self::EmptySegment => Severity::Warning,
+            Self::DuplicatePoint => Severity::Info,
+        }
+    }
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLatitude(lat) => write!(f, "latitude {lat} outside [-90, 90]"),
+            Self::InvalidLongitude(lon) => write!(f, "longitude {lon} outside [-180, 180]"),
+            Self::InvalidElevation => f.write_str("elevation is not a finite number"),
+            Self::TimeGoesBackwards => f.write_str("timestamp earlier than the previous point"),
+            Self::EmptySegment => f.write_str("segment has no points"),
+            Self::DuplicatePoint => f.write_str("duplicate of the previous point"),
+        }
+    }
+}
+
+/// Where a [`ValidationIssue`] was found; all indices start at 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueLocation {
+    /// A track point
+    Point {
+        /// Index of the track
+        track: usize,
+        /// Index of the segment within the track
+        segment: usize,
+        /// Index of the point within the segment
+        point: usize,
+    },
+    /// A whole track segment
+    Segment {
+        /// Index of the track
+        track: usize,
+        /// Index of the segment within the track
+        segment: usize,
+    },
+    /// A waypoint
+    Waypoint(usize),
+}
+
+impl fmt::Display for IssueLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Point {
+                track,
+                segment,
+                point,
+            } => write!(f, "track {track}, segment {segment}, point {point}"),
+            Self::Segment { track, segment } => write!(f, "track {track}, segment {segment}"),
+            Self::Waypoint(index) => write!(f, "waypoint {index}"),
+        }
+    }
+}
+
+/// One problem found by [`Gpx::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// What is wrong
+    pub kind: IssueKind,
+    /// Where it is
+    pub location: IssueLocation,
+}
+
+impl ValidationIssue {
+    fn new(kind: IssueKind, location: IssueLocation) -> Self {
+        Self {
+            severity: kind.severity(),
+            kind,
+            location,
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.location, self.kind)
+    }
+}
+
+/// Problemas de coordenadas y elevación de una posición
+fn check_position(
+    lat: f64,
+    lon: f64,
+    elevation: Option<f64>,
+    location: IssueLocation,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if !(-90.0..=90.0).contains(&lat) {
+        issues.push(ValidationIssue::new(
+            IssueKind::InvalidLatitude(lat),
+            location,
+        ));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        issues.push(ValidationIssue::new(
+            IssueKind::InvalidLongitude(lon),
+            location,
+        ));
+    }
+    if elevation.is_some_and(|ele| !ele.is_finite()) {
+        issues.push(ValidationIssue::new(IssueKind::InvalidElevation, location));
+    }
+}
+
+/// Mismo lugar y mismo instante que el punto anterior
+fn is_duplicate(previous: &Point, point: &Point) -> bool {
+    previous.lat.to_bits() == point.lat.to_bits()
+        && previous.lon.to_bits() == point.lon.to_bits()
+        && previous.time == point.time
+}
+
+impl Gpx {
+    /// Checks coordinates, elevations, timestamps and segment structure
+    ///
+    /// Reports, in document order, coordinates outside the WGS84 range, NaN or
+    /// infinite elevations ([`Severity::Error`]), timestamps that go backwards
+    /// and empty segments ([`Severity::Warning`]), and consecutive points with
+    /// the same position and time ([`Severity::Info`]). An empty list means the
+    /// document is clean. To refuse writing documents with errors, set
+    /// [`WriteOptions::validate`](crate::WriteOptions::validate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gpx_extractor::{Gpx, IssueKind, Point, Severity, Track, TrackSegment};
+    ///
+    /// let mut segment = TrackSegment::new();
+    /// segment.add_point(Point::new(40.0, -3.0));
+    /// segment.add_point(Point::new(95.0, -3.0));
+    /// let mut track = Track::new();
+    /// track.add_segment(segment);
+    /// track.add_segment(TrackSegment::new());
+    /// let mut gpx = Gpx::new();
+    /// gpx.add_track(track);
+    ///
+    /// let issues = gpx.validate();
+    /// assert_eq!(issues.len(), 2);
+    /// assert_eq!(issues[0].kind, IssueKind::InvalidLatitude(95.0));
+    /// assert_eq!(issues[0].severity, Severity::Error);
+    /// assert_eq!(issues[1].to_string(), "warning: track 0, segment 1: segment has no points");
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (s, segment) in track.segments.iter().enumerate() {
+                if segment.points.is_empty() {
+                    issues.push(ValidationIssue::new(
+                        IssueKind::EmptySegment,
+                        IssueLocation::Segment {
+                            track: t,
+                            segment: s,
+                        },
+                    ));
+                }
+                let mut last_time = None;
+                for (p, point) in segment.points.iter().enumerate() {
+                    let location = IssueLocation::Point {
+                        track: t,
+                        segment: s,
+                        point: p,
+                    };
+                    check_position(point.lat, point.lon, point.elevation, location, &mut issues);
+                    if let Some(time) = point.time {
+                        if last_time.is_some_and(|last| time < last) {
+                            issues
+                                .push(ValidationIssue::new(IssueKind::TimeGoesBackwards, location));
+                        }
+                        last_time = Some(time);
+                    }
+                    if p > 0 && is_duplicate(&segment.points[p - 1], point) {
+                        issues.push(ValidationIssue::new(IssueKind::DuplicatePoint, location));
+                    }
+                }
+            }
+        }
+        for (w, waypoint) in self.waypoints.iter().enumerate() {
+            check_position(
+                waypoint.lat,
+                waypoint.lon,
+                waypoint.elevation,
+                IssueLocation::Waypoint(w),
+                &mut issues,
+            );
+        }
+        issues
+    }
+
+    /// Falla con [`GpxError::Validation`] si el documento tiene errores graves
+    pub(crate) fn check_valid(&self) -> Result<(), GpxError> {
+        let errors: Vec<ValidationIssue> = self
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(GpxError::Validation(errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpx::{track::Track, track::TrackSegment, waypoint::Waypoint};
+    use crate::WriteOptions;
+
+    #[test]
+    fn test_validate_reports_every_kind() {
+        let mut gpx = Gpx::try_from_str(
+            r#"<gpx><trk><trkseg>
+                <trkpt lat="40.000" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+                <trkpt lat="40.000" lon="-3.0"><time>2024-07-01T08:00:00Z</time></trkpt>
+                <trkpt lat="40.001" lon="-3.0"><time>2024-07-01T07:59:00Z</time></trkpt>
+                <trkpt lat="40.002" lon="-3.0"/>
+                <trkpt lat="40.003" lon="-3.0"><time>2024-07-01T07:59:30Z</time></trkpt>
+            </trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        assert!(gpx.validate().iter().all(|i| i.severity < Severity::Error));
+        gpx.tracks[0].segments[0].points[3].elevation = Some(f64::NAN);
+        gpx.add_waypoint(Waypoint::new(40.0, 181.0));
+
+        let kinds: Vec<(IssueKind, IssueLocation)> = gpx
+            .validate()
+            .into_iter()
+            .map(|issue| (issue.kind, issue.location))
+            .collect();
+        let point = |point| IssueLocation::Point {
+            track: 0,
+            segment: 0,
+            point,
+        };
+        assert_eq!(
+            kinds,
+            vec![
+                (IssueKind::DuplicatePoint, point(1)),
+                (IssueKind::TimeGoesBackwards, point(2)),
+                (IssueKind::InvalidElevation, point(3)),
+                (
+                    IssueKind::InvalidLongitude(181.0),
+                    IssueLocation::Waypoint(0)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_refuses_invalid_documents_when_asked() {
+        let mut track = Track::new();
+        track.add_segment(TrackSegment::from_latlon_pairs(&[
+            (40.0, -3.0),
+            (-91.0, -3.0),
+        ]));
+        let mut gpx = Gpx::new();
+        gpx.add_track(track);
+
+        let strict = WriteOptions {
+            validate: true,
+            ..WriteOptions::default()
+        };
+        match gpx.to_xml_with(&strict) {
+            Err(GpxError::Validation(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].kind, IssueKind::InvalidLatitude(-91.0));
+            }
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+        assert!(gpx.to_xml_with(&WriteOptions::default()).is_ok());
+
+        // Los avisos no impiden escribir
+        gpx.tracks[0].add_segment(TrackSegment::new());
+        gpx.tracks[0].segments[0].points[1].lat = 40.001;
+        assert!(gpx.to_xml_with(&strict).is_ok());
+    }
+}
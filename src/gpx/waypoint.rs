@@ -1,11 +1,17 @@
+use crate::gpx::metadata::Link;
+use crate::gpx::unknown::UnknownXml;
+use crate::gpx::user_data::UserData;
 use chrono::{DateTime, Utc};
+#[cfg(feature = "regex")]
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// A waypoint representing a point of interest
 ///
 /// Waypoints mark specific locations such as destinations, landmarks,
 /// or important points along a route.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Waypoint {
     /// Latitude in decimal degrees (WGS84)
     #[serde(rename = "@lat")]
@@ -13,15 +19,72 @@ pub struct Waypoint {
     /// Longitude in decimal degrees (WGS84)
     #[serde(rename = "@lon")]
     pub lon: f64,
-    /// Optional name describing the waypoint
-    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
     /// Elevation in meters above sea level
     #[serde(rename = "ele", skip_serializing_if = "Option::is_none")]
     pub elevation: Option<f64>,
     /// Timestamp of when the waypoint was created
     #[serde(rename = "time", skip_serializing_if = "Option::is_none")]
     pub time: Option<DateTime<Utc>>,
+    /// Optional name describing the waypoint
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Free-form comment (`<cmt>`), e.g. a cue sheet note
+    #[serde(rename = "cmt", skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Longer description (`<desc>`)
+    #[serde(rename = "desc", skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Source of the data (`<src>`), e.g. the map or device it came from
+    #[serde(rename = "src", skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Links to more information, such as a photo or web page
+    #[serde(rename = "link", default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+    /// Symbol name (`<sym>`), e.g. `"Summit"` or `"Flag, Blue"`
+    #[serde(rename = "sym", skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Category (`<type>`), e.g. `"Geocache|Traditional Cache"`
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Type of GPS fix (`<fix>`): `none`, `2d`, `3d`, `dgps` or `pps`
+    #[serde(rename = "fix", default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+    /// Number of satellites used for the fix (`<sat>`)
+    #[serde(rename = "sat", default, skip_serializing_if = "Option::is_none")]
+    pub satellites: Option<u32>,
+    /// Horizontal dilution of precision (`<hdop>`); lower is more accurate
+    #[serde(rename = "hdop", default, skip_serializing_if = "Option::is_none")]
+    pub hdop: Option<f64>,
+    /// Vertical dilution of precision (`<vdop>`)
+    #[serde(rename = "vdop", default, skip_serializing_if = "Option::is_none")]
+    pub vdop: Option<f64>,
+    /// Position dilution of precision (`<pdop>`)
+    #[serde(rename = "pdop", default, skip_serializing_if = "Option::is_none")]
+    pub pdop: Option<f64>,
+    /// Seconds since the last DGPS update (`<ageofdgpsdata>`)
+    #[serde(
+        rename = "ageofdgpsdata",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dgps_age: Option<f64>,
+    /// Id of the DGPS station used (`<dgpsid>`)
+    #[serde(rename = "dgpsid", default, skip_serializing_if = "Option::is_none")]
+    pub dgps_id: Option<u16>,
+    /// Child elements not modeled by this crate (`<fix>`, `<extensions>`...), written back as read
+    #[serde(
+        rename = "gpx_extractor_raw",
+        skip_deserializing,
+        skip_serializing_if = "UnknownXml::is_empty"
+    )]
+    pub unknown: UnknownXml,
+    /// Application annotations, written into a `gpxud:UserData` extension
+    #[serde(
+        rename = "gpx_extractor_user_data",
+        skip_deserializing,
+        skip_serializing_if = "UserData::is_empty"
+    )]
+    pub user_data: UserData,
 }
 
 impl Waypoint {
@@ -30,20 +93,32 @@ impl Waypoint {
         Self {
             lat,
             lon,
-            name: None,
             elevation: None,
             time: None,
+            name: None,
+            comment: None,
+            description: None,
+            source: None,
+            links: Vec::new(),
+            symbol: None,
+            kind: None,
+            fix: None,
+            satellites: None,
+            hdop: None,
+            vdop: None,
+            pdop: None,
+            dgps_age: None,
+            dgps_id: None,
+            unknown: UnknownXml::default(),
+            user_data: UserData::default(),
         }
     }
 
     /// Crea un nuevo waypoint con nombre
     pub fn with_name(lat: f64, lon: f64, name: String) -> Self {
         Self {
-            lat,
-            lon,
             name: Some(name),
-            elevation: None,
-            time: None,
+            ..Self::new(lat, lon)
         }
     }
 
@@ -56,11 +131,10 @@ impl Waypoint {
         time: Option<DateTime<Utc>>,
     ) -> Self {
         Self {
-            lat,
-            lon,
-            name,
             elevation,
             time,
+            name,
+            ..Self::new(lat, lon)
         }
     }
 
@@ -108,6 +182,64 @@ impl Waypoint {
     }
 }
 
+/// A predicate selecting waypoints by symbol, type or name
+///
+/// Parsed from `key=value` strings, as used by the CLI `--keep-waypoints` flag:
+/// `sym=Summit`, `type=Geocache` (both case-insensitive) or `name=<regex>`.
+/// Name filters need the `regex` feature.
+#[derive(Debug, Clone)]
+pub enum WaypointFilter {
+    /// Matches the `<sym>` element, ignoring case
+    Symbol(String),
+    /// Matches the `<type>` element, ignoring case
+    Kind(String),
+    /// Matches the name against a regular expression
+    #[cfg(feature = "regex")]
+    Name(Regex),
+}
+
+impl WaypointFilter {
+    /// Verifica si el waypoint cumple el filtro
+    pub fn matches(&self, waypoint: &Waypoint) -> bool {
+        match self {
+            Self::Symbol(symbol) => waypoint
+                .symbol
+                .as_deref()
+                .is_some_and(|s| s.eq_ignore_ascii_case(symbol)),
+            Self::Kind(kind) => waypoint
+                .kind
+                .as_deref()
+                .is_some_and(|k| k.eq_ignore_ascii_case(kind)),
+            #[cfg(feature = "regex")]
+            Self::Name(regex) => waypoint.name.as_deref().is_some_and(|n| regex.is_match(n)),
+        }
+    }
+}
+
+impl FromStr for WaypointFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, got '{s}'"))?;
+
+        match key.trim() {
+            "sym" | "symbol" => Ok(Self::Symbol(value.to_string())),
+            "type" => Ok(Self::Kind(value.to_string())),
+            #[cfg(feature = "regex")]
+            "name" => Regex::new(value)
+                .map(Self::Name)
+                .map_err(|e| format!("invalid name pattern: {e}")),
+            #[cfg(not(feature = "regex"))]
+            "name" => Err("name filters need the `regex` feature".to_string()),
+            other => Err(format!(
+                "unknown waypoint filter '{other}' (expected sym, type or name)"
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +336,75 @@ mod tests {
         assert!(waypoint.has_elevation());
         assert!(waypoint.has_time());
     }
+
+    #[test]
+    fn test_waypoint_basecamp_fields_round_trip() {
+        let xml = r#"<gpx><wpt lat="46.0" lon="7.0"><ele>3312</ele><name>Piz Buin</name>
+            <cmt>Summit cross</cmt><desc>Highest point of Vorarlberg</desc><src>BaseCamp</src>
+            <link href="https://example.com/piz-buin.jpg"><type>image/jpeg</type></link>
+            <sym>Summit</sym><type>Peak</type></wpt></gpx>"#;
+        let gpx = crate::Gpx::try_from_str(xml).unwrap();
+        let waypoint = &gpx.waypoints[0];
+        assert_eq!(
+            waypoint.description.as_deref(),
+            Some("Highest point of Vorarlberg")
+        );
+        assert_eq!(waypoint.source.as_deref(), Some("BaseCamp"));
+        assert_eq!(waypoint.links[0].mime_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(waypoint.symbol.as_deref(), Some("Summit"));
+        assert!(waypoint.unknown.is_empty());
+
+        let reparsed = crate::Gpx::try_from_str(&gpx.to_xml()).unwrap();
+        assert_eq!(
+            reparsed.waypoints[0].comment.as_deref(),
+            Some("Summit cross")
+        );
+        assert_eq!(reparsed.waypoints[0].links, waypoint.links);
+    }
+
+    #[test]
+    fn test_waypoint_children_follow_the_wpt_type_order() {
+        let mut waypoint = Waypoint::with_details(
+            46.0,
+            7.0,
+            Some("W".to_string()),
+            Some(3.0),
+            Some(Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap()),
+        );
+        waypoint.comment = Some("c".to_string());
+        waypoint.symbol = Some("Flag".to_string());
+        waypoint.satellites = Some(7);
+        let mut gpx = crate::Gpx::new();
+        gpx.add_waypoint(waypoint);
+
+        assert!(gpx.to_xml().contains(
+            "<ele>3</ele><time>2024-07-01T08:00:00Z</time><name>W</name><cmt>c</cmt><sym>Flag</sym><sat>7</sat></wpt>"
+        ));
+    }
+
+    #[test]
+    fn test_waypoint_filter_parse_and_match() {
+        let mut summit = Waypoint::with_name(46.0, 7.0, "Piz Buin".to_string());
+        summit.symbol = Some("Summit".to_string());
+        summit.kind = Some("Peak".to_string());
+        let parking = Waypoint::with_name(46.0, 7.1, "Parking Nord".to_string());
+
+        let filter: WaypointFilter = "sym=summit".parse().unwrap();
+        assert!(filter.matches(&summit));
+        assert!(!filter.matches(&parking));
+
+        let filter: WaypointFilter = "type=PEAK".parse().unwrap();
+        assert!(filter.matches(&summit));
+
+        #[cfg(feature = "regex")]
+        {
+            let filter: WaypointFilter = "name=^Park".parse().unwrap();
+            assert!(filter.matches(&parking));
+            assert!(!filter.matches(&summit));
+        }
+
+        assert!("Summit".parse::<WaypointFilter>().is_err());
+        assert!("color=red".parse::<WaypointFilter>().is_err());
+        assert!("name=(".parse::<WaypointFilter>().is_err());
+    }
 }
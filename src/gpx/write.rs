@@ -0,0 +1,523 @@
+use crate::gpx::{
+    bounds::Bounds,
+    error::GpxError,
+    extensions::{
+        PointExtensions, TrackExtensions, GARMIN_GPXX_NAMESPACE, GARMIN_TPX_NAMESPACE,
+        GPX_STYLE_NAMESPACE,
+    },
+    metadata::{self, Metadata},
+    track::Track,
+    unknown::{self, UnknownXml},
+    user_data,
+    waypoint::Waypoint,
+};
+use quick_xml::se::Serializer;
+use serde::Serialize;
+use std::fmt;
+
+/// Namespace of GPX 1.0 documents
+pub const GPX_10_NAMESPACE: &str = "http://www.topografix.com/GPX/1/0";
+
+/// Namespace of GPX 1.1 documents
+pub const GPX_11_NAMESPACE: &str = "http://www.topografix.com/GPX/1/1";
+
+/// Namespace de XML Schema instance, para `xsi:schemaLocation`
+pub(crate) const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Version of the GPX schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GpxVersion {
+    /// GPX 1.0: metadata fields live directly under `<gpx>` and there is no
+    /// `<extensions>` element. Still emitted by many older devices and tools.
+    V1_0,
+    /// GPX 1.1, the current version
+    #[default]
+    V1_1,
+}
+
+impl GpxVersion {
+    /// Value of the `version` attribute (`"1.0"` or `"1.1"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::V1_0 => "1.0",
+            Self::V1_1 => "1.1",
+        }
+    }
+
+    /// Default XML namespace of this version
+    pub fn namespace(self) -> &'static str {
+        match self {
+            Self::V1_0 => GPX_10_NAMESPACE,
+            Self::V1_1 => GPX_11_NAMESPACE,
+        }
+    }
+
+    /// Value of `xsi:schemaLocation` pointing at the official schema of this version
+    pub fn schema_location(self) -> String {
+        format!("{0} {0}/gpx.xsd", self.namespace())
+    }
+
+    /// Parses a `version` attribute, returning `None` for unsupported versions
+    pub fn from_attribute(version: &str) -> Option<Self> {
+        match version.trim() {
+            "1.0" => Some(Self::V1_0),
+            "1.1" => Some(Self::V1_1),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GpxVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Line terminator used in the serialized XML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineEnding {
+    /// `\n`, as used on Unix and by most tools
+    #[default]
+    Lf,
+    /// `\r\n`, expected by some legacy Windows software
+    CrLf,
+}
+
+impl LineEnding {
+    /// Secuencia de caracteres del fin de línea
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Options controlling how a document is serialized to XML
+///
+/// The defaults reproduce [`Gpx::to_xml`](crate::Gpx::to_xml): an XML declaration
+/// followed by the whole document on a single line, with the GPX namespace and
+/// `xsi:schemaLocation` declared so validators accept it.
+///
+/// # Examples
+///
+/// ```
+/// use gpx_extractor::{Gpx, WriteOptions};
+///
+/// let options = WriteOptions {
+///     indent: 4,
+///     ..WriteOptions::default()
+/// };
+/// let xml = Gpx::new().to_xml_with(&options).unwrap();
+/// assert!(xml.starts_with("<?xml"));
+///
+/// // Extensiones de Garmin declaradas una sola vez en la raíz
+/// let options = WriteOptions::default().with_extension_namespaces();
+/// let xml = Gpx::new().to_xml_with(&options).unwrap();
+/// assert!(xml.contains(r#"xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v1""#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct WriteOptions {
+    /// Number of indent characters per nesting level; `0` writes a single line
+    pub indent: usize,
+    /// Character used for indentation (usually `' '` or `'\t'`)
+    pub indent_char: char,
+    /// Whether to start the output with `<?xml version="1.0" encoding="UTF-8"?>`
+    pub declaration: bool,
+    /// Default namespace to declare on `<gpx>`, overriding the document's own;
+    /// without it the namespace of [`version`](Self::version) is declared
+    pub xmlns: Option<String>,
+    /// Target schema version; GPX 1.0 output moves the metadata fields to the root,
+    /// drops track extensions and declares the 1.0 namespace unless `xmlns` is set
+    pub version: GpxVersion,
+    /// Whether to write `<bounds>` computed from the points and waypoints, so map
+    /// viewers can set the initial viewport without scanning the document
+    pub bounds: bool,
+    /// Line terminator after the declaration and between indented elements
+    pub line_ending: LineEnding,
+    /// Whether files written with these options start with a UTF-8 byte order mark;
+    /// only applies when saving to a file, never to the returned strings
+    pub bom: bool,
+    /// Whether to refuse writing documents with [`Severity::Error`](crate::Severity::Error)
+    /// issues, returning [`GpxError::Validation`](crate::GpxError::Validation) instead
+    pub validate: bool,
+    /// Whether to write the [`UserData`](crate::UserData) of points, tracks and
+    /// waypoints into a `gpxud:UserData` extension; turn off to keep it in memory only.
+    /// GPX 1.0 output never carries it
+    pub user_data: bool,
+    /// Whether to declare `xsi:schemaLocation` with the official schema of the
+    /// output version, as Garmin Connect and schema validators expect
+    pub schema_location: bool,
+    /// Extra namespaces declared on `<gpx>` as `(prefix, uri)` pairs, such as
+    /// `("gpxtpx", GARMIN_TPX_NAMESPACE)`; see [`WriteOptions::with_namespace`]
+    pub namespaces: Vec<(String, String)>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            indent: 0,
+            indent_char: ' ',
+            declaration: true,
+            xmlns: None,
+            version: GpxVersion::V1_1,
+            bounds: true,
+            line_ending: LineEnding::Lf,
+            bom: false,
+            validate: false,
+            user_data: true,
+            schema_location: true,
+            namespaces: Vec::new(),
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Human-readable output indented with two spaces
+    pub fn pretty() -> Self {
+        Self {
+            indent: 2,
+            ..Self::default()
+        }
+    }
+
+    /// Indented output with CRLF line endings and a byte order mark, for Windows tools
+    pub fn windows() -> Self {
+        Self {
+            line_ending: LineEnding::CrLf,
+            bom: true,
+            ..Self::pretty()
+        }
+    }
+
+    /// Output for legacy GPX 1.0 consumers
+    pub fn v1_0() -> Self {
+        Self {
+            version: GpxVersion::V1_0,
+            ..Self::default()
+        }
+    }
+
+    /// Declares an extra namespace on `<gpx>` as `xmlns:{prefix}="{uri}"`
+    #[must_use]
+    pub fn with_namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Declares the namespaces of the extensions this crate writes (`gpxtpx`,
+    /// `gpxx` and `gpx_style`) on `<gpx>`, as Garmin devices do
+    #[must_use]
+    pub fn with_extension_namespaces(self) -> Self {
+        self.with_namespace("gpxtpx", GARMIN_TPX_NAMESPACE)
+            .with_namespace("gpxx", GARMIN_GPXX_NAMESPACE)
+            .with_namespace("gpx_style", GPX_STYLE_NAMESPACE)
+    }
+}
+
+/// Vista prestada del elemento `<gpx>`, para serializar sin clonar tracks ni waypoints
+#[derive(Serialize, Default)]
+#[serde(rename = "gpx")]
+pub(crate) struct RootRef<'a> {
+    #[serde(rename = "@version")]
+    pub version: &'a str,
+    #[serde(rename = "@creator")]
+    pub creator: &'a str,
+    #[serde(rename = "@xmlns", skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<&'a str>,
+    #[serde(rename = "metadata", skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<&'a Metadata>,
+    // Metadatos directamente bajo <gpx> (solo GPX 1.0)
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+    #[serde(rename = "desc", skip_serializing_if = "Option::is_none")]
+    pub desc: Option<&'a str>,
+    #[serde(rename = "author", skip_serializing_if = "Option::is_none")]
+    pub author: Option<&'a str>,
+    #[serde(rename = "email", skip_serializing_if = "Option::is_none")]
+    pub email: Option<&'a str>,
+    #[serde(rename = "url", skip_serializing_if = "Option::is_none")]
+    pub url: Option<&'a str>,
+    #[serde(rename = "urlname", skip_serializing_if = "Option::is_none")]
+    pub urlname: Option<&'a str>,
+    #[serde(rename = "time", skip_serializing_if = "Option::is_none")]
+    pub time: Option<&'a str>,
+    #[serde(rename = "keywords", skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<&'a str>,
+    #[serde(
+        rename = "bounds",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "metadata::serialize_bounds"
+    )]
+    pub bounds: Option<Bounds>,
+    // El esquema pide los waypoints antes que los tracks
+    #[serde(rename = "wpt")]
+    pub waypoints: &'a [Waypoint],
+    #[serde(rename = "trk")]
+    pub tracks: &'a [Track],
+    #[serde(rename = "gpx_extractor_raw", skip_serializing_if = "Option::is_none")]
+    pub unknown: Option<&'a UnknownXml>,
+    // Sin namespace por defecto: GpxRoot escribe solo el que tiene
+    #[serde(skip)]
+    pub exact: bool,
+}
+
+impl RootRef<'_> {
+    pub(crate) fn write(self, options: &WriteOptions) -> Result<String, GpxError> {
+        // GPX 1.0 no tiene <metadata>, <extensions>, ni <type> o <link> de track: la
+        // fecha sube a la raíz y el tipo, los enlaces, los colores y los datos de
+        // sensores se descartan
+        let legacy_tracks: Vec<Track>;
+        let mut root = self;
+        if options.version == GpxVersion::V1_0 {
+            root.version = GpxVersion::V1_0.as_str();
+            if let Some(metadata) = root.metadata.take() {
+                let author = metadata.author.as_ref();
+                let link = metadata.links.first();
+                root.name = metadata.name.as_deref();
+                root.desc = metadata.description.as_deref();
+                root.author = author.and_then(|a| a.name.as_deref());
+                root.email = author.and_then(|a| a.email.as_deref());
+                root.url = link.map(|l| l.href.as_str());
+                root.urlname = link.and_then(|l| l.text.as_deref());
+                root.time = metadata.time.as_deref();
+                root.keywords = metadata.keywords.as_deref();
+                root.bounds = metadata.bounds;
+            }
+            if root.tracks.iter().any(has_v1_1_only_data) {
+                legacy_tracks = root
+                    .tracks
+                    .iter()
+                    .cloned()
+                    .map(|mut track| {
+                        track.kind = None;
+                        track.links.clear();
+                        track.extensions = TrackExtensions::default();
+                        for segment in &mut track.segments {
+                            for point in &mut segment.points {
+                                point.extensions = PointExtensions::default();
+                            }
+                        }
+                        track
+                    })
+                    .collect();
+                root.tracks = &legacy_tracks;
+            }
+        }
+        // La versión escrita manda sobre la de las opciones (GpxRoot conserva la suya)
+        let version = GpxVersion::from_attribute(root.version).unwrap_or(options.version);
+        root.xmlns = options.xmlns.as_deref().or(root.xmlns);
+        if !root.exact {
+            root.xmlns = root.xmlns.or(Some(version.namespace()));
+        }
+
+        let mut xml = String::new();
+        if options.declaration {
+            xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        }
+
+        let mut serializer = Serializer::new(&mut xml);
+        if options.indent > 0 {
+            serializer.indent(options.indent_char, options.indent);
+        }
+        root.serialize(serializer)?;
+        // Lo que no modela el crate se escribe tal como se leyó
+        xml = unknown::expand_markers(xml);
+        xml = user_data::place_user_data(
+            xml,
+            options.user_data && options.version == GpxVersion::V1_1,
+        );
+        // Primero los atributos del documento original, que pueden listar más esquemas
+        let mut attributes = root
+            .unknown
+            .map(|unknown| unknown.attributes().to_vec())
+            .unwrap_or_default();
+        if options.schema_location {
+            attributes.push(("xmlns:xsi".to_string(), XSI_NAMESPACE.to_string()));
+            attributes.push(("xsi:schemaLocation".to_string(), version.schema_location()));
+        }
+        for (prefix, uri) in &options.namespaces {
+            attributes.push((format!("xmlns:{prefix}"), uri.clone()));
+        }
+        unknown::insert_root_attributes(&mut xml, &attributes);
+        if options.line_ending == LineEnding::CrLf {
+            xml = xml.replace('\n', LineEnding::CrLf.as_str());
+        }
+        Ok(xml)
+    }
+}
+
+fn has_v1_1_only_data(track: &Track) -> bool {
+    track.kind.is_some()
+        || !track.links.is_empty()
+        || !track.extensions.is_empty()
+        || track
+            .segments
+            .iter()
+            .flat_map(|segment| &segment.points)
+            .any(|point| !point.extensions.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gpx, GpxBuilder};
+
+    fn sample() -> Gpx {
+        GpxBuilder::new()
+            .track(|t| {
+                t.name("Loop")
+                    .segment(|s| s.point(40.0, -3.0).point(40.1, -3.1))
+            })
+            .waypoint_at(40.0, -3.0)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_default_options_match_to_xml() {
+        let gpx = sample();
+        assert_eq!(
+            gpx.to_xml_with(&WriteOptions::default()).unwrap(),
+            gpx.to_xml()
+        );
+    }
+
+    #[test]
+    fn test_pretty_output_is_indented_and_reparses() {
+        let gpx = sample();
+        let xml = gpx.to_xml_pretty();
+
+        assert!(xml.contains("\n  <trk>\n    <name>Loop</name>"));
+        assert!(xml.contains("\n      <trkpt lat=\"40\" lon=\"-3\"/>"));
+
+        let reparsed = Gpx::try_from_str(&xml).unwrap();
+        assert_eq!(reparsed.total_points(), 2);
+        assert_eq!(reparsed.track_names(), vec!["Loop"]);
+    }
+
+    #[test]
+    fn test_tabs_without_declaration_and_namespace() {
+        let options = WriteOptions {
+            indent: 1,
+            indent_char: '\t',
+            declaration: false,
+            xmlns: Some(GPX_11_NAMESPACE.to_string()),
+            ..WriteOptions::default()
+        };
+        let xml = sample().to_xml_with(&options).unwrap();
+
+        assert!(xml.starts_with("<gpx "));
+        assert!(xml.contains("xmlns=\"http://www.topografix.com/GPX/1/1\""));
+        assert!(xml.contains("\n\t<trk>"));
+    }
+
+    #[test]
+    fn test_children_follow_the_gpx_type_order() {
+        let gpx = Gpx::try_from_str(
+            r#"<gpx version="1.1" creator="x">
+  <trk><name>Loop</name><trkseg><trkpt lat="40.0" lon="-3.0"/></trkseg></trk>
+  <extensions><app>x</app></extensions>
+  <wpt lat="40.0" lon="-3.0"><name>W</name><ele>3</ele></wpt>
+  <rte><name>Plan</name><rtept lat="40.0" lon="-3.0"/></rte>
+  <metadata><name>Day</name></metadata>
+</gpx>"#,
+        )
+        .unwrap();
+        for xml in [gpx.to_xml(), gpx.to_xml_pretty(), sample().to_xml()] {
+            let position = |tag: &str| xml.find(tag).unwrap_or(usize::MAX);
+            let order: Vec<usize> = ["<metadata>", "<wpt ", "<rte>", "<trk>", "<extensions>"]
+                .iter()
+                .map(|tag| position(tag))
+                .filter(|&at| at != usize::MAX)
+                .collect();
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert_eq!(order, sorted, "{xml}");
+        }
+        assert!(gpx.to_xml().contains("</wpt><rte>"));
+        assert!(gpx.to_xml().contains("</trk><extensions>"));
+    }
+
+    #[test]
+    fn test_namespaces_and_schema_location() {
+        let xml = sample().to_xml();
+        assert!(xml.contains(&format!(
+            "<gpx version=\"1.1\" creator=\"gpx-extractor\" xmlns=\"{GPX_11_NAMESPACE}\" xmlns:xsi=\"{XSI_NAMESPACE}\" xsi:schemaLocation=\"{GPX_11_NAMESPACE} {GPX_11_NAMESPACE}/gpx.xsd\">"
+        )));
+        assert!(sample()
+            .to_xml_v10()
+            .contains("xsi:schemaLocation=\"http://www.topografix.com/GPX/1/0 http://www.topografix.com/GPX/1/0/gpx.xsd\""));
+
+        let options = WriteOptions {
+            schema_location: false,
+            ..WriteOptions::default()
+        }
+        .with_extension_namespaces();
+        let xml = sample().to_xml_with(&options).unwrap();
+        assert!(!xml.contains("xsi:"));
+        assert!(xml.contains(&format!("xmlns:gpxtpx=\"{GARMIN_TPX_NAMESPACE}\"")));
+
+        // Los atributos que el documento ya traía no se repiten
+        let gpx = Gpx::try_from_str(&xml).unwrap();
+        let xml = gpx.to_xml_with(&options).unwrap();
+        assert_eq!(xml.matches("xmlns:gpxtpx=").count(), 1);
+        assert_eq!(xml.matches(" xmlns=").count(), 1);
+    }
+
+    #[test]
+    fn test_v1_0_output_moves_time_and_drops_extensions() {
+        let gpx = GpxBuilder::new()
+            .time("2024-07-11T10:00:00Z")
+            .track(|t| {
+                t.name("Old")
+                    .color(crate::Color::rgb(0xFF, 0, 0))
+                    .segment(|s| s.point(40.0, -3.0))
+            })
+            .build()
+            .unwrap();
+        let xml = gpx.to_xml_v10();
+
+        assert!(xml.contains("version=\"1.0\""));
+        assert!(xml.contains(&format!("xmlns=\"{GPX_10_NAMESPACE}\"")));
+        assert!(xml.contains("<time>2024-07-11T10:00:00Z</time><bounds "));
+        assert!(!xml.contains("<metadata"));
+        assert!(!xml.contains("<extensions"));
+
+        // El documento original no se modifica
+        assert!(gpx.tracks[0].color().is_some());
+        let reparsed = Gpx::try_from_str(&xml).unwrap();
+        assert_eq!(reparsed.date(), Some("2024-07-11T10:00:00Z"));
+        assert_eq!(reparsed.total_points(), 1);
+    }
+
+    #[test]
+    fn test_crlf_output_and_bom_file() {
+        let gpx = sample();
+        let xml = gpx.to_xml_with(&WriteOptions::windows()).unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\r\n<gpx "));
+        assert!(xml.contains("\r\n  <trk>\r\n"));
+        assert!(!xml.replace("\r\n", "").contains('\n'));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("windows.gpx");
+        let path = path.to_str().unwrap();
+        gpx.save_to_file_with(path, &WriteOptions::windows())
+            .unwrap();
+        let bytes = std::fs::read(path).unwrap();
+        assert!(bytes.starts_with(&[0xEF, 0xBB, 0xBF, b'<']));
+
+        // Los documentos con BOM se leen sin problemas
+        let reparsed = Gpx::try_from_str(&String::from_utf8(bytes).unwrap()).unwrap();
+        assert_eq!(reparsed.total_points(), 2);
+    }
+
+    #[test]
+    fn test_version_attribute() {
+        assert_eq!(GpxVersion::from_attribute("1.0"), Some(GpxVersion::V1_0));
+        assert_eq!(GpxVersion::from_attribute(" 1.1 "), Some(GpxVersion::V1_1));
+        assert_eq!(GpxVersion::from_attribute("2.0"), None);
+        assert_eq!(GpxVersion::default().to_string(), "1.1");
+    }
+}
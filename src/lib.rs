@@ -71,11 +71,9 @@
 //!
 //! ```rust,no_run
 //! use gpx_extractor::Gpx;
-//! use std::convert::TryFrom;
 //!
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
-//! let xml = std::fs::read_to_string("route.gpx")?;
-//! let gpx = Gpx::try_from(xml.as_str())?;
+//! let gpx = Gpx::from_file("route.gpx")?;
 //!
 //! println!("📊 GPX Analysis:");
 //! println!("Tracks: {}", gpx.tracks.len());
@@ -97,19 +95,259 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::uninlined_format_args)]
 #![allow(clippy::format_push_string)]
+#![cfg_attr(
+    test,
+    allow(
+        clippy::float_cmp,
+        clippy::unreadable_literal,
+        clippy::needless_raw_string_hashes
+    )
+)]
 
 mod gpx;
 
 // Re-export public API
-pub use gpx::parser::{Gpx, GpxStatistics, Metadata};
-pub use gpx::point::Point;
+pub use gpx::bounds::Bounds;
+pub use gpx::builder::{GpxBuilder, SegmentBuilder, TrackBuilder, WaypointBuilder};
+pub use gpx::climbs::{Climb, ClimbCategory, ClimbOptions};
+pub use gpx::collection::{CollectionEntry, GpxCollection, MergeOptions};
+pub use gpx::crop::TimeRange;
+pub use gpx::csv::{CsvColumn, CsvFormat, CsvOptions};
+#[cfg(feature = "elevation-http")]
+pub use gpx::dem::HttpElevationProvider;
+pub use gpx::dem::{ElevationProvider, FillMode, SrtmProvider};
+pub use gpx::distance::{vincenty_distance, DistanceModel, Planet};
+pub use gpx::elevation::{ElevationEstimate, ElevationFilter, ElevationOptions, ElevationStrategy};
+#[cfg(feature = "encryption")]
+pub use gpx::encryption::KEY_LEN;
+pub use gpx::error::GpxError;
+pub use gpx::eta::{CheckpointSplit, PaceModel, PacingChart};
+pub use gpx::extensions::{
+    Color, PointExtensions, TrackExtensions, GARMIN_GPXX_NAMESPACE, GARMIN_TPX_NAMESPACE,
+    GPX_STYLE_NAMESPACE,
+};
+pub use gpx::fitness::{ActivityGuess, ActivityKind, ProfileParams};
+pub use gpx::geocode::{Geocoder, OfflineGeocoder};
+pub use gpx::geofence::Geofence;
+pub use gpx::geotag::DEFAULT_MAX_GAP_SECONDS;
+pub use gpx::goals::{Comparison, Goal, GoalMetric, GoalReport, GoalResult, Goals};
+#[cfg(feature = "manifest")]
+pub use gpx::index::{CollectionIndex, IndexEntry};
+pub use gpx::laps::{Lap, LapStrategy, DEFAULT_LAP_RADIUS_M};
+#[cfg(feature = "manifest")]
+pub use gpx::manifest::{Manifest, ManifestCheck, ManifestEntry};
+pub use gpx::metadata::{Copyright, Link, Metadata, Person};
+pub use gpx::outliers::{
+    Anomaly, AnomalyKind, Outlier, OutlierAction, OutlierOptions, OutlierReason, OutlierReport,
+    ANOMALY_WAYPOINT_TYPE,
+};
+pub use gpx::pace::{Pace, PaceSplit, SplitUnit};
+pub use gpx::parser::{Gpx, GpxRoot, GpxStatistics, ParseOptions};
+pub use gpx::point::{
+    haversine_distance, haversine_distance_with_radius, Point, EARTH_RADIUS_KM,
+    MAX_GEOHASH_PRECISION,
+};
+pub use gpx::polyline::{DEFAULT_POLYLINE_PRECISION, MAX_POLYLINE_PRECISION};
+pub use gpx::quality::{QualityCriterion, QualityReport, QualityScore};
+pub use gpx::resample::Interval;
+pub use gpx::route::LoopClosure;
+#[cfg(feature = "s3")]
+pub use gpx::s3::S3Store;
+pub use gpx::simplify::SimplifyAlgorithm;
+pub use gpx::snapshot::{History, Snapshot};
+pub use gpx::split::SplitStrategy;
+pub use gpx::sport::{Sport, SportLeg, SportOptions};
+pub use gpx::statistics::StatisticsOptions;
+pub use gpx::stops::{Stop, StopOptions};
+pub use gpx::store::{FsStore, GpxStore};
+pub use gpx::template::{NameTemplate, TemplateValue, TemplateValues};
 pub use gpx::track::{Track, TrackSegment};
-pub use gpx::waypoint::Waypoint;
+pub use gpx::units::{Distance, Elevation, Speed, StatisticsDisplay, Units};
+pub use gpx::unknown::UnknownXml;
+pub use gpx::user_data::{UserData, USER_DATA_NAMESPACE};
+pub use gpx::validate::{IssueKind, IssueLocation, Severity, ValidationIssue};
+pub use gpx::waypoint::{Waypoint, WaypointFilter};
+pub use gpx::write::{GpxVersion, LineEnding, WriteOptions, GPX_10_NAMESPACE, GPX_11_NAMESPACE};
 
 /// Error types for GPX operations
 pub mod error {
+    pub use crate::gpx::error::GpxError;
+
     /// Re-export quick-xml errors for convenience
     pub use quick_xml::DeError as ParseError;
+
+    /// Result alias used by the fallible APIs of this crate
+    pub type Result<T, E = GpxError> = std::result::Result<T, E>;
+}
+
+/// Training summaries across many activities: period totals, personal records and heatmaps
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::aggregate::ActivityLog;
+/// use gpx_extractor::Gpx;
+///
+/// let log = ActivityLog::from_gpx_iter(Vec::<Gpx>::new());
+/// assert_eq!(log.totals.activities, 0);
+/// assert!(log.records.longest_distance.is_none());
+/// ```
+pub mod aggregate {
+    pub use crate::gpx::aggregate::{
+        ActivityLog, PeriodTotals, PersonalRecord, PersonalRecords, DEFAULT_HEATMAP_ZOOM,
+    };
+}
+
+/// Per-point derived metrics: cumulative distance, speed, grade and vertical speed
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::GpxBuilder;
+///
+/// let gpx = GpxBuilder::new()
+///     .track(|t| t.segment(|s| s.point(40.0, -3.0).point(40.01, -3.0)))
+///     .build()
+///     .unwrap();
+/// let metrics = gpx.point_metrics();
+/// assert!((metrics[1].distance_km - 1.112).abs() < 0.001);
+/// ```
+pub mod analysis {
+    pub use crate::gpx::analysis::{
+        best_window, BestWindow, DistanceWindow, DistanceWindows, Metric, PointMetrics,
+    };
+    pub use crate::gpx::series::{SeriesKind, XAxis};
+}
+
+/// Track similarity: Hausdorff and Fréchet distances, route overlap and deviations, elevation overlays
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::compare::CompareOptions;
+/// use gpx_extractor::{Track, TrackSegment};
+///
+/// let mut track = Track::new();
+/// track.add_segment(TrackSegment::from_latlon_pairs(&[(40.0, -3.0), (40.01, -3.0)]));
+/// let same = track.similarity(&track, CompareOptions::default()).unwrap();
+/// assert_eq!(same.distance_m, 0.0);
+/// ```
+pub mod compare {
+    pub use crate::gpx::compare::{
+        elevation_overlay, CompareOptions, Deviation, DistanceMetric, OverlaySample, Similarity,
+    };
+}
+
+/// Turn-by-turn cues from the changes of direction of a track
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::cues::{generate, CueOptions};
+/// use gpx_extractor::Track;
+///
+/// assert!(generate(&Track::new(), CueOptions::default()).is_empty());
+/// ```
+pub mod cues {
+    pub use crate::gpx::cues::{generate, Cue, CueDirection, CueOptions};
+}
+
+/// Plain-text reports without emoji, box drawing or color, for screen readers
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::report::plain_summary;
+/// use gpx_extractor::Gpx;
+///
+/// let report = plain_summary(&Gpx::new(), false);
+/// assert!(report.starts_with("Tracks: 0\n"));
+/// ```
+pub mod report {
+    pub use crate::gpx::report::{plain_statistics, plain_summary};
+}
+
+/// Slippy-map tiles and tile-aligned splitting of tracks
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::tiles::Tile;
+///
+/// let tile = Tile::containing(51.5074, -0.1278, 10);
+/// assert_eq!(tile.to_string(), "10/511/340");
+/// ```
+pub mod tiles {
+    pub use crate::gpx::tiles::{Tile, MAX_TILE_ZOOM};
+}
+
+/// Pluggable readers and writers for GPX and other track formats
+pub mod formats {
+    pub use crate::gpx::formats::{
+        detect, Format, FormatReader, FormatRegistration, FormatRegistry, FormatWriter,
+        GpxXmlFormat,
+    };
+
+    #[cfg(feature = "geojson")]
+    pub use crate::gpx::geojson::GeoJsonFormat;
+}
+
+/// Streaming parser and writer for GPX files too large to load in memory
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::stream::GpxReader;
+///
+/// let xml = r#"<gpx><trk><trkseg><trkpt lat="1.0" lon="2.0"/></trkseg></trk></gpx>"#;
+/// let stats = GpxReader::new(xml.as_bytes()).statistics().unwrap();
+/// assert_eq!(stats.total_points, 1);
+/// ```
+pub mod stream {
+    pub use crate::gpx::stream::{GpxReader, TrackPointEvent};
+    pub use crate::gpx::stream_writer::{GpxWriter, RolloverWriter};
+}
+
+/// Garmin Training Center XML (TCX) import and export
+///
+/// # Example
+///
+/// ```rust
+/// use gpx_extractor::{tcx::TcxFormat, formats::FormatRegistry};
+///
+/// let registry = FormatRegistry::default();
+/// assert!(registry.reader_for_extension("tcx").is_some());
+/// ```
+pub mod tcx {
+    pub use crate::gpx::tcx::{TcxFormat, TCX_NAMESPACE};
+}
+
+/// KML export, plus zipped KMZ archives with the `kmz` feature
+pub mod kml {
+    #[cfg(feature = "kmz")]
+    pub use crate::gpx::kml::KmzWriter;
+    pub use crate::gpx::kml::{KmlFormat, KML_NAMESPACE};
+}
+
+/// Garmin FIT activity decoding (requires the `fit` feature)
+#[cfg(feature = "fit")]
+pub mod fit {
+    pub use crate::gpx::fit::FitFormat;
+}
+
+/// C interface for embedding the parser in mobile and native apps (requires the `ffi` feature)
+///
+/// Build a shared or static library with
+/// `cargo rustc --lib --release --features ffi --crate-type cdylib` (or `staticlib`)
+/// and include `include/gpx_extractor.h`. Documents are opaque pointers
+/// released with [`gpx_free`](ffi::gpx_free); errors are reported as `NULL`
+/// results with the message in [`gpx_last_error`](ffi::gpx_last_error).
+#[cfg(feature = "ffi")]
+pub mod ffi {
+    pub use crate::gpx::ffi::{
+        gpx_free, gpx_last_error, gpx_parse, gpx_parse_file, gpx_statistics, gpx_string_free,
+        gpx_to_geojson, GpxCStatistics,
+    };
 }
 
 /// Prelude module for convenient imports
@@ -125,5 +363,7 @@ pub mod error {
 /// let point = Point::new(40.7128, -74.0060);
 /// ```
 pub mod prelude {
-    pub use crate::{Gpx, GpxStatistics, Point, Track, TrackSegment, Waypoint};
+    pub use crate::{
+        Gpx, GpxBuilder, GpxError, GpxStatistics, Point, Track, TrackSegment, Waypoint,
+    };
 }
@@ -73,10 +73,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn load_gpx_file(gpx_file_name: &str) -> Result<Gpx, Box<dyn std::error::Error>> {
-    use std::convert::TryFrom;
-    let gpx_content = std::fs::read_to_string(gpx_file_name)?;
-    let gpx = Gpx::try_from(gpx_content.as_str())?;
-    Ok(gpx)
+    Ok(Gpx::from_file(gpx_file_name)?)
 }
 
 fn print_gpx_info(gpx: &Gpx) {